@@ -0,0 +1,17 @@
+// Compiles the gRPC service definition when the `grpc` feature is on.
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto");
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::configure()
+            .build_client(false)
+            .type_attribute(
+                ".juno_keys.v1",
+                "#[derive(serde::Serialize, serde::Deserialize)] #[serde(default)]",
+            )
+            .compile_protos(&["proto/juno_keys/v1/keys.proto"], &["proto"])
+            .expect("compile keys.proto");
+    }
+}
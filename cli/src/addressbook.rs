@@ -0,0 +1,373 @@
+//! `addressbook`: labels for addresses and UFVKs, so commands that take one also take
+//! `@label`.
+//!
+//! The book is one JSON file holding the Argon2id parameters and salt, a nonce, and
+//! the ChaCha20-Poly1305 ciphertext of the entries. Every change rewrites it with a
+//! fresh salt and nonce (mode 0600). The passphrase comes from
+//! `JUNO_KEYS_ADDRESSBOOK_PASSPHRASE`. A rewrite keeps the book's Argon2id settings
+//! unless `kdf_memory_mib` / `kdf_passes` are configured.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chacha20poly1305::aead::{Aead as _, KeyInit as _};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use clap::{Args, Subcommand};
+use juno_keys::{Ufvk, UnifiedAddress};
+use rand::RngCore as _;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::kdf::Kdf;
+use crate::{with_passphrase, write_json_ok, write_secret_file, AppError, Cli, Settings};
+
+const PASSPHRASE_VAR: &str = "JUNO_KEYS_ADDRESSBOOK_PASSPHRASE";
+
+// OWASP's minimum for Argon2id: 19 MiB, two passes.
+const DEFAULT_KDF: Kdf = Kdf {
+    memory_kib: 19 * 1024,
+    passes: 2,
+};
+
+#[derive(Subcommand)]
+pub(crate) enum AddressbookCmd {
+    /// Label an address or UFVK
+    #[command(name = "add")]
+    Add(AddArgs),
+    /// Print every entry
+    #[command(name = "list")]
+    List,
+    /// Print the address or UFVK behind a label
+    #[command(name = "resolve")]
+    Resolve(LabelArgs),
+    /// Delete a label
+    #[command(name = "remove")]
+    Remove(LabelArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct AddArgs {
+    #[arg(help = "Label: letters, digits, '.', '_' or '-'")]
+    label: String,
+
+    #[arg(help = "Unified address (j1...) or UFVK (jview1...)")]
+    value: String,
+
+    #[arg(long, help = "Replace an existing entry")]
+    force: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct LabelArgs {
+    #[arg(help = "Label, with or without a leading '@'")]
+    label: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum EntryKind {
+    Address,
+    Ufvk,
+}
+
+impl EntryKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            EntryKind::Address => "an address",
+            EntryKind::Ufvk => "a UFVK",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    kind: EntryKind,
+    value: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Book {
+    entries: BTreeMap<String, Entry>,
+    // What the file was sealed with, reused when it is saved again.
+    #[serde(skip)]
+    kdf: Option<Kdf>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SealedBook {
+    version: u32,
+    kdf: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize)]
+struct EntryOut<'a> {
+    label: &'a str,
+    kind: EntryKind,
+    value: &'a str,
+}
+
+pub(crate) fn cmd_addressbook(
+    cli: &Cli,
+    settings: &Settings,
+    command: &AddressbookCmd,
+) -> Result<(), AppError> {
+    let path = book_path(settings)?;
+    match command {
+        AddressbookCmd::Add(args) => {
+            let label = check_label(&args.label)?;
+            let kind = if UnifiedAddress::parse(&args.value).is_ok() {
+                EntryKind::Address
+            } else {
+                Ufvk::parse(&args.value).map_err(AppError::Keys)?;
+                EntryKind::Ufvk
+            };
+            let mut book = load(&path, true)?;
+            if !args.force && book.entries.contains_key(label) {
+                return Err(AppError::InvalidRequest(format!(
+                    "@{label} exists (pass --force to replace it)"
+                )));
+            }
+            let value = args.value.trim().to_string();
+            book.entries
+                .insert(label.to_string(), Entry { kind, value });
+            save(&path, &book, settings)?;
+            print_entry(cli, label, &book.entries[label])
+        }
+        AddressbookCmd::List => {
+            let book = load(&path, true)?;
+            let entries = book
+                .entries
+                .iter()
+                .map(|(label, entry)| EntryOut {
+                    label,
+                    kind: entry.kind,
+                    value: &entry.value,
+                })
+                .collect::<Vec<_>>();
+            if cli.json {
+                return write_json_ok(cli, &serde_json::json!({ "entries": entries }));
+            }
+            for entry in entries {
+                println!("@{}\t{}", entry.label, entry.value);
+            }
+            Ok(())
+        }
+        AddressbookCmd::Resolve(args) => {
+            let label = check_label(&args.label)?;
+            let book = load(&path, false)?;
+            let entry = book.entries.get(label).ok_or_else(|| unknown(label))?;
+            print_entry(cli, label, entry)
+        }
+        AddressbookCmd::Remove(args) => {
+            let label = check_label(&args.label)?;
+            let mut book = load(&path, false)?;
+            let entry = book.entries.remove(label).ok_or_else(|| unknown(label))?;
+            save(&path, &book, settings)?;
+            print_entry(cli, label, &entry)
+        }
+    }
+}
+
+/// `value` itself, or the entry it names if it is `@label`. An entry of the wrong
+/// kind is an error, so `--ufvk @shop` can't pick up the shop's address.
+pub(crate) fn resolve(
+    settings: &Settings,
+    value: &str,
+    kind: EntryKind,
+) -> Result<String, AppError> {
+    let Some(label) = value.strip_prefix('@') else {
+        return Ok(value.to_string());
+    };
+    let book = load(&book_path(settings)?, false)?;
+    let entry = book.entries.get(label).ok_or_else(|| unknown(label))?;
+    if entry.kind != kind {
+        return Err(AppError::InvalidRequest(format!(
+            "@{label} is {}, not {}",
+            entry.kind.as_str(),
+            kind.as_str()
+        )));
+    }
+    Ok(entry.value.clone())
+}
+
+fn print_entry(cli: &Cli, label: &str, entry: &Entry) -> Result<(), AppError> {
+    if cli.json {
+        return write_json_ok(
+            cli,
+            &EntryOut {
+                label,
+                kind: entry.kind,
+                value: &entry.value,
+            },
+        );
+    }
+    println!("{}", entry.value);
+    Ok(())
+}
+
+fn unknown(label: &str) -> AppError {
+    AppError::InvalidRequest(format!("no address book entry @{label}"))
+}
+
+fn check_label(label: &str) -> Result<&str, AppError> {
+    let label = label.strip_prefix('@').unwrap_or(label);
+    let valid = !label.is_empty()
+        && label.len() <= 64
+        && label
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"._-".contains(&b));
+    if !valid {
+        return Err(AppError::InvalidRequest(
+            "labels are 1 to 64 letters, digits, '.', '_' or '-'".into(),
+        ));
+    }
+    Ok(label)
+}
+
+fn book_path(settings: &Settings) -> Result<PathBuf, AppError> {
+    settings
+        .addressbook
+        .clone()
+        .or_else(|| Some(crate::default_config_path()?.with_file_name("addressbook.json")))
+        .ok_or_else(|| {
+            AppError::InvalidRequest("no address book path (set addressbook in the config)".into())
+        })
+}
+
+fn passphrase() -> Result<Zeroizing<String>, AppError> {
+    std::env::var(PASSPHRASE_VAR)
+        .ok()
+        .filter(|p| !p.is_empty())
+        .map(Zeroizing::new)
+        .ok_or_else(|| {
+            AppError::InvalidRequest(format!("set {PASSPHRASE_VAR} to open the address book"))
+        })
+}
+
+fn cipher(passphrase: &str, salt: &[u8], kdf: Kdf) -> Result<ChaCha20Poly1305, AppError> {
+    let key = kdf.key(passphrase, salt)?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_ref())))
+}
+
+// A missing file is an empty book when `create` is set.
+fn load(path: &Path, create: bool) -> Result<Book, AppError> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if create {
+                return Ok(Book::default());
+            }
+            return Err(AppError::InvalidRequest(format!(
+                "no address book at {} (`addressbook add` creates one)",
+                path.display()
+            )));
+        }
+        Err(e) => return Err(AppError::Io(format!("read {}: {e}", path.display()))),
+    };
+    with_passphrase(&passphrase()?, |passphrase| open(&raw, passphrase)).map_err(|e| match e {
+        AppError::InvalidRequest(msg) => {
+            AppError::InvalidRequest(format!("{}: {msg}", path.display()))
+        }
+        e => e,
+    })
+}
+
+fn open(raw: &str, passphrase: &str) -> Result<Book, AppError> {
+    let corrupt = || AppError::InvalidRequest("not an address book".into());
+    let sealed: SealedBook = serde_json::from_str(raw).map_err(|_| corrupt())?;
+    if sealed.version != 1 || sealed.kdf != "argon2id" || sealed.p_cost != 1 {
+        return Err(corrupt());
+    }
+    let kdf = Kdf {
+        memory_kib: sealed.m_cost,
+        passes: sealed.t_cost,
+    };
+    let salt = hex::decode(&sealed.salt).map_err(|_| corrupt())?;
+    let nonce = hex::decode(&sealed.nonce)
+        .ok()
+        .filter(|n| n.len() == 12)
+        .ok_or_else(corrupt)?;
+    let ciphertext = hex::decode(&sealed.ciphertext).map_err(|_| corrupt())?;
+    let plaintext = Zeroizing::new(
+        cipher(passphrase, &salt, kdf)?
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| AppError::InvalidRequest("wrong passphrase or damaged file".into()))?,
+    );
+    let mut book: Book = serde_json::from_slice(&plaintext).map_err(|_| corrupt())?;
+    book.kdf = Some(kdf);
+    Ok(book)
+}
+
+fn save(path: &Path, book: &Book, settings: &Settings) -> Result<(), AppError> {
+    let kdf = Kdf::configured(settings, book.kdf.unwrap_or(DEFAULT_KDF));
+    let json = seal(book, &juno_keys::normalize_secret(&passphrase()?), kdf)?;
+    write_secret_file(path, &(json + "\n"), true)
+}
+
+fn seal(book: &Book, passphrase: &str, kdf: Kdf) -> Result<String, AppError> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(book).map_err(|e| AppError::Io(format!("json encode: {e}")))?,
+    );
+    let ciphertext = cipher(passphrase, &salt, kdf)?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| AppError::InvalidRequest("address book encryption failed".into()))?;
+    let sealed = SealedBook {
+        version: 1,
+        kdf: "argon2id".into(),
+        m_cost: kdf.memory_kib,
+        t_cost: kdf.passes,
+        p_cost: 1,
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&sealed).map_err(|e| AppError::Io(format!("json encode: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_book_opens_only_with_its_passphrase() {
+        let mut book = Book::default();
+        book.entries.insert(
+            "shop".into(),
+            Entry {
+                kind: EntryKind::Address,
+                value: "j1example".into(),
+            },
+        );
+        let kdf = Kdf {
+            memory_kib: 256,
+            passes: 1,
+        };
+        let sealed = seal(&book, "correct horse", kdf).unwrap();
+        assert!(!sealed.contains("j1example"));
+
+        let opened = open(&sealed, "correct horse").unwrap();
+        assert_eq!(opened.entries["shop"].value, "j1example");
+        assert_eq!(opened.entries["shop"].kind, EntryKind::Address);
+        assert_eq!(opened.kdf, Some(kdf));
+        assert!(open(&sealed, "battery staple").is_err());
+    }
+
+    #[test]
+    fn labels_are_plain_names() {
+        assert_eq!(check_label("@shop-2.a_b").unwrap(), "shop-2.a_b");
+        for bad in ["", "@", "has space", "semi;colon", &"x".repeat(65)] {
+            assert!(check_label(bad).is_err(), "{bad:?}");
+        }
+    }
+}
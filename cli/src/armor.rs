@@ -0,0 +1,160 @@
+//! `--armor`: keys and seeds as BEGIN/END blocks, for pasting into tickets, emails and
+//! config management that reflow or indent text.
+//!
+//! ```text
+//! -----BEGIN JUNO ENCRYPTED SEED-----
+//! jseedenc1qygq80pc67k6ct98latcsrqnrjkq07r49xdc8h982f7vms7lezlyw2h
+//! ...
+//! =nZ2v
+//! -----END JUNO ENCRYPTED SEED-----
+//! ```
+//!
+//! The body is the usual text encoding wrapped at 64 columns, and the `=` line is the
+//! base64 of an OpenPGP CRC-24 over the unwrapped body. Reading ignores indentation,
+//! line endings and blank lines, and a wrong checksum fails rather than decoding a
+//! damaged key.
+
+use std::ops::Deref;
+
+use base64::Engine as _;
+use zeroize::Zeroizing;
+
+use crate::{AppError, Cli};
+
+const WIDTH: usize = 64;
+
+pub(crate) const SEED: &str = "JUNO SEED";
+pub(crate) const ENCRYPTED_SEED: &str = "JUNO ENCRYPTED SEED";
+pub(crate) const UFVK: &str = "JUNO UFVK";
+pub(crate) const APP_KEY: &str = "JUNO APP KEY";
+pub(crate) const USK: &str = "JUNO SPENDING KEY";
+pub(crate) const SEED_SHARE: &str = "JUNO SEED SHARE";
+
+/// `text` as a `label` block with `--armor`, else unchanged; no trailing newline.
+pub(crate) fn wrap(cli: &Cli, label: &str, text: &str) -> Zeroizing<String> {
+    if !cli.armor {
+        return Zeroizing::new(text.to_string());
+    }
+    let mut block = Zeroizing::new(format!("-----BEGIN {label}-----\n"));
+    for line in text.as_bytes().chunks(WIDTH) {
+        block.push_str(&String::from_utf8_lossy(line));
+        block.push('\n');
+    }
+    block.push('=');
+    block.push_str(&checksum(text.as_bytes()));
+    block.push_str(&format!("\n-----END {label}-----"));
+    block
+}
+
+/// Text read by [`unwrap`]: the input itself, or an armored block's body.
+pub(crate) enum Unwrapped<'a> {
+    Plain(&'a str),
+    Body(Zeroizing<String>),
+}
+
+impl Deref for Unwrapped<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Unwrapped::Plain(text) => text,
+            Unwrapped::Body(body) => body,
+        }
+    }
+}
+
+/// The body of `text` if it is a `label` block, or `text` itself if it isn't armored.
+///
+/// Plain text is borrowed rather than copied, and a body is gathered into a buffer
+/// reserved up front, so no partial copy of a secret is freed without being zeroized.
+pub(crate) fn unwrap<'a>(label: &str, text: &'a str) -> Result<Unwrapped<'a>, AppError> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let Some(found) = lines
+        .clone()
+        .next()
+        .and_then(|line| line.strip_prefix("-----BEGIN "))
+        .and_then(|line| line.strip_suffix("-----"))
+    else {
+        return Ok(Unwrapped::Plain(text));
+    };
+    let bad = |what: &str| AppError::InvalidRequest(format!("armored {label}: {what}"));
+    if found != label {
+        return Err(bad(&format!("found a {found} block instead")));
+    }
+    lines.next();
+    let mut body = Zeroizing::new(String::with_capacity(text.len()));
+    let mut sum = None;
+    for line in lines.by_ref() {
+        if line == format!("-----END {label}-----") {
+            let sum = sum.ok_or_else(|| bad("missing checksum line"))?;
+            if sum != checksum(body.as_bytes()) {
+                return Err(bad("checksum mismatch (the block was altered in transit)"));
+            }
+            return Ok(Unwrapped::Body(body));
+        }
+        if sum.is_some() {
+            return Err(bad("text after the checksum line"));
+        }
+        match line.strip_prefix('=') {
+            // Base64 padding can start a body line, but never fills four characters.
+            Some(found) if found.len() == 4 => sum = Some(found.to_string()),
+            _ => body.push_str(line),
+        }
+    }
+    Err(bad("missing END line"))
+}
+
+// OpenPGP's CRC-24 (RFC 4880 section 6.1), base64-encoded.
+fn checksum(bytes: &[u8]) -> String {
+    let mut crc: u32 = 0x00b7_04ce;
+    for &byte in bytes {
+        crc ^= u32::from(byte) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= 0x0186_4cfb;
+            }
+        }
+    }
+    base64::engine::general_purpose::STANDARD.encode(&crc.to_be_bytes()[1..])
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+
+    #[test]
+    fn blocks_survive_reflowing_and_catch_damage() {
+        let cli = Cli::parse_from(["juno-keys", "--armor", "selftest"]);
+        let text = format!("jseedenc1{}", "q".repeat(150));
+        let block = wrap(&cli, ENCRYPTED_SEED, &text);
+        assert!(block.starts_with("-----BEGIN JUNO ENCRYPTED SEED-----\njseedenc1"));
+        assert!(block.lines().all(|line| line.len() <= WIDTH));
+        assert_eq!(*unwrap(ENCRYPTED_SEED, &block).unwrap(), text);
+
+        // Indented and with CRLF line endings, as a mail client or YAML file might.
+        let pasted = block
+            .lines()
+            .map(|line| format!("    {line}\r\n"))
+            .collect::<String>();
+        assert_eq!(*unwrap(ENCRYPTED_SEED, &pasted).unwrap(), text);
+
+        let damaged = block.replacen("qqq", "qpq", 1);
+        assert!(unwrap(ENCRYPTED_SEED, &damaged).is_err());
+        assert!(unwrap(SEED, &block).is_err());
+        // Plain text comes back as the caller's own buffer, not a copy.
+        let plain = "c2VlZA==\n";
+        assert!(
+            matches!(unwrap(SEED, plain).unwrap(), Unwrapped::Plain(text) if std::ptr::eq(text, plain))
+        );
+
+        // A body line can be nothing but base64 padding.
+        let padded = format!("{}==", "A".repeat(WIDTH));
+        assert_eq!(*unwrap(SEED, &wrap(&cli, SEED, &padded)).unwrap(), padded);
+
+        // The RFC 4880 CRC-24 of no bytes is its initial value.
+        assert_eq!(checksum(b""), "twTO");
+    }
+}
@@ -0,0 +1,223 @@
+//! Audit events for `--audit` (or the `audit` config key): one structured record per
+//! CLI command or daemon request, sent to syslog or the systemd journal.
+//!
+//! A record holds the operation, its non-secret parameters, the seed and UFVK
+//! fingerprints involved, the caller and the outcome. Seeds and other secret inputs
+//! are never recorded; request params are stripped of them with [`redact`].
+//! Delivery is best-effort: a failed send is reported on stderr and never fails the
+//! operation, which has already happened by then.
+
+use std::sync::{Mutex, PoisonError};
+
+use clap::ValueEnum;
+use juno_keys::{Seed, Ufvk};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::backup::Rotated;
+use crate::style;
+
+// Request params that carry secrets.
+const SECRET_PARAMS: [&str; 3] = ["seed_base64", "mnemonic", "passphrase"];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub(crate) enum AuditSink {
+    /// RFC 3164 datagrams to /dev/log, with a JSON (CEE) message.
+    Syslog,
+    /// The journal's native protocol, one `JUNO_KEYS_*` field per event member.
+    Journald,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct Event<'a> {
+    pub(crate) operation: &'a str,
+    pub(crate) outcome: &'a str,
+    pub(crate) caller: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) request_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) seed_fingerprint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) ufvk_fingerprint: Option<String>,
+    // Secret files `--backup` moved aside.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) backups: Vec<Rotated>,
+    pub(crate) params: Value,
+}
+
+// Fingerprints noted by the CLI command that is running; the CLI runs one per process.
+static NOTED: Mutex<(Option<String>, Option<String>)> = Mutex::new((None, None));
+
+/// Notes the seed a CLI command used, for its audit event.
+pub(crate) fn note_seed(seed: &Seed) {
+    NOTED.lock().unwrap_or_else(PoisonError::into_inner).0 = juno_keys::seed_fingerprint(seed).ok();
+}
+
+/// Notes the UFVK a CLI command produced, for its audit event.
+pub(crate) fn note_ufvk(ufvk: &str) {
+    NOTED.lock().unwrap_or_else(PoisonError::into_inner).1 =
+        Ufvk::parse(ufvk).ok().map(|ufvk| ufvk.fingerprint());
+}
+
+/// Takes over fingerprints noted by an `--isolate` child.
+pub(crate) fn restore(noted: (Option<String>, Option<String>)) {
+    *NOTED.lock().unwrap_or_else(PoisonError::into_inner) = noted;
+}
+
+/// The fingerprints noted so far, as (seed, UFVK).
+pub(crate) fn noted() -> (Option<String>, Option<String>) {
+    NOTED.lock().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// `params` without its secret members.
+pub(crate) fn redact(params: &Value) -> Value {
+    let mut params = params.clone();
+    if let Some(object) = params.as_object_mut() {
+        object.retain(|key, _| !SECRET_PARAMS.contains(&key.as_str()));
+    }
+    params
+}
+
+/// The identity of the local user running the CLI.
+pub(crate) fn local_caller() -> String {
+    #[cfg(unix)]
+    {
+        // SAFETY: getuid has no preconditions and cannot fail.
+        format!("uid:{}", unsafe { libc::getuid() })
+    }
+    #[cfg(not(unix))]
+    {
+        "local".to_string()
+    }
+}
+
+/// Sends `event` to `sink`, warning on stderr if it can't be delivered.
+pub(crate) fn emit(sink: AuditSink, event: &Event) {
+    if let Err(e) = send(sink, event) {
+        style::warning(&format!("audit event not recorded: {e}"));
+    }
+}
+
+#[cfg(unix)]
+fn send(sink: AuditSink, event: &Event) -> Result<(), String> {
+    use std::os::unix::net::UnixDatagram;
+
+    let (payload, path) = match sink {
+        AuditSink::Syslog => (syslog_record(event)?, "/dev/log"),
+        AuditSink::Journald => (journal_record(event)?, "/run/systemd/journal/socket"),
+    };
+    let socket = UnixDatagram::unbound().map_err(|e| format!("socket: {e}"))?;
+    socket
+        .send_to(&payload, path)
+        .map_err(|e| format!("send to {path}: {e}"))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send(_sink: AuditSink, _event: &Event) -> Result<(), String> {
+    Err("audit sinks need syslog or journald (Unix only)".to_string())
+}
+
+// LOG_AUTH, as other security-relevant tools log; notice for failures, info otherwise.
+fn priority(event: &Event) -> u8 {
+    if event.outcome == "ok" {
+        6
+    } else {
+        5
+    }
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn syslog_record(event: &Event) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_string(event).map_err(|e| format!("json encode: {e}"))?;
+    // The daemon adds the timestamp and host.
+    let record = format!(
+        "<{}>juno-keys[{}]: @cee:{json}",
+        4 * 8 + priority(event),
+        std::process::id()
+    );
+    Ok(record.into_bytes())
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn journal_record(event: &Event) -> Result<Vec<u8>, String> {
+    let params = serde_json::to_string(&event.params).map_err(|e| format!("json encode: {e}"))?;
+    let backups = match event.backups.as_slice() {
+        [] => None,
+        backups => Some(serde_json::to_string(backups).map_err(|e| format!("json encode: {e}"))?),
+    };
+    let message = format!("audit: {} {}", event.operation, event.outcome);
+    let mut fields = vec![
+        ("MESSAGE", message.as_str()),
+        ("SYSLOG_IDENTIFIER", "juno-keys"),
+        ("JUNO_KEYS_OPERATION", event.operation),
+        ("JUNO_KEYS_OUTCOME", event.outcome),
+        ("JUNO_KEYS_CALLER", &event.caller),
+        ("JUNO_KEYS_PARAMS", &params),
+    ];
+    let optional = [
+        ("JUNO_KEYS_REQUEST_ID", event.request_id),
+        (
+            "JUNO_KEYS_SEED_FINGERPRINT",
+            event.seed_fingerprint.as_deref(),
+        ),
+        (
+            "JUNO_KEYS_UFVK_FINGERPRINT",
+            event.ufvk_fingerprint.as_deref(),
+        ),
+        ("JUNO_KEYS_BACKUPS", backups.as_deref()),
+    ];
+    fields.extend(
+        optional
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?))),
+    );
+
+    let mut record = format!("PRIORITY={}\nSYSLOG_FACILITY=4\n", priority(event)).into_bytes();
+    for (key, value) in fields {
+        record.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            // Multi-line values are length-prefixed in the native protocol.
+            record.push(b'\n');
+            record.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            record.push(b'=');
+        }
+        record.extend_from_slice(value.as_bytes());
+        record.push(b'\n');
+    }
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn records_redacted_events() {
+        let event = Event {
+            operation: "ufvk.fromSeed",
+            outcome: "ok",
+            caller: "uid:1000".to_string(),
+            request_id: Some("job\n42"),
+            params: redact(&json!({ "seed_base64": "c2VjcmV0", "account": 3 })),
+            ..Event::default()
+        };
+        let syslog = String::from_utf8(syslog_record(&event).expect("syslog")).expect("utf-8");
+        assert!(syslog.starts_with("<38>juno-keys["));
+        assert!(syslog.contains(r#"@cee:{"operation":"ufvk.fromSeed","outcome":"ok""#));
+        assert!(!syslog.contains("c2VjcmV0"));
+
+        let journal = journal_record(&event).expect("journal");
+        let text = String::from_utf8_lossy(&journal);
+        assert!(text.contains("JUNO_KEYS_PARAMS={\"account\":3}\n"));
+        let mut binary = b"JUNO_KEYS_REQUEST_ID\n".to_vec();
+        binary.extend_from_slice(&6u64.to_le_bytes());
+        binary.extend_from_slice(b"job\n42\n");
+        assert!(journal
+            .windows(binary.len())
+            .any(|w| w == binary.as_slice()));
+    }
+}
@@ -0,0 +1,141 @@
+//! `--backup`: a secret file that `--force` (or an address book rewrite) would replace
+//! is first renamed to `<file>.<timestamp>.bak`, so regenerating by mistake can't
+//! destroy the only copy of a seed.
+//!
+//! A rename keeps the file's permissions and never copies the secret. Each rotation is
+//! reported on stderr and in the command's audit event, with the old file's seed
+//! fingerprint when it holds a base64 seed and a BLAKE2b-256 of its bytes otherwise,
+//! so the backup can be matched to what it holds without opening it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::sync::{Mutex, PoisonError};
+
+use juno_keys::Seed;
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+use crate::{armor, outpath, style, AppError};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Rotations made by the CLI command that is running, for its audit event.
+static ROTATED: Mutex<Vec<Rotated>> = Mutex::new(Vec::new());
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Rotated {
+    pub(crate) path: String,
+    pub(crate) backup: String,
+    pub(crate) fingerprint: String,
+}
+
+/// Turns rotation on for the rest of the process.
+pub(crate) fn enable() {
+    ENABLED.store(true, Relaxed);
+}
+
+/// The rotations made so far.
+pub(crate) fn rotated() -> Vec<Rotated> {
+    ROTATED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Moves `path` aside if rotation is on and there is a file to replace.
+pub(crate) fn before_overwrite(path: &Path) -> Result<(), AppError> {
+    if !ENABLED.load(Relaxed) || !path.is_file() {
+        return Ok(());
+    }
+    let rotated = rotate(path, &outpath::timestamp(outpath::now()))?;
+    style::note(&format!(
+        "moved the old {} to {} ({})",
+        rotated.path, rotated.backup, rotated.fingerprint
+    ));
+    ROTATED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(rotated);
+    Ok(())
+}
+
+fn rotate(path: &Path, timestamp: &str) -> Result<Rotated, AppError> {
+    let contents = Zeroizing::new(
+        fs::read(path).map_err(|e| AppError::Io(format!("read {}: {e}", path.display())))?,
+    );
+    let fingerprint = fingerprint(&contents);
+    // Two rotations within a second get numbered names rather than one replacing the other.
+    let backup = (0..)
+        .map(|n| match n {
+            0 => suffixed(path, &format!("{timestamp}.bak")),
+            n => suffixed(path, &format!("{timestamp}.{n}.bak")),
+        })
+        .find(|backup| !backup.exists())
+        .unwrap_or_default();
+    fs::rename(path, &backup)
+        .map_err(|e| AppError::Io(format!("back up {}: {e}", path.display())))?;
+    Ok(Rotated {
+        path: path.display().to_string(),
+        backup: backup.display().to_string(),
+        fingerprint,
+    })
+}
+
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn fingerprint(contents: &[u8]) -> String {
+    let seed = std::str::from_utf8(contents)
+        .ok()
+        .and_then(|text| armor::unwrap(armor::SEED, text).ok())
+        .and_then(|text| Seed::from_base64(text.trim()).ok())
+        .and_then(|seed| juno_keys::seed_fingerprint(&seed).ok());
+    seed.unwrap_or_else(|| {
+        let hash = blake2b_simd::Params::new().hash_length(32).hash(contents);
+        format!("blake2b-256:{}", hex::encode(hash.as_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use juno_keys::ExposeSecret as _;
+
+    use super::*;
+
+    #[test]
+    fn rotation_keeps_every_old_copy() {
+        let dir = std::env::temp_dir().join(format!("juno-keys-backup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hot.seed");
+        let seed = Seed::from_bytes(&[7u8; 32]).unwrap();
+        fs::write(&path, format!("{}\n", seed.to_base64().expose_secret())).unwrap();
+
+        let first = rotate(&path, "20261016T093000Z").unwrap();
+        assert!(!path.exists());
+        assert_eq!(
+            first.backup,
+            dir.join("hot.seed.20261016T093000Z.bak")
+                .display()
+                .to_string()
+        );
+        assert_eq!(
+            first.fingerprint,
+            juno_keys::seed_fingerprint(&seed).unwrap()
+        );
+
+        fs::write(&path, "jseedenc1...\n").unwrap();
+        let second = rotate(&path, "20261016T093000Z").unwrap();
+        assert!(second.backup.ends_with("hot.seed.20261016T093000Z.1.bak"));
+        assert!(second.fingerprint.starts_with("blake2b-256:"));
+        assert_eq!(
+            fs::read_to_string(&second.backup).unwrap(),
+            "jseedenc1...\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,245 @@
+//! Streaming output for commands that emit one row per address, so a batch of a
+//! million rows needs no more memory than a batch of ten.
+//!
+//! Rows go out as one JSON object holding an array ([`JsonArray`]), or as one JSON
+//! object per line ([`Rows::start`] with `--ndjson`) for consumers that read as they go.
+//!
+//! Rows are computed by a pool of workers and written in order as they arrive. Each
+//! worker has a bounded channel to the writer, so at most `BLOCK` rows per worker are
+//! ever held; a writer stalled on a slow pipe stalls the workers too.
+
+use std::io::{self, BufWriter, Write as _};
+use std::sync::mpsc;
+use std::thread;
+
+use serde::Serialize;
+
+use crate::{json_version, AppError, Cli, OkEnvelope, RequestId};
+
+// Rows per block. Blocks go to workers in turn and a worker computes its block in
+// order, so state it keeps between rows (an account's keys) is reused across the block.
+const BLOCK: u64 = 256;
+
+/// Computes rows `0..count` on a worker pool and hands them to `write` in order.
+///
+/// `produce` gets a per-worker `state`, starting from `S::default()`. The first error,
+/// by row order, from either side stops the batch and is returned.
+pub(crate) fn ordered<S, T, P, W>(count: u64, produce: P, mut write: W) -> Result<(), AppError>
+where
+    S: Default,
+    T: Send,
+    P: Fn(&mut S, u64) -> Result<T, AppError> + Sync,
+    W: FnMut(T) -> Result<(), AppError>,
+{
+    let blocks = count.div_ceil(BLOCK);
+    let workers = thread::available_parallelism()
+        .map_or(1, |n| n.get() as u64)
+        .clamp(1, blocks.max(1));
+    let produce = &produce;
+    thread::scope(|scope| {
+        let receivers = (0..workers)
+            .map(|worker| {
+                let (sender, receiver) = mpsc::sync_channel(BLOCK as usize);
+                scope.spawn(move || {
+                    let mut state = S::default();
+                    for block in (worker..blocks).step_by(workers as usize) {
+                        for index in block * BLOCK..((block + 1) * BLOCK).min(count) {
+                            let row = produce(&mut state, index);
+                            let failed = row.is_err();
+                            // A closed channel means the writer gave up.
+                            if sender.send(row).is_err() || failed {
+                                return;
+                            }
+                        }
+                    }
+                });
+                receiver
+            })
+            .collect::<Vec<_>>();
+        // Returning drops the receivers, which stops any worker still sending.
+        for index in 0..count {
+            let row = receivers[((index / BLOCK) % workers) as usize]
+                .recv()
+                .map_err(|_| AppError::Io("batch worker stopped".into()))??;
+            write(row)?;
+        }
+        Ok(())
+    })
+}
+
+/// Writes `{"<key>": [...]}` to stdout one item at a time, in the same bytes as
+/// printing the whole object: pretty-printed, or compact inside the `--json` envelope.
+pub(crate) struct JsonArray {
+    out: BufWriter<io::StdoutLock<'static>>,
+    pretty: bool,
+    tail: String,
+    empty: bool,
+}
+
+impl JsonArray {
+    pub(crate) fn start(cli: &Cli, key: &str) -> Result<JsonArray, AppError> {
+        let (head, tail) = if cli.json {
+            let envelope = serde_json::to_string(&OkEnvelope {
+                version: json_version(),
+                status: "ok",
+                request_id: RequestId(cli.request_id.as_deref()),
+                data: serde_json::json!({ key: [] }),
+            })
+            .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+            let split = envelope.rfind("[]").map_or(0, |i| i + 1);
+            let (head, tail) = envelope.split_at(split);
+            (head.to_string(), tail.to_string())
+        } else {
+            let key = serde_json::to_string(key)
+                .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+            (format!("{{\n  {key}: ["), "]\n}".to_string())
+        };
+        let mut array = JsonArray {
+            out: BufWriter::new(io::stdout().lock()),
+            pretty: !cli.json,
+            tail,
+            empty: true,
+        };
+        array.write(head.as_bytes())?;
+        Ok(array)
+    }
+
+    pub(crate) fn push<T: Serialize>(&mut self, item: &T) -> Result<(), AppError> {
+        let encoded = if self.pretty {
+            serde_json::to_string_pretty(item)
+        } else {
+            serde_json::to_string(item)
+        }
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+        let separator = match (self.empty, self.pretty) {
+            (true, false) => "",
+            (false, false) => ",",
+            (true, true) => "\n",
+            (false, true) => ",\n",
+        };
+        self.empty = false;
+        self.write(separator.as_bytes())?;
+        if !self.pretty {
+            return self.write(encoded.as_bytes());
+        }
+        for (i, line) in encoded.lines().enumerate() {
+            if i > 0 {
+                self.write(b"\n")?;
+            }
+            self.write(b"    ")?;
+            self.write(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> Result<(), AppError> {
+        if self.pretty && !self.empty {
+            self.write(b"\n  ")?;
+        }
+        let tail = std::mem::take(&mut self.tail);
+        self.write(tail.as_bytes())?;
+        self.write(b"\n")?;
+        self.out
+            .flush()
+            .map_err(|e| AppError::Io(format!("write stdout: {e}")))
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), AppError> {
+        self.out
+            .write_all(bytes)
+            .map_err(|e| AppError::Io(format!("write stdout: {e}")))
+    }
+}
+
+/// A [`JsonArray`], or with `ndjson` bare lines of compact JSON, one per row.
+pub(crate) enum Rows {
+    Array(JsonArray),
+    Lines(BufWriter<io::StdoutLock<'static>>),
+}
+
+impl Rows {
+    pub(crate) fn start(cli: &Cli, key: &str, ndjson: bool) -> Result<Rows, AppError> {
+        if !ndjson {
+            return Ok(Rows::Array(JsonArray::start(cli, key)?));
+        }
+        Self::check(cli, ndjson)?;
+        Ok(Rows::Lines(BufWriter::new(io::stdout().lock())))
+    }
+
+    /// Fails as [`Self::start`] would for these flags, to check before any work.
+    pub(crate) fn check(cli: &Cli, ndjson: bool) -> Result<(), AppError> {
+        // The envelope is one JSON document; lines can't sit inside it.
+        if ndjson && cli.json {
+            return Err(AppError::InvalidRequest(
+                "--ndjson writes one row per line without an envelope; drop --json".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn push<T: Serialize>(&mut self, row: &T) -> Result<(), AppError> {
+        match self {
+            Rows::Array(array) => array.push(row),
+            Rows::Lines(out) => {
+                serde_json::to_writer(&mut *out, row)
+                    .map_err(|e| AppError::Io(format!("write stdout: {e}")))?;
+                out.write_all(b"\n")
+                    .map_err(|e| AppError::Io(format!("write stdout: {e}")))
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> Result<(), AppError> {
+        match self {
+            Rows::Array(array) => array.finish(),
+            Rows::Lines(mut out) => out
+                .flush()
+                .map_err(|e| AppError::Io(format!("write stdout: {e}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_arrive_in_order_and_errors_stop_the_batch() {
+        let count = 3 * BLOCK + 7;
+        let mut seen = Vec::new();
+        ordered(
+            count,
+            |calls: &mut u64, index| {
+                *calls += 1;
+                Ok(index * 2)
+            },
+            |row| {
+                seen.push(row);
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(seen, (0..count).map(|i| i * 2).collect::<Vec<_>>());
+
+        let failing = ordered(
+            count,
+            |_: &mut (), index| match index {
+                500 => Err(AppError::InvalidRequest("row 500".into())),
+                _ => Ok(index),
+            },
+            |row| {
+                assert!(row < 500);
+                Ok(())
+            },
+        );
+        assert_eq!(failing.unwrap_err().message(), "row 500");
+
+        // A writer that gives up doesn't leave workers blocked.
+        let stopped = ordered(
+            count,
+            |_: &mut (), index| Ok(index),
+            |_| Err(AppError::Io("closed".into())),
+        );
+        assert!(stopped.is_err());
+    }
+}
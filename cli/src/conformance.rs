@@ -0,0 +1,341 @@
+//! `testvectors conformance`: fixed inputs and this crate's outputs for every public
+//! operation, so the C, Node, Swift/Kotlin and wasm bindings and the daemon can be
+//! checked for byte-exact parity.
+//!
+//! Each case names an operation and its input, plus either the expected `output` or
+//! the string `error` code. Binary values are hex. Inputs are fixed, so the suite only
+//! changes when the library's behaviour does.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use juno_keys::zip316::{self, Zip316Error};
+use juno_keys::{
+    DerivationRequest, HardeningPolicy, KeysError, Network, Ufvk, UnifiedAddress, ZcashNetwork,
+    ZipPath,
+};
+
+use crate::{fixture_seed, AppError};
+
+// Bumped when the layout of the suite changes, not when cases are added.
+const SUITE_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub(crate) struct Suite {
+    suite_version: u32,
+    cases: Vec<Case>,
+}
+
+#[derive(Serialize)]
+struct Case {
+    id: String,
+    operation: &'static str,
+    input: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'static str>,
+}
+
+#[derive(Default)]
+struct Cases(Vec<Case>);
+
+impl Cases {
+    fn add(&mut self, operation: &'static str, input: Value, result: Result<Value, KeysError>) {
+        let n = self.0.iter().filter(|c| c.operation == operation).count();
+        let (output, error) = match result {
+            Ok(output) => (Some(output), None),
+            Err(e) => (None, Some(e.code())),
+        };
+        self.0.push(Case {
+            id: format!("{operation}/{n}"),
+            operation,
+            input,
+            output,
+            error,
+        });
+    }
+}
+
+pub(crate) fn suite() -> Result<Suite, AppError> {
+    let mut cases = Cases::default();
+    let seeds = [
+        vec![0u8; 32],
+        vec![7u8; 32],
+        fixture_seed(0).to_vec(),
+        vec![0xffu8; 252],
+    ];
+    derivation(&mut cases, &seeds);
+
+    // One account per network feeds the UFVK-based operations.
+    let mut ufvks = Vec::new();
+    for net in Network::ALL {
+        ufvks.push(juno_keys::ufvk_from_seed(&seeds[1], &net, 0).map_err(AppError::Keys)?);
+    }
+    viewing(&mut cases, &ufvks);
+    encoding(&mut cases)?;
+
+    Ok(Suite {
+        suite_version: SUITE_VERSION,
+        cases: cases.0,
+    })
+}
+
+fn derivation(cases: &mut Cases, seeds: &[Vec<u8>]) {
+    for seed in seeds {
+        let input = json!({ "seed": hex::encode(seed) });
+        let result = juno_keys::seed_fingerprint(seed).map(|fp| json!({ "fingerprint": fp }));
+        cases.add("seed_fingerprint", input, result);
+        for net in Network::ALL {
+            for account in [0, 1, 0x7fff_ffff] {
+                let input = json!({ "seed": hex::encode(seed), "network": net.as_str(), "account": account });
+                let result = juno_keys::ufvk_from_seed(seed, &net, account)
+                    .map(|ufvk| json!({ "ufvk": ufvk }));
+                cases.add("ufvk_from_seed", input, result);
+            }
+            for diversifier_index in [0, 1, u32::MAX] {
+                let input = json!({
+                    "seed": hex::encode(seed),
+                    "network": net.as_str(),
+                    "account": 0,
+                    "diversifier_index": diversifier_index,
+                });
+                let result = DerivationRequest::new(seed)
+                    .network(net)
+                    .diversifier_index(diversifier_index)
+                    .build()
+                    .map(|keys| json!({ "address": keys.address.to_string() }));
+                cases.add("address_from_seed", input, result);
+            }
+        }
+    }
+
+    let seed = &seeds[1];
+    for (network, account) in [("mainnet", 0x8000_0000u32), ("moonnet", 0)] {
+        let input = json!({ "seed": hex::encode(seed), "network": network, "account": account });
+        let result = network
+            .parse::<Network>()
+            .and_then(|net| juno_keys::ufvk_from_seed(seed, &net, account))
+            .map(|ufvk| json!({ "ufvk": ufvk }));
+        cases.add("ufvk_from_seed", input, result);
+    }
+    for short in [vec![7u8; 31], vec![7u8; 253]] {
+        let input = json!({ "seed": hex::encode(&short), "network": "mainnet", "account": 0 });
+        let result = juno_keys::ufvk_from_seed(&short, &Network::Mainnet, 0)
+            .map(|ufvk| json!({ "ufvk": ufvk }));
+        cases.add("ufvk_from_seed", input, result);
+    }
+
+    for path in [
+        "m/32'/8133'/0'/7'",
+        "m/32'/1'/5'/0'/1'",
+        "m/32'/8133'/0'/7",
+        "m/44'/8133'/0'",
+    ] {
+        let input = json!({ "seed": hex::encode(seed), "network": "mainnet", "path": path, "diversifier_index": 2 });
+        let result = ZipPath::parse_with_policy(path, HardeningPolicy::HardenedOnly)
+            .and_then(|path| {
+                DerivationRequest::new(seed)
+                    .network(Network::Mainnet)
+                    .path(path)
+                    .diversifier_index(2u32)
+                    .build()
+            })
+            .map(|keys| {
+                json!({ "ufvk": keys.ufvk.to_string(), "uivk": keys.uivk, "address": keys.address.to_string() })
+            });
+        cases.add("derive_path", input, result);
+    }
+
+    // Zcash encodings of the same keys, as `convert from-zcash` re-labels them.
+    for net in Network::ALL {
+        if let Ok(ufvk) = juno_keys::ufvk_from_seed(seed, &ZcashNetwork(net), 0) {
+            let input = json!({ "encoded": ufvk });
+            let result = juno_keys::convert_from_zcash(&ufvk).map(|c| {
+                json!({
+                    "encoded": c.encoded,
+                    "network": c.network.as_str(),
+                    "kind": if c.is_ufvk { "ufvk" } else { "ua" },
+                    "typecodes": c.typecodes,
+                })
+            });
+            cases.add("convert_from_zcash", input, result);
+        }
+    }
+}
+
+fn viewing(cases: &mut Cases, ufvks: &[String]) {
+    for ufvk in ufvks {
+        for diversifier_index in [0, 1, 1000, u32::MAX] {
+            let input = json!({ "ufvk": ufvk, "diversifier_index": diversifier_index });
+            let result = juno_keys::ua_from_ufvk(ufvk, diversifier_index)
+                .map(|address| json!({ "address": address }));
+            cases.add("address_from_ufvk", input, result);
+        }
+        let result = juno_keys::uivk_from_ufvk(ufvk).map(|uivk| json!({ "uivk": uivk }));
+        cases.add("uivk_from_ufvk", json!({ "ufvk": ufvk }), result);
+        let fingerprint = juno_keys::ufvk_fingerprint(ufvk).map(|fp| json!({ "fingerprint": fp }));
+        cases.add("ufvk_fingerprint", json!({ "ufvk": ufvk }), fingerprint);
+
+        let address = juno_keys::ua_from_ufvk(ufvk, 0u32).unwrap_or_default();
+        let mut encodings = vec![ufvk.clone(), address.clone()];
+        encodings.extend(juno_keys::uivk_from_ufvk(ufvk).ok());
+        for encoded in encodings {
+            let result =
+                juno_keys::detect_network(&encoded).map(|net| json!({ "network": net.as_str() }));
+            cases.add("detect_network", json!({ "encoded": encoded }), result);
+        }
+
+        for candidate in [ufvk.clone(), corrupt(ufvk), address.clone()] {
+            let result = Ufvk::parse(&candidate).map(|_| json!({}));
+            cases.add("validate_ufvk", json!({ "ufvk": candidate }), result);
+        }
+        for candidate in [address.clone(), corrupt(&address), ufvk.clone()] {
+            let result = UnifiedAddress::parse(&candidate).map(|_| json!({}));
+            cases.add("validate_address", json!({ "address": candidate }), result);
+        }
+    }
+    let result =
+        juno_keys::ua_from_ufvk("jview1qqqqqq", 0u32).map(|address| json!({ "address": address }));
+    cases.add(
+        "address_from_ufvk",
+        json!({ "ufvk": "jview1qqqqqq", "diversifier_index": 0 }),
+        result,
+    );
+}
+
+fn encoding(cases: &mut Cases) -> Result<(), AppError> {
+    let keys = |result: Result<Value, Zip316Error>| result.map_err(KeysError::from);
+
+    for len in [48, 100, 47] {
+        let message = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+        let result = zip316::f4jumble(&message).map(|j| json!({ "message": hex::encode(j) }));
+        cases.add(
+            "f4jumble",
+            json!({ "message": hex::encode(&message) }),
+            keys(result),
+        );
+        if let Ok(jumbled) = zip316::f4jumble(&message) {
+            let result =
+                zip316::f4jumble_inv(&jumbled).map(|m| json!({ "message": hex::encode(m) }));
+            cases.add(
+                "f4jumble_inv",
+                json!({ "message": hex::encode(&jumbled) }),
+                keys(result),
+            );
+        }
+    }
+
+    for (hrp, data) in [
+        ("j", vec![]),
+        ("jtest", vec![0u8, 1, 2, 0xff]),
+        ("J", vec![1]),
+    ] {
+        let result = zip316::bech32m_encode(hrp, &data).map(|e| json!({ "encoded": e }));
+        let input = json!({ "hrp": hrp, "data": hex::encode(&data) });
+        cases.add("bech32m_encode", input, keys(result));
+        if let Ok(encoded) = zip316::bech32m_encode(hrp, &data) {
+            let result = zip316::bech32m_decode(&encoded)
+                .map(|(hrp, data)| json!({ "hrp": hrp, "data": hex::encode(data) }));
+            cases.add(
+                "bech32m_decode",
+                json!({ "encoded": encoded }),
+                keys(result),
+            );
+        }
+    }
+    let result = zip316::bech32m_decode("j1qqqqqqqq")
+        .map(|(hrp, data)| json!({ "hrp": hrp, "data": hex::encode(data) }));
+    cases.add(
+        "bech32m_decode",
+        json!({ "encoded": "j1qqqqqqqq" }),
+        keys(result),
+    );
+
+    let hrps = Network::ALL.map(|net| net.ua_hrp());
+    for vector in zip316::test_vectors(&hrps).map_err(AppError::Zip316)? {
+        let items = vector
+            .items
+            .iter()
+            .map(|(typecode, value)| json!({ "typecode": typecode, "hex": hex::encode(value) }))
+            .collect::<Vec<_>>();
+        cases.add(
+            "zip316_encode",
+            json!({ "hrp": vector.hrp, "items": items }),
+            Ok(json!({ "encoded": vector.encoded })),
+        );
+        cases.add(
+            "zip316_decode",
+            json!({ "hrp": vector.hrp, "encoded": vector.encoded }),
+            Ok(json!({ "items": items })),
+        );
+    }
+    Ok(())
+}
+
+// `s` with its last character swapped, which breaks the bech32m checksum.
+fn corrupt(s: &str) -> String {
+    let mut chars = s.chars().collect::<Vec<_>>();
+    if let Some(last) = chars.last_mut() {
+        *last = if *last == 'q' { 'p' } else { 'q' };
+    }
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn covers_every_operation() {
+        let suite = suite().map_err(|e| e.message()).expect("suite");
+        let operations = suite
+            .cases
+            .iter()
+            .map(|c| c.operation)
+            .collect::<BTreeSet<_>>();
+        for operation in [
+            "seed_fingerprint",
+            "ufvk_from_seed",
+            "address_from_seed",
+            "derive_path",
+            "convert_from_zcash",
+            "address_from_ufvk",
+            "uivk_from_ufvk",
+            "ufvk_fingerprint",
+            "detect_network",
+            "validate_ufvk",
+            "validate_address",
+            "f4jumble",
+            "f4jumble_inv",
+            "bech32m_encode",
+            "bech32m_decode",
+            "zip316_encode",
+            "zip316_decode",
+        ] {
+            assert!(operations.contains(operation), "no {operation} cases");
+        }
+        let ids = suite
+            .cases
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(ids.len(), suite.cases.len());
+        let errors = suite
+            .cases
+            .iter()
+            .filter_map(|c| c.error)
+            .collect::<BTreeSet<_>>();
+        for code in [
+            "seed_invalid",
+            "account_invalid",
+            "network_unknown",
+            "path_not_hardened",
+            "encoding_invalid",
+        ] {
+            assert!(errors.contains(code), "no {code} case");
+        }
+    }
+}
@@ -0,0 +1,662 @@
+//! Seeds kept in the OS credential store rather than in files, via `seed store
+//! --credential NAME` and `--seed-credential NAME` (or the config's `seed_credential`).
+//!
+//! On Windows each seed is a generic credential named `juno-keys:NAME` holding its
+//! base64, which Windows encrypts at rest under the user's logon (DPAPI), so it is
+//! unreadable to other accounts. `cmdkey /delete:juno-keys:NAME` or the Credential
+//! Manager control panel removes one.
+//!
+//! On macOS it is a Keychain item (service `juno-keys`, account NAME) in the data
+//! protection keychain, readable only on this device while it is unlocked, and only
+//! after Touch ID or the login password confirms the user is present. That keychain
+//! needs a binary signed with a `keychain-access-groups` entitlement.
+//!
+//! On Linux it is a `user` key named `juno-keys:NAME` in the session kernel keyring,
+//! which expires after `--ttl` (15 minutes by default). Every command run from the
+//! same login session can read it until then, so the steps of a ceremony share one
+//! unlocked seed without a daemon or a plaintext file; it never touches the disk and
+//! is gone at logout or reboot. `keyctl purge user juno-keys:NAME` removes it early.
+//!
+//! Other platforms have no such store, and both flags fail there.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+use crate::{audit, resolve_seed, reveal, write_json_ok, AppError, Cli, SeedStoreArgs, Settings};
+
+const PREFIX: &str = "juno-keys:";
+
+/// How long a kernel keyring credential lives without `--ttl`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Parses `--ttl`: `30s`, `10m`, `2h`, up to a day.
+pub(crate) fn ttl_arg(s: &str) -> Result<Duration, String> {
+    reveal::parse_duration(s, Duration::from_secs(24 * 3600))
+}
+
+pub(crate) fn cmd_seed_store(
+    cli: &Cli,
+    settings: &Settings,
+    args: &SeedStoreArgs,
+) -> Result<(), AppError> {
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    audit::note_seed(&seed);
+    let seed_fingerprint = juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?;
+    write(
+        &args.credential,
+        juno_keys::ExposeSecret::expose_secret(&seed.to_base64()),
+        args.force,
+        args.ttl,
+    )?;
+
+    #[derive(Serialize)]
+    struct StoreOut<'a> {
+        credential: String,
+        seed_fingerprint: &'a str,
+    }
+    let data = StoreOut {
+        credential: label(&args.credential),
+        seed_fingerprint: &seed_fingerprint,
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    println!(
+        "stored seed {} as credential {}",
+        data.seed_fingerprint, data.credential
+    );
+    Ok(())
+}
+
+/// How credential `name` is named in the store, e.g. `juno-keys:prod`.
+pub(crate) fn label(name: &str) -> String {
+    format!("{PREFIX}{name}")
+}
+
+/// The secret stored as credential `name`.
+pub(crate) fn read(name: &str) -> Result<Zeroizing<String>, AppError> {
+    imp::read(name)?.ok_or_else(|| {
+        AppError::InvalidRequest(format!(
+            "no credential {PREFIX}{name} (store one with `seed store --credential {name}`)"
+        ))
+    })
+}
+
+/// Stores `secret` as credential `name`, replacing an existing one only with `force`.
+/// `ttl` is for the kernel keyring, the only store whose entries expire.
+pub(crate) fn write(
+    name: &str,
+    secret: &str,
+    force: bool,
+    ttl: Option<Duration>,
+) -> Result<(), AppError> {
+    if name.is_empty() {
+        return Err(AppError::InvalidRequest("empty credential name".into()));
+    }
+    if ttl.is_some() && !cfg!(target_os = "linux") {
+        return Err(AppError::InvalidRequest(
+            "--ttl is only for the Linux kernel keyring".into(),
+        ));
+    }
+    imp::write(name, secret, force, ttl)
+}
+
+/// The name of this platform's credential store, if it can be used here.
+pub(crate) fn available() -> Result<&'static str, AppError> {
+    imp::available()
+}
+
+#[cfg(any(windows, target_os = "macos", target_os = "linux"))]
+fn exists(name: &str) -> AppError {
+    AppError::InvalidRequest(format!(
+        "credential {PREFIX}{name} exists (use --force to replace it)"
+    ))
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::ptr;
+
+    use windows_sys::Win32::Foundation::{GetLastError, ERROR_NOT_FOUND};
+    use windows_sys::Win32::Security::Credentials::{
+        CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC,
+    };
+    use zeroize::Zeroizing;
+
+    use super::PREFIX;
+    use crate::AppError;
+
+    pub(super) fn available() -> Result<&'static str, AppError> {
+        Ok("the Windows Credential Manager")
+    }
+
+    // A NUL-terminated UTF-16 string for the API.
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(Some(0)).collect()
+    }
+
+    pub(super) fn read(name: &str) -> Result<Option<Zeroizing<String>>, AppError> {
+        let target = wide(&format!("{PREFIX}{name}"));
+        let mut credential: *mut CREDENTIALW = ptr::null_mut();
+        // SAFETY: `target` is NUL-terminated and outlives the call; on success
+        // `credential` points at a block that is released with `CredFree` below.
+        if unsafe { CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) } == 0 {
+            // SAFETY: no other call has been made on this thread since CredReadW.
+            return match unsafe { GetLastError() } {
+                ERROR_NOT_FOUND => Ok(None),
+                code => Err(AppError::Io(format!(
+                    "read credential {PREFIX}{name}: Windows error {code}"
+                ))),
+            };
+        }
+        // SAFETY: CredReadW succeeded, so `credential` is valid and its blob holds
+        // `CredentialBlobSize` bytes until it is freed. The blob is wiped before the
+        // block goes back to the system.
+        let text = unsafe {
+            let blob = (*credential).CredentialBlob;
+            let len = (*credential).CredentialBlobSize as usize;
+            let text = match len {
+                0 => Ok(""),
+                len => std::str::from_utf8(std::slice::from_raw_parts(blob, len)),
+            }
+            .map(|text| Zeroizing::new(text.to_string()));
+            if !blob.is_null() {
+                ptr::write_bytes(blob, 0, len);
+            }
+            CredFree(credential.cast());
+            text
+        };
+        text.map(Some).map_err(|_| {
+            AppError::InvalidRequest(format!("credential {PREFIX}{name} doesn't hold text"))
+        })
+    }
+
+    pub(super) fn write(
+        name: &str,
+        secret: &str,
+        force: bool,
+        _ttl: Option<std::time::Duration>,
+    ) -> Result<(), AppError> {
+        // CredWriteW replaces silently.
+        if !force && read(name)?.is_some() {
+            return Err(super::exists(name));
+        }
+        let mut target = wide(&format!("{PREFIX}{name}"));
+        let mut comment = wide("Juno seed (juno-keys)");
+        let blob = Zeroizing::new(secret.as_bytes().to_vec());
+        let credential = CREDENTIALW {
+            Type: CRED_TYPE_GENERIC,
+            TargetName: target.as_mut_ptr(),
+            Comment: comment.as_mut_ptr(),
+            CredentialBlobSize: blob.len() as u32,
+            CredentialBlob: blob.as_ptr().cast_mut(),
+            Persist: CRED_PERSIST_LOCAL_MACHINE,
+            ..Default::default()
+        };
+        // SAFETY: every pointer in `credential` refers to a buffer that outlives the
+        // call, which only reads them.
+        if unsafe { CredWriteW(&credential, 0) } == 0 {
+            // SAFETY: no other call has been made on this thread since CredWriteW.
+            let code = unsafe { GetLastError() };
+            return Err(AppError::Io(format!(
+                "store credential {PREFIX}{name}: Windows error {code}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::ffi::c_void;
+    use std::ptr;
+
+    use zeroize::Zeroizing;
+
+    use crate::AppError;
+
+    type CFTypeRef = *const c_void;
+    type OSStatus = i32;
+
+    // Opaque; only their addresses are passed.
+    #[repr(C)]
+    struct CFDictionaryKeyCallBacks([u8; 0]);
+    #[repr(C)]
+    struct CFDictionaryValueCallBacks([u8; 0]);
+
+    const UTF8: u32 = 0x0800_0100;
+    const USER_PRESENCE: usize = 1;
+
+    const NOT_FOUND: OSStatus = -25300;
+    const DUPLICATE: OSStatus = -25299;
+    const USER_CANCELED: OSStatus = -128;
+    const AUTH_FAILED: OSStatus = -25293;
+    const MISSING_ENTITLEMENT: OSStatus = -34018;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        static kCFTypeDictionaryKeyCallBacks: CFDictionaryKeyCallBacks;
+        static kCFTypeDictionaryValueCallBacks: CFDictionaryValueCallBacks;
+        static kCFBooleanTrue: CFTypeRef;
+
+        fn CFDictionaryCreate(
+            allocator: CFTypeRef,
+            keys: *const CFTypeRef,
+            values: *const CFTypeRef,
+            count: isize,
+            key_callbacks: *const CFDictionaryKeyCallBacks,
+            value_callbacks: *const CFDictionaryValueCallBacks,
+        ) -> CFTypeRef;
+        fn CFStringCreateWithBytes(
+            allocator: CFTypeRef,
+            bytes: *const u8,
+            len: isize,
+            encoding: u32,
+            external: u8,
+        ) -> CFTypeRef;
+        fn CFDataCreate(allocator: CFTypeRef, bytes: *const u8, len: isize) -> CFTypeRef;
+        fn CFDataGetBytePtr(data: CFTypeRef) -> *const u8;
+        fn CFDataGetLength(data: CFTypeRef) -> isize;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    #[link(name = "Security", kind = "framework")]
+    extern "C" {
+        static kSecClass: CFTypeRef;
+        static kSecClassGenericPassword: CFTypeRef;
+        static kSecAttrService: CFTypeRef;
+        static kSecAttrAccount: CFTypeRef;
+        static kSecAttrAccessControl: CFTypeRef;
+        static kSecAttrAccessibleWhenUnlockedThisDeviceOnly: CFTypeRef;
+        static kSecValueData: CFTypeRef;
+        static kSecReturnData: CFTypeRef;
+        static kSecMatchLimit: CFTypeRef;
+        static kSecMatchLimitOne: CFTypeRef;
+        static kSecUseDataProtectionKeychain: CFTypeRef;
+        static kSecUseOperationPrompt: CFTypeRef;
+
+        fn SecAccessControlCreateWithFlags(
+            allocator: CFTypeRef,
+            protection: CFTypeRef,
+            flags: usize,
+            error: *mut CFTypeRef,
+        ) -> CFTypeRef;
+        fn SecItemAdd(attributes: CFTypeRef, result: *mut CFTypeRef) -> OSStatus;
+        fn SecItemCopyMatching(query: CFTypeRef, result: *mut CFTypeRef) -> OSStatus;
+        fn SecItemDelete(query: CFTypeRef) -> OSStatus;
+    }
+
+    // An owned CoreFoundation object, released on drop.
+    struct Owned(CFTypeRef);
+
+    impl Drop for Owned {
+        fn drop(&mut self) {
+            if !self.0.is_null() {
+                // SAFETY: `self.0` came from a Create or Copy call, so this owns it.
+                unsafe { CFRelease(self.0) }
+            }
+        }
+    }
+
+    fn string(s: &str) -> Owned {
+        // SAFETY: the bytes are valid for `s.len()` and copied by the call.
+        Owned(unsafe {
+            CFStringCreateWithBytes(ptr::null(), s.as_ptr(), s.len() as isize, UTF8, 0)
+        })
+    }
+
+    fn dictionary(pairs: &[(CFTypeRef, CFTypeRef)]) -> Owned {
+        let (keys, values): (Vec<_>, Vec<_>) = pairs.iter().copied().unzip();
+        // SAFETY: both arrays hold `pairs.len()` live CF objects, which the dictionary
+        // retains; the callbacks are CoreFoundation's own.
+        Owned(unsafe {
+            CFDictionaryCreate(
+                ptr::null(),
+                keys.as_ptr(),
+                values.as_ptr(),
+                pairs.len() as isize,
+                &kCFTypeDictionaryKeyCallBacks,
+                &kCFTypeDictionaryValueCallBacks,
+            )
+        })
+    }
+
+    // The class, service and account naming credential `name`, plus `extra`.
+    fn query(account: &Owned, service: &Owned, extra: &[(CFTypeRef, CFTypeRef)]) -> Owned {
+        // SAFETY: the constants are immutable CFStrings exported by the frameworks.
+        let mut pairs = unsafe {
+            vec![
+                (kSecClass, kSecClassGenericPassword),
+                (kSecAttrService, service.0),
+                (kSecAttrAccount, account.0),
+                (kSecUseDataProtectionKeychain, kCFBooleanTrue),
+            ]
+        };
+        pairs.extend_from_slice(extra);
+        dictionary(&pairs)
+    }
+
+    fn failed(what: &str, name: &str, status: OSStatus) -> AppError {
+        let why = match status {
+            USER_CANCELED => "confirmation was canceled".to_string(),
+            AUTH_FAILED => "Touch ID or the password didn't match".to_string(),
+            MISSING_ENTITLEMENT => {
+                "this build isn't signed with a keychain-access-groups entitlement".to_string()
+            }
+            status => format!("Keychain error {status}"),
+        };
+        AppError::Io(format!("{what} credential {}{name}: {why}", super::PREFIX))
+    }
+
+    // Whether items can be read is only known once one is, after the prompt.
+    pub(super) fn available() -> Result<&'static str, AppError> {
+        Ok("the macOS Keychain")
+    }
+
+    pub(super) fn read(name: &str) -> Result<Option<Zeroizing<String>>, AppError> {
+        let (account, service) = (string(name), string("juno-keys"));
+        let prompt = string(&format!("read the Juno seed {name}"));
+        // SAFETY: the constants are immutable CFStrings exported by the framework.
+        let query = query(&account, &service, &unsafe {
+            [
+                (kSecReturnData, kCFBooleanTrue),
+                (kSecMatchLimit, kSecMatchLimitOne),
+                (kSecUseOperationPrompt, prompt.0),
+            ]
+        });
+        let mut data = ptr::null();
+        // SAFETY: `query` is a live dictionary; on success `data` is a CFData we own.
+        let status = unsafe { SecItemCopyMatching(query.0, &mut data) };
+        let data = Owned(data);
+        match status {
+            0 => {}
+            NOT_FOUND => return Ok(None),
+            status => return Err(failed("read", name, status)),
+        }
+        // SAFETY: `data` is a CFData, valid for its length until released. Its
+        // storage is immutable, so the copy in the string is the only one we can wipe.
+        let bytes = unsafe {
+            std::slice::from_raw_parts(CFDataGetBytePtr(data.0), CFDataGetLength(data.0) as usize)
+        };
+        std::str::from_utf8(bytes)
+            .map(|text| Some(Zeroizing::new(text.to_string())))
+            .map_err(|_| {
+                AppError::InvalidRequest(format!(
+                    "credential {}{name} doesn't hold text",
+                    super::PREFIX
+                ))
+            })
+    }
+
+    pub(super) fn write(
+        name: &str,
+        secret: &str,
+        force: bool,
+        _ttl: Option<std::time::Duration>,
+    ) -> Result<(), AppError> {
+        let (account, service) = (string(name), string("juno-keys"));
+        // SAFETY: the protection constant is an immutable CFString; the bytes are
+        // valid for their length and copied.
+        let (access, data) = unsafe {
+            (
+                Owned(SecAccessControlCreateWithFlags(
+                    ptr::null(),
+                    kSecAttrAccessibleWhenUnlockedThisDeviceOnly,
+                    USER_PRESENCE,
+                    ptr::null_mut(),
+                )),
+                Owned(CFDataCreate(
+                    ptr::null(),
+                    secret.as_ptr(),
+                    secret.len() as isize,
+                )),
+            )
+        };
+        if access.0.is_null() || data.0.is_null() {
+            return Err(AppError::Io("create Keychain access control".into()));
+        }
+        // SAFETY: the constants are immutable CFStrings exported by the framework.
+        let item = query(&account, &service, &unsafe {
+            [(kSecValueData, data.0), (kSecAttrAccessControl, access.0)]
+        });
+        // SAFETY: `item` is a live dictionary, and no result is asked for.
+        let mut status = unsafe { SecItemAdd(item.0, ptr::null_mut()) };
+        if status == DUPLICATE {
+            if !force {
+                return Err(super::exists(name));
+            }
+            // SAFETY: as above; deleting needs no confirmation.
+            status = unsafe {
+                match SecItemDelete(query(&account, &service, &[]).0) {
+                    0 => SecItemAdd(item.0, ptr::null_mut()),
+                    status => status,
+                }
+            };
+        }
+        match status {
+            0 => Ok(()),
+            status => Err(failed("store", name, status)),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::ffi::CString;
+    use std::io;
+    use std::ptr;
+    use std::time::Duration;
+
+    use zeroize::{Zeroize as _, Zeroizing};
+
+    use super::PREFIX;
+    use crate::AppError;
+
+    // From <linux/keyctl.h>.
+    const KEY_SPEC_SESSION_KEYRING: libc::c_long = -3;
+    const KEYCTL_GET_KEYRING_ID: libc::c_long = 0;
+    const KEYCTL_SET_TIMEOUT: libc::c_long = 15;
+    const KEYCTL_READ: libc::c_long = 11;
+    const KEYCTL_REVOKE: libc::c_long = 3;
+    const KEYCTL_INVALIDATE: libc::c_long = 21;
+
+    fn description(name: &str) -> Result<CString, AppError> {
+        CString::new(format!("{PREFIX}{name}"))
+            .map_err(|_| AppError::InvalidRequest("credential names can't hold NUL".into()))
+    }
+
+    fn failed(what: &str, name: &str) -> AppError {
+        AppError::Io(format!(
+            "{what} credential {PREFIX}{name} in the kernel keyring: {}",
+            io::Error::last_os_error()
+        ))
+    }
+
+    // The serial of the live key named for `name` in this session, if any.
+    fn find(name: &str) -> Result<Option<libc::c_long>, AppError> {
+        let description = description(name)?;
+        // SAFETY: both strings are NUL-terminated and outlive the call.
+        let id = unsafe {
+            libc::syscall(
+                libc::SYS_request_key,
+                c"user".as_ptr(),
+                description.as_ptr(),
+                ptr::null::<libc::c_char>(),
+                0,
+            )
+        };
+        if id >= 0 {
+            return Ok(Some(id));
+        }
+        match io::Error::last_os_error().raw_os_error() {
+            Some(libc::ENOKEY | libc::EKEYEXPIRED | libc::EKEYREVOKED) => Ok(None),
+            _ => Err(failed("find", name)),
+        }
+    }
+
+    // A process outside any login session (cron, a container) has no session keyring,
+    // and adding to `KEY_SPEC_SESSION_KEYRING` would make a private one that dies with
+    // the process. Looked up without creating, it resolves to the user's default
+    // session keyring instead, which `find` searches too.
+    fn session_keyring() -> io::Result<libc::c_long> {
+        // SAFETY: plain integer arguments; 0 asks not to create the keyring.
+        let keyring = unsafe {
+            libc::syscall(
+                libc::SYS_keyctl,
+                KEYCTL_GET_KEYRING_ID,
+                KEY_SPEC_SESSION_KEYRING,
+                0,
+            )
+        };
+        if keyring < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(keyring)
+    }
+
+    pub(super) fn available() -> Result<&'static str, AppError> {
+        session_keyring()
+            .map(|_| "the Linux kernel keyring")
+            .map_err(|e| AppError::Io(format!("the kernel keyring is unavailable: {e}")))
+    }
+
+    pub(super) fn read(name: &str) -> Result<Option<Zeroizing<String>>, AppError> {
+        let Some(id) = find(name)? else {
+            return Ok(None);
+        };
+        let mut payload = Zeroizing::new(Vec::<u8>::new());
+        // The first call sizes the buffer; a key updated in between is read again.
+        loop {
+            // SAFETY: the buffer is valid for writes of its length.
+            let len = unsafe {
+                libc::syscall(
+                    libc::SYS_keyctl,
+                    KEYCTL_READ,
+                    id,
+                    payload.as_mut_ptr(),
+                    payload.len(),
+                )
+            };
+            if len < 0 {
+                return Err(failed("read", name));
+            }
+            if len as usize <= payload.len() {
+                payload.truncate(len as usize);
+                break;
+            }
+            payload.zeroize();
+            payload.resize(len as usize, 0);
+        }
+        std::str::from_utf8(&payload)
+            .map(|text| Some(Zeroizing::new(text.to_string())))
+            .map_err(|_| {
+                AppError::InvalidRequest(format!("credential {PREFIX}{name} doesn't hold text"))
+            })
+    }
+
+    pub(super) fn write(
+        name: &str,
+        secret: &str,
+        force: bool,
+        ttl: Option<Duration>,
+    ) -> Result<(), AppError> {
+        // add_key replaces a key with the same name in place.
+        if !force && find(name)?.is_some() {
+            return Err(super::exists(name));
+        }
+        let description = description(name)?;
+        let keyring = session_keyring().map_err(|_| failed("find a keyring for", name))?;
+        // SAFETY: the strings are NUL-terminated and the payload is valid for its
+        // length; the kernel copies all three.
+        let id = unsafe {
+            libc::syscall(
+                libc::SYS_add_key,
+                c"user".as_ptr(),
+                description.as_ptr(),
+                secret.as_ptr(),
+                secret.len(),
+                keyring,
+            )
+        };
+        if id < 0 {
+            return Err(failed("store", name));
+        }
+        let ttl = ttl.unwrap_or(super::DEFAULT_TTL).as_secs();
+        // SAFETY: plain integer arguments.
+        if unsafe { libc::syscall(libc::SYS_keyctl, KEYCTL_SET_TIMEOUT, id, ttl) } < 0 {
+            // A seed that never expires is worse than none: take it out again.
+            discard(id);
+            return Err(failed("set the timeout of", name));
+        }
+        Ok(())
+    }
+
+    // Kernels before 3.5 can't invalidate a key, but a revoked key can't be read either.
+    fn discard(id: libc::c_long) {
+        // SAFETY: plain integer arguments.
+        unsafe {
+            if libc::syscall(libc::SYS_keyctl, KEYCTL_INVALIDATE, id) < 0 {
+                libc::syscall(libc::SYS_keyctl, KEYCTL_REVOKE, id);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn stored_seeds_read_back_and_can_be_discarded() {
+            if available().is_err() {
+                return;
+            }
+            let name = format!("test-{}", std::process::id());
+            write(&name, "c2VlZA==", true, Some(Duration::from_secs(60))).expect("store");
+            assert_eq!(
+                read(&name).expect("read").as_deref().map(String::as_str),
+                Some("c2VlZA==")
+            );
+            // A longer value makes the read grow its buffer.
+            write(&name, &"A".repeat(300), true, Some(Duration::from_secs(60))).expect("replace");
+            assert_eq!(read(&name).expect("read").map(|s| s.len()), Some(300));
+            assert!(write(&name, "x", false, None).is_err());
+
+            discard(find(&name).expect("find").expect("stored"));
+            assert!(read(&name).expect("read").is_none());
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod imp {
+    use zeroize::Zeroizing;
+
+    use crate::AppError;
+
+    fn unsupported() -> AppError {
+        AppError::InvalidRequest(
+            "credentials need the Windows Credential Manager, the macOS Keychain or the Linux kernel keyring"
+                .into(),
+        )
+    }
+
+    pub(super) fn available() -> Result<&'static str, AppError> {
+        Err(unsupported())
+    }
+
+    pub(super) fn read(_name: &str) -> Result<Option<Zeroizing<String>>, AppError> {
+        Err(unsupported())
+    }
+
+    pub(super) fn write(
+        _name: &str,
+        _secret: &str,
+        _force: bool,
+        _ttl: Option<std::time::Duration>,
+    ) -> Result<(), AppError> {
+        Err(unsupported())
+    }
+}
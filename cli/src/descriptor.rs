@@ -0,0 +1,159 @@
+//! `descriptor`: wallet descriptors, one line naming how an account is derived.
+//!
+//! ```text
+//! juno(mainnet,[zip32seedfp1...]m/32'/8133'/0',orchard,ufvk=<hex>)#<checksum>
+//! ```
+//!
+//! `derive` prints one for what it derived, and derives from one with `--descriptor`,
+//! failing if the seed or the UFVK doesn't match the fingerprints it names. `descriptor
+//! show` checks one without a seed and lists its parts.
+
+use clap::{Args, Subcommand};
+use juno_keys::{DerivedKeys, KeysError, Seed, Ufvk, WalletDescriptor};
+use serde::Serialize;
+
+use crate::{addressbook, audit, hooks, style, write_json_ok, AppError, Cli, EntryKind, Settings};
+
+#[derive(Subcommand)]
+pub(crate) enum DescriptorCmd {
+    /// Check a descriptor and list its parts, optionally against a UFVK
+    #[command(name = "show")]
+    Show(ShowArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct ShowArgs {
+    #[arg(help = "Wallet descriptor, as printed by `derive`")]
+    descriptor: String,
+
+    #[arg(
+        long,
+        help = "Also require this UFVK (or @label) to match the descriptor's network and fingerprint"
+    )]
+    ufvk: Option<String>,
+}
+
+pub(crate) fn parse(text: &str) -> Result<WalletDescriptor, AppError> {
+    text.parse().map_err(AppError::Keys)
+}
+
+/// Fails unless `keys`, derived from `seed` at the descriptor's path, are what the
+/// descriptor names.
+pub(crate) fn check_derived(
+    descriptor: &WalletDescriptor,
+    seed: &Seed,
+    keys: &DerivedKeys,
+) -> Result<(), AppError> {
+    if let Some(expected) = &descriptor.seed_fingerprint {
+        if juno_keys::seed_fingerprint(seed).map_err(AppError::Keys)? != *expected {
+            return Err(AppError::Keys(KeysError::Descriptor(
+                "the seed's fingerprint doesn't match",
+            )));
+        }
+    }
+    // Derivation only makes Orchard receivers.
+    if descriptor.receiver_types != keys.ufvk.typecodes() {
+        return Err(AppError::Keys(KeysError::Descriptor(
+            "only Orchard receivers can be derived",
+        )));
+    }
+    check_ufvk(descriptor, &keys.ufvk)
+}
+
+fn check_ufvk(descriptor: &WalletDescriptor, ufvk: &Ufvk) -> Result<(), AppError> {
+    if descriptor
+        .ufvk_fingerprint
+        .as_ref()
+        .is_some_and(|expected| *expected != ufvk.fingerprint())
+    {
+        return Err(AppError::Keys(KeysError::Descriptor(
+            "the UFVK's fingerprint doesn't match",
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn cmd_descriptor(
+    cli: &Cli,
+    settings: &Settings,
+    command: &DescriptorCmd,
+) -> Result<(), AppError> {
+    let DescriptorCmd::Show(args) = command;
+    let descriptor = parse(&args.descriptor)?;
+    if let Some(ufvk) = &args.ufvk {
+        let ufvk = addressbook::resolve(settings, ufvk, EntryKind::Ufvk)?;
+        let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+        // Zcash-encoded UFVKs of the same key share its fingerprint.
+        if ufvk.network().or(ufvk.zcash_network()) != Some(descriptor.network) {
+            return Err(AppError::Keys(KeysError::Descriptor(
+                "the UFVK is for another network",
+            )));
+        }
+        check_ufvk(&descriptor, &ufvk)?;
+        audit::note_ufvk(ufvk.as_str());
+    }
+
+    #[derive(Serialize)]
+    struct ReceiverOut<'a> {
+        typecode: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<&'a str>,
+    }
+    #[derive(Serialize)]
+    struct ShowOut<'a> {
+        descriptor: String,
+        network: &'static str,
+        path: String,
+        coin_type: u32,
+        account: u32,
+        receiver_types: Vec<ReceiverOut<'a>>,
+        seed_fingerprint: Option<&'a str>,
+        ufvk_fingerprint: Option<&'a str>,
+        ufvk_checked: bool,
+    }
+    // Parsing guarantees the account prefix.
+    let (coin_type, account) = descriptor.path.account_prefix().unwrap_or_default();
+    let data = ShowOut {
+        descriptor: descriptor.to_string(),
+        network: descriptor.network.as_str(),
+        path: descriptor.path.to_string(),
+        coin_type,
+        account,
+        receiver_types: descriptor
+            .receiver_types
+            .iter()
+            .map(|&typecode| ReceiverOut {
+                typecode,
+                name: hooks::typecode_name(settings, typecode),
+            })
+            .collect(),
+        seed_fingerprint: descriptor.seed_fingerprint.as_deref(),
+        ufvk_fingerprint: descriptor.ufvk_fingerprint.as_deref(),
+        ufvk_checked: args.ufvk.is_some(),
+    };
+
+    if cli.json {
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+    let receivers = data
+        .receiver_types
+        .iter()
+        .map(|r| match r.name {
+            Some(name) => name.to_string(),
+            None => r.typecode.to_string(),
+        })
+        .collect::<Vec<_>>();
+    style::fields(&[
+        ("descriptor", &data.descriptor),
+        ("network", data.network),
+        ("path", &data.path),
+        ("receivers", &receivers.join(", ")),
+        ("seed fp", data.seed_fingerprint.unwrap_or("-")),
+        ("ufvk fp", data.ufvk_fingerprint.unwrap_or("-")),
+    ]);
+    if data.ufvk_checked {
+        println!("ok: the UFVK matches");
+    }
+    Ok(())
+}
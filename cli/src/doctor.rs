@@ -0,0 +1,393 @@
+//! `doctor`: checks the machine before a ceremony rather than halfway through one.
+//!
+//! Each check reports `ok`, `info`, `warn` or `fail` with a line saying what to do:
+//! the OS random number generator, the locale (a non-UTF-8 terminal can mangle a
+//! passphrase typed with accents), the config and keystore permissions, the clock
+//! (audit events and backup names carry timestamps), and which optional hardware and
+//! OS stores are present. Only a `fail` makes the command fail.
+
+use std::io::{self, IsTerminal as _};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::RngCore as _;
+use serde::Serialize;
+
+use crate::style::{self, Paint, Stream};
+use crate::{
+    credential, default_config_path, load_settings, outpath, write_json_ok, AppError, Cli, Settings,
+};
+
+// 2026-01-01T00:00:00Z; no clock running this release can be earlier.
+const CLOCK_FLOOR: u64 = 1_767_225_600;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Info,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    check: &'static str,
+    status: Status,
+    message: String,
+}
+
+fn check(check: &'static str, status: Status, message: impl Into<String>) -> Check {
+    Check {
+        check,
+        status,
+        message: message.into(),
+    }
+}
+
+pub(crate) fn cmd_doctor(cli: &Cli) -> Result<(), AppError> {
+    let mut checks = vec![rng(), locale(|name| std::env::var(name).ok())];
+    checks.extend(terminal());
+    let settings = load_settings(&cli.config_source());
+    checks.extend(config(cli, &settings));
+    checks.push(clock());
+    let wanted = cli.seed_credential.is_some()
+        || settings.is_ok_and(|settings| settings.seed_credential.is_some());
+    checks.extend(stores(wanted));
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if cli.json {
+        if failed == 0 {
+            #[derive(Serialize)]
+            struct DoctorOut {
+                checks: Vec<Check>,
+            }
+            write_json_ok(cli, &DoctorOut { checks })?;
+            return Ok(());
+        }
+    } else {
+        for c in &checks {
+            let paint = match c.status {
+                Status::Ok => Paint::Ok,
+                Status::Info => Paint::Label,
+                Status::Warn => Paint::Warn,
+                Status::Fail => Paint::Fail,
+            };
+            let status = format!("{:<5}", format!("{:?}", c.status).to_lowercase());
+            let status = style::paint(Stream::Stdout, paint, &status);
+            println!("{status} {:<10} {}", c.check, c.message);
+        }
+    }
+    if failed == 0 {
+        return Ok(());
+    }
+    let mut summary = format!(
+        "doctor found {failed} problem{}",
+        if failed == 1 { "" } else { "s" }
+    );
+    if cli.json {
+        let lines = checks
+            .iter()
+            .filter(|c| c.status == Status::Fail)
+            .map(|c| format!("{}: {}", c.check, c.message))
+            .collect::<Vec<_>>();
+        summary = format!("{summary}: {}", lines.join("; "));
+    }
+    Err(AppError::Config(summary))
+}
+
+fn rng() -> Check {
+    let mut a = [0u8; 32];
+    let mut b = [0u8; 32];
+    let drawn = rand::rngs::OsRng
+        .try_fill_bytes(&mut a)
+        .and_then(|()| rand::rngs::OsRng.try_fill_bytes(&mut b));
+    match drawn {
+        Err(e) => check(
+            "rng",
+            Status::Fail,
+            format!("the OS random number generator failed ({e}); don't generate seeds here"),
+        ),
+        Ok(()) if a == b || a == [0; 32] => check(
+            "rng",
+            Status::Fail,
+            "the OS random number generator repeats itself; don't generate seeds here",
+        ),
+        Ok(()) => check("rng", Status::Ok, "the OS random number generator works"),
+    }
+}
+
+// The locale as the C library would pick it: the first of these that is set.
+fn locale(var: impl Fn(&str) -> Option<String>) -> Check {
+    if cfg!(windows) {
+        return check("locale", Status::Info, "not checked on Windows");
+    }
+    let Some((name, value)) = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|name| var(name).filter(|v| !v.is_empty()).map(|v| (name, v)))
+    else {
+        return check(
+            "locale",
+            Status::Warn,
+            "no locale is set, so non-ASCII passphrases may be mangled; set LANG=C.UTF-8",
+        );
+    };
+    let lower = value.to_ascii_lowercase();
+    if lower.contains("utf-8") || lower.contains("utf8") {
+        check("locale", Status::Ok, format!("{name}={value}"))
+    } else {
+        check(
+            "locale",
+            Status::Warn,
+            format!("{name}={value} isn't UTF-8, so non-ASCII passphrases may be mangled; set {name}=C.UTF-8"),
+        )
+    }
+}
+
+fn terminal() -> Option<Check> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    match std::env::var("TERM") {
+        Ok(term) if term == "dumb" => Some(check(
+            "terminal",
+            Status::Warn,
+            "TERM=dumb: --show-for can't clear secrets from this terminal",
+        )),
+        _ => Some(check("terminal", Status::Ok, "stdout is a terminal")),
+    }
+}
+
+fn config(cli: &Cli, settings: &Result<Settings, AppError>) -> Vec<Check> {
+    // A missing --config FILE is reported by loading it, below.
+    let mut checks = match cli.config.clone().or_else(default_config_path) {
+        Some(path) if path.is_file() => permissions("config", &path, false),
+        Some(path) if cli.config.is_none() => vec![check(
+            "config",
+            Status::Info,
+            format!("no config at {}; defaults apply", path.display()),
+        )],
+        _ => Vec::new(),
+    };
+    let settings = match settings {
+        Ok(settings) => settings,
+        Err(e @ AppError::Config(_)) => {
+            checks.push(check(
+                "config",
+                Status::Fail,
+                format!("{}; `lint --config` lists every problem", e.message()),
+            ));
+            return checks;
+        }
+        Err(e) => {
+            checks.push(check("config", Status::Fail, e.message()));
+            return checks;
+        }
+    };
+    if let Some(keystore) = &settings.keystore {
+        if keystore.is_file() {
+            checks.extend(permissions("keystore", keystore, true));
+        } else {
+            checks.push(check(
+                "keystore",
+                Status::Fail,
+                format!(
+                    "{} doesn't exist; fix `keystore` in the config",
+                    keystore.display()
+                ),
+            ));
+        }
+    }
+    checks
+}
+
+// Secret files must be private to their owner; other files only safe from edits by
+// others, since whoever can edit the config can point `keystore` elsewhere.
+#[cfg(unix)]
+fn permissions(name: &'static str, path: &Path, secret: bool) -> Vec<Check> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let mut checks = Vec::new();
+    let mode = match std::fs::metadata(path) {
+        Ok(meta) => meta.permissions().mode(),
+        Err(e) => {
+            return vec![check(
+                name,
+                Status::Fail,
+                format!("read {}: {e}", path.display()),
+            )]
+        }
+    };
+    checks.push(mode_check(name, path, mode, secret));
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    if let Some(mode) = dir
+        .and_then(|dir| std::fs::metadata(dir).ok())
+        .map(|meta| meta.permissions().mode())
+    {
+        if mode & 0o022 != 0 && mode & 0o1000 == 0 {
+            checks.push(check(
+                name,
+                Status::Warn,
+                format!(
+                    "its directory is writable by others, who could replace it; chmod go-w {}",
+                    dir.unwrap_or(path).display()
+                ),
+            ));
+        }
+    }
+    checks
+}
+
+#[cfg(not(unix))]
+fn permissions(name: &'static str, path: &Path, _secret: bool) -> Vec<Check> {
+    vec![check(
+        name,
+        Status::Ok,
+        format!("{} exists (permissions not checked here)", path.display()),
+    )]
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn mode_check(name: &'static str, path: &Path, mode: u32, secret: bool) -> Check {
+    let (mask, what, fix) = if secret {
+        (0o077, "readable by others", "chmod 600")
+    } else {
+        (0o022, "writable by others", "chmod go-w")
+    };
+    if mode & mask == 0 {
+        check(
+            name,
+            Status::Ok,
+            format!("{} is not {what}", path.display()),
+        )
+    } else {
+        check(
+            name,
+            if secret { Status::Fail } else { Status::Warn },
+            format!(
+                "{} is {what} (mode {:o}); {fix} {}",
+                path.display(),
+                mode & 0o777,
+                path.display()
+            ),
+        )
+    }
+}
+
+fn clock() -> Check {
+    let now = SystemTime::now();
+    let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    if since_epoch < Duration::from_secs(CLOCK_FLOOR) {
+        return check(
+            "clock",
+            Status::Fail,
+            "the clock is before 2026, so audit timestamps and backup names will be wrong; set the date",
+        );
+    }
+    // An offline machine's clock drifts; a binary from the future shows it is behind.
+    let built = std::env::current_exe()
+        .and_then(std::fs::metadata)
+        .and_then(|meta| meta.modified());
+    if let Ok(built) = built {
+        if built
+            .duration_since(now)
+            .is_ok_and(|ahead| ahead > Duration::from_secs(24 * 3600))
+        {
+            return check(
+                "clock",
+                Status::Warn,
+                "the clock is behind this binary's file time; set the date",
+            );
+        }
+    }
+    check(
+        "clock",
+        Status::Ok,
+        format!("now {}", outpath::timestamp(since_epoch.as_secs())),
+    )
+}
+
+// `wanted`: a seed credential is configured, so the store must work.
+fn stores(wanted: bool) -> Vec<Check> {
+    let mut checks = Vec::new();
+    checks.push(match credential::available() {
+        Ok(store) => check("keyring", Status::Ok, format!("{store} is available")),
+        Err(e) if wanted => check(
+            "keyring",
+            Status::Fail,
+            format!("{}; seed_credential can't be read here", e.message()),
+        ),
+        Err(e) => check("keyring", Status::Info, e.message()),
+    });
+    let present = |paths: &[&str]| paths.iter().any(|p| Path::new(p).exists());
+    if cfg!(target_os = "linux") {
+        checks.push(if present(&["/dev/tpmrm0", "/dev/tpm0"]) {
+            check(
+                "tpm",
+                Status::Info,
+                "a TPM is present (not used by juno-keys)",
+            )
+        } else {
+            check("tpm", Status::Info, "no TPM device")
+        });
+        checks.push(if present(&["/run/pcscd/pcscd.comm"]) {
+            check(
+                "smartcard",
+                Status::Info,
+                "pcscd is running (smartcards are not used by juno-keys)",
+            )
+        } else {
+            check("smartcard", Status::Info, "no PC/SC daemon")
+        });
+    } else {
+        checks.push(check("tpm", Status::Info, "not checked on this platform"));
+        checks.push(check(
+            "smartcard",
+            Status::Info,
+            "not checked on this platform",
+        ));
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_and_modes_are_judged() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        if !cfg!(windows) {
+            assert_eq!(locale(env(&[])).status, Status::Warn);
+            assert_eq!(locale(env(&[("LANG", "en_US.UTF-8")])).status, Status::Ok);
+            // LC_ALL wins over LANG, as it does in the C library.
+            let latin = locale(env(&[("LC_ALL", "de_DE.ISO-8859-1"), ("LANG", "C.UTF-8")]));
+            assert_eq!(latin.status, Status::Warn);
+            assert!(latin.message.starts_with("LC_ALL=de_DE.ISO-8859-1"));
+        }
+
+        let path = Path::new("hot.seed");
+        assert_eq!(
+            mode_check("keystore", path, 0o100600, true).status,
+            Status::Ok
+        );
+        let open = mode_check("keystore", path, 0o100644, true);
+        assert_eq!(open.status, Status::Fail);
+        assert!(open.message.ends_with("(mode 644); chmod 600 hot.seed"));
+        assert_eq!(
+            mode_check("config", path, 0o100644, false).status,
+            Status::Ok
+        );
+        assert_eq!(
+            mode_check("config", path, 0o100664, false).status,
+            Status::Warn
+        );
+    }
+}
@@ -0,0 +1,702 @@
+//! `frost`: split an Orchard account's spend authority t-of-n, with a trusted dealer
+//! or a three-part distributed key generation, and sign with it in two rounds, on top
+//! of `juno_keys::frost`.
+//!
+//! Every package is a JSON file with hex byte strings. Key packages, round-2
+//! packages, the round-1 secret and signing nonces are written with mode 0600;
+//! round-1 packages, the public key package, signing commitments and signature
+//! shares are public.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+use juno_keys::frost::{self, dkg, sign, Identifier, KeyPackage, PublicKeyPackage};
+use juno_keys::Network;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize as _, Zeroizing};
+
+use crate::{
+    network_params, read_message, resolve_network, write_json_ok, write_secret_file, AppError, Cli,
+    NetworkArg, Settings,
+};
+
+#[derive(Subcommand)]
+pub(crate) enum FrostCmd {
+    /// Sample a spend authorizing key on this machine and split it into shares
+    #[command(name = "dealer")]
+    Dealer(DealerArgs),
+    /// Distributed key generation: no machine ever holds the whole key
+    #[command(name = "dkg")]
+    Dkg {
+        #[command(subcommand)]
+        command: DkgCmd,
+    },
+    /// Threshold signing: commit, sign, then aggregate the shares
+    #[command(name = "sign")]
+    Sign {
+        #[command(subcommand)]
+        command: FrostSignCmd,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum DkgCmd {
+    /// Start as one participant; broadcast the printed round-1 package to everyone
+    #[command(name = "part1")]
+    Part1(Part1Args),
+    /// Check everyone's round-1 packages and write a private round-2 package per peer
+    #[command(name = "part2")]
+    Part2(Part2Args),
+    /// Check the round-2 packages sent to you and write your key package
+    #[command(name = "part3")]
+    Part3(Part3Args),
+}
+
+#[derive(Subcommand)]
+pub(crate) enum FrostSignCmd {
+    /// Commit to fresh nonces; send the printed commitments to the other signers
+    #[command(name = "round1")]
+    Round1(SignRound1Args),
+    /// Sign with everyone's commitments; send the printed share to the aggregator
+    #[command(name = "round2")]
+    Round2(SignRound2Args),
+    /// Combine the signers' shares into a spend authorization signature
+    #[command(name = "aggregate")]
+    Aggregate(AggregateArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct DealerArgs {
+    #[arg(long, help = "Shares needed to sign (t)")]
+    min_signers: u16,
+
+    #[arg(long, help = "Shares to create (n)")]
+    max_signers: u16,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network for the group UFVK (default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        help = "Directory for key-package-<i>.json and public-key-package.json"
+    )]
+    out_dir: PathBuf,
+
+    #[arg(long, help = "Overwrite existing files")]
+    force: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct Part1Args {
+    #[arg(long, help = "This participant's identifier, 1..=max-signers")]
+    identifier: Identifier,
+
+    #[arg(long, help = "Shares needed to sign (t)")]
+    min_signers: u16,
+
+    #[arg(long, help = "Participants (n)")]
+    max_signers: u16,
+
+    #[arg(long, help = "Where to keep this participant's round-1 secret")]
+    secret_out: PathBuf,
+
+    #[arg(long, help = "Overwrite existing files")]
+    force: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct Part2Args {
+    #[arg(long, help = "Round-1 secret from part1")]
+    secret: PathBuf,
+
+    #[arg(
+        long,
+        num_args = 1..,
+        required = true,
+        help = "Every other participant's round-1 package"
+    )]
+    round1: Vec<PathBuf>,
+
+    #[arg(long, help = "Directory for round2-<from>-to-<to>.json")]
+    out_dir: PathBuf,
+
+    #[arg(long, help = "Overwrite existing files")]
+    force: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct Part3Args {
+    #[arg(long, help = "Round-1 secret from part1")]
+    secret: PathBuf,
+
+    #[arg(
+        long,
+        num_args = 1..,
+        required = true,
+        help = "Every other participant's round-1 package"
+    )]
+    round1: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        num_args = 1..,
+        required = true,
+        help = "The round-2 packages addressed to you"
+    )]
+    round2: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network for the group UFVK (default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        help = "Directory for key-package-<i>.json and public-key-package.json"
+    )]
+    out_dir: PathBuf,
+
+    #[arg(long, help = "Overwrite existing files")]
+    force: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct SignRound1Args {
+    #[arg(long, help = "This signer's key package")]
+    key_package: PathBuf,
+
+    #[arg(long, help = "Where to keep the nonces until round 2")]
+    nonces_out: PathBuf,
+
+    #[arg(long, help = "Overwrite existing files")]
+    force: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct SignRound2Args {
+    #[arg(long, help = "This signer's key package")]
+    key_package: PathBuf,
+
+    #[arg(long, help = "Nonces from round 1; deleted once used")]
+    nonces: PathBuf,
+
+    #[command(flatten)]
+    signed: SignedData,
+}
+
+#[derive(Args)]
+pub(crate) struct AggregateArgs {
+    #[arg(long, help = "The group's public key package")]
+    public_key_package: PathBuf,
+
+    #[arg(
+        long,
+        num_args = 1..,
+        required = true,
+        help = "Every signer's signature share"
+    )]
+    shares: Vec<PathBuf>,
+
+    #[command(flatten)]
+    signed: SignedData,
+}
+
+// The signing package every signer and the aggregator rebuild from the same inputs.
+#[derive(Args)]
+#[group(skip)]
+#[command(group(clap::ArgGroup::new("data").required(true).args(["message", "message_file", "sighash"])))]
+pub(crate) struct SignedData {
+    #[arg(
+        long,
+        num_args = 1..,
+        required = true,
+        help = "Every signer's round-1 commitments, including your own"
+    )]
+    commitments: Vec<PathBuf>,
+
+    #[arg(long, help = "Message to sign as with `sign message`, as UTF-8 text")]
+    message: Option<String>,
+
+    #[arg(long, help = "Sign the exact bytes of a file as with `sign message`")]
+    message_file: Option<PathBuf>,
+
+    #[arg(long, help = "Transaction sighash to authorize, as hex")]
+    sighash: Option<String>,
+
+    #[arg(
+        long,
+        requires = "sighash",
+        help = "The spend's alpha as 32 bytes of hex (default: zero)"
+    )]
+    randomizer: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyPackageFile {
+    identifier: Identifier,
+    min_signers: u16,
+    signing_share: String,
+    verifying_share: String,
+    full_viewing_key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PublicKeyPackageFile {
+    min_signers: u16,
+    verifying_key: String,
+    verifying_shares: std::collections::BTreeMap<Identifier, String>,
+    full_viewing_key: String,
+    ufvk: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Round1SecretFile {
+    round1_secret: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Round1File {
+    identifier: Identifier,
+    commitment: Vec<String>,
+    proof: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Round2File {
+    sender: Identifier,
+    recipient: Identifier,
+    signing_share: String,
+    viewing_contribution: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoncesFile {
+    nonces: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CommitmentsFile {
+    identifier: Identifier,
+    hiding: String,
+    binding: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ShareFile {
+    identifier: Identifier,
+    share: String,
+}
+
+#[derive(Serialize)]
+struct SignatureOut {
+    signature: String,
+    verifying_key: String,
+    randomizer: String,
+}
+
+impl Drop for NoncesFile {
+    fn drop(&mut self) {
+        self.nonces.zeroize();
+    }
+}
+
+impl Drop for KeyPackageFile {
+    fn drop(&mut self) {
+        self.signing_share.zeroize();
+    }
+}
+
+impl Drop for Round1SecretFile {
+    fn drop(&mut self) {
+        self.round1_secret.zeroize();
+    }
+}
+
+impl Drop for Round2File {
+    fn drop(&mut self) {
+        self.signing_share.zeroize();
+        self.viewing_contribution.zeroize();
+    }
+}
+
+#[derive(Serialize)]
+struct GroupOut {
+    ufvk: String,
+    ufvk_fingerprint: String,
+    verifying_key: String,
+    files: Vec<String>,
+}
+
+pub(crate) fn cmd_frost(
+    cli: &Cli,
+    settings: &Settings,
+    command: &FrostCmd,
+) -> Result<(), AppError> {
+    match command {
+        FrostCmd::Dealer(args) => {
+            let net = resolve_network(settings, args.network)?;
+            let (shares, public) =
+                frost::trusted_dealer(&mut rand::rngs::OsRng, args.min_signers, args.max_signers)
+                    .map_err(AppError::Keys)?;
+            let files = write_packages(cli, net, &shares, &public, &args.out_dir, args.force)?;
+            print_group(cli, net, &public, files)
+        }
+        FrostCmd::Dkg {
+            command: DkgCmd::Part1(args),
+        } => {
+            let (secret, package) = dkg::part1(
+                &mut rand::rngs::OsRng,
+                args.identifier,
+                args.min_signers,
+                args.max_signers,
+            )
+            .map_err(AppError::Keys)?;
+            let file = Round1SecretFile {
+                round1_secret: hex::encode(secret.to_bytes().as_slice()),
+            };
+            write_secret_json(&args.secret_out, &file, args.force)?;
+            let out = Round1File {
+                identifier: package.identifier,
+                commitment: package.commitment.iter().map(hex::encode).collect(),
+                proof: hex::encode(package.proof),
+            };
+            print_json(cli, &out)
+        }
+        FrostCmd::Dkg {
+            command: DkgCmd::Part2(args),
+        } => {
+            let secret = read_secret(&args.secret)?;
+            let round1 = read_round1(&args.round1)?;
+            let packages = dkg::part2(&secret, &round1).map_err(AppError::Keys)?;
+            let mut files = Vec::new();
+            for package in packages {
+                let path = args.out_dir.join(format!(
+                    "round2-{}-to-{}.json",
+                    package.sender, package.recipient
+                ));
+                let file = Round2File {
+                    sender: package.sender,
+                    recipient: package.recipient,
+                    signing_share: hex::encode(package.signing_share.as_ref()),
+                    viewing_contribution: hex::encode(package.viewing_contribution.as_ref()),
+                };
+                write_secret_json(&path, &file, args.force)?;
+                files.push(path.display().to_string());
+            }
+            print_json(cli, &serde_json::json!({ "files": files }))
+        }
+        FrostCmd::Dkg {
+            command: DkgCmd::Part3(args),
+        } => {
+            let net = resolve_network(settings, args.network)?;
+            let secret = read_secret(&args.secret)?;
+            let round1 = read_round1(&args.round1)?;
+            let round2 = args
+                .round2
+                .iter()
+                .map(|path| {
+                    let file: Round2File = read_json(path)?;
+                    Ok(dkg::Round2Package {
+                        sender: file.sender,
+                        recipient: file.recipient,
+                        signing_share: Zeroizing::new(bytes32(&file.signing_share, path)?),
+                        viewing_contribution: Zeroizing::new(bytes32(
+                            &file.viewing_contribution,
+                            path,
+                        )?),
+                    })
+                })
+                .collect::<Result<Vec<_>, AppError>>()?;
+            let (share, public) = dkg::part3(&secret, &round1, &round2).map_err(AppError::Keys)?;
+            let files = write_packages(cli, net, &[share], &public, &args.out_dir, args.force)?;
+            print_group(cli, net, &public, files)
+        }
+        FrostCmd::Sign {
+            command: FrostSignCmd::Round1(args),
+        } => {
+            let key = read_key_package(&args.key_package)?;
+            let (nonces, commitments) = sign::round1(&mut rand::rngs::OsRng, &key);
+            let file = NoncesFile {
+                nonces: hex::encode(nonces.to_bytes().as_slice()),
+            };
+            write_secret_json(&args.nonces_out, &file, args.force)?;
+            let out = CommitmentsFile {
+                identifier: commitments.identifier,
+                hiding: hex::encode(commitments.hiding),
+                binding: hex::encode(commitments.binding),
+            };
+            print_json(cli, &out)
+        }
+        FrostCmd::Sign {
+            command: FrostSignCmd::Round2(args),
+        } => {
+            let key = read_key_package(&args.key_package)?;
+            let package = signing_package(&args.signed)?;
+            let file: NoncesFile = read_json(&args.nonces)?;
+            let bytes = Zeroizing::new(hex::decode(&file.nonces).map_err(|_| {
+                AppError::InvalidRequest(format!("{}: nonces is not hex", args.nonces.display()))
+            })?);
+            let nonces = sign::SigningNonces::from_bytes(&bytes).map_err(AppError::Keys)?;
+            let share = sign::round2(&package, nonces, &key).map_err(AppError::Keys)?;
+            // Reusing nonces for a second package would reveal the signing share.
+            fs::remove_file(&args.nonces)
+                .map_err(|e| AppError::Io(format!("remove {}: {e}", args.nonces.display())))?;
+            let out = ShareFile {
+                identifier: share.identifier,
+                share: hex::encode(share.share),
+            };
+            print_json(cli, &out)
+        }
+        FrostCmd::Sign {
+            command: FrostSignCmd::Aggregate(args),
+        } => {
+            let public = read_public_package(&args.public_key_package)?;
+            let package = signing_package(&args.signed)?;
+            let shares = args
+                .shares
+                .iter()
+                .map(|path| {
+                    let file: ShareFile = read_json(path)?;
+                    Ok(sign::SignatureShare {
+                        identifier: file.identifier,
+                        share: bytes32(&file.share, path)?,
+                    })
+                })
+                .collect::<Result<Vec<_>, AppError>>()?;
+            let signature = sign::aggregate(&package, &shares, &public).map_err(AppError::Keys)?;
+            let out = SignatureOut {
+                signature: hex::encode(signature),
+                verifying_key: hex::encode(public.verifying_key()),
+                randomizer: hex::encode(package.randomizer),
+            };
+            if cli.json {
+                return write_json_ok(cli, &out);
+            }
+            println!("{}", out.signature);
+            Ok(())
+        }
+    }
+}
+
+fn write_packages(
+    cli: &Cli,
+    net: Network,
+    shares: &[KeyPackage],
+    public: &PublicKeyPackage,
+    out_dir: &Path,
+    force: bool,
+) -> Result<Vec<String>, AppError> {
+    let mut files = Vec::new();
+    for share in shares {
+        let path = out_dir.join(format!("key-package-{}.json", share.identifier));
+        let file = KeyPackageFile {
+            identifier: share.identifier,
+            min_signers: share.min_signers,
+            signing_share: hex::encode(share.signing_share.as_ref()),
+            verifying_share: hex::encode(share.verifying_share),
+            full_viewing_key: hex::encode(share.full_viewing_key),
+        };
+        write_secret_json(&path, &file, force)?;
+        files.push(path.display().to_string());
+    }
+
+    let path = out_dir.join("public-key-package.json");
+    let ufvk = public
+        .ufvk(network_params(cli, net).as_ref())
+        .map_err(AppError::Keys)?;
+    let file = PublicKeyPackageFile {
+        min_signers: public.min_signers,
+        verifying_key: hex::encode(public.verifying_key()),
+        verifying_shares: public
+            .verifying_shares
+            .iter()
+            .map(|(id, share)| (*id, hex::encode(share)))
+            .collect(),
+        full_viewing_key: hex::encode(public.full_viewing_key),
+        ufvk: ufvk.to_string(),
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    if !force && path.exists() {
+        return Err(AppError::Io(format!("{} exists", path.display())));
+    }
+    fs::write(&path, json + "\n").map_err(|e| AppError::Io(format!("write file: {e}")))?;
+    files.push(path.display().to_string());
+    Ok(files)
+}
+
+fn print_group(
+    cli: &Cli,
+    net: Network,
+    public: &PublicKeyPackage,
+    files: Vec<String>,
+) -> Result<(), AppError> {
+    let ufvk = public
+        .ufvk(network_params(cli, net).as_ref())
+        .map_err(AppError::Keys)?;
+    let out = GroupOut {
+        ufvk: ufvk.to_string(),
+        ufvk_fingerprint: ufvk.fingerprint(),
+        verifying_key: hex::encode(public.verifying_key()),
+        files,
+    };
+    if cli.json {
+        return write_json_ok(cli, &out);
+    }
+    println!("{}", out.ufvk);
+    Ok(())
+}
+
+fn print_json<T: Serialize>(cli: &Cli, data: &T) -> Result<(), AppError> {
+    if cli.json {
+        return write_json_ok(cli, data);
+    }
+    let pretty = serde_json::to_string_pretty(data)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    println!("{pretty}");
+    Ok(())
+}
+
+fn write_secret_json<T: Serialize>(path: &Path, data: &T, force: bool) -> Result<(), AppError> {
+    let json = Zeroizing::new(
+        serde_json::to_string_pretty(data)
+            .map_err(|e| AppError::Io(format!("json encode: {e}")))?
+            + "\n",
+    );
+    write_secret_file(path, &json, force)
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, AppError> {
+    let raw = Zeroizing::new(
+        fs::read_to_string(path)
+            .map_err(|e| AppError::Io(format!("read {}: {e}", path.display())))?,
+    );
+    serde_json::from_str(&raw)
+        .map_err(|e| AppError::InvalidRequest(format!("{}: {e}", path.display())))
+}
+
+fn bytes32(s: &str, path: &Path) -> Result<[u8; 32], AppError> {
+    hex::decode(s)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| {
+            AppError::InvalidRequest(format!("{}: expected 32 bytes of hex", path.display()))
+        })
+}
+
+fn read_key_package(path: &Path) -> Result<KeyPackage, AppError> {
+    let file: KeyPackageFile = read_json(path)?;
+    let full_viewing_key = hex::decode(&file.full_viewing_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            AppError::InvalidRequest(format!(
+                "{}: full_viewing_key must be 96 bytes of hex",
+                path.display()
+            ))
+        })?;
+    Ok(KeyPackage {
+        identifier: file.identifier,
+        min_signers: file.min_signers,
+        signing_share: Zeroizing::new(bytes32(&file.signing_share, path)?),
+        verifying_share: bytes32(&file.verifying_share, path)?,
+        full_viewing_key,
+    })
+}
+
+fn read_public_package(path: &Path) -> Result<PublicKeyPackage, AppError> {
+    let file: PublicKeyPackageFile = read_json(path)?;
+    let full_viewing_key = hex::decode(&file.full_viewing_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            AppError::InvalidRequest(format!(
+                "{}: full_viewing_key must be 96 bytes of hex",
+                path.display()
+            ))
+        })?;
+    Ok(PublicKeyPackage {
+        min_signers: file.min_signers,
+        verifying_shares: file
+            .verifying_shares
+            .iter()
+            .map(|(id, share)| Ok((*id, bytes32(share, path)?)))
+            .collect::<Result<_, AppError>>()?,
+        full_viewing_key,
+    })
+}
+
+// Message signatures sign the `sign message` digest, so `verify message` checks them
+// against the group UFVK; sighashes are signed as given.
+fn signing_package(signed: &SignedData) -> Result<sign::SigningPackage, AppError> {
+    let message = match &signed.sighash {
+        Some(sighash) => hex::decode(sighash)
+            .map_err(|_| AppError::InvalidRequest("sighash must be hex".into()))?,
+        None => juno_keys::message_digest(&read_message(&signed.message, &signed.message_file)?)
+            .to_vec(),
+    };
+    let randomizer = match &signed.randomizer {
+        Some(randomizer) => bytes32(randomizer, Path::new("--randomizer"))?,
+        None => [0u8; 32],
+    };
+    let commitments = signed
+        .commitments
+        .iter()
+        .map(|path| {
+            let file: CommitmentsFile = read_json(path)?;
+            Ok(sign::SigningCommitments {
+                identifier: file.identifier,
+                hiding: bytes32(&file.hiding, path)?,
+                binding: bytes32(&file.binding, path)?,
+            })
+        })
+        .collect::<Result<_, AppError>>()?;
+    Ok(sign::SigningPackage {
+        commitments,
+        message,
+        randomizer,
+    })
+}
+
+fn read_secret(path: &Path) -> Result<dkg::Round1Secret, AppError> {
+    let file: Round1SecretFile = read_json(path)?;
+    let bytes = Zeroizing::new(hex::decode(&file.round1_secret).map_err(|_| {
+        AppError::InvalidRequest(format!("{}: round1_secret is not hex", path.display()))
+    })?);
+    dkg::Round1Secret::from_bytes(&bytes).map_err(AppError::Keys)
+}
+
+fn read_round1(paths: &[PathBuf]) -> Result<Vec<dkg::Round1Package>, AppError> {
+    paths
+        .iter()
+        .map(|path| {
+            let file: Round1File = read_json(path)?;
+            let proof = hex::decode(&file.proof)
+                .ok()
+                .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+                .ok_or_else(|| {
+                    AppError::InvalidRequest(format!(
+                        "{}: proof must be 64 bytes of hex",
+                        path.display()
+                    ))
+                })?;
+            Ok(dkg::Round1Package {
+                identifier: file.identifier,
+                commitment: file
+                    .commitment
+                    .iter()
+                    .map(|c| bytes32(c, path))
+                    .collect::<Result<_, _>>()?,
+                proof,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,152 @@
+//! Extension points for deployments that need more than the built-in outputs, so
+//! teams can integrate without forking the CLI:
+//!
+//! - an output sink: an external program that receives each `derive` and
+//!   `ufvk from-seed` result as one JSON line on stdin (`sink` or `--sink`);
+//! - named receiver types: private ZIP-316 typecodes (`[receiver_types]`) that
+//!   `zip316 decode` labels instead of warning about.
+//!
+//! Sinks only see viewing keys and addresses; seeds are never passed to them.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write as _};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use juno_keys::zip316::{self, is_known_typecode};
+use serde::Serialize;
+
+use crate::{json_version, AppError, Cli, Settings};
+
+#[derive(Serialize)]
+struct SinkEvent<'a, T: Serialize> {
+    version: &'static str,
+    command: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<&'a str>,
+    data: &'a T,
+}
+
+/// Hands `data` to the configured sink, if any, and waits for it to exit. A failing
+/// sink fails the command, so a zero exit status means the result was delivered.
+///
+/// A sink exists to push results elsewhere, so `--assert-offline` refuses to run one.
+pub(crate) fn emit<T: Serialize>(
+    cli: &Cli,
+    settings: &Settings,
+    command: &str,
+    data: &T,
+) -> Result<(), AppError> {
+    let Some(program) = cli.sink.as_deref().or(settings.sink.as_deref()) else {
+        return Ok(());
+    };
+    if settings.assert_offline {
+        return Err(AppError::NotOffline(
+            "output sinks are forbidden by --assert-offline".to_string(),
+        ));
+    }
+    let mut line = serde_json::to_vec(&SinkEvent {
+        version: json_version(),
+        command,
+        request_id: cli.request_id.as_deref(),
+        data,
+    })
+    .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    line.push(b'\n');
+    run_sink(program, &line)
+}
+
+fn run_sink(program: &Path, line: &[u8]) -> Result<(), AppError> {
+    let mut child = Command::new(program)
+        .stdin(Stdio::piped())
+        // Our stdout may be a JSON envelope; the sink's output goes to stderr instead.
+        .stdout(io::stderr())
+        .spawn()
+        .map_err(|e| AppError::Io(format!("start sink {}: {e}", program.display())))?;
+    // A sink that exits without reading its input is judged by its status alone.
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(line);
+    }
+    let status = child
+        .wait()
+        .map_err(|e| AppError::Io(format!("wait for sink {}: {e}", program.display())))?;
+    if !status.success() {
+        return Err(AppError::Io(format!(
+            "sink {} failed: {status}",
+            program.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Inverts the config's `[receiver_types]` (name → typecode) table, rejecting
+/// typecodes this crate already defines or that are named twice.
+pub(crate) fn receiver_types(
+    table: &BTreeMap<String, u64>,
+) -> Result<BTreeMap<u64, String>, AppError> {
+    let mut types = BTreeMap::new();
+    for (name, &typecode) in table {
+        if is_known_typecode(typecode) {
+            return Err(AppError::Config(format!(
+                "receiver_types.{name}: typecode {typecode} is built in"
+            )));
+        }
+        if let Some(other) = types.insert(typecode, name.clone()) {
+            return Err(AppError::Config(format!(
+                "receiver_types: typecode {typecode} is named both {other} and {name}"
+            )));
+        }
+    }
+    Ok(types)
+}
+
+/// The name of `typecode`: built-in ones first, then the configured receiver types.
+pub(crate) fn typecode_name(settings: &Settings, typecode: u64) -> Option<&str> {
+    match typecode {
+        zip316::TYPECODE_P2PKH => Some("p2pkh"),
+        zip316::TYPECODE_P2SH => Some("p2sh"),
+        zip316::TYPECODE_SAPLING => Some("sapling"),
+        zip316::TYPECODE_ORCHARD => Some("orchard"),
+        _ => settings.receiver_types.get(&typecode).map(String::as_str),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+
+    #[test]
+    fn validates_receiver_types() {
+        let table = BTreeMap::from([("inventory".to_string(), 0xf0)]);
+        let types = receiver_types(&table).expect("valid table");
+        assert_eq!(types.get(&0xf0).map(String::as_str), Some("inventory"));
+
+        let builtin = BTreeMap::from([("shadow".to_string(), zip316::TYPECODE_ORCHARD)]);
+        assert!(receiver_types(&builtin).is_err());
+        let twice = BTreeMap::from([("a".to_string(), 0xf0), ("b".to_string(), 0xf0)]);
+        assert!(receiver_types(&twice).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fails_with_the_sink() {
+        assert!(run_sink(Path::new("true"), b"{}\n").is_ok());
+        assert!(run_sink(Path::new("false"), b"{}\n").is_err());
+        assert!(run_sink(Path::new("/nonexistent/juno-keys-sink"), b"{}\n").is_err());
+    }
+
+    #[test]
+    fn offline_runs_refuse_the_sink() {
+        let cli = Cli::parse_from(["juno-keys", "--sink", "true", "selftest"]);
+        let settings = Settings {
+            assert_offline: true,
+            ..Settings::default()
+        };
+        assert!(matches!(
+            emit(&cli, &settings, "derive", &()),
+            Err(AppError::NotOffline(_))
+        ));
+    }
+}
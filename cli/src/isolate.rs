@@ -0,0 +1,185 @@
+//! `--isolate`: the part of `derive` and `ufvk from-seed` that touches the seed runs in
+//! a short-lived forked child.
+//!
+//! The child reads and decodes the seed, derives, and writes only the public result
+//! (and the fingerprints noted for the audit event) back over a pipe as JSON, then
+//! exits without unwinding into the parent's code. The seed is never read into the
+//! parent, so a bug in what it does with the result (sinks, output, audit) has no
+//! secret to leak. The child is forked before the seed is read and drops core dumps.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{audit, AppError, Cli};
+
+#[derive(Serialize, Deserialize)]
+enum Reply<T> {
+    Ok {
+        data: T,
+        noted: (Option<String>, Option<String>),
+    },
+    Err {
+        code: String,
+        message: String,
+    },
+}
+
+/// Runs `derive` in a child when `--isolate` is set, and in this process otherwise.
+pub(crate) fn run<T, F>(cli: &Cli, derive: F) -> Result<T, AppError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, AppError>,
+{
+    if !cli.isolate {
+        return derive();
+    }
+    let reply: Reply<T> = serde_json::from_slice(&in_child(derive)?)
+        .map_err(|e| AppError::Io(format!("isolated derivation sent a bad reply: {e}")))?;
+    match reply {
+        Reply::Ok { data, noted } => {
+            audit::restore(noted);
+            Ok(data)
+        }
+        Reply::Err { code, message } => Err(AppError::Isolated(
+            juno_keys::ErrorCode::ALL
+                .into_iter()
+                .find(|c| c.as_str() == code)
+                .unwrap_or(juno_keys::ErrorCode::IoError),
+            message,
+        )),
+    }
+}
+
+#[cfg(unix)]
+fn reply<T: Serialize, F: FnOnce() -> Result<T, AppError>>(derive: F) -> Vec<u8> {
+    let reply = match derive() {
+        Ok(data) => Reply::Ok {
+            data,
+            noted: audit::noted(),
+        },
+        Err(e) => Reply::Err {
+            code: e.code().to_string(),
+            message: e.message(),
+        },
+    };
+    serde_json::to_vec(&reply).unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn in_child<T: Serialize, F: FnOnce() -> Result<T, AppError>>(
+    derive: F,
+) -> Result<Vec<u8>, AppError> {
+    use std::fs::File;
+    use std::io::{Read as _, Write as _};
+    use std::os::fd::FromRawFd as _;
+
+    let failed = |what: &str| {
+        AppError::Io(format!(
+            "isolated derivation: {what}: {}",
+            std::io::Error::last_os_error()
+        ))
+    };
+    // Anything still buffered would otherwise be printed twice.
+    let _ = std::io::stdout().flush();
+    let mut fds = [0; 2];
+    // SAFETY: `fds` has room for the two descriptors pipe(2) writes.
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(failed("pipe"));
+    }
+    // SAFETY: derivations run on the main thread of a command with no other threads
+    // (`serve`, whose handlers are threaded, refuses `--isolate`), so the child's copy
+    // of the process is consistent; the child leaves only through `_exit`.
+    let pid = unsafe { libc::fork() };
+    if pid == 0 {
+        // SAFETY: the child owns its copies of both descriptors; setrlimit only reads
+        // the struct; `_exit` ends the process without running the parent's code.
+        unsafe {
+            libc::close(fds[0]);
+            let no_core = libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            };
+            libc::setrlimit(libc::RLIMIT_CORE, &no_core);
+            let mut pipe = File::from_raw_fd(fds[1]);
+            let status =
+                match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| reply(derive))) {
+                    Ok(bytes) if !bytes.is_empty() && pipe.write_all(&bytes).is_ok() => 0,
+                    _ => 1,
+                };
+            drop(pipe);
+            libc::_exit(status)
+        }
+    }
+    // SAFETY: the write end belongs to the child; the read end is ours to own.
+    let mut pipe = unsafe {
+        libc::close(fds[1]);
+        File::from_raw_fd(fds[0])
+    };
+    if pid < 0 {
+        return Err(failed("fork"));
+    }
+    let mut bytes = Vec::new();
+    let read = pipe.read_to_end(&mut bytes);
+    let mut status = 0;
+    // SAFETY: `pid` is our child and `status` is a valid out-pointer.
+    if unsafe { libc::waitpid(pid, &mut status, 0) } != pid {
+        return Err(failed("waitpid"));
+    }
+    read.map_err(|e| AppError::Io(format!("isolated derivation: read: {e}")))?;
+    if !libc::WIFEXITED(status) || libc::WEXITSTATUS(status) != 0 {
+        return Err(AppError::Io(format!(
+            "isolated derivation failed (wait status {status})"
+        )));
+    }
+    Ok(bytes)
+}
+
+#[cfg(not(unix))]
+fn in_child<T: Serialize, F: FnOnce() -> Result<T, AppError>>(
+    _derive: F,
+) -> Result<Vec<u8>, AppError> {
+    Err(AppError::InvalidRequest(
+        "--isolate needs fork(2), which this platform doesn't have".into(),
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+
+    const ALONE: &str = "JUNO_KEYS_TEST_FORK_ALONE";
+
+    // fork(2) is only sound with no other threads running, so the assertions run in a
+    // fresh copy of the test binary that runs this test alone.
+    #[test]
+    fn only_the_result_crosses_back() {
+        if std::env::var_os(ALONE).is_none() {
+            let output = std::process::Command::new(std::env::current_exe().unwrap())
+                .args([
+                    "--exact",
+                    "isolate::tests::only_the_result_crosses_back",
+                    "--test-threads=1",
+                ])
+                .env(ALONE, "1")
+                .output()
+                .unwrap();
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert!(output.status.success(), "{stdout}");
+            assert!(stdout.contains("1 passed"), "{stdout}");
+            return;
+        }
+        let cli = Cli::parse_from(["juno-keys", "--isolate", "selftest"]);
+        let parent = std::process::id();
+        let child = run(&cli, || Ok(std::process::id())).unwrap();
+        assert_ne!(child, parent);
+
+        let err = run::<u32, _>(&cli, || Err(AppError::MainnetInterlock)).unwrap_err();
+        assert_eq!(err.code(), "mainnet_interlock");
+        assert!(err.message().contains("--mainnet-ok"));
+
+        let panicked = run::<u32, _>(&cli, || panic!("in the child")).unwrap_err();
+        assert_eq!(panicked.code(), "io_error");
+    }
+}
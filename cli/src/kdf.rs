@@ -0,0 +1,178 @@
+//! Argon2id settings for the passphrase-encrypted formats (`seed encrypt`, the address
+//! book), and `bench kdf`, which picks them for this machine.
+//!
+//! Each format records the settings it was sealed with, so changing them only
+//! affects what is sealed next. `kdf_memory_mib` and `kdf_passes` in the config
+//! override each format's defaults.
+
+use std::time::{Duration, Instant};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+use crate::{write_json_ok, AppError, BenchKdfArgs, Cli, Settings};
+
+// `seed encrypt` stores memory as a power of two up to 4 GiB and at most 16 passes.
+pub(crate) const MAX_MEMORY_MIB: u32 = juno_keys::SeedKdf::MAX_MEMORY_KIB / 1024;
+pub(crate) const MAX_PASSES: u32 = juno_keys::SeedKdf::MAX_PASSES;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Kdf {
+    pub(crate) memory_kib: u32,
+    pub(crate) passes: u32,
+}
+
+impl Kdf {
+    /// `default`, with whatever the config sets in its place.
+    pub(crate) fn configured(settings: &Settings, default: Kdf) -> Kdf {
+        Kdf {
+            memory_kib: settings
+                .kdf_memory_mib
+                .map_or(default.memory_kib, |mib| mib * 1024),
+            passes: settings.kdf_passes.unwrap_or(default.passes),
+        }
+    }
+
+    /// A 32-byte key from `passphrase` and `salt`, one lane.
+    pub(crate) fn key(
+        &self,
+        passphrase: &str,
+        salt: &[u8],
+    ) -> Result<Zeroizing<[u8; 32]>, AppError> {
+        let params = Params::new(self.memory_kib, self.passes, 1, Some(32))
+            .map_err(|e| AppError::InvalidRequest(format!("KDF parameters: {e}")))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+            .map_err(|e| AppError::InvalidRequest(format!("KDF: {e}")))?;
+        Ok(key)
+    }
+}
+
+/// Checks config values; memory must be a power of two so `seed encrypt` can record it.
+pub(crate) fn check_config(memory_mib: Option<u32>, passes: Option<u32>) -> Result<(), AppError> {
+    if memory_mib.is_some_and(|m| !m.is_power_of_two() || m > MAX_MEMORY_MIB) {
+        return Err(AppError::Config(format!(
+            "kdf_memory_mib must be a power of two from 1 to {MAX_MEMORY_MIB}"
+        )));
+    }
+    if passes.is_some_and(|p| p == 0 || p > MAX_PASSES) {
+        return Err(AppError::Config(format!(
+            "kdf_passes must be from 1 to {MAX_PASSES}"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Measurement {
+    memory_mib: u32,
+    passes: u32,
+    ms: u64,
+}
+
+#[derive(Serialize)]
+struct Recommendation {
+    memory_mib: u32,
+    passes: u32,
+    estimated_ms: u64,
+}
+
+pub(crate) fn cmd_bench_kdf(cli: &Cli, args: &BenchKdfArgs) -> Result<(), AppError> {
+    if args.target_ms == 0 {
+        return Err(AppError::InvalidRequest(
+            "--target-ms must be at least 1".into(),
+        ));
+    }
+    if !args.max_memory_mib.is_power_of_two() || args.max_memory_mib > MAX_MEMORY_MIB {
+        return Err(AppError::InvalidRequest(format!(
+            "--max-memory-mib must be a power of two up to {MAX_MEMORY_MIB}"
+        )));
+    }
+    let target = Duration::from_millis(args.target_ms);
+
+    // One pass at each size, doubling until a single pass overshoots the target.
+    let mut measured = Vec::new();
+    let mut mib = 8.min(args.max_memory_mib);
+    while mib <= args.max_memory_mib {
+        let kdf = Kdf {
+            memory_kib: mib * 1024,
+            passes: 1,
+        };
+        let start = Instant::now();
+        kdf.key("juno-keys bench", b"juno-keys bench salt")?;
+        let elapsed = start.elapsed();
+        if !cli.json {
+            eprintln!("{mib:>5} MiB, 1 pass: {} ms", elapsed.as_millis());
+        }
+        measured.push((mib, elapsed));
+        if elapsed > target {
+            break;
+        }
+        mib *= 2;
+    }
+    let (memory_mib, passes, estimate) = recommend(&measured, target);
+
+    let data = serde_json::json!({
+        "target_ms": args.target_ms,
+        "measurements": measured
+            .iter()
+            .map(|&(memory_mib, elapsed)| Measurement {
+                memory_mib,
+                passes: 1,
+                ms: elapsed.as_millis() as u64,
+            })
+            .collect::<Vec<_>>(),
+        "recommended": Recommendation {
+            memory_mib,
+            passes,
+            estimated_ms: estimate.as_millis() as u64,
+        },
+    });
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    println!(
+        "# about {} ms on this machine (target {} ms)",
+        estimate.as_millis(),
+        args.target_ms
+    );
+    println!("kdf_memory_mib = {memory_mib}");
+    println!("kdf_passes = {passes}");
+    Ok(())
+}
+
+// Memory is what makes guessing expensive on GPUs, so take the most memory that
+// leaves room for three passes (RFC 9106's second choice is 64 MiB, 3 passes), then
+// as many passes as fit the target.
+fn recommend(measured: &[(u32, Duration)], target: Duration) -> (u32, u32, Duration) {
+    let &(mib, one_pass) = measured
+        .iter()
+        .rev()
+        .find(|(_, elapsed)| *elapsed * 3 <= target)
+        .or(measured.first())
+        .unwrap_or(&(8, Duration::ZERO));
+    let passes = if one_pass.is_zero() {
+        1
+    } else {
+        (target.as_secs_f64() / one_pass.as_secs_f64()).floor() as u32
+    }
+    .clamp(1, MAX_PASSES);
+    (mib, passes, one_pass * passes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recommendations_favour_memory_then_passes() {
+        let ms = Duration::from_millis;
+        let measured = [(8, ms(20)), (16, ms(40)), (32, ms(90)), (64, ms(400))];
+        assert_eq!(recommend(&measured, ms(300)), (32, 3, ms(270)));
+        assert_eq!(recommend(&measured, ms(1000)), (32, 11, ms(990)));
+        // Too slow even for one pass at the smallest size: one pass anyway.
+        assert_eq!(recommend(&measured[..1], ms(10)), (8, 1, ms(20)));
+    }
+}
@@ -0,0 +1,388 @@
+//! `lint`: checks `serve` job files and the config before anything runs, so a typo in
+//! a batch of a thousand requests fails up front rather than at request 800.
+//!
+//! A job file holds `serve` requests, either as a JSON array or one per line, in the
+//! JSON-RPC form or the `--stdin-json` form (`{"method": ..., "params": ...}`). Each is
+//! checked as its method would check it (unknown fields, networks, accounts past
+//! 2^31 - 1, diversifier indices past 2^88, UFVKs and seeds that don't decode) without
+//! deriving anything. The config is checked for unknown keys, which loading it
+//! otherwise ignores, and every profile is resolved as `--profile` would resolve it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    default_config_path, resolve_settings, serve, write_json_ok, AppError, Cli, Config,
+    ProfileConfig, PROFILE_KEYS,
+};
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("input").required(true).multiple(true).args(["jobs", "config"])))]
+pub(crate) struct LintArgs {
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "serve requests to check (a JSON array, or one request per line)"
+    )]
+    pub(crate) jobs: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        help = "Config file to check (without FILE: the one --config or the default names)"
+    )]
+    pub(crate) config: Option<Option<PathBuf>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Issue {
+    file: String,
+    // `line 3` or `job 3` in a job file, a key path such as `profiles.cold.network` in
+    // the config; absent when the whole file is at fault.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    at: Option<String>,
+    message: String,
+}
+
+pub(crate) fn cmd_lint(cli: &Cli, args: &LintArgs) -> Result<(), AppError> {
+    let mut issues = Vec::new();
+    let mut jobs = None;
+    if let Some(path) = &args.jobs {
+        let raw = fs::read_to_string(path)
+            .map_err(|e| AppError::Io(format!("read {}: {e}", path.display())))?;
+        jobs = Some(lint_jobs(path, &raw, &mut issues));
+    }
+    let mut config = None;
+    if let Some(path) = &args.config {
+        let path = path
+            .clone()
+            .or_else(|| cli.config.clone())
+            .or_else(default_config_path)
+            .ok_or_else(|| AppError::Config("no config path (set --config FILE)".into()))?;
+        let raw = fs::read_to_string(&path)
+            .map_err(|e| AppError::Io(format!("read {}: {e}", path.display())))?;
+        lint_config(&path, &raw, &mut issues);
+        config = Some(path.display().to_string());
+    }
+
+    if cli.json {
+        if issues.is_empty() {
+            #[derive(Serialize)]
+            struct LintOut {
+                #[serde(skip_serializing_if = "Option::is_none")]
+                jobs: Option<usize>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                config: Option<String>,
+                issues: Vec<Issue>,
+            }
+            write_json_ok(
+                cli,
+                &LintOut {
+                    jobs,
+                    config,
+                    issues,
+                },
+            )?;
+            return Ok(());
+        }
+    } else {
+        for issue in &issues {
+            println!("{}", issue.line());
+        }
+        if issues.is_empty() {
+            match (jobs, &config) {
+                (Some(n), Some(path)) => println!("ok: {n} jobs, config {path}"),
+                (Some(n), None) => println!("ok: {n} jobs"),
+                (None, Some(path)) => println!("ok: config {path}"),
+                (None, None) => {}
+            }
+        }
+    }
+
+    if issues.is_empty() {
+        return Ok(());
+    }
+    let mut summary = format!(
+        "lint found {} issue{}",
+        issues.len(),
+        if issues.len() == 1 { "" } else { "s" }
+    );
+    // Text output has already listed them.
+    if cli.json {
+        let lines = issues.iter().map(Issue::line).collect::<Vec<_>>();
+        summary = format!("{summary}: {}", lines.join("; "));
+    }
+    // A bad config is a config error even when jobs were checked alongside it.
+    if args.config.is_some() && issues.iter().any(|i| Some(&i.file) == config.as_ref()) {
+        Err(AppError::Config(summary))
+    } else {
+        Err(AppError::InvalidRequest(summary))
+    }
+}
+
+impl Issue {
+    fn line(&self) -> String {
+        match &self.at {
+            Some(at) => format!("{}: {at}: {}", self.file, self.message),
+            None => format!("{}: {}", self.file, self.message),
+        }
+    }
+}
+
+// Checks each job in `raw` and returns how many there were.
+fn lint_jobs(path: &Path, raw: &str, issues: &mut Vec<Issue>) -> usize {
+    let file = path.display().to_string();
+    let mut report = |at: String, message: String| {
+        issues.push(Issue {
+            file: file.clone(),
+            at: Some(at),
+            message,
+        })
+    };
+    // A file that opens with `[` is one array; anything else is a request per line.
+    let jobs = if raw.trim_start().starts_with('[') {
+        match serde_json::from_str::<Vec<Value>>(raw) {
+            Ok(jobs) => jobs
+                .into_iter()
+                .enumerate()
+                .map(|(i, job)| (format!("job {}", i + 1), Ok(job)))
+                .collect(),
+            Err(e) => {
+                report(
+                    format!("line {}", e.line()),
+                    format!("not a JSON array: {e}"),
+                );
+                return 0;
+            }
+        }
+    } else {
+        raw.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (format!("line {}", i + 1), serde_json::from_str(line)))
+            .collect::<Vec<_>>()
+    };
+    let count = jobs.len();
+    for (at, job) in jobs {
+        let checked = job
+            .map_err(|e| format!("not JSON: {e}"))
+            .and_then(check_job);
+        if let Err(message) = checked {
+            report(at, message);
+        }
+    }
+    count
+}
+
+fn check_job(job: Value) -> Result<(), String> {
+    let Value::Object(mut job) = job else {
+        return Err("a request must be a JSON object".into());
+    };
+    // The JSON-RPC form names its version; the `--stdin-json` form has only these two.
+    let rpc = job.contains_key("jsonrpc");
+    let known: &[&str] = if rpc {
+        &["jsonrpc", "method", "params", "id"]
+    } else {
+        &["method", "params"]
+    };
+    if let Some(key) = job.keys().find(|key| !known.contains(&key.as_str())) {
+        return Err(format!("unknown field `{key}`"));
+    }
+    if rpc && job["jsonrpc"] != "2.0" {
+        return Err("jsonrpc must be \"2.0\"".into());
+    }
+    let method = match job.remove("method") {
+        Some(Value::String(method)) => method,
+        Some(_) => return Err("method must be a string".into()),
+        None => return Err("missing method".into()),
+    };
+    let params = job.remove("params").unwrap_or(Value::Null);
+    serve::check_params(&method, params).map_err(|e| format!("{method}: {e}"))
+}
+
+/// Keys that loading `table` as a config would ignore, as key paths with the problem;
+/// `--strict` rejects a config that has any.
+pub(crate) fn unknown_config_keys(table: &toml::Table) -> Vec<(String, &'static str)> {
+    let mut found = Vec::new();
+    let unknown = |key: &str| !PROFILE_KEYS.contains(&key);
+    for key in table.keys() {
+        if unknown(key)
+            && !["default_profile", "profiles", "receiver_types"].contains(&key.as_str())
+        {
+            found.push((key.clone(), "unknown key"));
+        }
+    }
+    if let Some(toml::Value::Table(profiles)) = table.get("profiles") {
+        for (name, profile) in profiles {
+            let Some(profile) = profile.as_table() else {
+                continue;
+            };
+            for key in profile.keys().filter(|key| unknown(key)) {
+                let message = if key == "receiver_types" {
+                    "only allowed at the top level"
+                } else {
+                    "unknown key"
+                };
+                found.push((format!("profiles.{name}.{key}"), message));
+            }
+        }
+    }
+    found
+}
+
+fn lint_config(path: &Path, raw: &str, issues: &mut Vec<Issue>) {
+    let file = path.display().to_string();
+    let mut report = |at: Option<String>, message: String| {
+        issues.push(Issue {
+            file: file.clone(),
+            at,
+            message,
+        })
+    };
+    let table = match raw.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(e) => return report(None, e.message().to_string()),
+    };
+    for (at, message) in unknown_config_keys(&table) {
+        report(Some(at), message.into());
+    }
+
+    // Wrong types, then what `--profile` would reject for each profile.
+    let cfg = match toml::from_str::<Config>(raw) {
+        Ok(cfg) => cfg,
+        Err(e) => return report(None, e.message().to_string()),
+    };
+    if let Some(name) = &cfg.default_profile {
+        if !cfg.profiles.contains_key(name) {
+            return report(
+                Some("default_profile".into()),
+                format!("no profile named {name}"),
+            );
+        }
+    }
+    let check = |profile: Option<&str>| {
+        resolve_settings(&cfg, profile, ProfileConfig::default(), false)
+            .err()
+            .map(|e| e.message())
+    };
+    if let Some(message) = check(None) {
+        return report(None, message);
+    }
+    for name in cfg.profiles.keys() {
+        if let Some(message) = check(Some(name)) {
+            report(Some(format!("profiles.{name}")), message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(issues: &[Issue]) -> Vec<String> {
+        issues.iter().map(Issue::line).collect()
+    }
+
+    #[test]
+    fn job_files_report_each_bad_request() {
+        let jobs = concat!(
+            r#"{"jsonrpc":"2.0","method":"ufvk.fromSeed","params":{"network":"testnet","account":1},"id":1}"#,
+            "\n\n",
+            r#"{"method":"address.derive","params":{"network":"mainet"}}"#,
+            "\n",
+            r#"{"method":"ufvk.fromSeed","params":{"network":"testnet","account":2147483648}}"#,
+            "\n",
+            r#"{"method":"address.derive","params":{"network":"testnet","diversifier_index":"0x10000000000000000000000"}}"#,
+            "\n",
+            r#"{"method":"seed.new","params":{"bytes":16},"id":3}"#,
+            "\n",
+            r#"{"method":"status","params":{"verbose":true}}"#,
+            "\n",
+        );
+        let mut issues = Vec::new();
+        assert_eq!(lint_jobs(Path::new("jobs.jsonl"), jobs, &mut issues), 6);
+        let found = messages(&issues);
+        assert_eq!(found.len(), 5, "{found:?}");
+        assert!(
+            found[0].starts_with("jobs.jsonl: line 3: address.derive: unknown variant `mainet`")
+        );
+        assert!(found[1].contains("line 4: ufvk.fromSeed: account 2147483648 is not below 2^31"));
+        assert!(found[2].contains("line 5: address.derive: diversifier_index"));
+        assert!(found[3].contains("line 6: unknown field `id`"));
+        assert!(found[4].contains("line 7: status: unknown field `verbose`"));
+
+        let mut issues = Vec::new();
+        let array = r#"[{"method":"validate","params":{"value":"x"}},{"method":"nope"}]"#;
+        assert_eq!(lint_jobs(Path::new("jobs.json"), array, &mut issues), 2);
+        assert_eq!(
+            messages(&issues),
+            ["jobs.json: job 2: nope: unknown method: nope"]
+        );
+    }
+
+    #[test]
+    fn configs_report_unknown_keys_and_bad_profiles() {
+        let raw = r#"
+            netwrok = "testnet"
+            default_profile = "cold"
+
+            [profiles.cold]
+            network = "testnet"
+            receiver_types = {}
+
+            [profiles.hot]
+            network = "mainet"
+            rate_limt = 5
+        "#;
+        let mut issues = Vec::new();
+        lint_config(Path::new("config.toml"), raw, &mut issues);
+        assert_eq!(
+            messages(&issues),
+            [
+                "config.toml: netwrok: unknown key",
+                "config.toml: profiles.cold.receiver_types: only allowed at the top level",
+                "config.toml: profiles.hot.rate_limt: unknown key",
+                "config.toml: profiles.hot: unknown network: mainet",
+            ]
+        );
+
+        let mut issues = Vec::new();
+        lint_config(
+            Path::new("c.toml"),
+            "default_profile = \"x\"\n",
+            &mut issues,
+        );
+        assert_eq!(
+            messages(&issues),
+            ["c.toml: default_profile: no profile named x"]
+        );
+    }
+
+    #[test]
+    fn strict_configs_reject_unknown_keys() {
+        let path =
+            std::env::temp_dir().join(format!("juno-keys-strict-{}.toml", std::process::id()));
+        let source = |strict| crate::ConfigSource {
+            config: Some(path.clone()),
+            profile: None,
+            assert_offline: false,
+            strict,
+        };
+        std::fs::write(&path, "netwrok = \"testnet\"\n").expect("write config");
+        assert!(crate::load_config(&source(false)).is_ok());
+        assert!(crate::load_config(&source(true)).is_err());
+
+        // The config can ask for strict mode itself.
+        std::fs::write(&path, "strict = true\nnetwrok = \"testnet\"\n").expect("write config");
+        let err = crate::load_config(&source(false))
+            .err()
+            .expect("unknown key");
+        assert!(err.message().contains("netwrok"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,5152 @@
+mod addressbook;
+mod armor;
+mod audit;
+mod backup;
+mod batch;
+mod conformance;
+mod credential;
+mod descriptor;
+mod doctor;
+mod frost;
+mod hooks;
+mod isolate;
+mod kdf;
+mod lint;
+mod networks;
+mod outpath;
+mod ownership;
+mod paper;
+mod prompt;
+mod ranges;
+mod raw_fvk;
+mod receivers;
+mod replay;
+mod reveal;
+mod rotation;
+mod sealed_seed;
+mod serve;
+mod shares;
+mod style;
+mod transcribe;
+mod usk;
+mod vanity;
+mod vectors;
+mod visual;
+mod zcashd;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, IsTerminal as _, Read as _, Write as _};
+use std::net::SocketAddr;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Args, CommandFactory as _, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use zeroize::Zeroizing;
+
+use juno_keys::{
+    zip321, AccountRange, DiversifierIndex, ErrorCode, ExposeSecret as _, HardeningPolicy,
+    KeysError, MnemonicLanguage, Network, NetworkParams, OrchardKeySet, Seed, Ufvk, UnifiedAddress,
+    WalletCompat, WalletDescriptor, ZcashNetwork, ZipPath,
+};
+
+use addressbook::{AddressbookCmd, EntryKind};
+use audit::AuditSink;
+use frost::{DkgCmd, FrostCmd, FrostSignCmd};
+use style::{Paint, Stream};
+use transcribe::TranscribeCmd;
+
+/// JSON envelope versions this build writes; `--json-version` picks one, v1 by default.
+///
+/// v2 always includes `request_id` (null when unset) and adds the numeric `exit` code
+/// next to the string code of an error.
+const JSON_VERSIONS: [&str; 2] = ["v1", "v2"];
+
+// The chosen version, as an index into `JSON_VERSIONS`.
+static JSON_VERSION: AtomicUsize = AtomicUsize::new(0);
+
+fn json_version() -> &'static str {
+    JSON_VERSIONS[JSON_VERSION.load(Relaxed)]
+}
+
+fn json_v2() -> bool {
+    JSON_VERSION.load(Relaxed) >= 1
+}
+
+// Applies `--json-version`; an unknown version fails in a v1 envelope.
+fn select_json_version(cli: &Cli) -> Result<(), AppError> {
+    let Some(requested) = &cli.json_version else {
+        return Ok(());
+    };
+    let index = JSON_VERSIONS
+        .iter()
+        .position(|v| v == requested)
+        .ok_or_else(|| AppError::JsonVersion(requested.clone()))?;
+    JSON_VERSION.store(index, Relaxed);
+    Ok(())
+}
+
+#[derive(Parser)]
+#[command(
+    name = "juno-keys",
+    about = "Seed + UFVK derivation for Juno Cash",
+    version
+)]
+struct Cli {
+    #[arg(long, help = "JSON output (stable)")]
+    json: bool,
+
+    #[arg(
+        long,
+        env = "JUNO_KEYS_JSON_VERSION",
+        value_name = "VERSION",
+        help = "JSON envelope version to write: v1 (default) or v2"
+    )]
+    json_version: Option<String>,
+
+    #[arg(
+        long,
+        env = "JUNO_KEYS_CONFIG",
+        help = "Config file (default: $XDG_CONFIG_HOME/juno-keys/config.toml)"
+    )]
+    config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "JUNO_KEYS_PROFILE",
+        help = "Config profile to use (default: default_profile from the config)"
+    )]
+    profile: Option<String>,
+
+    #[arg(long, help = "Allow printing mainnet secret material to a terminal")]
+    mainnet_ok: bool,
+
+    #[arg(
+        long,
+        help = "Reject unknown keys in the config, unknown members in serve requests and SLIP-44 coin types (default: the config's strict)"
+    )]
+    strict: bool,
+
+    #[arg(
+        long,
+        help = "Refuse to run unless every non-loopback network interface is down"
+    )]
+    assert_offline: bool,
+
+    #[arg(
+        long,
+        help = "Disable core dumps and fail unless every seed and spending key can be locked in memory"
+    )]
+    harden: bool,
+
+    #[arg(
+        long,
+        help = "Use Zcash HRPs (u/uview) and coin types (133/1) for cross-validation"
+    )]
+    zcash_compat: bool,
+
+    #[arg(
+        long,
+        value_name = "HRP",
+        requires = "coin_type",
+        conflicts_with_all = ["zcash_compat", "custom_network"],
+        help = "Custom network: derive and encode under this UA HRP (with --coin-type), e.g. for a devnet fork; --network still names the network it follows"
+    )]
+    ua_hrp: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "ua_hrp",
+        help = "Custom network: ZIP-32 coin type to derive under (with --ua-hrp)"
+    )]
+    coin_type: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        env = "JUNO_KEYS_NETWORK_FILE",
+        help = "TOML registry of named custom networks, for --custom-network"
+    )]
+    network_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with = "zcash_compat",
+        help = "Custom network: use network NAME from --network-file"
+    )]
+    custom_network: Option<String>,
+
+    #[arg(
+        long,
+        env = "JUNO_KEYS_REQUEST_ID",
+        value_name = "ID",
+        help = "Correlation ID echoed in JSON output (and by `serve` when a request has none)"
+    )]
+    request_id: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PROGRAM",
+        help = "Pipe derive and ufvk from-seed results to PROGRAM (default: the config's sink)"
+    )]
+    sink: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Record an audit event per command or request (default: the config's audit)"
+    )]
+    audit: Option<AuditSink>,
+
+    #[arg(
+        long,
+        help = "Read the seed and derive in a forked child; only the public result comes back (derive, ufvk from-seed)"
+    )]
+    isolate: bool,
+
+    #[arg(
+        long,
+        help = "Before --force replaces a secret file, keep the old one as <file>.<timestamp>.bak"
+    )]
+    backup: bool,
+
+    #[arg(
+        long,
+        help = "Print and write seeds, encrypted seeds, UFVKs and app keys as checksummed BEGIN/END blocks"
+    )]
+    armor: bool,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = reveal::duration_arg,
+        help = "Show a secret printed to a terminal for DURATION (30s, 2m), then clear it from the screen"
+    )]
+    show_for: Option<std::time::Duration>,
+
+    #[arg(
+        long,
+        help = "Never color text output (also when NO_COLOR is set); JSON and piped output are never colored"
+    )]
+    no_color: bool,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Read the seed from credential juno-keys:NAME (Windows Credential Manager, macOS Keychain or Linux kernel keyring) when no seed flag is given (default: the config's seed_credential)"
+    )]
+    seed_credential: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "seed_prompt",
+        help = "Read the seed (base64, armored or encrypted) from stdin, in place of the command's seed flags"
+    )]
+    seed_stdin: bool,
+
+    #[arg(
+        long,
+        help = "Ask for the seed (base64) at the terminal, without echo, in place of the command's seed flags"
+    )]
+    seed_prompt: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Read the passphrase of an encrypted --seed-file or keystore from FILE instead of asking at the terminal"
+    )]
+    seed_passphrase_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(visible_alias = "s")]
+    Seed {
+        #[command(subcommand)]
+        command: SeedCmd,
+    },
+    #[command(
+        visible_alias = "d",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
+    Derive(DeriveArgs),
+    #[command(visible_alias = "a", alias = "addr")]
+    Address {
+        #[command(subcommand)]
+        command: AddressCmd,
+    },
+    #[command(name = "ufvk", visible_alias = "u", alias = "fvk")]
+    Ufvk {
+        #[command(subcommand)]
+        command: UfvkCmd,
+    },
+    Network {
+        #[command(subcommand)]
+        command: NetworkCmd,
+    },
+    /// Incoming viewing keys (jivk1...): detect payments without seeing spends
+    #[command(name = "uivk", alias = "ivk")]
+    Uivk {
+        #[command(subcommand)]
+        command: UivkCmd,
+    },
+    /// Shortcut for `ufvk from-seed`
+    #[command(name = "from-seed")]
+    FromSeed(UfvkFromSeedArgs),
+    /// Shortcut for `address vanity`
+    #[command(name = "vanity")]
+    Vanity(AddressVanityArgs),
+    /// Unified spending keys (jsecret1...): one account's keys without the seed
+    Usk {
+        #[command(subcommand)]
+        command: usk::UskCmd,
+    },
+    /// Wallet descriptors: one line naming an account's network, path and fingerprints
+    #[command(alias = "desc")]
+    Descriptor {
+        #[command(subcommand)]
+        command: descriptor::DescriptorCmd,
+    },
+    Convert {
+        #[command(subcommand)]
+        command: ConvertCmd,
+    },
+    Sign {
+        #[command(subcommand)]
+        command: SignCmd,
+    },
+    Verify {
+        #[command(subcommand)]
+        command: VerifyCmd,
+    },
+    Prove {
+        #[command(subcommand)]
+        command: ProveCmd,
+    },
+    Export {
+        #[command(subcommand)]
+        command: ExportCmd,
+    },
+    /// Reconcile address lists against a UFVK
+    Audit {
+        #[command(subcommand)]
+        command: AuditCmd,
+    },
+    /// ZIP-321 payment request URIs (juno:...)
+    Uri {
+        #[command(subcommand)]
+        command: UriCmd,
+    },
+    /// Labels for addresses and UFVKs, usable as @label (encrypted file)
+    #[command(visible_alias = "ab", alias = "address-book")]
+    Addressbook {
+        #[command(subcommand)]
+        command: AddressbookCmd,
+    },
+    /// Randomart and emoji fingerprints of an address or UFVK, for comparing at a glance
+    #[command(visible_alias = "fp")]
+    Fingerprint(visual::FingerprintArgs),
+    /// Split an account's spend authority t-of-n (FROST key generation)
+    Frost {
+        #[command(subcommand)]
+        command: FrostCmd,
+    },
+    /// Seeds and UFVKs in checksummed groups for copying onto paper or metal
+    Transcribe {
+        #[command(subcommand)]
+        command: TranscribeCmd,
+    },
+    /// Confirm that a hand-made backup matches the secret
+    Backup {
+        #[command(subcommand)]
+        command: BackupCmd,
+    },
+    /// Measure this machine to choose settings
+    Bench {
+        #[command(subcommand)]
+        command: BenchCmd,
+    },
+    Fixtures {
+        #[command(subcommand)]
+        command: FixturesCmd,
+    },
+    /// Golden vectors on every network, for other implementations to test against
+    Vectors {
+        #[command(subcommand)]
+        command: vectors::VectorsCmd,
+    },
+    #[command(name = "zip316")]
+    Zip316 {
+        #[command(subcommand)]
+        command: Zip316Cmd,
+    },
+    Encoding {
+        #[command(subcommand)]
+        command: EncodingCmd,
+    },
+    Selftest,
+    /// Check serve job files and the config before anything runs
+    Lint(lint::LintArgs),
+    /// Check this machine (RNG, locale, permissions, clock, OS stores) before a ceremony
+    Doctor,
+    /// Answer JSON-RPC 2.0 requests, one per line, on stdio or a Unix socket.
+    Serve(ServeArgs),
+    #[command(name = "testvectors")]
+    Testvectors {
+        #[command(subcommand)]
+        command: TestvectorsCmd,
+    },
+}
+
+#[derive(Subcommand)]
+enum SeedCmd {
+    #[command(name = "new")]
+    New(SeedNewArgs),
+    /// Import a BIP-39 recovery phrase, optionally as another wallet derives it
+    #[command(name = "import")]
+    Import(SeedImportArgs),
+    /// Encrypt a seed under a passphrase as one printable string (jseedenc1...)
+    #[command(name = "encrypt")]
+    Encrypt(SeedEncryptArgs),
+    /// Recover a seed from `seed encrypt` output
+    #[command(name = "decrypt")]
+    Decrypt(SeedDecryptArgs),
+    /// Keep a seed in the Windows Credential Manager, macOS Keychain or Linux kernel keyring instead of a file
+    #[command(name = "store")]
+    Store(SeedStoreArgs),
+    /// Split a seed into M-of-N Shamir shares (jshare1...)
+    #[command(name = "split")]
+    Split(shares::SeedSplitArgs),
+    /// Rebuild a seed from `seed split` shares
+    #[command(name = "combine")]
+    Combine(shares::SeedCombineArgs),
+}
+
+#[derive(Args)]
+struct SeedNewArgs {
+    #[arg(
+        long,
+        default_value_t = 64,
+        help = "Seed size in bytes (ZIP32 allows 32..252)"
+    )]
+    bytes: usize,
+
+    #[arg(
+        long,
+        help = "Write seed (base64) to a file (mode 0600 on unix; placeholders like {fingerprint} and {date} are filled in)"
+    )]
+    out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+
+    #[arg(long, help = "Print seed to stdout (warning: avoid logs)")]
+    print: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network the seed is meant for (unset counts as mainnet for the interlock)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        conflicts_with = "bytes",
+        help = "Generate a BIP-39 recovery phrase instead of a base64 seed"
+    )]
+    mnemonic: bool,
+
+    #[arg(
+        long,
+        default_value_t = 24,
+        requires = "mnemonic",
+        help = "Words in the phrase: 12, 15, 18, 21 or 24"
+    )]
+    words: usize,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "english",
+        requires = "mnemonic",
+        help = "Wordlist of the phrase"
+    )]
+    language: MnemonicLanguageArg,
+
+    #[arg(
+        long,
+        requires = "out",
+        conflicts_with_all = ["print", "mnemonic"],
+        help = "Encrypt the seed file under a passphrase (jseedenc1...), asked for at the terminal"
+    )]
+    encrypt: bool,
+
+    #[arg(
+        long,
+        requires = "encrypt",
+        help = "Read the encryption passphrase from a file instead of asking"
+    )]
+    passphrase_file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct SeedImportArgs {
+    #[arg(
+        long,
+        required_unless_present = "zcashd_dump",
+        help = "Read the recovery phrase from a file"
+    )]
+    mnemonic_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["mnemonic_file", "passphrase_file", "wallet"],
+        help = "Read the seed from a zcashd z_exportwallet or dumpwallet file"
+    )]
+    zcashd_dump: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "zcashd_dump",
+        help = "Import the dump's pre-4.7 HDSeed rather than its recovery phrase"
+    )]
+    legacy_hdseed: bool,
+
+    #[arg(long, help = "Read the BIP-39 passphrase from a file")]
+    passphrase_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Derive as this wallet does (default: plain BIP-39 under Juno's coin types)"
+    )]
+    wallet: Option<WalletArg>,
+
+    #[arg(long, default_value_t = 0, help = "Account, numbered as in the wallet")]
+    account: u32,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        help = "Write the seed (base64) to a file (mode 0600 on unix; placeholders like {fingerprint} and {date} are filled in)"
+    )]
+    out: PathBuf,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+}
+
+#[derive(Args)]
+struct SeedStoreArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Store it as credential juno-keys:NAME"
+    )]
+    credential: String,
+
+    #[arg(long, help = "Replace the credential if it exists")]
+    force: bool,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = credential::ttl_arg,
+        help = "Linux: drop the keyring entry after DURATION (30s, 10m, 2h; default 15m)"
+    )]
+    ttl: Option<std::time::Duration>,
+}
+
+#[derive(Args)]
+struct SeedEncryptArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(long, help = "Read the encryption passphrase from a file")]
+    passphrase_file: PathBuf,
+
+    #[arg(
+        long,
+        help = "Also write the encrypted seed to a file (placeholders like {fingerprint} and {date} are filled in)"
+    )]
+    out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+}
+
+#[derive(Args)]
+struct SeedDecryptArgs {
+    #[arg(help = "Encrypted seed (jseedenc1...)")]
+    encrypted: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "encrypted",
+        help = "Read the encrypted seed from a file ('-' for stdin)"
+    )]
+    file: Option<PathBuf>,
+
+    #[arg(long, help = "Read the encryption passphrase from a file")]
+    passphrase_file: PathBuf,
+
+    #[arg(
+        long,
+        help = "Write the seed (base64) to a file (mode 0600 on unix; placeholders like {fingerprint} and {date} are filled in)"
+    )]
+    out: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Store the seed as credential juno-keys:NAME, as `seed store` does"
+    )]
+    credential: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        requires = "credential",
+        value_parser = credential::ttl_arg,
+        help = "Linux: drop the keyring entry after DURATION (default 15m)"
+    )]
+    ttl: Option<std::time::Duration>,
+
+    #[arg(long, help = "Overwrite --out or --credential if it exists")]
+    force: bool,
+
+    #[arg(long, help = "Print seed to stdout (warning: avoid logs)")]
+    print: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network the seed is meant for (unset counts as mainnet for the interlock)"
+    )]
+    network: Option<NetworkArg>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum WalletArg {
+    Zingo,
+    Ywallet,
+    Zcashd,
+}
+
+impl From<WalletArg> for WalletCompat {
+    fn from(v: WalletArg) -> Self {
+        match v {
+            WalletArg::Zingo => WalletCompat::Zingo,
+            WalletArg::Ywallet => WalletCompat::Ywallet,
+            WalletArg::Zcashd => WalletCompat::Zcashd,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum MnemonicLanguageArg {
+    English,
+    ChineseSimplified,
+    ChineseTraditional,
+    Czech,
+    French,
+    Italian,
+    Japanese,
+    Korean,
+    Portuguese,
+    Spanish,
+}
+
+impl From<MnemonicLanguageArg> for MnemonicLanguage {
+    fn from(v: MnemonicLanguageArg) -> Self {
+        match v {
+            MnemonicLanguageArg::English => MnemonicLanguage::English,
+            MnemonicLanguageArg::ChineseSimplified => MnemonicLanguage::SimplifiedChinese,
+            MnemonicLanguageArg::ChineseTraditional => MnemonicLanguage::TraditionalChinese,
+            MnemonicLanguageArg::Czech => MnemonicLanguage::Czech,
+            MnemonicLanguageArg::French => MnemonicLanguage::French,
+            MnemonicLanguageArg::Italian => MnemonicLanguage::Italian,
+            MnemonicLanguageArg::Japanese => MnemonicLanguage::Japanese,
+            MnemonicLanguageArg::Korean => MnemonicLanguage::Korean,
+            MnemonicLanguageArg::Portuguese => MnemonicLanguage::Portuguese,
+            MnemonicLanguageArg::Spanish => MnemonicLanguage::Spanish,
+        }
+    }
+}
+
+// Named after their `from-*` commands.
+#[allow(clippy::enum_variant_names)]
+#[derive(Subcommand)]
+enum UfvkCmd {
+    #[command(name = "from-seed")]
+    FromSeed(UfvkFromSeedArgs),
+    /// The UFVK of an account of a BIP-39 recovery phrase
+    #[command(name = "from-mnemonic")]
+    FromMnemonic(UfvkFromMnemonicArgs),
+    /// Check a UFVK and list its HRP, network, fingerprint and items
+    #[command(name = "inspect")]
+    Inspect(UfvkInspectArgs),
+    /// The UFVK of a unified spending key (jsecret1...), without the seed
+    #[command(name = "from-usk")]
+    FromUsk(usk::UskArgs),
+    /// Check that a UFVK came from the seed, and find its account
+    #[command(name = "verify")]
+    Verify(ownership::UfvkVerifyArgs),
+    /// Print a UFVK's Orchard full viewing key as raw hex (96 bytes)
+    #[command(name = "export-raw")]
+    ExportRaw(raw_fvk::UfvkExportRawArgs),
+    /// Wrap a raw Orchard full viewing key (hex) as a UFVK for a network
+    #[command(name = "from-raw")]
+    FromRaw(raw_fvk::UfvkFromRawArgs),
+}
+
+#[derive(Subcommand)]
+enum SignCmd {
+    /// Sign a message with the account's Orchard spend authorizing key (RedPallas)
+    #[command(name = "message")]
+    Message(SignMessageArgs),
+}
+
+#[derive(Subcommand)]
+enum VerifyCmd {
+    /// Check a `sign message` signature against a UFVK; needs no secrets
+    #[command(name = "message")]
+    Message(VerifyMessageArgs),
+}
+
+#[derive(Subcommand)]
+enum ProveCmd {
+    /// Sign a statement binding one of the account's addresses to a challenge
+    #[command(name = "address-ownership")]
+    AddressOwnership(ProveAddressOwnershipArgs),
+}
+
+#[derive(Subcommand)]
+enum UriCmd {
+    /// Build a payment request URI for one or more recipients
+    #[command(name = "build")]
+    Build(UriBuildArgs),
+    /// Decode and validate a payment request URI
+    #[command(name = "parse")]
+    Parse(UriParseArgs),
+    /// Build a payment request and show it as a QR code (terminal or SVG)
+    #[command(name = "qr")]
+    Qr(UriQrArgs),
+}
+
+#[derive(Args)]
+struct UriQrArgs {
+    #[command(flatten)]
+    request: UriBuildArgs,
+
+    #[arg(long, help = "Write the QR code as an SVG image instead of drawing it")]
+    svg: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --svg if it exists")]
+    force: bool,
+}
+
+#[derive(Args)]
+struct UriParseArgs {
+    #[arg(help = "Payment request URI (juno:...)")]
+    uri: String,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network every recipient must be on (default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+}
+
+#[derive(Args)]
+struct UriBuildArgs {
+    #[arg(
+        long,
+        required = true,
+        visible_alias = "to",
+        help = "Recipient address or @label; repeat for each recipient"
+    )]
+    address: Vec<String>,
+
+    #[arg(long, help = "Amount in coins, e.g. 1.5; one per recipient, in order")]
+    amount: Vec<String>,
+
+    #[arg(long, help = "Memo text; one per recipient, in order (\"\" for none)")]
+    memo: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Recipient name for the payer; one per recipient, in order"
+    )]
+    label: Vec<String>,
+
+    #[arg(long, help = "Payment description; one per recipient, in order")]
+    message: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum BackupCmd {
+    /// Re-enter the written-down phrase or transcription and compare it with the secret
+    #[command(name = "verify-transcription")]
+    VerifyTranscription(BackupVerifyTranscriptionArgs),
+}
+
+#[derive(Args)]
+struct BackupVerifyTranscriptionArgs {
+    #[arg(
+        long,
+        help = "Recovery phrase that was written down (otherwise, a `transcribe encode` copy of the seed)"
+    )]
+    mnemonic_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "mnemonic_file",
+        help = "Read the BIP-39 passphrase from a file"
+    )]
+    passphrase_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "mnemonic_file",
+        help = "Read seed base64 from a file"
+    )]
+    seed_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "mnemonic_file",
+        help = "Seed as base64 (warning: avoid logs)"
+    )]
+    seed_base64: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum BenchCmd {
+    /// Time Argon2id and recommend kdf_memory_mib / kdf_passes for a target unlock time
+    #[command(name = "kdf")]
+    Kdf(BenchKdfArgs),
+}
+
+#[derive(Args)]
+struct BenchKdfArgs {
+    #[arg(
+        long,
+        default_value_t = 1000,
+        help = "How long unlocking may take, in milliseconds"
+    )]
+    target_ms: u64,
+
+    #[arg(
+        long,
+        default_value_t = 1024,
+        help = "Most memory to try, in MiB (a power of two)"
+    )]
+    max_memory_mib: u32,
+}
+
+#[derive(Subcommand)]
+enum AuditCmd {
+    /// Check that every address in a CSV file derives from a UFVK
+    #[command(name = "addresses")]
+    Addresses(AuditAddressesArgs),
+    /// Re-derive a manifest of earlier derivations from the seed and report any drift
+    #[command(name = "replay")]
+    Replay(replay::ReplayArgs),
+}
+
+#[derive(Args)]
+struct AuditAddressesArgs {
+    #[arg(
+        long,
+        help = "UFVK the addresses should derive from (jview1... or @label)"
+    )]
+    ufvk: String,
+
+    #[arg(long, help = "CSV file with an address per row ('-' for stdin)")]
+    file: PathBuf,
+
+    #[arg(
+        long,
+        default_value = "0",
+        help = "Address column: a 0-based index, or a header name (the first row is then the header)"
+    )]
+    column: String,
+
+    #[arg(long, help = "Skip the first row when --column is an index")]
+    header: bool,
+
+    #[arg(
+        long,
+        help = "Fail unless every address derives from the UFVK exactly once"
+    )]
+    strict: bool,
+}
+
+#[derive(Subcommand)]
+enum ExportCmd {
+    /// Write a UFVK's viewing keys as a node/indexer wallet table (TOML or JSON)
+    #[command(name = "indexer-config")]
+    IndexerConfig(ExportIndexerConfigArgs),
+    /// Write a printable backup sheet (PDF) with the secret, fingerprints and QR codes
+    #[command(name = "paper")]
+    Paper(ExportPaperArgs),
+}
+
+#[derive(Args)]
+struct ExportPaperArgs {
+    #[arg(
+        long,
+        conflicts_with_all = ["seed_file", "seed_base64"],
+        help = "Back up this recovery phrase (default: the seed, as hex)"
+    )]
+    mnemonic_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "mnemonic_file",
+        help = "Read the BIP-39 passphrase from a file (it is not printed)"
+    )]
+    passphrase_file: Option<PathBuf>,
+
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp + coin_type; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Account whose path and address are printed"
+    )]
+    account: u32,
+
+    #[arg(
+        long,
+        help = "Title printed under the heading, e.g. \"Treasury cold wallet\""
+    )]
+    label: Option<String>,
+
+    #[arg(long, help = "Ceremony date (left blank to fill in by hand)")]
+    date: Option<String>,
+
+    #[arg(long, help = "Ceremony note, e.g. location or witnesses")]
+    ceremony: Option<String>,
+
+    #[arg(long, help = "PDF to write (mode 0600 on unix)")]
+    out: PathBuf,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+}
+
+#[derive(Args)]
+struct ExportIndexerConfigArgs {
+    #[arg(long, help = "UFVK to export (jview1... or @label)")]
+    ufvk: String,
+
+    #[arg(long, help = "Wallet ID the indexer files it under")]
+    wallet_id: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Height to start scanning from (the wallet's birthday)"
+    )]
+    birthday_height: u32,
+
+    #[arg(long, value_enum, default_value_t = ConfigFormat::Toml, help = "Output format")]
+    format: ConfigFormat,
+
+    #[arg(long, help = "Write to a file (mode 0600 on unix) instead of stdout")]
+    out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum ConvertCmd {
+    #[command(name = "from-zcash")]
+    FromZcash(ConvertFromZcashArgs),
+}
+
+#[derive(Args)]
+struct ConvertFromZcashArgs {
+    #[arg(help = "Zcash UA or UFVK (u1... / uview1...)")]
+    encoded: String,
+
+    #[arg(
+        long,
+        help = "Confirm re-labelling: the keys stay derived under Zcash's coin type"
+    )]
+    yes: bool,
+}
+
+#[derive(Subcommand)]
+enum FixturesCmd {
+    #[command(name = "generate")]
+    Generate(FixturesGenerateArgs),
+}
+
+#[derive(Args)]
+struct FixturesGenerateArgs {
+    #[arg(long, default_value_t = 1, help = "Number of deterministic seeds")]
+    seeds: u32,
+
+    #[arg(long, default_value_t = 1, help = "Accounts per seed")]
+    accounts: u32,
+
+    #[arg(long, default_value_t = 1, help = "Addresses per account")]
+    addresses: u32,
+
+    #[arg(long, help = "Write the bundle to a file instead of stdout")]
+    out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+}
+
+#[derive(Args)]
+struct Zip32VectorsArgs {
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp + coin_type; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(long, default_value_t = 2, help = "Number of deterministic seeds")]
+    seeds: u32,
+
+    #[arg(long, default_value_t = 2, help = "Accounts per seed")]
+    accounts: u32,
+
+    #[arg(long, default_value_t = 2, help = "Addresses per account")]
+    addresses: u32,
+
+    #[arg(long, value_enum, default_value_t = VectorFormat::Json, help = "Output format")]
+    format: VectorFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum VectorFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum TestvectorsCmd {
+    /// Emit encodings from our ZIP-316 encoder for every Juno UA/UFVK/UIVK HRP
+    #[command(name = "zip316")]
+    Zip316,
+    /// Emit seed -> spending key -> FVK -> address vectors from our ZIP-32 Orchard derivation
+    #[command(name = "zip32")]
+    Zip32(Zip32VectorsArgs),
+    /// Emit inputs and expected outputs for every public operation, for binding parity tests
+    #[command(name = "conformance")]
+    Conformance,
+}
+
+#[derive(Subcommand)]
+enum Zip316Cmd {
+    #[command(name = "decode")]
+    Decode(Zip316DecodeArgs),
+    #[command(name = "jumble")]
+    Jumble(JumbleArgs),
+    #[command(name = "unjumble")]
+    Unjumble(JumbleArgs),
+}
+
+#[derive(Args)]
+struct Zip316DecodeArgs {
+    #[arg(help = "Unified container (UA/UFVK/UIVK)")]
+    encoded: String,
+
+    #[arg(
+        long,
+        help = "Report padding/trailing-data defects as warnings instead of failing"
+    )]
+    lenient: bool,
+}
+
+#[derive(Args)]
+struct JumbleArgs {
+    #[arg(help = "Message as hex (48..4194368 bytes), or - to read it from stdin")]
+    hex: String,
+}
+
+#[derive(Subcommand)]
+enum EncodingCmd {
+    #[command(name = "bech32m")]
+    Bech32m {
+        #[command(subcommand)]
+        command: Bech32mCmd,
+    },
+}
+
+#[derive(Subcommand)]
+enum Bech32mCmd {
+    #[command(name = "encode")]
+    Encode(Bech32mEncodeArgs),
+    #[command(name = "decode")]
+    Decode(Bech32mDecodeArgs),
+}
+
+#[derive(Args)]
+struct Bech32mEncodeArgs {
+    #[arg(long, help = "Human-readable part")]
+    hrp: String,
+
+    #[arg(help = "Payload as hex")]
+    hex: String,
+}
+
+#[derive(Args)]
+struct Bech32mDecodeArgs {
+    #[arg(help = "bech32m string")]
+    encoded: String,
+}
+
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+enum NetworkArg {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+impl From<NetworkArg> for Network {
+    fn from(v: NetworkArg) -> Self {
+        match v {
+            NetworkArg::Mainnet => Network::Mainnet,
+            NetworkArg::Testnet => Network::Testnet,
+            NetworkArg::Regtest => Network::Regtest,
+        }
+    }
+}
+
+#[derive(Args)]
+struct UfvkFromSeedArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp + coin_type; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(long, default_value_t = 0, help = "Account (typically 0)")]
+    account: u32,
+
+    #[arg(
+        long,
+        conflicts_with = "account",
+        value_parser = ranges::account_range_arg,
+        help = "Derive accounts START..END (END excluded), one row each"
+    )]
+    account_range: Option<AccountRange>,
+
+    #[arg(
+        long,
+        requires = "account_range",
+        help = "With --account-range, write one JSON object per line"
+    )]
+    ndjson: bool,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = receivers::receiver_arg,
+        help = "Receivers to include, comma-separated (orchard, sapling, p2pkh, p2sh; default: all derivable)"
+    )]
+    receivers: Vec<u64>,
+}
+
+#[derive(Subcommand)]
+enum NetworkCmd {
+    #[command(name = "detect")]
+    Detect(NetworkDetectArgs),
+}
+
+#[derive(Args)]
+struct NetworkDetectArgs {
+    #[arg(help = "UFVK or unified address")]
+    value: String,
+}
+
+// Named after their `from-*` commands.
+#[allow(clippy::enum_variant_names)]
+#[derive(Subcommand)]
+enum UivkCmd {
+    /// The UIVK of an account of the seed
+    #[command(name = "from-seed")]
+    FromSeed(UfvkFromSeedArgs),
+    /// The UIVK of a UFVK, a strictly weaker key to hand out
+    #[command(name = "from-ufvk")]
+    FromUfvk(UivkFromUfvkArgs),
+}
+
+#[derive(Args)]
+struct UivkFromUfvkArgs {
+    #[arg(long, help = "UFVK to derive from (jview1... or @label)")]
+    ufvk: String,
+}
+
+#[derive(Args)]
+struct UfvkInspectArgs {
+    #[arg(help = "UFVK (jview1..., uview1...) or @label")]
+    ufvk: String,
+}
+
+#[derive(Args)]
+struct UfvkFromMnemonicArgs {
+    #[arg(long, help = "Read the recovery phrase from a file")]
+    mnemonic_file: PathBuf,
+
+    #[arg(long, help = "Read the BIP-39 passphrase from a file")]
+    passphrase_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Wordlist of the phrase (default: the one holding its words)"
+    )]
+    language: Option<MnemonicLanguageArg>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp + coin_type; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(long, default_value_t = 0, help = "Account (typically 0)")]
+    account: u32,
+}
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("input").required(true).args(["message", "message_file"])))]
+struct SignMessageArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp + coin_type; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(long, default_value_t = 0, help = "Account (typically 0)")]
+    account: u32,
+
+    #[arg(long, help = "Message to sign, as UTF-8 text")]
+    message: Option<String>,
+
+    #[arg(long, help = "Sign the exact bytes of a file")]
+    message_file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("input").required(true).args(["message", "message_file"])))]
+struct VerifyMessageArgs {
+    #[arg(long, help = "UFVK of the signing account (or @label)")]
+    ufvk: String,
+
+    #[arg(
+        long,
+        help = "Also require this address (or @label) to belong to the UFVK"
+    )]
+    address: Option<String>,
+
+    #[arg(long, help = "Signature as hex, as printed by `sign message`")]
+    signature: String,
+
+    #[arg(long, help = "Signed message, as UTF-8 text")]
+    message: Option<String>,
+
+    #[arg(long, help = "Check the exact bytes of a file")]
+    message_file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ProveAddressOwnershipArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp + coin_type; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(long, default_value_t = 0, help = "Account (typically 0)")]
+    account: u32,
+
+    #[arg(long, help = "Address to prove ownership of")]
+    address: String,
+
+    #[arg(long, help = "Challenge string chosen by the verifier")]
+    challenge: String,
+}
+
+#[derive(Args)]
+struct DeriveArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        required_unless_present = "descriptor",
+        help = "Hardened ZIP-32 path starting m/32'/coin_type'/account', e.g. m/32'/8133'/0'/7'"
+    )]
+    path: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["network", "path", "harden_unmarked"],
+        help = "Derive the account a wallet descriptor names, checking its fingerprints"
+    )]
+    descriptor: Option<String>,
+
+    #[arg(
+        long,
+        help = "Treat path levels without a ' marker as hardened instead of rejecting them"
+    )]
+    harden_unmarked: bool,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = diversifier_index_arg,
+        help = "Diversifier index of the address, in decimal or 0x hex (below 2^88)"
+    )]
+    diversifier_index: DiversifierIndex,
+
+    #[command(subcommand)]
+    command: Option<DeriveCmd>,
+}
+
+#[derive(Subcommand)]
+enum DeriveCmd {
+    /// Derive an application's symmetric key from the seed (ZIP-32 arbitrary key derivation)
+    #[command(name = "app-key")]
+    AppKey(DeriveAppKeyArgs),
+    /// Export an account's unified spending key (jsecret1...), for vaults that keep the seed
+    #[command(name = "usk")]
+    Usk(usk::DeriveUskArgs),
+}
+
+#[derive(Args)]
+struct DeriveAppKeyArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        help = "Context string naming the application, e.g. \"juno-backup\" (1 to 252 bytes)"
+    )]
+    context: String,
+
+    #[arg(
+        long,
+        default_value = "m",
+        help = "Hardened path within the context's key tree, e.g. m/0'"
+    )]
+    path: String,
+
+    #[arg(
+        long,
+        help = "Write the key (hex) to a file (mode 0600 on unix; placeholders like {fingerprint} and {date} are filled in)"
+    )]
+    out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+
+    #[arg(
+        long,
+        help = "Print the key to stdout even with --out (warning: avoid logs)"
+    )]
+    print: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network the seed is meant for (unset counts as mainnet for the interlock)"
+    )]
+    network: Option<NetworkArg>,
+}
+
+#[derive(Subcommand)]
+enum AddressCmd {
+    /// The unified address at a diversifier index of an account of the seed
+    #[command(name = "from-seed")]
+    FromSeed(AddressFromSeedArgs),
+    /// The unified address at a diversifier index of a UFVK
+    #[command(name = "from-ufvk")]
+    FromUfvk(AddressFromUfvkArgs),
+    /// Search diversifier indices (and accounts) for an address with a chosen prefix
+    #[command(name = "vanity")]
+    Vanity(AddressVanityArgs),
+    /// An address of a unified spending key (jsecret1...), without the seed
+    #[command(name = "from-usk")]
+    FromUsk(usk::AddressFromUskArgs),
+    /// Check that an address came from the seed (or a UFVK), and find its account and index
+    #[command(name = "verify")]
+    Verify(ownership::AddressVerifyArgs),
+    /// The address at a diversifier index, reporting the index actually used
+    #[command(name = "at-index")]
+    AtIndex(rotation::AddressAtIndexArgs),
+    /// Consecutive addresses from a diversifier index, for rotating them
+    #[command(name = "list")]
+    List(rotation::AddressListArgs),
+}
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("range").multiple(true).args(["account_range", "diversifier_range"])))]
+struct AddressFromSeedArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp + coin_type; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(long, default_value_t = 0, help = "Account (typically 0)")]
+    account: u32,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = diversifier_index_arg,
+        help = "Diversifier index of the address, in decimal or 0x hex (below 2^88)"
+    )]
+    diversifier_index: DiversifierIndex,
+
+    #[arg(
+        long,
+        conflicts_with = "account",
+        value_parser = ranges::account_range_arg,
+        help = "Derive addresses of accounts START..END (END excluded)"
+    )]
+    account_range: Option<AccountRange>,
+
+    #[arg(
+        long,
+        conflicts_with = "diversifier_index",
+        value_parser = ranges::diversifier_range_arg,
+        help = "Derive addresses at diversifier indices START..END (END excluded), one row each"
+    )]
+    diversifier_range: Option<Range<u64>>,
+
+    #[arg(
+        long,
+        requires = "range",
+        help = "With a range, write one JSON object per line"
+    )]
+    ndjson: bool,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = receivers::receiver_arg,
+        help = "Receivers to include, comma-separated (orchard, sapling, p2pkh, p2sh; default: all derivable)"
+    )]
+    receivers: Vec<u64>,
+}
+
+#[derive(Args)]
+struct AddressFromUfvkArgs {
+    #[arg(long, help = "UFVK to derive from (jview1... or @label)")]
+    ufvk: String,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = diversifier_index_arg,
+        help = "Diversifier index of the address, in decimal or 0x hex (below 2^88)"
+    )]
+    diversifier_index: DiversifierIndex,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = receivers::receiver_arg,
+        help = "Receivers to include, comma-separated (orchard, sapling, p2pkh, p2sh; default: all derivable)"
+    )]
+    receivers: Vec<u64>,
+}
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("pattern").required(true).args(["prefix", "regex"])))]
+struct AddressVanityArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp; default: profile network)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        help = "Address prefix, e.g. j1jx (bech32 characters; the HRP and '1' may be left out)"
+    )]
+    prefix: Option<String>,
+
+    #[arg(
+        long,
+        help = "Regular expression the whole address must match, e.g. '^j1..*q{4}$'"
+    )]
+    regex: Option<String>,
+
+    #[arg(long, default_value_t = 0, help = "First account to search")]
+    account: u32,
+
+    #[arg(
+        long,
+        default_value_t = 1,
+        help = "Number of accounts to search, from --account"
+    )]
+    accounts: u32,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = diversifier_index_arg,
+        help = "First diversifier index to search, in decimal or 0x hex"
+    )]
+    start_index: DiversifierIndex,
+
+    #[arg(
+        long,
+        value_parser = diversifier_index_arg,
+        help = "Stop before this diversifier index (default: search to the last)"
+    )]
+    end_index: Option<DiversifierIndex>,
+
+    #[arg(
+        long,
+        help = "Record progress in this file, to continue later with --resume"
+    )]
+    checkpoint: Option<PathBuf>,
+
+    #[arg(long, default_value_t = 60, help = "Seconds between checkpoint writes")]
+    checkpoint_interval: u64,
+
+    #[arg(
+        long,
+        requires = "checkpoint",
+        help = "Continue the search recorded in --checkpoint"
+    )]
+    resume: bool,
+
+    #[arg(long, help = "Worker threads (default: one per CPU)")]
+    threads: Option<usize>,
+}
+
+#[derive(Args)]
+struct ServeArgs {
+    #[arg(
+        long,
+        group = "pipe",
+        conflicts_with_all = ["socket", "http", "grpc"],
+        required_unless_present_any = ["socket", "http", "grpc", "stdin_json"],
+        help = "Serve over stdin/stdout"
+    )]
+    stdio: bool,
+
+    #[arg(
+        long,
+        group = "pipe",
+        conflicts_with_all = ["socket", "http", "grpc"],
+        help = "Answer one JSON request from stdin, then exit with its error code"
+    )]
+    stdin_json: bool,
+
+    #[arg(
+        long,
+        requires = "stdio",
+        help = "With --stdio, read {\"method\", \"params\"} lines and answer each with a JSON output envelope instead of JSON-RPC"
+    )]
+    envelopes: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "tcp",
+        help = "Serve on a Unix domain socket, e.g. /run/juno-keys.sock"
+    )]
+    socket: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "ADDR",
+        num_args = 0..=1,
+        default_missing_value = "127.0.0.1:8787",
+        group = "tcp",
+        help = "Serve HTTP on ADDR (default 127.0.0.1:8787); needs the `server` feature"
+    )]
+    http: Option<SocketAddr>,
+
+    #[arg(
+        long,
+        value_name = "ADDR",
+        num_args = 0..=1,
+        default_missing_value = "127.0.0.1:8788",
+        group = "tcp",
+        help = "Serve gRPC on ADDR (default 127.0.0.1:8788); needs the `grpc` feature"
+    )]
+    grpc: Option<SocketAddr>,
+
+    #[arg(
+        long,
+        value_name = "ADDR",
+        conflicts_with = "pipe",
+        help = "Serve Prometheus metrics at http://ADDR/metrics, e.g. 127.0.0.1:9787"
+    )]
+    metrics: Option<SocketAddr>,
+
+    #[arg(
+        long,
+        help = "Allow --http, --grpc and --metrics to bind non-loopback addresses"
+    )]
+    allow_remote: bool,
+
+    #[arg(
+        long,
+        requires = "tcp",
+        value_name = "PATH",
+        help = "Require `Authorization: Bearer <token>` with the token in PATH"
+    )]
+    auth_token_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Sign every response with the ed25519 key in PATH (base64 of 32 bytes)"
+    )]
+    sign_key: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "pipe",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Serve each peer IP or uid at most N requests per second"
+    )]
+    rate_limit: Option<u32>,
+
+    #[arg(
+        long,
+        conflicts_with = "stdin_json",
+        help = "Seed (base64) to load once at startup (default: profile keystore)"
+    )]
+    seed_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["pipe", "tcp"],
+        value_name = "UID",
+        help = "Also accept socket peers with this uid (repeatable; default: own uid only)"
+    )]
+    allow_uid: Vec<u32>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Config {
+    default_profile: Option<String>,
+    #[serde(flatten)]
+    base: ProfileConfig,
+    profiles: BTreeMap<String, ProfileConfig>,
+    // Private ZIP-316 typecodes by name; top level only.
+    receiver_types: BTreeMap<String, u64>,
+}
+
+// Keys that may appear at the top level of the config or inside `[profiles.<name>]`;
+// `PROFILE_KEYS` lists them for `lint`.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+struct ProfileConfig {
+    network: Option<String>,
+    keystore: Option<PathBuf>,
+    mainnet_ok: Option<bool>,
+    output: Option<String>,
+    assert_offline: Option<bool>,
+    harden: Option<bool>,
+    strict: Option<bool>,
+    rate_limit: Option<u32>,
+    sink: Option<PathBuf>,
+    audit: Option<String>,
+    addressbook: Option<PathBuf>,
+    kdf_memory_mib: Option<u32>,
+    kdf_passes: Option<u32>,
+    seed_credential: Option<String>,
+}
+
+const PROFILE_KEYS: &[&str] = &[
+    "network",
+    "keystore",
+    "mainnet_ok",
+    "output",
+    "assert_offline",
+    "harden",
+    "strict",
+    "rate_limit",
+    "sink",
+    "audit",
+    "addressbook",
+    "kdf_memory_mib",
+    "kdf_passes",
+    "seed_credential",
+];
+
+/// Effective settings after overlaying the selected profile on the top-level config.
+#[derive(Clone, Default)]
+struct Settings {
+    network: Option<Network>,
+    keystore: Option<PathBuf>,
+    mainnet_ok: bool,
+    json: bool,
+    // Commands that would open a socket must refuse to run when this is set.
+    assert_offline: bool,
+    // Secrets must be locked in memory and core dumps are off; see `juno_keys::secret`.
+    harden: bool,
+    // `--strict`, or the config's `strict`.
+    strict: bool,
+    // Default for `serve --rate-limit`.
+    rate_limit: Option<u32>,
+    // Program that receives derivation results; see `hooks`.
+    sink: Option<PathBuf>,
+    receiver_types: BTreeMap<u64, String>,
+    audit: Option<AuditSink>,
+    // Encrypted label file; see `addressbook`.
+    addressbook: Option<PathBuf>,
+    // Argon2id settings for newly encrypted seeds and address books; see `kdf`.
+    kdf_memory_mib: Option<u32>,
+    kdf_passes: Option<u32>,
+    // Credential store entry read before `keystore`; see `credential`.
+    seed_credential: Option<String>,
+    // `--seed-passphrase-file`, for encrypted seed files; see `sealed_seed`.
+    seed_passphrase_file: Option<PathBuf>,
+    // `--seed-stdin` or `--seed-prompt`, in place of the command's seed flags.
+    seed_input: Option<SeedInput>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SeedInput {
+    Stdin,
+    Prompt,
+}
+
+/// The command-line inputs to `load_settings`, kept by daemons to reload on SIGHUP.
+#[derive(Clone)]
+struct ConfigSource {
+    config: Option<PathBuf>,
+    profile: Option<String>,
+    assert_offline: bool,
+    strict: bool,
+}
+
+impl Cli {
+    fn config_source(&self) -> ConfigSource {
+        ConfigSource {
+            config: self.config.clone(),
+            profile: self.profile.clone(),
+            assert_offline: self.assert_offline,
+            strict: self.strict,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum AppError {
+    InvalidRequest(String),
+    Io(String),
+    Config(String),
+    MainnetInterlock,
+    NotOffline(String),
+    SelftestFailed(String),
+    Keys(KeysError),
+    Zip316(juno_keys::zip316::Zip316Error),
+    // An error reported by an `--isolate` child, as its code and message.
+    Isolated(ErrorCode, String),
+    // `--json-version` asked for a version this build doesn't write.
+    JsonVersion(String),
+}
+
+impl AppError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            AppError::InvalidRequest(_) => ErrorCode::InvalidRequest,
+            AppError::Io(_) => ErrorCode::IoError,
+            AppError::Config(_) => ErrorCode::ConfigInvalid,
+            AppError::MainnetInterlock => ErrorCode::MainnetInterlock,
+            AppError::NotOffline(_) => ErrorCode::OfflineAssertionFailed,
+            AppError::SelftestFailed(_) => ErrorCode::SelftestFailed,
+            AppError::Keys(e) => e.error_code(),
+            AppError::Zip316(_) => ErrorCode::InvalidRequest,
+            AppError::Isolated(code, _) => *code,
+            AppError::JsonVersion(_) => ErrorCode::JsonVersionUnsupported,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        self.error_code().as_str()
+    }
+
+    fn diagnostic(&self) -> Option<juno_keys::zip316::ParseDiagnostic> {
+        match self {
+            AppError::Keys(KeysError::Bech32(e) | KeysError::Parse(e)) | AppError::Zip316(e) => {
+                e.diagnostic()
+            }
+            _ => None,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            AppError::InvalidRequest(s) => s.clone(),
+            AppError::Io(s) => s.clone(),
+            AppError::Config(s) => s.clone(),
+            AppError::MainnetInterlock => {
+                "refusing to print mainnet secret material to a terminal \
+                 (pass --mainnet-ok or set mainnet_ok = true in the config)"
+                    .to_string()
+            }
+            AppError::NotOffline(s) => s.clone(),
+            AppError::SelftestFailed(s) => format!("known-answer tests failed: {s}"),
+            AppError::Keys(e) => e.to_string(),
+            AppError::Zip316(e) => e.to_string(),
+            AppError::Isolated(_, message) => message.clone(),
+            AppError::JsonVersion(v) => format!(
+                "unsupported JSON version {v} (this build writes {})",
+                JSON_VERSIONS.join(", ")
+            ),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OkEnvelope<'a, T: Serialize> {
+    version: &'static str,
+    status: &'static str,
+    #[serde(skip_serializing_if = "RequestId::omit")]
+    request_id: RequestId<'a>,
+    data: T,
+}
+
+#[derive(Serialize)]
+struct ErrEnvelope<'a> {
+    version: &'static str,
+    status: &'static str,
+    #[serde(skip_serializing_if = "RequestId::omit")]
+    request_id: RequestId<'a>,
+    error: ErrObj,
+}
+
+// `request_id`, which v1 only writes when set and v2 always writes.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct RequestId<'a>(Option<&'a str>);
+
+impl RequestId<'_> {
+    fn omit(&self) -> bool {
+        self.0.is_none() && !json_v2()
+    }
+}
+
+#[derive(Serialize)]
+struct ErrObj {
+    code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exit: Option<i32>,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<ErrDetails>,
+}
+
+#[derive(Serialize)]
+struct ErrDetails {
+    stage: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
+}
+
+// clap suggests near misses among a command's own subcommands; failing that, this
+// names the commands the word is elsewhere, e.g. `ufvk from-seed` for `seed from-seed`.
+fn parse_cli() -> Cli {
+    Cli::try_parse().unwrap_or_else(|mut e| {
+        if e.kind() != ErrorKind::InvalidSubcommand {
+            e.exit()
+        }
+        // Short aliases (`u`, `fp`) resemble everything; suggest full names only.
+        let similar = match e.remove(ContextKind::SuggestedSubcommand) {
+            Some(ContextValue::Strings(similar)) => similar
+                .into_iter()
+                .filter(|name| name.len() > 2)
+                .collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+        let mut tips = match e.remove(ContextKind::Suggested) {
+            Some(ContextValue::StyledStrs(tips)) => tips,
+            _ => Vec::new(),
+        };
+        if !similar.is_empty() {
+            e.insert(
+                ContextKind::SuggestedSubcommand,
+                ContextValue::Strings(similar),
+            );
+        } else if let Some(ContextValue::String(word)) = e.get(ContextKind::InvalidSubcommand) {
+            let paths = command_paths(&Cli::command(), word)
+                .iter()
+                .map(|path| format!("`juno-keys {path}`"))
+                .collect::<Vec<_>>();
+            if !paths.is_empty() {
+                tips.push(format!("did you mean {}?", paths.join(" or ")).into());
+            }
+        }
+        if !tips.is_empty() {
+            e.insert(ContextKind::Suggested, ContextValue::StyledStrs(tips));
+        }
+        e.exit()
+    })
+}
+
+/// The least nested commands named or aliased `word`, ignoring case and `_` for `-`.
+fn command_paths(root: &clap::Command, word: &str) -> Vec<String> {
+    fn walk(cmd: &clap::Command, prefix: &str, word: &str, found: &mut Vec<String>) {
+        for sub in cmd.get_subcommands() {
+            let path = format!("{prefix}{}", sub.get_name());
+            let mut names = std::iter::once(sub.get_name()).chain(sub.get_all_aliases());
+            if names.any(|name| name == word) {
+                found.push(path.clone());
+            }
+            walk(sub, &format!("{path} "), word, found);
+        }
+    }
+    let word = word.to_ascii_lowercase().replace('_', "-");
+    let mut found = Vec::new();
+    walk(root, "", &word, &mut found);
+    let depth = |path: &String| path.matches(' ').count();
+    let least = found.iter().map(depth).min().unwrap_or(0);
+    found.retain(|path| depth(path) == least);
+    found
+}
+
+fn main() {
+    let mut cli = parse_cli();
+    style::init(&cli);
+    let mut audit_sink = cli.audit;
+    if cli.backup {
+        backup::enable();
+    }
+    let result = select_json_version(&cli).and_then(|()| match &cli.command {
+        // Lint can't rely on the config it may be checking.
+        Command::Lint(args) => lint::cmd_lint(&cli, args),
+        // Doctor reports a broken config rather than failing on it.
+        Command::Doctor => doctor::cmd_doctor(&cli),
+        _ => load_settings(&cli.config_source()).and_then(|mut settings| {
+            cli.json |= settings.json;
+            if let Some(name) = &cli.seed_credential {
+                settings.seed_credential = Some(name.clone());
+            }
+            settings.harden |= cli.harden;
+            settings.strict |= cli.strict;
+            settings.seed_passphrase_file = cli.seed_passphrase_file.clone();
+            settings.seed_input = match (cli.seed_stdin, cli.seed_prompt) {
+                (true, _) => Some(SeedInput::Stdin),
+                (false, true) => Some(SeedInput::Prompt),
+                (false, false) => None,
+            };
+            settings.network = networks::select(&cli, settings.strict)?.or(settings.network);
+            audit_sink = audit_sink.or(settings.audit);
+            run(&cli, &settings)
+        }),
+    });
+    if let (Some(sink), Some((operation, mut params))) = (audit_sink, audit_operation(&cli.command))
+    {
+        // The seed flags were left unset in favor of the global ones.
+        if params["seed"] == "keystore" && (cli.seed_stdin || cli.seed_prompt) {
+            params["seed"] = json!(if cli.seed_stdin { "stdin" } else { "prompt" });
+        }
+        let (seed_fingerprint, ufvk_fingerprint) = audit::noted();
+        let event = audit::Event {
+            operation,
+            outcome: result.as_ref().map_or_else(AppError::code, |()| "ok"),
+            caller: audit::local_caller(),
+            request_id: cli.request_id.as_deref(),
+            seed_fingerprint,
+            ufvk_fingerprint,
+            backups: backup::rotated(),
+            params,
+        };
+        audit::emit(sink, &event);
+    }
+    if result.is_err() && std::env::var("JUNO_KEYS_OUTPUT").is_ok_and(|v| v == "json") {
+        cli.json = true;
+    }
+    // Every seed a command made is dropped by now; one still live is a leaked copy.
+    #[cfg(feature = "secret-accounting")]
+    {
+        use juno_keys::secret_accounting::{allocations, live};
+        eprintln!(
+            "secret accounting: {} seed buffers allocated, {} live",
+            allocations(),
+            live()
+        );
+        debug_assert_eq!(live(), 0, "seed buffers outlived the command");
+    }
+    let exit_code = match result {
+        Ok(()) => 0,
+        Err(e) => {
+            write_error(&cli, &e);
+            e.error_code().into()
+        }
+    };
+    std::process::exit(exit_code);
+}
+
+fn run(cli: &Cli, settings: &Settings) -> Result<(), AppError> {
+    if settings.assert_offline {
+        ensure_offline()?;
+    }
+    // Before any seed is read, so none of them can end up in a core dump.
+    if settings.harden {
+        juno_keys::secret::harden().map_err(AppError::Keys)?;
+    }
+    if cli.show_for.is_some() && cli.json {
+        return Err(AppError::InvalidRequest(
+            "--show-for is for text output; JSON output can't be cleared".into(),
+        ));
+    }
+
+    match &cli.command {
+        Command::Seed {
+            command: SeedCmd::New(args),
+        } => cmd_seed_new(cli, settings, args),
+        Command::Seed {
+            command: SeedCmd::Import(args),
+        } => cmd_seed_import(cli, settings, args),
+        Command::Seed {
+            command: SeedCmd::Encrypt(args),
+        } => sealed_seed::cmd_seed_encrypt(cli, settings, args),
+        Command::Seed {
+            command: SeedCmd::Decrypt(args),
+        } => sealed_seed::cmd_seed_decrypt(cli, settings, args),
+        Command::Seed {
+            command: SeedCmd::Store(args),
+        } => credential::cmd_seed_store(cli, settings, args),
+        Command::Seed {
+            command: SeedCmd::Split(args),
+        } => shares::cmd_seed_split(cli, settings, args),
+        Command::Seed {
+            command: SeedCmd::Combine(args),
+        } => shares::cmd_seed_combine(cli, settings, args),
+        Command::Derive(DeriveArgs {
+            command: Some(DeriveCmd::AppKey(args)),
+            ..
+        }) => cmd_derive_app_key(cli, settings, args),
+        Command::Derive(DeriveArgs {
+            command: Some(DeriveCmd::Usk(args)),
+            ..
+        })
+        | Command::Usk {
+            command: usk::UskCmd::FromSeed(args),
+        } => usk::cmd_derive_usk(cli, settings, args),
+        Command::Derive(args) => cmd_derive(cli, settings, args),
+        Command::Address {
+            command: AddressCmd::Vanity(args),
+        }
+        | Command::Vanity(args) => vanity::cmd_address_vanity(cli, settings, args),
+        Command::Ufvk {
+            command: UfvkCmd::FromSeed(args),
+        }
+        | Command::FromSeed(args) => cmd_ufvk_from_seed(cli, settings, args),
+        Command::Ufvk {
+            command: UfvkCmd::FromMnemonic(args),
+        } => cmd_ufvk_from_mnemonic(cli, settings, args),
+        Command::Ufvk {
+            command: UfvkCmd::Inspect(args),
+        } => cmd_ufvk_inspect(cli, settings, args),
+        Command::Uivk {
+            command: UivkCmd::FromSeed(args),
+        } => cmd_uivk_from_seed(cli, settings, args),
+        Command::Uivk {
+            command: UivkCmd::FromUfvk(args),
+        } => cmd_uivk_from_ufvk(cli, settings, args),
+        Command::Ufvk {
+            command: UfvkCmd::FromUsk(args),
+        } => usk::cmd_ufvk_from_usk(cli, args),
+        Command::Address {
+            command: AddressCmd::FromUsk(args),
+        } => usk::cmd_address_from_usk(cli, args),
+        Command::Address {
+            command: AddressCmd::FromSeed(args),
+        } => cmd_address_from_seed(cli, settings, args),
+        Command::Address {
+            command: AddressCmd::FromUfvk(args),
+        } => cmd_address_from_ufvk(cli, settings, args),
+        Command::Ufvk {
+            command: UfvkCmd::Verify(args),
+        } => ownership::cmd_ufvk_verify(cli, settings, args),
+        Command::Ufvk {
+            command: UfvkCmd::ExportRaw(args),
+        } => raw_fvk::cmd_ufvk_export_raw(cli, settings, args),
+        Command::Ufvk {
+            command: UfvkCmd::FromRaw(args),
+        } => raw_fvk::cmd_ufvk_from_raw(cli, settings, args),
+        Command::Address {
+            command: AddressCmd::Verify(args),
+        } => ownership::cmd_address_verify(cli, settings, args),
+        Command::Address {
+            command: AddressCmd::AtIndex(args),
+        } => rotation::cmd_address_at_index(cli, settings, args),
+        Command::Address {
+            command: AddressCmd::List(args),
+        } => rotation::cmd_address_list(cli, settings, args),
+        Command::Network {
+            command: NetworkCmd::Detect(args),
+        } => cmd_network_detect(cli, args),
+        Command::Descriptor { command } => descriptor::cmd_descriptor(cli, settings, command),
+        Command::Convert {
+            command: ConvertCmd::FromZcash(args),
+        } => cmd_convert_from_zcash(cli, args),
+        Command::Sign {
+            command: SignCmd::Message(args),
+        } => cmd_sign_message(cli, settings, args),
+        Command::Verify {
+            command: VerifyCmd::Message(args),
+        } => cmd_verify_message(cli, settings, args),
+        Command::Prove {
+            command: ProveCmd::AddressOwnership(args),
+        } => cmd_prove_address_ownership(cli, settings, args),
+        Command::Export {
+            command: ExportCmd::IndexerConfig(args),
+        } => cmd_export_indexer_config(cli, settings, args),
+        Command::Export {
+            command: ExportCmd::Paper(args),
+        } => cmd_export_paper(cli, settings, args),
+        Command::Audit {
+            command: AuditCmd::Addresses(args),
+        } => cmd_audit_addresses(cli, settings, args),
+        Command::Audit {
+            command: AuditCmd::Replay(args),
+        } => replay::cmd_audit_replay(cli, settings, args),
+        Command::Uri {
+            command: UriCmd::Build(args),
+        } => cmd_uri_build(cli, settings, args),
+        Command::Uri {
+            command: UriCmd::Parse(args),
+        } => cmd_uri_parse(cli, settings, args),
+        Command::Uri {
+            command: UriCmd::Qr(args),
+        } => cmd_uri_qr(cli, settings, args),
+        Command::Addressbook { command } => addressbook::cmd_addressbook(cli, settings, command),
+        Command::Fingerprint(args) => visual::cmd_fingerprint(cli, settings, args),
+        Command::Frost { command } => frost::cmd_frost(cli, settings, command),
+        Command::Transcribe { command } => transcribe::cmd_transcribe(cli, settings, command),
+        Command::Backup {
+            command: BackupCmd::VerifyTranscription(args),
+        } => transcribe::cmd_backup_verify_transcription(cli, settings, args),
+        Command::Bench {
+            command: BenchCmd::Kdf(args),
+        } => kdf::cmd_bench_kdf(cli, args),
+        Command::Fixtures {
+            command: FixturesCmd::Generate(args),
+        } => cmd_fixtures_generate(cli, args),
+        Command::Vectors { command } => vectors::cmd_vectors(cli, settings, command),
+        Command::Selftest => cmd_selftest(cli),
+        Command::Lint(args) => lint::cmd_lint(cli, args),
+        Command::Doctor => doctor::cmd_doctor(cli),
+        Command::Serve(args) => serve::cmd_serve(cli, settings, args),
+        Command::Testvectors {
+            command: TestvectorsCmd::Zip316,
+        } => cmd_testvectors_zip316(cli),
+        Command::Testvectors {
+            command: TestvectorsCmd::Zip32(args),
+        } => cmd_testvectors_zip32(cli, settings, args),
+        Command::Testvectors {
+            command: TestvectorsCmd::Conformance,
+        } => cmd_testvectors_conformance(cli),
+        Command::Zip316 {
+            command: Zip316Cmd::Decode(args),
+        } => cmd_zip316_decode(cli, settings, args),
+        Command::Zip316 {
+            command: Zip316Cmd::Jumble(args),
+        } => cmd_zip316_jumble(cli, args, false),
+        Command::Zip316 {
+            command: Zip316Cmd::Unjumble(args),
+        } => cmd_zip316_jumble(cli, args, true),
+        Command::Encoding {
+            command:
+                EncodingCmd::Bech32m {
+                    command: Bech32mCmd::Encode(args),
+                },
+        } => cmd_bech32m_encode(cli, args),
+        Command::Encoding {
+            command:
+                EncodingCmd::Bech32m {
+                    command: Bech32mCmd::Decode(args),
+                },
+        } => cmd_bech32m_decode(cli, args),
+    }
+}
+
+// A command's audit operation and non-secret parameters. `serve` audits each request
+// instead.
+fn audit_operation(command: &Command) -> Option<(&'static str, serde_json::Value)> {
+    let network = |n: Option<NetworkArg>| n.map(|n| Network::from(n).to_string());
+    // Where the seed came from, never the seed itself.
+    let seed = |file: &Option<PathBuf>, inline: &Option<String>| match (file, inline) {
+        (Some(path), _) => path.display().to_string(),
+        (None, Some(_)) => "inline".to_string(),
+        (None, None) => "keystore".to_string(),
+    };
+    let usk = |key: &usk::UskArgs| seed(&key.usk_file.clone(), &key.usk);
+    let address_source = |source: &rotation::SourceArgs| match &source.ufvk {
+        Some(_) => json!({ "ufvk": true }),
+        None => json!({
+            "seed": seed(&source.seed_file, &source.seed_base64),
+            "network": network(source.network),
+            "account": source.account,
+        }),
+    };
+    Some(match command {
+        Command::Seed {
+            command: SeedCmd::New(args),
+        } => (
+            "seed new",
+            json!({
+                "bytes": (!args.mnemonic).then_some(args.bytes),
+                "words": args.mnemonic.then_some(args.words),
+                "network": network(args.network),
+                "out": args.out,
+                "print": args.print,
+            }),
+        ),
+        Command::Seed {
+            command: SeedCmd::Import(args),
+        } => (
+            "seed import",
+            json!({
+                "mnemonic_file": args.mnemonic_file,
+                "zcashd_dump": args.zcashd_dump,
+                "legacy_hdseed": args.legacy_hdseed,
+                "wallet": args.wallet.map(|w| format!("{w:?}").to_lowercase()),
+                "network": network(args.network),
+                "account": args.account,
+                "out": args.out,
+            }),
+        ),
+        Command::Seed {
+            command: SeedCmd::Encrypt(args),
+        } => (
+            "seed encrypt",
+            json!({ "seed": seed(&args.seed_file, &args.seed_base64), "out": args.out }),
+        ),
+        Command::Seed {
+            command: SeedCmd::Store(args),
+        } => (
+            "seed store",
+            json!({ "seed": seed(&args.seed_file, &args.seed_base64), "credential": args.credential }),
+        ),
+        Command::Seed {
+            command: SeedCmd::Decrypt(args),
+        } => (
+            "seed decrypt",
+            json!({ "file": args.file, "network": network(args.network), "out": args.out, "credential": args.credential, "print": args.print }),
+        ),
+        Command::Seed {
+            command: SeedCmd::Split(args),
+        } => (
+            "seed split",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "threshold": args.threshold,
+                "shares": args.shares,
+                "out_dir": args.out_dir,
+                "print": args.print,
+            }),
+        ),
+        Command::Seed {
+            command: SeedCmd::Combine(args),
+        } => (
+            "seed combine",
+            json!({
+                "inline_shares": args.share.len(),
+                "file": args.file,
+                "network": network(args.network),
+                "out": args.out,
+                "print": args.print,
+            }),
+        ),
+        Command::Derive(DeriveArgs {
+            command: Some(DeriveCmd::AppKey(args)),
+            ..
+        }) => (
+            "derive app-key",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "context": args.context,
+                "path": args.path,
+                "out": args.out,
+                "print": args.print,
+            }),
+        ),
+        Command::Derive(DeriveArgs {
+            command: Some(DeriveCmd::Usk(args)),
+            ..
+        })
+        | Command::Usk {
+            command: usk::UskCmd::FromSeed(args),
+        } => (
+            "derive usk",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "account": args.account,
+                "out": args.out,
+                "print": args.print,
+            }),
+        ),
+        Command::Ufvk {
+            command: UfvkCmd::FromUsk(args),
+        } => ("ufvk from-usk", json!({ "usk": usk(args) })),
+        Command::Address {
+            command: AddressCmd::FromUsk(args),
+        } => (
+            "address from-usk",
+            json!({
+                "usk": usk(&args.key),
+                "diversifier_index": diversifier_index_json(args.diversifier_index),
+            }),
+        ),
+        Command::Derive(args) => (
+            "derive",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "path": args.path,
+                "descriptor": args.descriptor,
+                "diversifier_index": diversifier_index_json(args.diversifier_index),
+            }),
+        ),
+        Command::Address {
+            command: AddressCmd::Vanity(args),
+        }
+        | Command::Vanity(args) => (
+            "address vanity",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "prefix": args.prefix,
+                "regex": args.regex,
+                "account": args.account,
+                "accounts": args.accounts,
+                "start_index": diversifier_index_json(args.start_index),
+                "end_index": args.end_index.map(diversifier_index_json),
+                "resume": args.resume,
+            }),
+        ),
+        Command::Ufvk {
+            command: UfvkCmd::FromSeed(args),
+        }
+        | Command::FromSeed(args) => (
+            "ufvk from-seed",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "account": args.account,
+                "account_range": args.account_range.map(|r| format!("{}..{}", r.start(), r.end())),
+                "receivers": args.receivers,
+            }),
+        ),
+        Command::Ufvk {
+            command: UfvkCmd::Inspect(_),
+        } => ("ufvk inspect", json!({})),
+        Command::Ufvk {
+            command: UfvkCmd::ExportRaw(_),
+        } => ("ufvk export-raw", json!({})),
+        Command::Ufvk {
+            command: UfvkCmd::FromRaw(args),
+        } => ("ufvk from-raw", json!({ "network": network(args.network) })),
+        Command::Uivk {
+            command: UivkCmd::FromSeed(args),
+        } => (
+            "uivk from-seed",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "account": args.account,
+                "account_range": args.account_range.map(|r| format!("{}..{}", r.start(), r.end())),
+                "receivers": args.receivers,
+            }),
+        ),
+        Command::Uivk {
+            command: UivkCmd::FromUfvk(_),
+        } => ("uivk from-ufvk", json!({})),
+        Command::Ufvk {
+            command: UfvkCmd::FromMnemonic(args),
+        } => (
+            "ufvk from-mnemonic",
+            json!({
+                "mnemonic_file": args.mnemonic_file,
+                "passphrase": args.passphrase_file.is_some(),
+                "network": network(args.network),
+                "account": args.account,
+            }),
+        ),
+        Command::Address {
+            command: AddressCmd::FromSeed(args),
+        } => (
+            "address from-seed",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "account": args.account,
+                "diversifier_index": diversifier_index_json(args.diversifier_index),
+                "account_range": args.account_range.map(|r| format!("{}..{}", r.start(), r.end())),
+                "diversifier_range": args.diversifier_range.as_ref().map(|r| format!("{}..{}", r.start, r.end)),
+                "receivers": args.receivers,
+            }),
+        ),
+        Command::Address {
+            command: AddressCmd::FromUfvk(args),
+        } => (
+            "address from-ufvk",
+            json!({
+                "diversifier_index": diversifier_index_json(args.diversifier_index),
+                "receivers": args.receivers,
+            }),
+        ),
+        Command::Ufvk {
+            command: UfvkCmd::Verify(args),
+        } => (
+            "ufvk verify",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "account_range": format!("{}..{}", args.account_range.start(), args.account_range.end()),
+            }),
+        ),
+        Command::Address {
+            command: AddressCmd::Verify(args),
+        } => (
+            "address verify",
+            match &args.ufvk {
+                Some(_) => json!({ "ufvk": true }),
+                None => json!({
+                    "seed": seed(&args.seed_file, &args.seed_base64),
+                    "network": network(args.network),
+                    "account_range": format!("{}..{}", args.account_range.start(), args.account_range.end()),
+                }),
+            },
+        ),
+        Command::Address {
+            command: AddressCmd::AtIndex(args),
+        } => (
+            "address at-index",
+            json!({
+                "source": address_source(&args.source),
+                "index": diversifier_index_json(args.index),
+            }),
+        ),
+        Command::Address {
+            command: AddressCmd::List(args),
+        } => (
+            "address list",
+            json!({
+                "source": address_source(&args.source),
+                "from": diversifier_index_json(args.from),
+                "count": args.count,
+            }),
+        ),
+        Command::Sign {
+            command: SignCmd::Message(args),
+        } => (
+            "sign message",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "account": args.account,
+                "message": if args.message_file.is_some() { "file" } else { "inline" },
+            }),
+        ),
+        Command::Verify {
+            command: VerifyCmd::Message(args),
+        } => (
+            "verify message",
+            json!({
+                "ufvk": args.ufvk,
+                "address": args.address,
+                "message": if args.message_file.is_some() { "file" } else { "inline" },
+            }),
+        ),
+        Command::Prove {
+            command: ProveCmd::AddressOwnership(args),
+        } => (
+            "prove address-ownership",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "network": network(args.network),
+                "account": args.account,
+                "address": args.address,
+                "challenge": args.challenge,
+            }),
+        ),
+        Command::Uri {
+            command: UriCmd::Build(args),
+        } => ("uri build", json!({ "recipients": args.address.len() })),
+        Command::Uri {
+            command: UriCmd::Parse(args),
+        } => ("uri parse", json!({ "network": network(args.network) })),
+        Command::Uri {
+            command: UriCmd::Qr(args),
+        } => (
+            "uri qr",
+            json!({ "recipients": args.request.address.len(), "svg": args.svg }),
+        ),
+        Command::Audit {
+            command: AuditCmd::Addresses(args),
+        } => (
+            "audit addresses",
+            json!({
+                "file": args.file,
+                "column": args.column,
+                "strict": args.strict,
+            }),
+        ),
+        Command::Audit {
+            command: AuditCmd::Replay(args),
+        } => (
+            "audit replay",
+            json!({
+                "manifest": args.manifest,
+                "seed": seed(&args.seed_file, &args.seed_base64),
+            }),
+        ),
+        Command::Export {
+            command: ExportCmd::Paper(args),
+        } => (
+            "export paper",
+            json!({
+                "seed": seed(&args.seed_file, &args.seed_base64),
+                "mnemonic": args.mnemonic_file.is_some(),
+                "network": network(args.network),
+                "account": args.account,
+                "out": args.out,
+            }),
+        ),
+        Command::Export {
+            command: ExportCmd::IndexerConfig(args),
+        } => (
+            "export indexer-config",
+            json!({
+                "wallet_id": args.wallet_id,
+                "birthday_height": args.birthday_height,
+                "format": format!("{:?}", args.format).to_lowercase(),
+                "out": args.out,
+            }),
+        ),
+        Command::Addressbook { command } => match command {
+            AddressbookCmd::Add(_) => ("addressbook add", json!({})),
+            AddressbookCmd::List => ("addressbook list", json!({})),
+            AddressbookCmd::Resolve(_) => ("addressbook resolve", json!({})),
+            AddressbookCmd::Remove(_) => ("addressbook remove", json!({})),
+        },
+        Command::Fingerprint(args) => (
+            "fingerprint",
+            json!({ "seed": args.value.is_none().then(|| seed(&args.seed_file, &args.seed_base64)) }),
+        ),
+        Command::Descriptor {
+            command: descriptor::DescriptorCmd::Show(_),
+        } => ("descriptor show", json!({})),
+        Command::Transcribe { command } => match command {
+            TranscribeCmd::Encode(_) => ("transcribe encode", json!({})),
+            TranscribeCmd::Verify(_) => ("transcribe verify", json!({})),
+        },
+        Command::Backup {
+            command: BackupCmd::VerifyTranscription(args),
+        } => (
+            "backup verify-transcription",
+            json!({
+                "mnemonic_file": args.mnemonic_file,
+                "seed": args
+                    .mnemonic_file
+                    .is_none()
+                    .then(|| seed(&args.seed_file, &args.seed_base64)),
+            }),
+        ),
+        Command::Bench {
+            command: BenchCmd::Kdf(args),
+        } => (
+            "bench kdf",
+            json!({ "target_ms": args.target_ms, "max_memory_mib": args.max_memory_mib }),
+        ),
+        Command::Frost { command } => (
+            match command {
+                FrostCmd::Dealer(_) => "frost dealer",
+                FrostCmd::Dkg {
+                    command: DkgCmd::Part1(_),
+                } => "frost dkg part1",
+                FrostCmd::Dkg {
+                    command: DkgCmd::Part2(_),
+                } => "frost dkg part2",
+                FrostCmd::Dkg {
+                    command: DkgCmd::Part3(_),
+                } => "frost dkg part3",
+                FrostCmd::Sign {
+                    command: FrostSignCmd::Round1(_),
+                } => "frost sign round1",
+                FrostCmd::Sign {
+                    command: FrostSignCmd::Round2(_),
+                } => "frost sign round2",
+                FrostCmd::Sign {
+                    command: FrostSignCmd::Aggregate(_),
+                } => "frost sign aggregate",
+            },
+            json!({}),
+        ),
+        Command::Convert {
+            command: ConvertCmd::FromZcash(args),
+        } => ("convert from-zcash", json!({ "encoded": args.encoded })),
+        Command::Network {
+            command: NetworkCmd::Detect(_),
+        } => ("network detect", json!({})),
+        Command::Fixtures {
+            command: FixturesCmd::Generate(args),
+        } => (
+            "fixtures generate",
+            json!({
+                "seeds": args.seeds,
+                "accounts": args.accounts,
+                "addresses": args.addresses,
+                "out": args.out,
+            }),
+        ),
+        Command::Vectors {
+            command: vectors::VectorsCmd::Generate(args),
+        } => (
+            "vectors generate",
+            json!({
+                "seed": if args.deterministic_seed {
+                    "deterministic".to_string()
+                } else {
+                    seed(&args.seed_file, &args.seed_base64)
+                },
+                "seeds": args.seeds,
+                "accounts": args.accounts,
+                "diversifier_indices": args.diversifier_indices.iter().copied().map(diversifier_index_json).collect::<Vec<_>>(),
+                "out": args.out,
+            }),
+        ),
+        Command::Serve(_) => return None,
+        Command::Selftest => ("selftest", json!({})),
+        Command::Doctor => ("doctor", json!({})),
+        Command::Lint(args) => (
+            "lint",
+            json!({
+                "jobs": args.jobs.as_ref().map(|p| p.display().to_string()),
+                "config": args.config.is_some(),
+            }),
+        ),
+        Command::Testvectors {
+            command: TestvectorsCmd::Zip316,
+        } => ("testvectors zip316", json!({})),
+        Command::Testvectors {
+            command: TestvectorsCmd::Zip32(args),
+        } => (
+            "testvectors zip32",
+            json!({
+                "network": network(args.network),
+                "seeds": args.seeds,
+                "accounts": args.accounts,
+                "addresses": args.addresses,
+            }),
+        ),
+        Command::Testvectors {
+            command: TestvectorsCmd::Conformance,
+        } => ("testvectors conformance", json!({})),
+        Command::Zip316 {
+            command: Zip316Cmd::Decode(_),
+        } => ("zip316 decode", json!({})),
+        Command::Zip316 {
+            command: Zip316Cmd::Jumble(_),
+        } => ("zip316 jumble", json!({})),
+        Command::Zip316 {
+            command: Zip316Cmd::Unjumble(_),
+        } => ("zip316 unjumble", json!({})),
+        Command::Encoding {
+            command:
+                EncodingCmd::Bech32m {
+                    command: Bech32mCmd::Encode(_),
+                },
+        } => ("encoding bech32m encode", json!({})),
+        Command::Encoding {
+            command:
+                EncodingCmd::Bech32m {
+                    command: Bech32mCmd::Decode(_),
+                },
+        } => ("encoding bech32m decode", json!({})),
+    })
+}
+
+fn cmd_seed_new(cli: &Cli, settings: &Settings, args: &SeedNewArgs) -> Result<(), AppError> {
+    let should_print = args.print || args.out.is_none();
+    if should_print {
+        let net = args.network.map(Network::from).or(settings.network);
+        ensure_mainnet_ok(cli, settings, net)?;
+    }
+
+    if args.mnemonic {
+        return cmd_seed_new_mnemonic(cli, settings, args, should_print);
+    }
+
+    // Asked for first, so a mistyped confirmation doesn't cost a seed.
+    let passphrase = args
+        .encrypt
+        .then(|| sealed_seed::new_passphrase(args.passphrase_file.as_deref()))
+        .transpose()?;
+    let seed = Seed::generate(args.bytes).map_err(AppError::Keys)?;
+    audit::note_seed(&seed);
+    let seed_b64 = seed.to_base64();
+
+    let out_path = if let Some(out) = &args.out {
+        let out = outpath::expand(
+            out,
+            &outpath::OutVars {
+                fingerprint: Some(juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?),
+                network: args.network.map(Network::from).or(settings.network),
+                account: None,
+            },
+        )?;
+        let text = match &passphrase {
+            Some(passphrase) => {
+                let encrypted = sealed_seed::seal(settings, &seed, passphrase)?;
+                armor::wrap(cli, armor::ENCRYPTED_SEED, &encrypted)
+            }
+            None => armor::wrap(cli, armor::SEED, seed_b64.expose_secret()),
+        };
+        write_secret_file(&out, &(text.to_string() + "\n"), args.force)?;
+        Some(out)
+    } else {
+        None
+    };
+
+    if cli.json {
+        #[derive(Serialize)]
+        struct SeedOut {
+            bytes: usize,
+            seed_fingerprint: String,
+            #[serde(skip_serializing_if = "std::ops::Not::not")]
+            encrypted: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            out_path: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            seed_base64: Option<String>,
+        }
+        let data = SeedOut {
+            bytes: args.bytes,
+            seed_fingerprint: juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?,
+            encrypted: args.encrypt,
+            out_path: out_path.as_ref().map(|p| p.display().to_string()),
+            seed_base64: if should_print {
+                Some(seed_b64.expose_secret().to_string())
+            } else {
+                None
+            },
+        };
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+
+    if should_print {
+        return reveal::print_secret(
+            cli,
+            &armor::wrap(cli, armor::SEED, seed_b64.expose_secret()),
+        );
+    }
+
+    if let Some(p) = out_path {
+        println!("{}", p.display());
+    }
+    Ok(())
+}
+
+// `seed new --mnemonic`: the phrase is the secret, written and printed as is, so
+// `seed import` and `ufvk from-mnemonic` read the file back.
+fn cmd_seed_new_mnemonic(
+    cli: &Cli,
+    settings: &Settings,
+    args: &SeedNewArgs,
+    should_print: bool,
+) -> Result<(), AppError> {
+    let language = MnemonicLanguage::from(args.language);
+    let phrase = juno_keys::generate_mnemonic(args.words, language).map_err(AppError::Keys)?;
+    let seed = Seed::from_mnemonic_in(Some(language), phrase.expose_secret(), "")
+        .map_err(AppError::Keys)?;
+    audit::note_seed(&seed);
+
+    let out_path = match &args.out {
+        Some(out) => {
+            let out = outpath::expand(
+                out,
+                &outpath::OutVars {
+                    fingerprint: Some(juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?),
+                    network: args.network.map(Network::from).or(settings.network),
+                    account: None,
+                },
+            )?;
+            let line = Zeroizing::new(format!("{}\n", phrase.expose_secret()));
+            write_secret_file(&out, &line, args.force)?;
+            Some(out)
+        }
+        None => None,
+    };
+
+    if cli.json {
+        #[derive(Serialize)]
+        struct MnemonicOut<'a> {
+            words: usize,
+            language: String,
+            seed_fingerprint: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            out_path: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            mnemonic: Option<&'a str>,
+        }
+        let data = MnemonicOut {
+            words: args.words,
+            language: args
+                .language
+                .to_possible_value()
+                .map(|v| v.get_name().to_string())
+                .unwrap_or_default(),
+            seed_fingerprint: juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?,
+            out_path: out_path.as_ref().map(|p| p.display().to_string()),
+            mnemonic: should_print.then(|| phrase.expose_secret()),
+        };
+        return write_json_ok(cli, &data);
+    }
+
+    if should_print {
+        return reveal::print_secret(cli, phrase.expose_secret());
+    }
+    if let Some(p) = out_path {
+        println!("{}", p.display());
+    }
+    Ok(())
+}
+
+fn cmd_uri_build(cli: &Cli, settings: &Settings, args: &UriBuildArgs) -> Result<(), AppError> {
+    let uri = build_uri(settings, args)?;
+    if cli.json {
+        return write_json_ok(cli, &json!({ "uri": uri }));
+    }
+    println!("{uri}");
+    Ok(())
+}
+
+fn cmd_uri_qr(cli: &Cli, settings: &Settings, args: &UriQrArgs) -> Result<(), AppError> {
+    let uri = build_uri(settings, &args.request)?;
+    let code =
+        qrcode::QrCode::with_error_correction_level(&uri, qrcode::EcLevel::M).map_err(|e| {
+            AppError::InvalidRequest(format!("payment request too long for a QR code: {e}"))
+        })?;
+    if let Some(path) = &args.svg {
+        let svg = code
+            .render::<qrcode::render::svg::Color>()
+            .min_dimensions(256, 256)
+            .build();
+        if !args.force && path.exists() {
+            return Err(AppError::Io(format!("{} exists", path.display())));
+        }
+        fs::write(path, svg + "\n").map_err(|e| AppError::Io(format!("write file: {e}")))?;
+    }
+
+    if cli.json {
+        return write_json_ok(
+            cli,
+            &json!({
+                "uri": uri,
+                "modules": code.width(),
+                "svg_path": args.svg.as_ref().map(|p| p.display().to_string()),
+            }),
+        );
+    }
+    if args.svg.is_none() {
+        // Terminals draw light text on a dark background, so filled blocks are the
+        // light modules.
+        let art = code
+            .render::<qrcode::render::unicode::Dense1x2>()
+            .dark_color(qrcode::render::unicode::Dense1x2::Light)
+            .light_color(qrcode::render::unicode::Dense1x2::Dark)
+            .build();
+        println!("{art}");
+    }
+    println!("{uri}");
+    Ok(())
+}
+
+fn build_uri(settings: &Settings, args: &UriBuildArgs) -> Result<String, AppError> {
+    let count = args.address.len();
+    // Per-recipient flags line up with --address by position; "" leaves one unset.
+    let column = |name: &str, values: &[String]| -> Result<Vec<Option<String>>, AppError> {
+        match values.len() {
+            0 => Ok(vec![None; count]),
+            n if n == count => Ok(values
+                .iter()
+                .map(|v| Some(v.clone()).filter(|v| !v.is_empty()))
+                .collect()),
+            n => Err(AppError::InvalidRequest(format!(
+                "{n} --{name} values for {count} recipients (give one per --address)"
+            ))),
+        }
+    };
+    let amounts = column("amount", &args.amount)?;
+    let memos = column("memo", &args.memo)?;
+    let labels = column("label", &args.label)?;
+    let messages = column("message", &args.message)?;
+
+    let mut network = None;
+    let mut payments = Vec::with_capacity(count);
+    for (i, address) in args.address.iter().enumerate() {
+        let address = addressbook::resolve(settings, address, EntryKind::Address)?;
+        let address = UnifiedAddress::parse(&address).map_err(AppError::Keys)?;
+        let net = address
+            .network()
+            .ok_or(AppError::Keys(KeysError::NetworkUnknown))?;
+        if *network.get_or_insert(net) != net {
+            return Err(AppError::InvalidRequest(
+                "recipients are on different networks".into(),
+            ));
+        }
+        payments.push(zip321::Payment {
+            address,
+            amount: amounts[i]
+                .as_deref()
+                .map(zip321::parse_amount)
+                .transpose()
+                .map_err(AppError::Keys)?,
+            memo: memos[i].as_ref().map(|memo| memo.as_bytes().to_vec()),
+            label: labels[i].clone(),
+            message: messages[i].clone(),
+        });
+    }
+    Ok(zip321::TransactionRequest::new(payments)
+        .map_err(AppError::Keys)?
+        .to_uri())
+}
+
+fn cmd_uri_parse(cli: &Cli, settings: &Settings, args: &UriParseArgs) -> Result<(), AppError> {
+    let net = resolve_network(settings, args.network)?;
+    let request = zip321::TransactionRequest::from_uri(args.uri.trim()).map_err(AppError::Keys)?;
+    if request
+        .payments
+        .iter()
+        .any(|payment| payment.address.network() != Some(net))
+    {
+        return Err(AppError::Keys(KeysError::PaymentRequest(
+            "recipient is on another network",
+        )));
+    }
+
+    #[derive(Serialize)]
+    struct PaymentOut {
+        address: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        amount: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        amount_zatoshis: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        memo_hex: Option<String>,
+        // Present when the memo is valid UTF-8.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        memo_text: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    }
+    #[derive(Serialize)]
+    struct UriOut {
+        network: String,
+        payments: Vec<PaymentOut>,
+    }
+    let data = UriOut {
+        network: net.to_string(),
+        payments: request
+            .payments
+            .into_iter()
+            .map(|payment| PaymentOut {
+                address: payment.address.to_string(),
+                amount: payment.amount.map(zip321::format_amount),
+                amount_zatoshis: payment.amount,
+                memo_hex: payment.memo.as_ref().map(hex::encode),
+                memo_text: payment.memo.and_then(|memo| String::from_utf8(memo).ok()),
+                label: payment.label,
+                message: payment.message,
+            })
+            .collect(),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    let pretty = serde_json::to_string_pretty(&data)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    println!("{pretty}");
+    Ok(())
+}
+
+fn cmd_audit_addresses(
+    cli: &Cli,
+    settings: &Settings,
+    args: &AuditAddressesArgs,
+) -> Result<(), AppError> {
+    let ufvk = addressbook::resolve(settings, &args.ufvk, EntryKind::Ufvk)?;
+    let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+    let locator = ufvk.address_locator().map_err(AppError::Keys)?;
+    audit::note_ufvk(ufvk.as_str());
+
+    let raw = if args.file.as_os_str() == "-" {
+        let mut s = String::new();
+        io::stdin()
+            .read_to_string(&mut s)
+            .map_err(|e| AppError::Io(format!("read stdin: {e}")))?;
+        s
+    } else {
+        fs::read_to_string(&args.file)
+            .map_err(|e| AppError::Io(format!("read {}: {e}", args.file.display())))?
+    };
+    // Addresses never contain commas or quotes, so a field split is enough CSV.
+    let fields = |line: &str| {
+        line.split(',')
+            .map(|f| f.trim().trim_matches('"').to_string())
+            .collect::<Vec<_>>()
+    };
+    let mut lines = raw
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .peekable();
+    let column = match args.column.parse::<usize>() {
+        Ok(index) => {
+            if args.header {
+                lines.next();
+            }
+            index
+        }
+        Err(_) => {
+            let header = lines
+                .next()
+                .map(|(_, line)| fields(line))
+                .unwrap_or_default();
+            header
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(&args.column))
+                .ok_or_else(|| {
+                    AppError::InvalidRequest(format!("no column named {:?}", args.column))
+                })?
+        }
+    };
+
+    #[derive(Serialize)]
+    struct AuditRow {
+        line: usize,
+        address: String,
+        status: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        scope: Option<&'static str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        diversifier_index: Option<u128>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        duplicate_of: Option<usize>,
+    }
+    let mut first_seen = BTreeMap::new();
+    let rows = lines
+        .map(|(line, text)| {
+            let address = fields(text).get(column).cloned().unwrap_or_default();
+            let located = UnifiedAddress::parse(&address)
+                .ok()
+                .map(|ua| locator.locate(&ua));
+            let (status, scope, diversifier_index) = match located {
+                None => ("invalid", None, None),
+                Some(None) => ("stranger", None, None),
+                Some(Some((scope, index))) => (
+                    "derived",
+                    Some(match scope {
+                        orchard::keys::Scope::External => "external",
+                        orchard::keys::Scope::Internal => "internal",
+                    }),
+                    Some(u128::from(index)),
+                ),
+            };
+            let duplicate_of = match first_seen.get(&address) {
+                Some(&first) => Some(first),
+                None => {
+                    first_seen.insert(address.clone(), line);
+                    None
+                }
+            };
+            AuditRow {
+                line,
+                address,
+                status,
+                scope,
+                diversifier_index,
+                duplicate_of,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let count = |f: &dyn Fn(&AuditRow) -> bool| rows.iter().filter(|r| f(r)).count();
+    #[derive(Serialize)]
+    struct AuditSummary {
+        rows: usize,
+        external: usize,
+        internal: usize,
+        strangers: usize,
+        invalid: usize,
+        duplicates: usize,
+    }
+    let summary = AuditSummary {
+        rows: rows.len(),
+        external: count(&|r| r.scope == Some("external")),
+        internal: count(&|r| r.scope == Some("internal")),
+        strangers: count(&|r| r.status == "stranger"),
+        invalid: count(&|r| r.status == "invalid"),
+        duplicates: count(&|r| r.duplicate_of.is_some()),
+    };
+    let failed = summary.strangers + summary.invalid + summary.duplicates;
+
+    if cli.json {
+        if !args.strict || failed == 0 {
+            write_json_ok(
+                cli,
+                &json!({
+                    "ufvk_fingerprint": ufvk.fingerprint(),
+                    "summary": summary,
+                    "rows": rows,
+                }),
+            )?;
+        }
+    } else {
+        for row in &rows {
+            let index = row
+                .diversifier_index
+                .map_or_else(|| "-".to_string(), |i| i.to_string());
+            let mut note = row.scope.unwrap_or(row.status).to_string();
+            if let Some(first) = row.duplicate_of {
+                note += &format!(" (duplicate of line {first})");
+            }
+            println!("{}\t{index}\t{}\t{note}", row.line, row.address);
+        }
+        println!(
+            "{} rows: {} external, {} internal, {} strangers, {} invalid, {} duplicates",
+            summary.rows,
+            summary.external,
+            summary.internal,
+            summary.strangers,
+            summary.invalid,
+            summary.duplicates
+        );
+    }
+
+    if args.strict && failed > 0 {
+        return Err(AppError::InvalidRequest(format!(
+            "{failed} of {} rows failed the audit",
+            summary.rows
+        )));
+    }
+    Ok(())
+}
+
+fn cmd_export_paper(
+    cli: &Cli,
+    settings: &Settings,
+    args: &ExportPaperArgs,
+) -> Result<(), AppError> {
+    let net = resolve_network(settings, args.network)?;
+    let params = network_params(cli, net);
+    let passphrase = read_passphrase_file(&args.passphrase_file)?;
+    let (seed, words) = match &args.mnemonic_file {
+        Some(path) => {
+            let phrase = Zeroizing::new(
+                fs::read_to_string(path)
+                    .map_err(|e| AppError::Io(format!("read mnemonic file: {e}")))?,
+            );
+            let seed = Seed::from_mnemonic(&phrase, &passphrase).map_err(AppError::Keys)?;
+            let words = phrase
+                .split_whitespace()
+                .map(|w| Zeroizing::new(w.to_lowercase()))
+                .collect();
+            (seed, Some(words))
+        }
+        None => (
+            resolve_seed(settings, &args.seed_file, &args.seed_base64)?,
+            None,
+        ),
+    };
+    let keys = juno_keys::DerivationRequest::new(&seed)
+        .network(params.as_ref())
+        .account(args.account)
+        .build()
+        .map_err(AppError::Keys)?;
+    audit::note_seed(&seed);
+    audit::note_ufvk(keys.ufvk.as_str());
+
+    let sheet = paper::Sheet {
+        secret: match words {
+            Some(words) => paper::Secret::Mnemonic(words),
+            None => paper::Secret::Seed(seed.as_bytes()),
+        },
+        label: args.label.clone(),
+        network: net.to_string(),
+        account: args.account,
+        path: keys.path.to_string(),
+        seed_fingerprint: juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?,
+        ufvk_fingerprint: keys.ufvk.fingerprint(),
+        address: keys.address.to_string(),
+        passphrase: !passphrase.is_empty(),
+        date: args.date.clone(),
+        ceremony: args.ceremony.clone(),
+    };
+    let pdf = paper::render(&sheet)?;
+    write_secret_file(&args.out, &pdf, args.force)?;
+
+    #[derive(Serialize)]
+    struct PaperOut {
+        out_path: String,
+        seed_fingerprint: String,
+        ufvk_fingerprint: String,
+        path: String,
+    }
+    let data = PaperOut {
+        out_path: args.out.display().to_string(),
+        seed_fingerprint: sheet.seed_fingerprint,
+        ufvk_fingerprint: sheet.ufvk_fingerprint,
+        path: sheet.path,
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    println!("wrote {}", data.out_path);
+    Ok(())
+}
+
+fn cmd_export_indexer_config(
+    cli: &Cli,
+    settings: &Settings,
+    args: &ExportIndexerConfigArgs,
+) -> Result<(), AppError> {
+    let ufvk = addressbook::resolve(settings, &args.ufvk, EntryKind::Ufvk)?;
+    let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+    let network = juno_keys::detect_network(ufvk.as_str()).map_err(AppError::Keys)?;
+    audit::note_ufvk(ufvk.as_str());
+
+    #[derive(Serialize)]
+    struct IndexerWallet {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        network: String,
+        ufvk: String,
+        uivk: String,
+        ufvk_fingerprint: String,
+        birthday_height: u32,
+    }
+    #[derive(Serialize)]
+    struct IndexerConfig {
+        wallet: IndexerWallet,
+    }
+    let config = IndexerConfig {
+        wallet: IndexerWallet {
+            id: args.wallet_id.clone(),
+            network: network.to_string(),
+            ufvk: ufvk.to_string(),
+            uivk: ufvk.uivk().map_err(AppError::Keys)?,
+            ufvk_fingerprint: ufvk.fingerprint(),
+            birthday_height: args.birthday_height,
+        },
+    };
+    if cli.json && args.out.is_none() {
+        return write_json_ok(cli, &config);
+    }
+    let text = match args.format {
+        ConfigFormat::Toml => {
+            toml::to_string(&config).map_err(|e| AppError::Io(format!("toml encode: {e}")))?
+        }
+        ConfigFormat::Json => {
+            serde_json::to_string_pretty(&config)
+                .map_err(|e| AppError::Io(format!("json encode: {e}")))?
+                + "\n"
+        }
+    };
+    match &args.out {
+        // UFVKs reveal incoming transactions, so the file is private like a seed's.
+        Some(out) => {
+            write_secret_file(out, &text, args.force)?;
+            if cli.json {
+                return write_json_ok(cli, &json!({ "out_path": out.display().to_string() }));
+            }
+            println!("{}", out.display());
+        }
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+
+fn cmd_seed_import(cli: &Cli, settings: &Settings, args: &SeedImportArgs) -> Result<(), AppError> {
+    let net = resolve_network(settings, args.network)?;
+    if let Some(dump) = &args.zcashd_dump {
+        let seed = zcashd::read(dump, net, args.legacy_hdseed)?;
+        let account = WalletCompat::Zcashd
+            .zip32_account(args.account)
+            .map_err(AppError::Keys)?;
+        return import_seed(cli, args, net, seed, Box::new(ZcashNetwork(net)), account);
+    }
+    let phrase = Zeroizing::new(
+        fs::read_to_string(args.mnemonic_file.clone().unwrap_or_default())
+            .map_err(|e| AppError::Io(format!("read mnemonic file: {e}")))?,
+    );
+    let passphrase = read_passphrase_file(&args.passphrase_file)?;
+    let (seed, params, account): (_, Box<dyn NetworkParams>, _) = match args.wallet {
+        Some(wallet @ (WalletArg::Zingo | WalletArg::Zcashd)) if !passphrase.is_empty() => {
+            return Err(AppError::InvalidRequest(format!(
+                "{} has no passphrase; drop --passphrase-file",
+                wallet
+                    .to_possible_value()
+                    .map(|v| v.get_name().to_string())
+                    .unwrap_or_default()
+            )))
+        }
+        // Other wallets derive under Zcash's coin types, whatever --zcash-compat says.
+        Some(wallet) => {
+            let wallet = WalletCompat::from(wallet);
+            (
+                wallet.seed(&phrase, &passphrase).map_err(AppError::Keys)?,
+                Box::new(ZcashNetwork(net)),
+                wallet.zip32_account(args.account).map_err(AppError::Keys)?,
+            )
+        }
+        None => (
+            juno_keys::seed_from_mnemonic(&phrase, &passphrase).map_err(AppError::Keys)?,
+            network_params(cli, net),
+            args.account,
+        ),
+    };
+    import_seed(cli, args, net, seed, params, account)
+}
+
+// Writes the imported `seed` to --out and prints the UFVK of its `account`.
+fn import_seed(
+    cli: &Cli,
+    args: &SeedImportArgs,
+    net: Network,
+    seed: Seed,
+    params: Box<dyn NetworkParams>,
+    account: u32,
+) -> Result<(), AppError> {
+    let ufvk =
+        juno_keys::ufvk_from_seed(&seed, params.as_ref(), account).map_err(AppError::Keys)?;
+    let seed_fingerprint = juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?;
+    let out = outpath::expand(
+        &args.out,
+        &outpath::OutVars {
+            fingerprint: Some(seed_fingerprint.clone()),
+            network: Some(net),
+            account: Some(account),
+        },
+    )?;
+    write_secret_file(
+        &out,
+        &(armor::wrap(cli, armor::SEED, seed.to_base64().expose_secret()).to_string() + "\n"),
+        args.force,
+    )?;
+    audit::note_seed(&seed);
+    audit::note_ufvk(&ufvk);
+
+    #[derive(Serialize)]
+    struct SeedImportOut {
+        out_path: String,
+        seed_fingerprint: String,
+        coin_type: u32,
+        account: u32,
+        ufvk: String,
+    }
+    let data = SeedImportOut {
+        out_path: out.display().to_string(),
+        seed_fingerprint,
+        coin_type: params.coin_type(),
+        account,
+        ufvk,
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    println!("{}", data.ufvk);
+    visual::note(cli, visual::Kind::Ufvk, &data.ufvk);
+    Ok(())
+}
+
+// Passphrases may start or end with spaces, so only the final newline is dropped.
+// The passphrase in `path`, NFKD-normalized like every human-entered secret.
+fn read_passphrase_file(path: &Option<PathBuf>) -> Result<Zeroizing<String>, AppError> {
+    read_typed_passphrase(path).map(|typed| juno_keys::normalize_secret(&typed))
+}
+
+// Runs `open` with the NFKD form of a typed passphrase and, if that fails where the
+// passphrase had something to normalize, again with it as typed, so what was sealed
+// before passphrases were normalized still opens.
+fn with_passphrase<T>(
+    typed: &str,
+    open: impl Fn(&str) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let normalized = juno_keys::normalize_secret(typed);
+    match open(&normalized) {
+        Err(e) if *normalized != typed => {
+            let opened = open(typed).map_err(|_| e)?;
+            style::note(
+                "opened with the passphrase as typed rather than NFKD-normalized; \
+                 re-encrypt so other keyboards can open it",
+            );
+            Ok(opened)
+        }
+        opened => opened,
+    }
+}
+
+fn read_typed_passphrase(path: &Option<PathBuf>) -> Result<Zeroizing<String>, AppError> {
+    let Some(path) = path else {
+        return Ok(Zeroizing::new(String::new()));
+    };
+    let raw = Zeroizing::new(
+        fs::read_to_string(path).map_err(|e| AppError::Io(format!("read passphrase file: {e}")))?,
+    );
+    let line = raw.strip_suffix('\n').unwrap_or(&raw);
+    Ok(Zeroizing::new(
+        line.strip_suffix('\r').unwrap_or(line).to_string(),
+    ))
+}
+
+fn cmd_ufvk_from_seed(
+    cli: &Cli,
+    settings: &Settings,
+    args: &UfvkFromSeedArgs,
+) -> Result<(), AppError> {
+    receivers::check(&args.receivers)?;
+    if let Some(accounts) = args.account_range {
+        return ranges::write_keys(
+            cli,
+            settings,
+            ranges::Key::Ufvk,
+            args.network,
+            accounts,
+            args.ndjson,
+            || resolve_seed(settings, &args.seed_file, &args.seed_base64),
+        );
+    }
+    write_ufvk(
+        cli,
+        settings,
+        "ufvk from-seed",
+        args.network,
+        args.account,
+        || resolve_seed(settings, &args.seed_file, &args.seed_base64),
+    )
+}
+
+fn cmd_ufvk_from_mnemonic(
+    cli: &Cli,
+    settings: &Settings,
+    args: &UfvkFromMnemonicArgs,
+) -> Result<(), AppError> {
+    write_ufvk(
+        cli,
+        settings,
+        "ufvk from-mnemonic",
+        args.network,
+        args.account,
+        || {
+            let phrase = Zeroizing::new(
+                fs::read_to_string(&args.mnemonic_file)
+                    .map_err(|e| AppError::Io(format!("read mnemonic file: {e}")))?,
+            );
+            let passphrase = read_passphrase_file(&args.passphrase_file)?;
+            Seed::from_mnemonic_in(args.language.map(Into::into), &phrase, &passphrase)
+                .map_err(AppError::Keys)
+        },
+    )
+}
+
+#[derive(Serialize, Deserialize)]
+struct UivkOut {
+    uivk: String,
+    ua_hrp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+}
+
+fn cmd_uivk_from_seed(
+    cli: &Cli,
+    settings: &Settings,
+    args: &UfvkFromSeedArgs,
+) -> Result<(), AppError> {
+    receivers::check(&args.receivers)?;
+    if let Some(accounts) = args.account_range {
+        return ranges::write_keys(
+            cli,
+            settings,
+            ranges::Key::Uivk,
+            args.network,
+            accounts,
+            args.ndjson,
+            || resolve_seed(settings, &args.seed_file, &args.seed_base64),
+        );
+    }
+    let data = isolate::run(cli, || {
+        let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+        let net = resolve_network(settings, args.network)?;
+        let params = network_params(cli, net);
+        let keys = juno_keys::DerivationRequest::new(&seed)
+            .network(params.as_ref())
+            .account(args.account)
+            .build()
+            .map_err(AppError::Keys)?;
+        audit::note_seed(&seed);
+        audit::note_ufvk(keys.ufvk.as_str());
+
+        Ok(UivkOut {
+            uivk: keys.uivk,
+            ua_hrp: params.ua_hrp().to_string(),
+            account: Some(keys.account),
+            path: Some(keys.path.to_string()),
+        })
+    })?;
+    hooks::emit(cli, settings, "uivk from-seed", &data)?;
+    write_uivk(cli, &data)
+}
+
+fn cmd_uivk_from_ufvk(
+    cli: &Cli,
+    settings: &Settings,
+    args: &UivkFromUfvkArgs,
+) -> Result<(), AppError> {
+    let ufvk = addressbook::resolve(settings, &args.ufvk, EntryKind::Ufvk)?;
+    let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+    audit::note_ufvk(ufvk.as_str());
+    let data = UivkOut {
+        uivk: ufvk.uivk().map_err(AppError::Keys)?,
+        ua_hrp: ufvk.ua_hrp().to_string(),
+        account: None,
+        path: None,
+    };
+    write_uivk(cli, &data)
+}
+
+fn write_uivk(cli: &Cli, data: &UivkOut) -> Result<(), AppError> {
+    if cli.json {
+        return write_json_ok(cli, data);
+    }
+    println!("{}", data.uivk);
+    Ok(())
+}
+
+fn cmd_ufvk_inspect(
+    cli: &Cli,
+    settings: &Settings,
+    args: &UfvkInspectArgs,
+) -> Result<(), AppError> {
+    let ufvk = addressbook::resolve(settings, &args.ufvk, EntryKind::Ufvk)?;
+    let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+    audit::note_ufvk(ufvk.as_str());
+
+    #[derive(Serialize)]
+    struct ItemOut<'a> {
+        typecode: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<&'a str>,
+        len: usize,
+    }
+    #[derive(Serialize)]
+    struct InspectOut<'a> {
+        hrp: &'a str,
+        ua_hrp: &'a str,
+        network: Option<&'static str>,
+        zcash: bool,
+        fingerprint: String,
+        items: Vec<ItemOut<'a>>,
+    }
+    let zcash = ufvk.zcash_network();
+    let data = InspectOut {
+        hrp: ufvk.hrp(),
+        ua_hrp: ufvk.ua_hrp(),
+        network: ufvk.network().or(zcash).as_ref().map(Network::as_str),
+        zcash: zcash.is_some(),
+        fingerprint: ufvk.fingerprint(),
+        items: ufvk
+            .items()
+            .iter()
+            .map(|(typecode, value)| ItemOut {
+                typecode: *typecode,
+                name: hooks::typecode_name(settings, *typecode),
+                len: value.len(),
+            })
+            .collect(),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    let network = match (data.network, data.zcash) {
+        (Some(network), true) => format!("{network} (Zcash)"),
+        (Some(network), false) => network.to_string(),
+        (None, _) => "custom".to_string(),
+    };
+    let items = data
+        .items
+        .iter()
+        .map(|item| match item.name {
+            Some(name) => format!("{name} ({}, {} bytes)", item.typecode, item.len),
+            None => format!("{} ({} bytes)", item.typecode, item.len),
+        })
+        .collect::<Vec<_>>();
+    style::fields(&[
+        ("hrp", data.hrp),
+        ("network", &network),
+        ("fingerprint", &data.fingerprint),
+        ("items", &items.join(", ")),
+    ]);
+    Ok(())
+}
+
+// Prints the UFVK of `account` of the seed from `seed`, for `command`.
+fn write_ufvk(
+    cli: &Cli,
+    settings: &Settings,
+    command: &str,
+    network: Option<NetworkArg>,
+    account: u32,
+    seed: impl FnOnce() -> Result<Seed, AppError>,
+) -> Result<(), AppError> {
+    #[derive(Serialize, Deserialize)]
+    struct UfvkOut {
+        ufvk: String,
+        ua_hrp: String,
+        coin_type: u32,
+        account: u32,
+        path: String,
+        seed_fingerprint: String,
+        ufvk_fingerprint: String,
+    }
+    let data = isolate::run(cli, || {
+        let seed = seed()?;
+        let net = resolve_network(settings, network)?;
+        let params = network_params(cli, net);
+        let ufvk =
+            juno_keys::ufvk_from_seed(&seed, params.as_ref(), account).map_err(AppError::Keys)?;
+        audit::note_seed(&seed);
+        audit::note_ufvk(&ufvk);
+
+        Ok(UfvkOut {
+            ua_hrp: params.ua_hrp().to_string(),
+            coin_type: params.coin_type(),
+            account,
+            path: ZipPath::account(params.coin_type(), account)
+                .map_err(AppError::Keys)?
+                .to_string(),
+            seed_fingerprint: juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?,
+            ufvk_fingerprint: juno_keys::ufvk_fingerprint(&ufvk).map_err(AppError::Keys)?,
+            ufvk,
+        })
+    })?;
+    hooks::emit(cli, settings, command, &data)?;
+
+    if cli.json {
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+
+    println!("{}", armor::wrap(cli, armor::UFVK, &data.ufvk).as_str());
+    visual::note(cli, visual::Kind::Ufvk, &data.ufvk);
+    Ok(())
+}
+
+fn cmd_network_detect(cli: &Cli, args: &NetworkDetectArgs) -> Result<(), AppError> {
+    let network = juno_keys::detect_network(&args.value)
+        .map_err(AppError::Keys)?
+        .as_str();
+
+    if cli.json {
+        #[derive(Serialize)]
+        struct NetworkOut {
+            network: &'static str,
+        }
+        write_json_ok(cli, &NetworkOut { network })?;
+        return Ok(());
+    }
+
+    println!("{network}");
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct AddressOut {
+    address: String,
+    ua_hrp: String,
+    diversifier_index: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+}
+
+fn cmd_address_from_seed(
+    cli: &Cli,
+    settings: &Settings,
+    args: &AddressFromSeedArgs,
+) -> Result<(), AppError> {
+    receivers::check(&args.receivers)?;
+    if args.account_range.is_some() || args.diversifier_range.is_some() {
+        let accounts = match args.account_range {
+            Some(accounts) => accounts,
+            None => AccountRange::from_count(args.account, 1).map_err(AppError::Keys)?,
+        };
+        let index = u64::try_from(u128::from(args.diversifier_index)).map_err(|_| {
+            AppError::InvalidRequest("give a --diversifier-range below 2^64".into())
+        })?;
+        return ranges::write_addresses(
+            cli,
+            settings,
+            args.network,
+            accounts,
+            args.diversifier_range.clone().unwrap_or(index..index + 1),
+            args.ndjson,
+            || resolve_seed(settings, &args.seed_file, &args.seed_base64),
+        );
+    }
+    let data = isolate::run(cli, || {
+        let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+        let net = resolve_network(settings, args.network)?;
+        let params = network_params(cli, net);
+        let keys = juno_keys::DerivationRequest::new(&seed)
+            .network(params.as_ref())
+            .account(args.account)
+            .diversifier_index(args.diversifier_index)
+            .build()
+            .map_err(AppError::Keys)?;
+        audit::note_seed(&seed);
+        audit::note_ufvk(keys.ufvk.as_str());
+
+        Ok(AddressOut {
+            address: keys.address.to_string(),
+            ua_hrp: params.ua_hrp().to_string(),
+            diversifier_index: keys.diversifier_index.into(),
+            account: Some(keys.account),
+            path: Some(keys.path.to_string()),
+        })
+    })?;
+    hooks::emit(cli, settings, "address from-seed", &data)?;
+    write_address(cli, &data)
+}
+
+fn cmd_address_from_ufvk(
+    cli: &Cli,
+    settings: &Settings,
+    args: &AddressFromUfvkArgs,
+) -> Result<(), AppError> {
+    receivers::check(&args.receivers)?;
+    let ufvk = addressbook::resolve(settings, &args.ufvk, EntryKind::Ufvk)?;
+    let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+    let address = ufvk
+        .address_at(args.diversifier_index)
+        .map_err(AppError::Keys)?;
+    audit::note_ufvk(ufvk.as_str());
+    let data = AddressOut {
+        address: address.to_string(),
+        ua_hrp: ufvk.ua_hrp().to_string(),
+        diversifier_index: args.diversifier_index.into(),
+        account: None,
+        path: None,
+    };
+    write_address(cli, &data)
+}
+
+fn write_address(cli: &Cli, data: &AddressOut) -> Result<(), AppError> {
+    if cli.json {
+        return write_json_ok(cli, data);
+    }
+    println!("{}", data.address);
+    visual::note(cli, visual::Kind::Address, &data.address);
+    Ok(())
+}
+
+fn cmd_sign_message(
+    cli: &Cli,
+    settings: &Settings,
+    args: &SignMessageArgs,
+) -> Result<(), AppError> {
+    let message = read_message(&args.message, &args.message_file)?;
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    let net = resolve_network(settings, args.network)?;
+    let params = network_params(cli, net);
+    let keys =
+        OrchardKeySet::from_seed(&seed, params.as_ref(), args.account).map_err(AppError::Keys)?;
+    let ufvk = keys.ufvk(params.as_ref()).map_err(AppError::Keys)?;
+    let signed = keys.sign_message(&message);
+    audit::note_seed(&seed);
+    audit::note_ufvk(ufvk.as_str());
+
+    #[derive(Serialize)]
+    struct SignatureOut {
+        ufvk: String,
+        account: u32,
+        message_digest: String,
+        spend_validating_key: String,
+        signature: String,
+    }
+    let data = SignatureOut {
+        ufvk: ufvk.to_string(),
+        account: args.account,
+        message_digest: hex::encode(juno_keys::message_digest(&message)),
+        spend_validating_key: hex::encode(signed.spend_validating_key),
+        signature: hex::encode(signed.signature),
+    };
+
+    if cli.json {
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+
+    println!("{}", data.signature);
+    Ok(())
+}
+
+fn cmd_prove_address_ownership(
+    cli: &Cli,
+    settings: &Settings,
+    args: &ProveAddressOwnershipArgs,
+) -> Result<(), AppError> {
+    let address = UnifiedAddress::parse(&args.address).map_err(AppError::Keys)?;
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    let net = resolve_network(settings, args.network)?;
+    let params = network_params(cli, net);
+    let keys =
+        OrchardKeySet::from_seed(&seed, params.as_ref(), args.account).map_err(AppError::Keys)?;
+    let ufvk = keys.ufvk(params.as_ref()).map_err(AppError::Keys)?;
+    let index = ufvk.diversifier_index_of(&address).ok_or_else(|| {
+        AppError::InvalidRequest(format!(
+            "address is not one of account {}'s external addresses",
+            args.account
+        ))
+    })?;
+    let statement = juno_keys::ownership_statement(&address, &args.challenge);
+    let signed = keys.sign_message(statement.as_bytes());
+    audit::note_seed(&seed);
+    audit::note_ufvk(ufvk.as_str());
+
+    #[derive(Serialize)]
+    struct ProofOut {
+        address: String,
+        challenge: String,
+        ufvk: String,
+        diversifier_index: u128,
+        statement: String,
+        spend_validating_key: String,
+        signature: String,
+    }
+    let data = ProofOut {
+        address: address.to_string(),
+        challenge: args.challenge.clone(),
+        ufvk: ufvk.to_string(),
+        diversifier_index: index.into(),
+        statement,
+        spend_validating_key: hex::encode(signed.spend_validating_key),
+        signature: hex::encode(signed.signature),
+    };
+
+    if cli.json {
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+
+    let pretty = serde_json::to_string_pretty(&data)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    println!("{pretty}");
+    Ok(())
+}
+
+fn cmd_verify_message(
+    cli: &Cli,
+    settings: &Settings,
+    args: &VerifyMessageArgs,
+) -> Result<(), AppError> {
+    let message = read_message(&args.message, &args.message_file)?;
+    let ufvk = addressbook::resolve(settings, &args.ufvk, EntryKind::Ufvk)?;
+    let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+    let signature = hex::decode(args.signature.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+        .ok_or_else(|| AppError::InvalidRequest("signature must be 64 bytes of hex".into()))?;
+    // The address is only tied to the signer through the UFVK.
+    let diversifier_index = match &args.address {
+        Some(address) => {
+            let address = addressbook::resolve(settings, address, EntryKind::Address)?;
+            let address = UnifiedAddress::parse(&address).map_err(AppError::Keys)?;
+            let index = ufvk
+                .diversifier_index_of(&address)
+                .ok_or(AppError::Keys(KeysError::SignatureInvalid))?;
+            Some(u128::from(index))
+        }
+        None => None,
+    };
+    ufvk.verify_message(&message, &signature)
+        .map_err(AppError::Keys)?;
+    audit::note_ufvk(ufvk.as_str());
+
+    #[derive(Serialize)]
+    struct VerifyOut {
+        valid: bool,
+        ufvk_fingerprint: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        diversifier_index: Option<u128>,
+    }
+    let data = VerifyOut {
+        valid: true,
+        ufvk_fingerprint: ufvk.fingerprint(),
+        diversifier_index,
+    };
+
+    if cli.json {
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+
+    println!("valid");
+    Ok(())
+}
+
+// The message for `sign message` and `verify message`, from --message or --message-file.
+fn read_message(text: &Option<String>, file: &Option<PathBuf>) -> Result<Vec<u8>, AppError> {
+    match (text, file) {
+        (Some(text), _) => Ok(text.as_bytes().to_vec()),
+        (None, Some(path)) => {
+            fs::read(path).map_err(|e| AppError::Io(format!("read message file: {e}")))
+        }
+        (None, None) => Err(AppError::InvalidRequest(
+            "missing message (set --message or --message-file)".into(),
+        )),
+    }
+}
+
+fn cmd_derive(cli: &Cli, settings: &Settings, args: &DeriveArgs) -> Result<(), AppError> {
+    #[derive(Serialize, Deserialize)]
+    struct DeriveOut {
+        path: String,
+        ua_hrp: String,
+        coin_type: u32,
+        account: u32,
+        ufvk: String,
+        uivk: String,
+        diversifier_index: u128,
+        address: String,
+        descriptor: String,
+    }
+    let descriptor = args
+        .descriptor
+        .as_deref()
+        .map(descriptor::parse)
+        .transpose()?;
+    let data = isolate::run(cli, || {
+        let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+        let (net, path) = match &descriptor {
+            Some(descriptor) => (descriptor.network, descriptor.path.clone()),
+            None => {
+                let policy = if args.harden_unmarked {
+                    HardeningPolicy::HardenUnmarked
+                } else {
+                    HardeningPolicy::HardenedOnly
+                };
+                let path =
+                    ZipPath::parse_with_policy(args.path.as_deref().unwrap_or_default(), policy)
+                        .map_err(AppError::Keys)?;
+                (resolve_network(settings, args.network)?, path)
+            }
+        };
+        let params = network_params(cli, net);
+        let keys = juno_keys::DerivationRequest::new(&seed)
+            .network(params.as_ref())
+            .path(path)
+            .diversifier_index(args.diversifier_index)
+            .build()
+            .map_err(AppError::Keys)?;
+        if let Some(descriptor) = &descriptor {
+            descriptor::check_derived(descriptor, &seed, &keys)?;
+        }
+        audit::note_seed(&seed);
+        audit::note_ufvk(keys.ufvk.as_str());
+
+        Ok(DeriveOut {
+            descriptor: WalletDescriptor::from_keys(net, &keys, Some(&seed))
+                .map_err(AppError::Keys)?
+                .to_string(),
+            path: keys.path.to_string(),
+            ua_hrp: params.ua_hrp().to_string(),
+            coin_type: keys.coin_type,
+            account: keys.account,
+            ufvk: keys.ufvk.to_string(),
+            uivk: keys.uivk,
+            diversifier_index: keys.diversifier_index.into(),
+            address: keys.address.to_string(),
+        })
+    })?;
+    hooks::emit(cli, settings, "derive", &data)?;
+
+    if cli.json {
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+
+    style::fields(&[
+        ("path", &data.path),
+        ("ufvk", &data.ufvk),
+        ("uivk", &data.uivk),
+        ("address", &data.address),
+        ("descriptor", &data.descriptor),
+    ]);
+    visual::note(cli, visual::Kind::Ufvk, &data.ufvk);
+    visual::note(cli, visual::Kind::Address, &data.address);
+    Ok(())
+}
+
+fn cmd_derive_app_key(
+    cli: &Cli,
+    settings: &Settings,
+    args: &DeriveAppKeyArgs,
+) -> Result<(), AppError> {
+    let should_print = args.print || args.out.is_none();
+    if should_print {
+        let net = args.network.map(Network::from).or(settings.network);
+        ensure_mainnet_ok(cli, settings, net)?;
+    }
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    let path = ZipPath::parse(&args.path).map_err(AppError::Keys)?;
+    let key = juno_keys::app_key(&seed, &args.context, &path).map_err(AppError::Keys)?;
+    audit::note_seed(&seed);
+    let key_hex = Zeroizing::new(hex::encode(*key));
+
+    let out = match &args.out {
+        Some(out) => {
+            let out = outpath::expand(
+                out,
+                &outpath::OutVars {
+                    fingerprint: Some(juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?),
+                    network: args.network.map(Network::from).or(settings.network),
+                    account: None,
+                },
+            )?;
+            let line = Zeroizing::new(format!(
+                "{}\n",
+                armor::wrap(cli, armor::APP_KEY, &key_hex).as_str()
+            ));
+            write_secret_file(&out, &line, args.force)?;
+            Some(out)
+        }
+        None => None,
+    };
+
+    #[derive(Serialize)]
+    struct AppKeyOut<'a> {
+        context: &'a str,
+        path: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        out_path: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        key_hex: Option<&'a str>,
+    }
+    let data = AppKeyOut {
+        context: &args.context,
+        path: path.to_string(),
+        out_path: out.map(|p| p.display().to_string()),
+        key_hex: should_print.then_some(key_hex.as_str()),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    match (data.key_hex, data.out_path) {
+        (Some(key_hex), _) => {
+            reveal::print_secret(cli, &armor::wrap(cli, armor::APP_KEY, key_hex))?
+        }
+        (None, Some(out_path)) => println!("{out_path}"),
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+fn cmd_convert_from_zcash(cli: &Cli, args: &ConvertFromZcashArgs) -> Result<(), AppError> {
+    if !args.yes {
+        return Err(AppError::InvalidRequest(
+            "conversion keeps Zcash-derived keys under a Juno encoding; pass --yes to confirm"
+                .to_string(),
+        ));
+    }
+
+    let converted = juno_keys::convert_from_zcash(&args.encoded).map_err(AppError::Keys)?;
+
+    if cli.json {
+        #[derive(Serialize)]
+        struct ConvertOut {
+            encoded: String,
+            kind: &'static str,
+            network: &'static str,
+            typecodes: Vec<u64>,
+        }
+        let data = ConvertOut {
+            kind: if converted.is_ufvk { "ufvk" } else { "ua" },
+            network: converted.network.as_str(),
+            typecodes: converted.typecodes,
+            encoded: converted.encoded,
+        };
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+
+    println!("{}", converted.encoded);
+    Ok(())
+}
+
+fn cmd_fixtures_generate(cli: &Cli, args: &FixturesGenerateArgs) -> Result<(), AppError> {
+    #[derive(Serialize)]
+    struct AddressOut {
+        diversifier_index: u32,
+        address: String,
+    }
+    #[derive(Serialize)]
+    struct AccountOut {
+        account: u32,
+        path: String,
+        ufvk: String,
+        ufvk_fingerprint: String,
+        uivk: String,
+        addresses: Vec<AddressOut>,
+    }
+    #[derive(Serialize)]
+    struct WalletOut {
+        seed_base64: String,
+        seed_fingerprint: String,
+        accounts: Vec<AccountOut>,
+    }
+    #[derive(Serialize)]
+    struct Bundle {
+        network: &'static str,
+        ua_hrp: &'static str,
+        coin_type: u32,
+        wallets: Vec<WalletOut>,
+    }
+
+    if args.seeds == 0 || args.accounts == 0 || args.addresses == 0 {
+        return Err(AppError::InvalidRequest(
+            "--seeds, --accounts and --addresses must be at least 1".to_string(),
+        ));
+    }
+
+    // Fixtures are regtest-only: their seeds are derived from a public label and are
+    // therefore never safe to fund on a real network.
+    let net = Network::Regtest;
+    let mut wallets = Vec::new();
+    for index in 0..args.seeds {
+        let seed = Seed::from_bytes(&fixture_seed(index)).map_err(AppError::Keys)?;
+
+        let mut accounts = Vec::new();
+        for account in AccountRange::from_count(0, args.accounts).map_err(AppError::Keys)? {
+            // Derived once per account; addresses come from the parsed UFVK.
+            let keys = juno_keys::DerivationRequest::new(&seed)
+                .network(net)
+                .account(account)
+                .build()
+                .map_err(AppError::Keys)?;
+            let generator = keys.ufvk.address_generator().map_err(AppError::Keys)?;
+            let addresses = (0..args.addresses)
+                .map(|diversifier_index| {
+                    Ok(AddressOut {
+                        diversifier_index,
+                        address: generator.address_at(diversifier_index)?.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<_>, KeysError>>()
+                .map_err(AppError::Keys)?;
+            accounts.push(AccountOut {
+                account,
+                path: keys.path.to_string(),
+                ufvk_fingerprint: keys.ufvk.fingerprint(),
+                uivk: keys.uivk,
+                ufvk: keys.ufvk.to_string(),
+                addresses,
+            });
+        }
+
+        wallets.push(WalletOut {
+            seed_fingerprint: juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?,
+            seed_base64: seed.to_base64().expose_secret().to_string(),
+            accounts,
+        });
+    }
+
+    let bundle = Bundle {
+        network: net.as_str(),
+        ua_hrp: net.ua_hrp(),
+        coin_type: net.coin_type(),
+        wallets,
+    };
+
+    if let Some(out) = &args.out {
+        let mut contents = serde_json::to_string_pretty(&bundle)
+            .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+        contents.push('\n');
+        write_secret_file(out, &contents, args.force)?;
+        if cli.json {
+            #[derive(Serialize)]
+            struct FixturesOut {
+                out_path: String,
+            }
+            write_json_ok(
+                cli,
+                &FixturesOut {
+                    out_path: out.display().to_string(),
+                },
+            )?;
+        } else {
+            println!("{}", out.display());
+        }
+        return Ok(());
+    }
+
+    if cli.json {
+        write_json_ok(cli, &bundle)?;
+        return Ok(());
+    }
+
+    let pretty = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    println!("{pretty}");
+    Ok(())
+}
+
+fn cmd_selftest(cli: &Cli) -> Result<(), AppError> {
+    let checks = juno_keys::selftest::run();
+    let failed = checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| c.name)
+        .collect::<Vec<_>>();
+
+    if cli.json {
+        if failed.is_empty() {
+            #[derive(Serialize)]
+            struct CheckOut {
+                name: &'static str,
+                passed: bool,
+            }
+            #[derive(Serialize)]
+            struct SelftestOut {
+                checks: Vec<CheckOut>,
+            }
+            write_json_ok(
+                cli,
+                &SelftestOut {
+                    checks: checks
+                        .iter()
+                        .map(|c| CheckOut {
+                            name: c.name,
+                            passed: c.passed,
+                        })
+                        .collect(),
+                },
+            )?;
+        }
+    } else {
+        for c in &checks {
+            let status = if c.passed {
+                style::paint(Stream::Stdout, Paint::Ok, "ok  ")
+            } else {
+                style::paint(Stream::Stdout, Paint::Fail, "FAIL")
+            };
+            println!("{status} {}", c.name);
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(AppError::SelftestFailed(failed.join(", ")));
+    }
+    Ok(())
+}
+
+fn cmd_testvectors_zip316(cli: &Cli) -> Result<(), AppError> {
+    #[derive(Serialize)]
+    struct ItemOut {
+        typecode: u64,
+        hex: String,
+    }
+    #[derive(Serialize)]
+    struct VectorOut {
+        hrp: String,
+        items: Vec<ItemOut>,
+        encoded: String,
+    }
+    #[derive(Serialize)]
+    struct VectorsOut {
+        vectors: Vec<VectorOut>,
+    }
+
+    let mut hrps = Vec::new();
+    for net in Network::ALL {
+        hrps.push(net.ua_hrp().to_string());
+        hrps.push(net.ufvk_hrp().to_string());
+        hrps.push(juno_keys::uivk_hrp_from_ua_hrp(net.ua_hrp()).map_err(AppError::Keys)?);
+    }
+    let hrps = hrps.iter().map(String::as_str).collect::<Vec<_>>();
+    let vectors = juno_keys::zip316::test_vectors(&hrps).map_err(AppError::Zip316)?;
+
+    let out = VectorsOut {
+        vectors: vectors
+            .into_iter()
+            .map(|v| VectorOut {
+                hrp: v.hrp,
+                items: v
+                    .items
+                    .iter()
+                    .map(|(typecode, value)| ItemOut {
+                        typecode: *typecode,
+                        hex: hex::encode(value),
+                    })
+                    .collect(),
+                encoded: v.encoded,
+            })
+            .collect(),
+    };
+
+    if cli.json {
+        write_json_ok(cli, &out)?;
+        return Ok(());
+    }
+
+    let pretty = serde_json::to_string_pretty(&out)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    println!("{pretty}");
+    Ok(())
+}
+
+// The seeds are the public `fixtures generate` seeds, so the spending keys are only
+// fit for checking implementations.
+fn cmd_testvectors_zip32(
+    cli: &Cli,
+    settings: &Settings,
+    args: &Zip32VectorsArgs,
+) -> Result<(), AppError> {
+    let net = resolve_network(settings, args.network)?;
+    if cli.json && matches!(args.format, VectorFormat::Csv) {
+        return Err(AppError::InvalidRequest(
+            "--format csv can't be combined with --json".into(),
+        ));
+    }
+
+    // Fields in alphabetical order, as the JSON has always listed them.
+    #[derive(Serialize)]
+    struct VectorOut {
+        address: String,
+        address_raw: String,
+        diversifier_index: u32,
+        fvk: String,
+        path: String,
+        seed: String,
+        sk: String,
+        ufvk: String,
+    }
+    // Rows run seed by seed, account by account, address by address, and are streamed
+    // as they are computed; see `batch`.
+    let (accounts, addresses) = (u64::from(args.accounts), u64::from(args.addresses));
+    let count = u64::from(args.seeds) * accounts * addresses;
+    let produce = |cached: &mut Option<((u32, u32), OrchardKeySet, Ufvk)>, row: u64| {
+        let seed_index = (row / (accounts * addresses)) as u32;
+        let account = (row / addresses % accounts) as u32;
+        let diversifier_index = (row % addresses) as u32;
+        let seed = fixture_seed(seed_index);
+        let params = network_params(cli, net);
+        // A worker walks an account's addresses in a row, so its keys are derived once.
+        if cached.as_ref().map(|(id, ..)| *id) != Some((seed_index, account)) {
+            let keys = OrchardKeySet::from_seed(&seed, params.as_ref(), account)
+                .map_err(AppError::Keys)?;
+            let ufvk = keys.ufvk(params.as_ref()).map_err(AppError::Keys)?;
+            *cached = Some(((seed_index, account), keys, ufvk));
+        }
+        let Some((_, keys, ufvk)) = cached.as_ref() else {
+            unreachable!("derived above")
+        };
+        let address = ufvk.address_at(diversifier_index).map_err(AppError::Keys)?;
+        Ok(VectorOut {
+            seed: hex::encode(seed),
+            path: format!("m/32'/{}'/{account}'", params.coin_type()),
+            sk: hex::encode(keys.to_bytes().as_ref()),
+            fvk: hex::encode(keys.full_viewing_key().to_bytes()),
+            ufvk: ufvk.to_string(),
+            diversifier_index,
+            address_raw: address
+                .orchard()
+                .map(|a| hex::encode(a.to_raw_address_bytes()))
+                .unwrap_or_default(),
+            address: address.to_string(),
+        })
+    };
+
+    match args.format {
+        VectorFormat::Json => {
+            let mut vectors = batch::JsonArray::start(cli, "vectors")?;
+            batch::ordered(count, produce, |v| vectors.push(&v))?;
+            vectors.finish()
+        }
+        VectorFormat::Csv => {
+            let mut out = io::BufWriter::new(io::stdout().lock());
+            let write_err = |e: io::Error| AppError::Io(format!("write stdout: {e}"));
+            writeln!(
+                out,
+                "seed,path,sk,fvk,ufvk,diversifier_index,address_raw,address"
+            )
+            .map_err(write_err)?;
+            batch::ordered(count, produce, |v| {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{}",
+                    v.seed,
+                    v.path,
+                    v.sk,
+                    v.fvk,
+                    v.ufvk,
+                    v.diversifier_index,
+                    v.address_raw,
+                    v.address
+                )
+                .map_err(write_err)
+            })?;
+            out.flush().map_err(write_err)
+        }
+    }
+}
+
+fn cmd_testvectors_conformance(cli: &Cli) -> Result<(), AppError> {
+    let out = conformance::suite()?;
+    if cli.json {
+        write_json_ok(cli, &out)?;
+        return Ok(());
+    }
+
+    let pretty = serde_json::to_string_pretty(&out)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    println!("{pretty}");
+    Ok(())
+}
+
+fn cmd_zip316_decode(
+    cli: &Cli,
+    settings: &Settings,
+    args: &Zip316DecodeArgs,
+) -> Result<(), AppError> {
+    use juno_keys::zip316::{self, DecodeMode};
+
+    let encoded = args.encoded.trim();
+    let Some((hrp, _)) = encoded.rsplit_once('1') else {
+        return Err(AppError::InvalidRequest(
+            "missing bech32m separator".to_string(),
+        ));
+    };
+    let mode = if args.lenient {
+        DecodeMode::Lenient
+    } else {
+        DecodeMode::Strict
+    };
+    let mut decoded =
+        zip316::decode_unified_container_with(hrp, encoded, mode).map_err(AppError::Zip316)?;
+    // Configured receiver types are known here, even though the library isn't aware of them.
+    decoded.warnings.retain(|w| {
+        !matches!(w, zip316::DecodeWarning::UnknownTypecode { typecode, .. }
+            if settings.receiver_types.contains_key(typecode))
+    });
+
+    if cli.json {
+        #[derive(Serialize)]
+        struct ItemOut<'a> {
+            typecode: u64,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            name: Option<&'a str>,
+            len: usize,
+            hex: String,
+        }
+        #[derive(Serialize)]
+        struct WarningOut {
+            code: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            typecode: Option<u64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            hex: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            reason: Option<String>,
+        }
+        #[derive(Serialize)]
+        struct DecodeOut<'a> {
+            hrp: &'a str,
+            items: Vec<ItemOut<'a>>,
+            warnings: Vec<WarningOut>,
+        }
+        write_json_ok(
+            cli,
+            &DecodeOut {
+                hrp,
+                items: decoded
+                    .items
+                    .iter()
+                    .map(|(typecode, value)| ItemOut {
+                        typecode: *typecode,
+                        name: hooks::typecode_name(settings, *typecode),
+                        len: value.len(),
+                        hex: hex::encode(value),
+                    })
+                    .collect(),
+                warnings: decoded
+                    .warnings
+                    .iter()
+                    .map(|w| match w {
+                        zip316::DecodeWarning::UnknownTypecode { typecode, value } => WarningOut {
+                            code: w.code(),
+                            typecode: Some(*typecode),
+                            hex: Some(hex::encode(value)),
+                            reason: None,
+                        },
+                        zip316::DecodeWarning::NonCanonical { reason } => WarningOut {
+                            code: w.code(),
+                            typecode: None,
+                            hex: None,
+                            reason: Some(reason.clone()),
+                        },
+                        _ => WarningOut {
+                            code: w.code(),
+                            typecode: None,
+                            hex: None,
+                            reason: None,
+                        },
+                    })
+                    .collect(),
+            },
+        )?;
+        return Ok(());
+    }
+
+    for (typecode, value) in &decoded.items {
+        println!("{typecode} {}", hex::encode(value));
+    }
+    for warning in &decoded.warnings {
+        style::warning(warning.code());
+    }
+    Ok(())
+}
+
+fn cmd_zip316_jumble(cli: &Cli, args: &JumbleArgs, inverse: bool) -> Result<(), AppError> {
+    // Maximum-size payloads are too long for argv, so allow piping them in.
+    let mut message = if args.hex == "-" {
+        let mut s = String::new();
+        io::stdin()
+            .read_to_string(&mut s)
+            .map_err(|e| AppError::Io(format!("read stdin: {e}")))?;
+        decode_hex_arg(&s)?
+    } else {
+        decode_hex_arg(&args.hex)?
+    };
+    if inverse {
+        juno_keys::zip316::f4jumble_inv_mut(&mut message)
+    } else {
+        juno_keys::zip316::f4jumble_mut(&mut message)
+    }
+    .map_err(|e| AppError::InvalidRequest(e.to_string()))?;
+    let out = hex::encode(&message);
+
+    if cli.json {
+        #[derive(Serialize)]
+        struct JumbleOut {
+            hex: String,
+            len: usize,
+        }
+        write_json_ok(
+            cli,
+            &JumbleOut {
+                len: message.len(),
+                hex: out,
+            },
+        )?;
+        return Ok(());
+    }
+
+    println!("{out}");
+    Ok(())
+}
+
+fn cmd_bech32m_encode(cli: &Cli, args: &Bech32mEncodeArgs) -> Result<(), AppError> {
+    let data = decode_hex_arg(&args.hex)?;
+    let encoded = juno_keys::zip316::bech32m_encode(&args.hrp, &data)
+        .map_err(|e| AppError::InvalidRequest(e.to_string()))?;
+
+    if cli.json {
+        #[derive(Serialize)]
+        struct EncodeOut {
+            encoded: String,
+        }
+        write_json_ok(cli, &EncodeOut { encoded })?;
+        return Ok(());
+    }
+
+    println!("{encoded}");
+    Ok(())
+}
+
+fn cmd_bech32m_decode(cli: &Cli, args: &Bech32mDecodeArgs) -> Result<(), AppError> {
+    let (hrp, data) = juno_keys::zip316::bech32m_decode(&args.encoded).map_err(AppError::Zip316)?;
+
+    if cli.json {
+        #[derive(Serialize)]
+        struct DecodeOut {
+            hrp: String,
+            hex: String,
+            len: usize,
+        }
+        write_json_ok(
+            cli,
+            &DecodeOut {
+                hrp,
+                len: data.len(),
+                hex: hex::encode(data),
+            },
+        )?;
+        return Ok(());
+    }
+
+    println!("{hrp} {}", hex::encode(data));
+    Ok(())
+}
+
+fn decode_hex_arg(s: &str) -> Result<Vec<u8>, AppError> {
+    let s = s.trim();
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    hex::decode(s).map_err(|e| AppError::InvalidRequest(format!("invalid hex: {e}")))
+}
+
+fn fixture_seed(index: u32) -> [u8; 64] {
+    let mut seed = [0u8; 64];
+    seed.copy_from_slice(juno_keys::vectors::deterministic_seed(index).as_bytes());
+    seed
+}
+
+fn load_settings(source: &ConfigSource) -> Result<Settings, AppError> {
+    let cfg = load_config(source)?;
+    resolve_settings(
+        &cfg,
+        source.profile.as_deref(),
+        env_overrides()?,
+        source.assert_offline,
+    )
+}
+
+// `cfg` with `profile` (or its default profile) and then `env` overlaid, checked.
+fn resolve_settings(
+    cfg: &Config,
+    profile: Option<&str>,
+    env: ProfileConfig,
+    assert_offline: bool,
+) -> Result<Settings, AppError> {
+    let mut merged = cfg.base.clone();
+    if let Some(name) = profile.or(cfg.default_profile.as_deref()) {
+        let profile = cfg
+            .profiles
+            .get(name)
+            .ok_or_else(|| AppError::Config(format!("unknown profile: {name}")))?;
+        merged.network = profile.network.clone().or(merged.network);
+        merged.keystore = profile.keystore.clone().or(merged.keystore);
+        merged.mainnet_ok = profile.mainnet_ok.or(merged.mainnet_ok);
+        merged.output = profile.output.clone().or(merged.output);
+        merged.assert_offline = profile.assert_offline.or(merged.assert_offline);
+        merged.harden = profile.harden.or(merged.harden);
+        merged.strict = profile.strict.or(merged.strict);
+        merged.rate_limit = profile.rate_limit.or(merged.rate_limit);
+        merged.sink = profile.sink.clone().or(merged.sink);
+        merged.audit = profile.audit.clone().or(merged.audit);
+        merged.addressbook = profile.addressbook.clone().or(merged.addressbook);
+        merged.kdf_memory_mib = profile.kdf_memory_mib.or(merged.kdf_memory_mib);
+        merged.kdf_passes = profile.kdf_passes.or(merged.kdf_passes);
+        merged.seed_credential = profile.seed_credential.clone().or(merged.seed_credential);
+    }
+
+    merged.network = env.network.or(merged.network);
+    merged.keystore = env.keystore.or(merged.keystore);
+    merged.mainnet_ok = env.mainnet_ok.or(merged.mainnet_ok);
+    merged.output = env.output.or(merged.output);
+    merged.assert_offline = env.assert_offline.or(merged.assert_offline);
+    merged.harden = env.harden.or(merged.harden);
+    merged.strict = env.strict.or(merged.strict);
+    merged.rate_limit = env.rate_limit.or(merged.rate_limit);
+    merged.sink = env.sink.or(merged.sink);
+    merged.audit = env.audit.or(merged.audit);
+    merged.addressbook = env.addressbook.or(merged.addressbook);
+    merged.kdf_memory_mib = env.kdf_memory_mib.or(merged.kdf_memory_mib);
+    merged.kdf_passes = env.kdf_passes.or(merged.kdf_passes);
+    merged.seed_credential = env.seed_credential.or(merged.seed_credential);
+    if merged.rate_limit == Some(0) {
+        return Err(AppError::Config(
+            "rate_limit must be at least 1".to_string(),
+        ));
+    }
+    kdf::check_config(merged.kdf_memory_mib, merged.kdf_passes)?;
+
+    let json = match merged.output.as_deref() {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(other) => return Err(AppError::Config(format!("unknown output format: {other}"))),
+    };
+    let audit = merged
+        .audit
+        .map(|a| {
+            AuditSink::from_str(&a, true)
+                .map_err(|_| AppError::Config(format!("unknown audit sink: {a}")))
+        })
+        .transpose()?;
+    let network = merged
+        .network
+        .map(|n| {
+            n.parse::<Network>()
+                .map_err(|_| AppError::Config(format!("unknown network: {n}")))
+        })
+        .transpose()?;
+    Ok(Settings {
+        network,
+        keystore: merged.keystore,
+        mainnet_ok: merged.mainnet_ok.unwrap_or(false),
+        json,
+        assert_offline: assert_offline || merged.assert_offline.unwrap_or(false),
+        harden: merged.harden.unwrap_or(false),
+        strict: merged.strict.unwrap_or(false),
+        rate_limit: merged.rate_limit,
+        sink: merged.sink,
+        receiver_types: hooks::receiver_types(&cfg.receiver_types)?,
+        audit,
+        addressbook: merged.addressbook,
+        kdf_memory_mib: merged.kdf_memory_mib,
+        kdf_passes: merged.kdf_passes,
+        seed_credential: merged.seed_credential,
+        seed_passphrase_file: None,
+        seed_input: None,
+    })
+}
+
+// Every config key can be overridden by `JUNO_KEYS_<KEY>`; see "Configuration" in the README.
+fn env_overrides() -> Result<ProfileConfig, AppError> {
+    let var = |key: &str| std::env::var(format!("JUNO_KEYS_{key}")).ok();
+    let bool_var = |key: &str| {
+        var(key)
+            .map(|v| match v.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Ok(true),
+                "0" | "false" | "no" | "off" | "" => Ok(false),
+                _ => Err(AppError::Config(format!(
+                    "JUNO_KEYS_{key}: invalid bool: {v}"
+                ))),
+            })
+            .transpose()
+    };
+    let number_var = |key: &str| {
+        var(key)
+            .map(|v| {
+                v.trim()
+                    .parse()
+                    .map_err(|_| AppError::Config(format!("JUNO_KEYS_{key}: invalid number: {v}")))
+            })
+            .transpose()
+    };
+    Ok(ProfileConfig {
+        network: var("NETWORK"),
+        keystore: var("KEYSTORE").map(PathBuf::from),
+        mainnet_ok: bool_var("MAINNET_OK")?,
+        output: var("OUTPUT"),
+        assert_offline: bool_var("ASSERT_OFFLINE")?,
+        harden: bool_var("HARDEN")?,
+        strict: bool_var("STRICT")?,
+        rate_limit: number_var("RATE_LIMIT")?,
+        sink: var("SINK").map(PathBuf::from),
+        audit: var("AUDIT"),
+        addressbook: var("ADDRESSBOOK").map(PathBuf::from),
+        kdf_memory_mib: number_var("KDF_MEMORY_MIB")?,
+        kdf_passes: number_var("KDF_PASSES")?,
+        seed_credential: var("SEED_CREDENTIAL"),
+    })
+}
+
+fn ensure_offline() -> Result<(), AppError> {
+    let up = active_network_interfaces()?;
+    if up.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::NotOffline(format!(
+        "network interfaces are up: {}",
+        up.join(", ")
+    )))
+}
+
+#[cfg(target_os = "linux")]
+fn active_network_interfaces() -> Result<Vec<String>, AppError> {
+    const ARPHRD_LOOPBACK: &str = "772";
+    const IFF_UP: u32 = 0x1;
+
+    let read = |path: PathBuf| {
+        fs::read_to_string(&path)
+            .map(|v| v.trim().to_string())
+            .map_err(|e| AppError::NotOffline(format!("read {}: {e}", path.display())))
+    };
+
+    let entries = fs::read_dir("/sys/class/net")
+        .map_err(|e| AppError::NotOffline(format!("list network interfaces: {e}")))?;
+    let mut up = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::NotOffline(format!("list interfaces: {e}")))?;
+        let dir = entry.path();
+        if read(dir.join("type"))? == ARPHRD_LOOPBACK {
+            continue;
+        }
+        let flags = read(dir.join("flags"))?;
+        let flags = u32::from_str_radix(flags.trim_start_matches("0x"), 16)
+            .map_err(|_| AppError::NotOffline(format!("unreadable flags for {}", dir.display())))?;
+        if flags & IFF_UP != 0 {
+            up.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    up.sort();
+    Ok(up)
+}
+
+// Fail closed where we can't enumerate interfaces.
+#[cfg(not(target_os = "linux"))]
+fn active_network_interfaces() -> Result<Vec<String>, AppError> {
+    Err(AppError::NotOffline(
+        "--assert-offline is only supported on Linux".to_string(),
+    ))
+}
+
+fn diversifier_index_arg(s: &str) -> Result<DiversifierIndex, String> {
+    juno_keys::parse_diversifier_index(s)
+        .map_err(|_| "expected a decimal or 0x-prefixed hex index below 2^88".to_string())
+}
+
+// A JSON number where one fits, else a decimal string; `Value` numbers stop at 2^64.
+fn diversifier_index_json(index: DiversifierIndex) -> serde_json::Value {
+    let index = u128::from(index);
+    u64::try_from(index).map_or_else(|_| index.to_string().into(), Into::into)
+}
+
+fn resolve_network(settings: &Settings, arg: Option<NetworkArg>) -> Result<Network, AppError> {
+    arg.map(Network::from).or(settings.network).ok_or_else(|| {
+        AppError::InvalidRequest("missing network (set --network or a profile network)".into())
+    })
+}
+
+fn load_config(source: &ConfigSource) -> Result<Config, AppError> {
+    let (path, explicit) = match &source.config {
+        Some(p) => (p.clone(), true),
+        None => match default_config_path() {
+            Some(p) => (p, false),
+            None => return Ok(Config::default()),
+        },
+    };
+
+    let raw = match fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) if !explicit && e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(AppError::Io(format!("read config: {e}"))),
+    };
+    let invalid = |e: String| AppError::Config(format!("{}: {e}", path.display()));
+    let cfg: Config = toml::from_str(&raw).map_err(|e| invalid(e.to_string()))?;
+    // The config may turn strict mode on for itself, so it is read leniently first.
+    let profile = source
+        .profile
+        .as_deref()
+        .or(cfg.default_profile.as_deref())
+        .and_then(|name| cfg.profiles.get(name));
+    let strict = env_overrides()?
+        .strict
+        .or(profile.and_then(|profile| profile.strict))
+        .or(cfg.base.strict)
+        .unwrap_or(false);
+    if source.strict || strict {
+        let table = raw
+            .parse::<toml::Table>()
+            .map_err(|e| invalid(e.to_string()))?;
+        if let Some((at, message)) = lint::unknown_config_keys(&table).into_iter().next() {
+            return Err(invalid(format!("{at}: {message} (--strict)")));
+        }
+    }
+    Ok(cfg)
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("juno-keys").join("config.toml"))
+}
+
+// Secrets for test networks flow freely; anything that may be mainnet material needs an
+// explicit opt-in before it lands on an interactive terminal.
+fn ensure_mainnet_ok(cli: &Cli, settings: &Settings, net: Option<Network>) -> Result<(), AppError> {
+    let mainnet = matches!(net, None | Some(Network::Mainnet));
+    if !mainnet || cli.mainnet_ok || settings.mainnet_ok || !io::stdout().is_terminal() {
+        return Ok(());
+    }
+    Err(AppError::MainnetInterlock)
+}
+
+fn network_params(cli: &Cli, net: Network) -> Box<dyn NetworkParams> {
+    if let Some(custom) = networks::custom() {
+        Box::new(custom.clone())
+    } else if cli.zcash_compat {
+        Box::new(ZcashNetwork(net))
+    } else {
+        Box::new(net)
+    }
+}
+
+fn resolve_seed(
+    settings: &Settings,
+    seed_file: &Option<PathBuf>,
+    seed_base64: &Option<String>,
+) -> Result<Seed, AppError> {
+    if let Some(input) = settings.seed_input {
+        if seed_file.is_some() || seed_base64.is_some() {
+            return Err(AppError::InvalidRequest(
+                "use either --seed-stdin/--seed-prompt or --seed-file/--seed-base64 (not both)"
+                    .to_string(),
+            ));
+        }
+        return match input {
+            SeedInput::Stdin => read_seed_stdin(settings),
+            SeedInput::Prompt => Seed::from_base64(&prompt::seed()?).map_err(AppError::Keys),
+        };
+    }
+    // Plain base64 is decoded from the text as read (`Seed::from_base64` trims without
+    // copying); only an armored block's body is gathered into a buffer of its own.
+    let seed_b64 = match (seed_file, seed_base64) {
+        (Some(_), Some(_)) => {
+            return Err(AppError::InvalidRequest(
+                "use either --seed-file or --seed-base64 (not both)".to_string(),
+            ))
+        }
+        (Some(p), None) => return read_seed_file(settings, p),
+        (None, Some(s)) => return Seed::from_base64(s).map_err(AppError::Keys),
+        (None, None) => match (&settings.seed_credential, &settings.keystore) {
+            (Some(name), _) => credential::read(name)?,
+            (None, Some(p)) => return read_seed_file(settings, p),
+            (None, None) => {
+                return Err(AppError::InvalidRequest(
+                    "missing seed (set --seed-file, --seed-base64, --seed-stdin, --seed-prompt, --seed-credential or a profile keystore)"
+                        .to_string(),
+                ))
+            }
+        },
+    };
+    Seed::from_base64(&seed_b64).map_err(AppError::Keys)
+}
+
+// `read_to_string` sizes its buffer from the file's length, so the text isn't
+// reallocated (and left behind) as it is read. Encrypted seed files are opened.
+fn read_seed_file(settings: &Settings, path: &Path) -> Result<Seed, AppError> {
+    let raw = Zeroizing::new(
+        fs::read_to_string(path).map_err(|e| AppError::Io(format!("read seed file: {e}")))?,
+    );
+    decode_seed_text(settings, path, &raw)
+}
+
+// Seed text is short, so reserving room for it up front keeps the buffer from being
+// reallocated (and left behind) as stdin is read.
+fn read_seed_stdin(settings: &Settings) -> Result<Seed, AppError> {
+    const MAX_SEED_TEXT: u64 = 4096;
+    let mut raw = Zeroizing::new(String::with_capacity(MAX_SEED_TEXT as usize));
+    io::stdin()
+        .lock()
+        .take(MAX_SEED_TEXT)
+        .read_to_string(&mut raw)
+        .map_err(|e| AppError::Io(format!("read seed from stdin: {e}")))?;
+    decode_seed_text(settings, Path::new("stdin"), &raw)
+}
+
+fn decode_seed_text(settings: &Settings, source: &Path, raw: &str) -> Result<Seed, AppError> {
+    if raw.trim().is_empty() {
+        return Err(AppError::Keys(KeysError::SeedInvalid));
+    }
+    if sealed_seed::is_sealed(raw) {
+        return sealed_seed::open(settings, source, raw);
+    }
+    Seed::from_base64(&armor::unwrap(armor::SEED, raw)?).map_err(AppError::Keys)
+}
+
+fn write_secret_file(path: &Path, contents: &str, force: bool) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent).map_err(|e| AppError::Io(format!("create dir: {e}")))?;
+        }
+    }
+    if force {
+        backup::before_overwrite(path)?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::fs::OpenOptions;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let mut opts = OpenOptions::new();
+        opts.write(true);
+        if force {
+            opts.create(true).truncate(true);
+        } else {
+            opts.create_new(true);
+        }
+        opts.mode(0o600);
+        let mut f = opts
+            .open(path)
+            .map_err(|e| AppError::Io(format!("open file: {e}")))?;
+        f.write_all(contents.as_bytes())
+            .map_err(|e| AppError::Io(format!("write file: {e}")))?;
+    }
+
+    #[cfg(not(unix))]
+    {
+        if !force && path.exists() {
+            return Err(AppError::Io("file exists".to_string()));
+        }
+        fs::write(path, contents).map_err(|e| AppError::Io(format!("write file: {e}")))?;
+    }
+
+    Ok(())
+}
+
+fn write_json_ok<T: Serialize>(cli: &Cli, data: &T) -> Result<(), AppError> {
+    let env = OkEnvelope {
+        version: json_version(),
+        status: "ok",
+        request_id: RequestId(cli.request_id.as_deref()),
+        data,
+    };
+    serde_json::to_writer(io::stdout(), &env)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    println!();
+    Ok(())
+}
+
+fn write_error(cli: &Cli, err: &AppError) {
+    if cli.json {
+        let env = ErrEnvelope {
+            version: json_version(),
+            status: "err",
+            request_id: RequestId(cli.request_id.as_deref()),
+            error: ErrObj {
+                code: err.code().to_string(),
+                exit: json_v2().then(|| err.error_code().into()),
+                message: err.message(),
+                details: err.diagnostic().map(|d| ErrDetails {
+                    stage: d.stage.as_str(),
+                    offset: d.offset,
+                }),
+            },
+        };
+        let _ = serde_json::to_writer(io::stdout(), &env);
+        println!();
+        return;
+    }
+
+    style::error(&err.message());
+}
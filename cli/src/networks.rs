@@ -0,0 +1,151 @@
+//! Custom networks for forks and devnets: `--ua-hrp` with `--coin-type`, or a network
+//! named in a `--network-file` registry and picked with `--custom-network`.
+//!
+//! Like `--zcash-compat`, a custom network only replaces the HRPs and coin type that
+//! keys are derived and encoded under. The built-in network the fork follows is still
+//! what `--network` names, and it alone decides the mainnet interlock, output labels
+//! and file names. A registry entry may name it as `network`, in place of the profile
+//! network. The registry is TOML, one table per network:
+//!
+//! ```toml
+//! [devnet]
+//! ua_hrp = "jdev"
+//! coin_type = 9999
+//! network = "regtest"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use juno_keys::{CustomNetwork, Network, NetworkParams as _};
+use serde::Deserialize;
+
+use crate::{style, AppError, Cli, NetworkArg};
+
+static CUSTOM: OnceLock<CustomNetwork> = OnceLock::new();
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    ua_hrp: String,
+    coin_type: u32,
+    network: Option<NetworkArg>,
+}
+
+/// Applies the command line's custom network, if any, for the rest of the process,
+/// returning the network its registry entry follows. Under `strict`, a coin type
+/// registered in SLIP-44 is an error.
+pub(crate) fn select(cli: &Cli, strict: bool) -> Result<Option<Network>, AppError> {
+    let (custom, follows) = match (&cli.ua_hrp, cli.coin_type, &cli.custom_network) {
+        (Some(ua_hrp), Some(coin_type), _) => (
+            CustomNetwork::new(ua_hrp, coin_type).map_err(AppError::Keys)?,
+            None,
+        ),
+        (_, _, Some(name)) => {
+            let Some(path) = &cli.network_file else {
+                return Err(AppError::InvalidRequest(
+                    "--custom-network needs --network-file".into(),
+                ));
+            };
+            lookup(path, name)?
+        }
+        _ => return Ok(None),
+    };
+    check_collision(&custom, strict)?;
+    let _ = CUSTOM.set(custom);
+    Ok(follows)
+}
+
+/// Warns when the coin type is registered to another coin in SLIP-44; under
+/// `--strict` that is an error instead.
+fn check_collision(custom: &CustomNetwork, strict: bool) -> Result<(), AppError> {
+    let collision =
+        juno_keys::slip44::check_coin_type(custom.coin_type(), strict).map_err(AppError::Keys)?;
+    if let Some(coin) = collision {
+        style::warning(&format!(
+            "coin type {} is registered to {} ({}) in SLIP-44",
+            coin.coin_type, coin.name, coin.symbol
+        ));
+    }
+    Ok(())
+}
+
+/// The custom network in effect, if any.
+pub(crate) fn custom() -> Option<&'static CustomNetwork> {
+    CUSTOM.get()
+}
+
+fn lookup(path: &Path, name: &str) -> Result<(CustomNetwork, Option<Network>), AppError> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| AppError::Io(format!("read {}: {e}", path.display())))?;
+    let mut registry =
+        parse(&raw).map_err(|e| AppError::Config(format!("{}: {e}", path.display())))?;
+    let Some(entry) = registry.remove(name) else {
+        let names = registry.keys().cloned().collect::<Vec<_>>();
+        return Err(AppError::Config(format!(
+            "{}: no network named {name} (it has: {})",
+            path.display(),
+            names.join(", ")
+        )));
+    };
+    let custom = CustomNetwork::new(&entry.ua_hrp, entry.coin_type)
+        .map_err(|e| AppError::Config(format!("{}: [{name}]: {e}", path.display())))?;
+    Ok((custom, entry.network.map(Network::from)))
+}
+
+fn parse(raw: &str) -> Result<BTreeMap<String, Entry>, String> {
+    toml::from_str(raw).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+    use juno_keys::KeysError;
+
+    use super::*;
+
+    #[test]
+    fn registries_parse() {
+        let registry = parse(
+            "[devnet]\nua_hrp = \"jdev\"\ncoin_type = 9999\nnetwork = \"regtest\"\n\n\
+             [staging]\nua_hrp = \"jstage\"\ncoin_type = 9998\n",
+        )
+        .unwrap();
+        assert_eq!(registry.len(), 2);
+        let devnet = &registry["devnet"];
+        assert_eq!((devnet.ua_hrp.as_str(), devnet.coin_type), ("jdev", 9999));
+        assert!(matches!(devnet.network, Some(NetworkArg::Regtest)));
+        assert!(registry["staging"].network.is_none());
+
+        assert!(parse("[devnet]\nua_hrp = \"jdev\"\n").is_err());
+        assert!(parse("[devnet]\nua_hrp = \"jdev\"\ncoin_type = 1\nhrp = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn registered_coin_types_fail_under_strict() {
+        // Ethereum's coin type: a warning normally, an error under --strict.
+        let ethereum = CustomNetwork::new("jdev", 60).expect("custom network");
+        assert!(check_collision(&ethereum, false).is_ok());
+        assert!(matches!(
+            check_collision(&ethereum, true),
+            Err(AppError::Keys(KeysError::CoinTypeRegistered))
+        ));
+
+        // Fails before the network is applied, so no other test sees it.
+        let cli = Cli::parse_from([
+            "juno-keys",
+            "--strict",
+            "--ua-hrp",
+            "jdev",
+            "--coin-type",
+            "60",
+            "selftest",
+        ]);
+        assert!(matches!(
+            select(&cli, cli.strict),
+            Err(AppError::Keys(KeysError::CoinTypeRegistered))
+        ));
+    }
+}
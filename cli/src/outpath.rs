@@ -0,0 +1,135 @@
+//! `--out` templates for commands that write secrets, so batch provisioning can name
+//! each file after what it holds: `--out 'seeds/seed-{fingerprint}-{date}.enc'`.
+//!
+//! `{fingerprint}` is the seed fingerprint, `{network}` and `{account}` what the
+//! command was run for, `{date}` the UTC date (`2026-10-16`) and `{timestamp}` the UTC
+//! time (`20261016T093000Z`). `{{` and `}}` are literal braces. A placeholder the
+//! command can't fill fails before anything is written; an existing file still needs
+//! `--force`, so a template never silently replaces an earlier secret.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use juno_keys::Network;
+
+use crate::AppError;
+
+/// What a command knows about the secret it is writing.
+#[derive(Default)]
+pub(crate) struct OutVars {
+    pub(crate) fingerprint: Option<String>,
+    pub(crate) network: Option<Network>,
+    pub(crate) account: Option<u32>,
+}
+
+/// `template` with its placeholders filled in for now.
+pub(crate) fn expand(template: &Path, vars: &OutVars) -> Result<PathBuf, AppError> {
+    expand_at(template, vars, now())
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// `unix_secs` as `{timestamp}` writes it, e.g. `20261016T093000Z`.
+pub(crate) fn timestamp(unix_secs: u64) -> String {
+    let (y, m, d, hh, mm, ss) = utc(unix_secs);
+    format!("{y:04}{m:02}{d:02}T{hh:02}{mm:02}{ss:02}Z")
+}
+
+fn expand_at(template: &Path, vars: &OutVars, unix_secs: u64) -> Result<PathBuf, AppError> {
+    // Only a UTF-8 path can hold a placeholder.
+    let Some(template) = template.to_str() else {
+        return Ok(template.to_path_buf());
+    };
+    let bad = |what: String| AppError::InvalidRequest(format!("--out template: {what}"));
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if let Some(after) = tail.strip_prefix("{{").or_else(|| tail.strip_prefix("}}")) {
+            out.push_str(&tail[..1]);
+            rest = after;
+            continue;
+        }
+        let end = match tail.strip_prefix('{').and_then(|t| t.find('}')) {
+            Some(end) => end + 1,
+            None => {
+                return Err(bad(
+                    "unmatched brace (write {{ or }} for a literal one)".into()
+                ))
+            }
+        };
+        let name = &tail[1..end];
+        let value = match name {
+            "fingerprint" => vars.fingerprint.clone(),
+            "network" => vars.network.map(|n| n.as_str().to_string()),
+            "account" => vars.account.map(|a| a.to_string()),
+            "date" => {
+                let (y, m, d, ..) = utc(unix_secs);
+                Some(format!("{y:04}-{m:02}-{d:02}"))
+            }
+            "timestamp" => Some(timestamp(unix_secs)),
+            _ => return Err(bad(format!("unknown placeholder {{{name}}}"))),
+        };
+        match value {
+            Some(value) => out.push_str(&value),
+            None => return Err(bad(format!("{{{name}}} isn't known for this command"))),
+        }
+        rest = &tail[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(PathBuf::from(out))
+}
+
+// Civil date from days since 1970-01-01 (Howard Hinnant's `civil_from_days`).
+fn utc(unix_secs: u64) -> (i64, u32, u32, u64, u64, u64) {
+    let (days, secs) = ((unix_secs / 86_400) as i64, unix_secs % 86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day, secs / 3_600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_fill_placeholders() {
+        let vars = OutVars {
+            fingerprint: Some("zip32seedfp1abc".into()),
+            network: Some(Network::Testnet),
+            account: Some(3),
+        };
+        // 2026-10-16 09:30:05 UTC.
+        let at = 1_792_143_005;
+        let expand = |t: &str| expand_at(Path::new(t), &vars, at).map(|p| p.display().to_string());
+        assert_eq!(
+            expand("seeds/seed-{fingerprint}-{date}.enc").unwrap(),
+            "seeds/seed-zip32seedfp1abc-2026-10-16.enc"
+        );
+        assert_eq!(
+            expand("{network}/{account}-{timestamp}{{x}}").unwrap(),
+            "testnet/3-20261016T093005Z{x}"
+        );
+        assert_eq!(expand("plain.seed").unwrap(), "plain.seed");
+        assert!(expand("{nope}").is_err());
+        assert!(expand("{date").is_err());
+        assert!(expand("a}b").is_err());
+        let unknown = expand_at(Path::new("{account}"), &OutVars::default(), at);
+        assert!(unknown.unwrap_err().message().contains("isn't known"));
+
+        assert_eq!(utc(0), (1970, 1, 1, 0, 0, 0));
+        assert_eq!(utc(951_782_400), (2000, 2, 29, 0, 0, 0));
+    }
+}
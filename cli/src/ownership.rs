@@ -0,0 +1,231 @@
+//! `ufvk verify` and `address verify`: whether a UFVK or address handed out came from
+//! the seed, and from which account, by deriving it again.
+//!
+//! A UFVK doesn't record its account, so the accounts of `--account-range` are derived
+//! in turn until one matches. An address needs no diversifier window: its index is
+//! decrypted from the Orchard receiver with each account's viewing key. Either way the
+//! answer is `matches`, true or false; a mismatch isn't an error.
+
+use std::path::PathBuf;
+
+use clap::Args;
+use juno_keys::{AccountRange, Network, Ufvk, UnifiedAddress, ZipPath};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    addressbook, audit, isolate, network_params, ranges, resolve_seed, write_json_ok, AppError,
+    Cli, EntryKind, NetworkArg, Settings,
+};
+
+#[derive(Args)]
+pub(crate) struct UfvkVerifyArgs {
+    #[arg(long, help = "UFVK to check (jview1... or @label)")]
+    pub(crate) ufvk: String,
+
+    #[arg(long, help = "Read seed base64 from a file")]
+    pub(crate) seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    pub(crate) seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network whose coin type to derive under (default: the UFVK's, else the profile network)"
+    )]
+    pub(crate) network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        default_value = "0..100",
+        value_parser = ranges::account_range_arg,
+        help = "Accounts to search, as START..END with END excluded"
+    )]
+    pub(crate) account_range: AccountRange,
+}
+
+#[derive(Args)]
+pub(crate) struct AddressVerifyArgs {
+    #[arg(long, help = "Address to check (j1... or @label)")]
+    pub(crate) address: String,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["seed_file", "seed_base64", "account_range"],
+        help = "Check against a UFVK (jview1... or @label) instead of the seed"
+    )]
+    pub(crate) ufvk: Option<String>,
+
+    #[arg(long, help = "Read seed base64 from a file")]
+    pub(crate) seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    pub(crate) seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network whose coin type to derive under (default: the address's, else the profile network)"
+    )]
+    pub(crate) network: Option<NetworkArg>,
+
+    #[arg(
+        long,
+        default_value = "0..100",
+        value_parser = ranges::account_range_arg,
+        help = "Accounts to search, as START..END with END excluded"
+    )]
+    pub(crate) account_range: AccountRange,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VerifyOut {
+    matches: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diversifier_index: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accounts: Option<String>,
+}
+
+// `--network`, else the network the key's HRP names, else the profile's.
+fn network(
+    settings: &Settings,
+    arg: Option<NetworkArg>,
+    from_key: Option<Network>,
+) -> Result<Network, AppError> {
+    arg.map(Network::from)
+        .or(from_key)
+        .or(settings.network)
+        .ok_or_else(|| {
+            AppError::InvalidRequest("missing network (set --network or a profile network)".into())
+        })
+}
+
+fn range(accounts: AccountRange) -> String {
+    format!("{}..{}", accounts.start(), accounts.end())
+}
+
+fn scope_name(scope: orchard::keys::Scope) -> &'static str {
+    match scope {
+        orchard::keys::Scope::External => "external",
+        orchard::keys::Scope::Internal => "internal",
+    }
+}
+
+pub(crate) fn cmd_ufvk_verify(
+    cli: &Cli,
+    settings: &Settings,
+    args: &UfvkVerifyArgs,
+) -> Result<(), AppError> {
+    let ufvk = addressbook::resolve(settings, &args.ufvk, EntryKind::Ufvk)?;
+    let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+    audit::note_ufvk(ufvk.as_str());
+    let net = network(settings, args.network, ufvk.network())?;
+    let params = network_params(cli, net);
+    let accounts = args.account_range;
+    let account: Option<u32> = isolate::run(cli, || {
+        let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+        let account = juno_keys::find_ufvk_account(&seed, params.as_ref(), &ufvk, accounts)
+            .map_err(AppError::Keys)?;
+        audit::note_seed(&seed);
+        Ok(account)
+    })?;
+    let data = VerifyOut {
+        matches: account.is_some(),
+        account,
+        path: account
+            .map(|account| ZipPath::account(params.coin_type(), account))
+            .transpose()
+            .map_err(AppError::Keys)?
+            .map(|path| path.to_string()),
+        scope: None,
+        diversifier_index: None,
+        accounts: Some(range(accounts)),
+    };
+    write(cli, &data)
+}
+
+pub(crate) fn cmd_address_verify(
+    cli: &Cli,
+    settings: &Settings,
+    args: &AddressVerifyArgs,
+) -> Result<(), AppError> {
+    let address = addressbook::resolve(settings, &args.address, EntryKind::Address)?;
+    let address = UnifiedAddress::parse(&address).map_err(AppError::Keys)?;
+    let data = match &args.ufvk {
+        Some(ufvk) => {
+            let ufvk = addressbook::resolve(settings, ufvk, EntryKind::Ufvk)?;
+            let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+            audit::note_ufvk(ufvk.as_str());
+            let located = ufvk
+                .address_locator()
+                .map_err(AppError::Keys)?
+                .locate(&address);
+            VerifyOut {
+                matches: located.is_some(),
+                account: None,
+                path: None,
+                scope: located.map(|(scope, _)| scope_name(scope).to_string()),
+                diversifier_index: located.map(|(_, index)| index.into()),
+                accounts: None,
+            }
+        }
+        None => {
+            let net = network(settings, args.network, address.network())?;
+            let params = network_params(cli, net);
+            let accounts = args.account_range;
+            let found: Option<(u32, String, u128)> = isolate::run(cli, || {
+                let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+                let found =
+                    juno_keys::find_address_account(&seed, params.as_ref(), &address, accounts)
+                        .map_err(AppError::Keys)?;
+                audit::note_seed(&seed);
+                Ok(found.map(|(account, scope, index)| {
+                    (account, scope_name(scope).to_string(), index.into())
+                }))
+            })?;
+            VerifyOut {
+                matches: found.is_some(),
+                path: found
+                    .as_ref()
+                    .map(|(account, ..)| ZipPath::account(params.coin_type(), *account))
+                    .transpose()
+                    .map_err(AppError::Keys)?
+                    .map(|path| path.to_string()),
+                account: found.as_ref().map(|(account, ..)| *account),
+                diversifier_index: found.as_ref().map(|(.., index)| *index),
+                scope: found.map(|(_, scope, _)| scope),
+                accounts: Some(range(accounts)),
+            }
+        }
+    };
+    write(cli, &data)
+}
+
+fn write(cli: &Cli, data: &VerifyOut) -> Result<(), AppError> {
+    if cli.json {
+        return write_json_ok(cli, data);
+    }
+    if !data.matches {
+        match &data.accounts {
+            Some(accounts) => println!("no match (accounts {accounts})"),
+            None => println!("no match"),
+        }
+        return Ok(());
+    }
+    let mut line = String::from("match:");
+    if let (Some(account), Some(path)) = (data.account, &data.path) {
+        line += &format!(" account {account} ({path})");
+    }
+    if let (Some(scope), Some(index)) = (&data.scope, data.diversifier_index) {
+        line += &format!(" {scope} diversifier index {index}");
+    }
+    println!("{line}");
+    Ok(())
+}
@@ -0,0 +1,303 @@
+//! `export paper`: a one-page printable backup sheet, written as a PDF by hand so the
+//! command needs no fonts, PDF library or network.
+//!
+//! The page is A4 and only uses the PDF base-14 fonts (Helvetica and Courier), so
+//! every viewer and printer lays it out the same way. QR codes are drawn as filled
+//! squares. Text is printable ASCII; anything else prints as '?'.
+
+use std::fmt::Write as _;
+
+use qrcode::{Color, EcLevel, QrCode};
+use zeroize::Zeroizing;
+
+use crate::AppError;
+
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+const MARGIN: f64 = 50.0;
+
+/// What the sheet backs up.
+pub(crate) enum Secret<'a> {
+    /// BIP-39 words, in order.
+    Mnemonic(Vec<Zeroizing<String>>),
+    /// Raw seed bytes, borrowed from the seed.
+    Seed(&'a [u8]),
+}
+
+pub(crate) struct Sheet<'a> {
+    pub(crate) secret: Secret<'a>,
+    pub(crate) label: Option<String>,
+    pub(crate) network: String,
+    pub(crate) account: u32,
+    pub(crate) path: String,
+    pub(crate) seed_fingerprint: String,
+    pub(crate) ufvk_fingerprint: String,
+    pub(crate) address: String,
+    pub(crate) passphrase: bool,
+    pub(crate) date: Option<String>,
+    pub(crate) ceremony: Option<String>,
+}
+
+/// The sheet as a complete PDF file. It holds the secret, so it is zeroized on drop.
+pub(crate) fn render(sheet: &Sheet<'_>) -> Result<Zeroizing<String>, AppError> {
+    let mut page = Page::default();
+    let mut y = PAGE_HEIGHT - MARGIN - 10.0;
+    page.text(Font::Bold, 18.0, MARGIN, y, "Juno Cash key backup");
+    if let Some(label) = &sheet.label {
+        y -= 20.0;
+        page.text(Font::Regular, 12.0, MARGIN, y, label);
+    }
+
+    y -= 30.0;
+    let passphrase = if sheet.passphrase {
+        "yes (not printed; needed to restore)"
+    } else {
+        "none"
+    };
+    let rows = [
+        ("Network", sheet.network.as_str()),
+        ("Account", &sheet.account.to_string()),
+        ("Derivation path", &sheet.path),
+        ("Seed fingerprint", &sheet.seed_fingerprint),
+        ("UFVK fingerprint", &sheet.ufvk_fingerprint),
+        ("BIP-39 passphrase", passphrase),
+        ("Date", sheet.date.as_deref().unwrap_or("")),
+        ("Ceremony", sheet.ceremony.as_deref().unwrap_or("")),
+        (
+            "Written by",
+            concat!("juno-keys ", env!("CARGO_PKG_VERSION")),
+        ),
+    ];
+    for (name, value) in rows {
+        page.text(Font::Regular, 10.0, MARGIN, y, name);
+        if value.is_empty() {
+            // Left blank to fill in by hand.
+            page.line(MARGIN + 120.0, y - 2.0, PAGE_WIDTH - MARGIN, y - 2.0);
+        } else {
+            page.text(Font::Mono, 8.0, MARGIN + 120.0, y, value);
+        }
+        y -= 16.0;
+    }
+
+    y -= 6.0;
+    page.line(MARGIN, y, PAGE_WIDTH - MARGIN, y);
+    y -= 24.0;
+    let section_top = y;
+    let qr_payload = match &sheet.secret {
+        Secret::Mnemonic(words) => {
+            page.text(Font::Bold, 12.0, MARGIN, y, "Recovery phrase (BIP-39)");
+            y -= 24.0;
+            // Numbered down the columns, as wallets show them.
+            let rows = words.len().div_ceil(3);
+            for (i, word) in words.iter().enumerate() {
+                let x = MARGIN + (i / rows) as f64 * 105.0;
+                let line = format!("{:>2} {}", i + 1, word.as_str());
+                page.text(Font::Mono, 11.0, x, y - (i % rows) as f64 * 18.0, &line);
+            }
+            y -= rows as f64 * 18.0;
+            Zeroizing::new(
+                words
+                    .iter()
+                    .map(|w| w.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        }
+        Secret::Seed(bytes) => {
+            page.text(Font::Bold, 12.0, MARGIN, y, "Seed (hex)");
+            y -= 24.0;
+            let hex = Zeroizing::new(hex::encode(bytes));
+            for (i, chunk) in hex.as_bytes().chunks(32).enumerate() {
+                let groups = chunk
+                    .chunks(4)
+                    .map(|g| String::from_utf8_lossy(g).into_owned())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let line = Zeroizing::new(format!("{:>2}  {groups}", i + 1));
+                page.text(Font::Mono, 11.0, MARGIN, y, &line);
+                y -= 16.0;
+            }
+            // Upper case keeps the QR code in its compact alphanumeric mode.
+            Zeroizing::new(hex.to_ascii_uppercase())
+        }
+    };
+    let qr_size = 150.0;
+    page.qr(
+        PAGE_WIDTH - MARGIN - qr_size,
+        section_top + 12.0,
+        qr_size,
+        &qr_payload,
+    )?;
+    y = y.min(section_top - qr_size) - 16.0;
+
+    page.line(MARGIN, y, PAGE_WIDTH - MARGIN, y);
+    y -= 24.0;
+    let address_top = y;
+    page.text(Font::Bold, 12.0, MARGIN, y, "Receiving address (index 0)");
+    y -= 20.0;
+    for chunk in sheet.address.as_bytes().chunks(56) {
+        page.text(Font::Mono, 8.0, MARGIN, y, &String::from_utf8_lossy(chunk));
+        y -= 12.0;
+    }
+    let address_qr = 110.0;
+    page.qr(
+        PAGE_WIDTH - MARGIN - address_qr,
+        address_top + 12.0,
+        address_qr,
+        &sheet.address,
+    )?;
+
+    y = 95.0;
+    page.text(Font::Regular, 10.0, MARGIN, y, "Verified by: ");
+    page.line(MARGIN + 70.0, y - 2.0, MARGIN + 250.0, y - 2.0);
+    y -= 24.0;
+    for note in [
+        "Anyone holding this sheet can spend the account's funds. Keep it offline and out of sight.",
+        "Before funding, restore from this sheet and check that the seed fingerprint matches.",
+    ] {
+        page.text(Font::Regular, 9.0, MARGIN, y, note);
+        y -= 13.0;
+    }
+
+    Ok(document(&page.ops))
+}
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+#[derive(Default)]
+struct Page {
+    ops: Zeroizing<String>,
+}
+
+impl Page {
+    fn text(&mut self, font: Font, size: f64, x: f64, y: f64, text: &str) {
+        let name = match font {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        };
+        let _ = write!(self.ops, "BT /{name} {size:.1} Tf {x:.2} {y:.2} Td (");
+        for c in text.chars() {
+            match c {
+                '(' | ')' | '\\' => {
+                    self.ops.push('\\');
+                    self.ops.push(c);
+                }
+                ' '..='~' => self.ops.push(c),
+                _ => self.ops.push('?'),
+            }
+        }
+        self.ops.push_str(") Tj ET\n");
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        let _ = writeln!(self.ops, "0.5 w {x1:.2} {y1:.2} m {x2:.2} {y2:.2} l S");
+    }
+
+    // `top` is the upper edge; dark modules in a row are merged into one rectangle.
+    fn qr(&mut self, x: f64, top: f64, size: f64, payload: &str) -> Result<(), AppError> {
+        let code = QrCode::with_error_correction_level(payload, EcLevel::M)
+            .map_err(|e| AppError::InvalidRequest(format!("too much data for a QR code: {e}")))?;
+        let width = code.width();
+        let module = size / width as f64;
+        let colors = code.to_colors();
+        for (r, row) in colors.chunks(width).enumerate() {
+            let mut c = 0;
+            while c < width {
+                if row[c] == Color::Light {
+                    c += 1;
+                    continue;
+                }
+                let start = c;
+                while c < width && row[c] == Color::Dark {
+                    c += 1;
+                }
+                let _ = writeln!(
+                    self.ops,
+                    "{:.3} {:.3} {:.3} {module:.3} re",
+                    x + start as f64 * module,
+                    top - (r + 1) as f64 * module,
+                    (c - start) as f64 * module,
+                );
+            }
+        }
+        self.ops.push_str("f\n");
+        Ok(())
+    }
+}
+
+fn document(content: &str) -> Zeroizing<String> {
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+             /Resources << /Font << /F1 4 0 R /F2 5 0 R /F3 6 0 R >> >> /Contents 7 0 R >>"
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>".to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{content}endstream",
+            content.len()
+        ),
+        "<< /Title (Juno Cash key backup) /Producer (juno-keys) >>".to_string(),
+    ];
+
+    let mut pdf = Zeroizing::new(String::from("%PDF-1.4\n"));
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{object}\nendobj\n", i + 1);
+    }
+    let xref = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R /Info {} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1,
+        objects.len()
+    );
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xref_offsets_point_at_objects() {
+        let sheet = Sheet {
+            secret: Secret::Seed(&[7; 64]),
+            label: Some("cold (vault)".into()),
+            network: "regtest".into(),
+            account: 0,
+            path: "m/32'/8135'/0'".into(),
+            seed_fingerprint: "zip32seedfp1example".into(),
+            ufvk_fingerprint: "00".repeat(32),
+            address: "jregtest1example".into(),
+            passphrase: false,
+            date: None,
+            ceremony: None,
+        };
+        let pdf = render(&sheet).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n") && pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(cold \\(vault\\)) Tj"));
+
+        let xref = pdf.rfind("startxref\n").unwrap();
+        let start: usize = pdf[xref + 10..].lines().next().unwrap().parse().unwrap();
+        assert!(pdf[start..].starts_with("xref\n0 9\n"));
+        for (i, entry) in pdf[start..].lines().skip(3).take(8).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", i + 1)));
+        }
+    }
+}
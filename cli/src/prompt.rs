@@ -0,0 +1,107 @@
+//! Secrets typed at the terminal: passphrases for encrypted seed files when no
+//! passphrase file is given, and the seed itself with `--seed-prompt`.
+//!
+//! The prompt goes to stderr and echo is off while the secret is typed, so it is
+//! neither shown nor left in scrollback. Without a terminal on stdin there is nobody
+//! to ask, and the caller is told which flag to pass instead.
+
+use std::io::{self, IsTerminal as _, Write as _};
+
+use zeroize::Zeroizing;
+
+use crate::AppError;
+
+/// A passphrase typed after `prompt`; with `confirm`, typed twice and compared.
+/// Only the final newline is dropped, as for passphrase files.
+pub(crate) fn passphrase(
+    prompt: &str,
+    confirm: bool,
+    flag: &str,
+) -> Result<Zeroizing<String>, AppError> {
+    if !io::stdin().is_terminal() {
+        return Err(AppError::InvalidRequest(format!(
+            "no terminal to ask for the passphrase; pass {flag}"
+        )));
+    }
+    let typed = read_hidden(&format!("{prompt}: "))?;
+    if confirm && *read_hidden("Repeat it: ")? != *typed {
+        return Err(AppError::InvalidRequest(
+            "the passphrases don't match".into(),
+        ));
+    }
+    Ok(typed)
+}
+
+/// A seed typed as base64 for `--seed-prompt`.
+pub(crate) fn seed() -> Result<Zeroizing<String>, AppError> {
+    if !io::stdin().is_terminal() {
+        return Err(AppError::InvalidRequest(
+            "no terminal to ask for the seed; pass --seed-stdin or --seed-file".into(),
+        ));
+    }
+    read_hidden("Seed (base64): ")
+}
+
+fn read_hidden(prompt: &str) -> Result<Zeroizing<String>, AppError> {
+    let echo = EchoOff::new()?;
+    let mut stderr = io::stderr();
+    let _ = write!(stderr, "{prompt}");
+    let _ = stderr.flush();
+    let mut line = Zeroizing::new(String::new());
+    let read = io::stdin().read_line(&mut line);
+    drop(echo);
+    // The newline typed wasn't echoed.
+    let _ = writeln!(stderr);
+    read.map_err(|e| AppError::Io(format!("read passphrase: {e}")))?;
+    let typed = line.strip_suffix('\n').unwrap_or(&line);
+    Ok(Zeroizing::new(
+        typed.strip_suffix('\r').unwrap_or(typed).to_string(),
+    ))
+}
+
+// Turns terminal echo off until dropped.
+#[cfg(unix)]
+struct EchoOff(libc::termios);
+
+#[cfg(unix)]
+impl EchoOff {
+    fn new() -> Result<Self, AppError> {
+        // SAFETY: `termios` is plain data, filled in by `tcgetattr` before it is read.
+        let mut saved = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut saved) } != 0 {
+            return Err(AppError::Io(format!(
+                "read terminal settings: {}",
+                io::Error::last_os_error()
+            )));
+        }
+        let mut hidden = saved;
+        hidden.c_lflag &= !libc::ECHO;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &hidden) } != 0 {
+            return Err(AppError::Io(format!(
+                "turn off terminal echo: {}",
+                io::Error::last_os_error()
+            )));
+        }
+        Ok(EchoOff(saved))
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0) };
+    }
+}
+
+// Without termios the passphrase would be shown as typed, so don't ask for one.
+#[cfg(not(unix))]
+struct EchoOff;
+
+#[cfg(not(unix))]
+impl EchoOff {
+    fn new() -> Result<Self, AppError> {
+        Err(AppError::InvalidRequest(
+            "passphrase prompts aren't supported on this platform; pass a passphrase file".into(),
+        ))
+    }
+}
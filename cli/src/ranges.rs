@@ -0,0 +1,185 @@
+//! `--account-range` and `--diversifier-range`: a row per account (`ufvk from-seed`,
+//! `uivk from-seed`) or per address (`address from-seed`) from one read of the seed.
+//!
+//! Only the UFVKs of the accounts touch the seed. They are derived together, from one
+//! ZIP-32 master and coin-type key (in the child under `--isolate`), and everything
+//! else is derived from them. Rows are streamed as one JSON array, or with `--ndjson`
+//! as one JSON object per line; see `batch`. Hooks aren't run for ranges.
+
+use std::ops::Range;
+
+use juno_keys::{AccountRange, Seed, Ufvk, ZipPath};
+use serde::Serialize;
+
+use crate::{
+    audit, batch, isolate, network_params, resolve_network, AppError, Cli, NetworkArg, Settings,
+};
+
+/// `START..END` with `END` excluded, as accounts.
+pub(crate) fn account_range_arg(s: &str) -> Result<AccountRange, String> {
+    let (start, end) = parse(s)?;
+    let (Ok(start), Ok(end)) = (u32::try_from(start), u32::try_from(end)) else {
+        return Err(format!("accounts must be below 2^31, got {s}"));
+    };
+    AccountRange::new(start, end).map_err(|_| format!("accounts must be below 2^31, got {s}"))
+}
+
+/// `START..END` with `END` excluded, as diversifier indices.
+pub(crate) fn diversifier_range_arg(s: &str) -> Result<Range<u64>, String> {
+    let (start, end) = parse(s)?;
+    Ok(start..end)
+}
+
+fn parse(s: &str) -> Result<(u64, u64), String> {
+    let Some((start, end)) = s.split_once("..") else {
+        return Err(format!("expected START..END (END excluded), got {s}"));
+    };
+    let number = |n: &str| {
+        n.trim()
+            .parse::<u64>()
+            .map_err(|_| format!("expected START..END (END excluded), got {s}"))
+    };
+    let (start, end) = (number(start)?, number(end)?);
+    if start >= end {
+        return Err(format!("the range {s} is empty"));
+    }
+    Ok((start, end))
+}
+
+/// Which key `write_keys` prints for each account.
+#[derive(Clone, Copy)]
+pub(crate) enum Key {
+    Ufvk,
+    Uivk,
+}
+
+// The UFVK of each account, derived in one pass over the seed.
+fn account_ufvks(
+    cli: &Cli,
+    settings: &Settings,
+    network: Option<NetworkArg>,
+    accounts: AccountRange,
+    ndjson: bool,
+    seed: impl FnOnce() -> Result<Seed, AppError>,
+) -> Result<(u32, Vec<Ufvk>), AppError> {
+    batch::Rows::check(cli, ndjson)?;
+    let net = resolve_network(settings, network)?;
+    let params = network_params(cli, net);
+    let ufvks: Vec<String> = isolate::run(cli, || {
+        let seed = seed()?;
+        let ufvks = juno_keys::derive_ufvk_batch(&seed, params.as_ref(), accounts)
+            .map_err(AppError::Keys)?;
+        audit::note_seed(&seed);
+        Ok(ufvks.iter().map(Ufvk::to_string).collect())
+    })?;
+    let ufvks = ufvks
+        .iter()
+        .map(|ufvk| Ufvk::parse(ufvk).map_err(AppError::Keys))
+        .collect::<Result<_, _>>()?;
+    Ok((params.coin_type(), ufvks))
+}
+
+fn path(coin_type: u32, account: u32) -> Result<String, AppError> {
+    Ok(ZipPath::account(coin_type, account)
+        .map_err(AppError::Keys)?
+        .to_string())
+}
+
+/// `ufvk from-seed` and `uivk from-seed` with `--account-range`.
+pub(crate) fn write_keys(
+    cli: &Cli,
+    settings: &Settings,
+    key: Key,
+    network: Option<NetworkArg>,
+    accounts: AccountRange,
+    ndjson: bool,
+    seed: impl FnOnce() -> Result<Seed, AppError>,
+) -> Result<(), AppError> {
+    #[derive(Serialize)]
+    struct KeyRow {
+        account: u32,
+        path: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ufvk: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        uivk: Option<String>,
+    }
+    let (coin_type, ufvks) = account_ufvks(cli, settings, network, accounts, ndjson, seed)?;
+    let name = match key {
+        Key::Ufvk => "ufvks",
+        Key::Uivk => "uivks",
+    };
+    let mut rows = batch::Rows::start(cli, name, ndjson)?;
+    for (account, ufvk) in accounts.into_iter().zip(ufvks) {
+        let (ufvk, uivk) = match key {
+            Key::Ufvk => (Some(ufvk.to_string()), None),
+            Key::Uivk => (None, Some(ufvk.uivk().map_err(AppError::Keys)?)),
+        };
+        rows.push(&KeyRow {
+            account,
+            path: path(coin_type, account)?,
+            ufvk,
+            uivk,
+        })?;
+    }
+    rows.finish()
+}
+
+/// `address from-seed` with `--account-range` or `--diversifier-range`: each account's
+/// addresses in turn, computed on a worker pool.
+pub(crate) fn write_addresses(
+    cli: &Cli,
+    settings: &Settings,
+    network: Option<NetworkArg>,
+    accounts: AccountRange,
+    diversifier_indices: Range<u64>,
+    ndjson: bool,
+    seed: impl FnOnce() -> Result<Seed, AppError>,
+) -> Result<(), AppError> {
+    #[derive(Serialize)]
+    struct AddressRow {
+        account: u32,
+        path: String,
+        diversifier_index: u64,
+        address: String,
+    }
+    let (coin_type, ufvks) = account_ufvks(cli, settings, network, accounts, ndjson, seed)?;
+    let mut rows = batch::Rows::start(cli, "addresses", ndjson)?;
+    for (account, ufvk) in accounts.into_iter().zip(ufvks) {
+        let path = path(coin_type, account)?;
+        let generator = ufvk.address_generator().map_err(AppError::Keys)?;
+        let start = diversifier_indices.start;
+        batch::ordered(
+            diversifier_indices.end - start,
+            |_: &mut (), i| {
+                let address = generator.address_at(start + i).map_err(AppError::Keys)?;
+                Ok(AddressRow {
+                    account,
+                    path: path.clone(),
+                    diversifier_index: start + i,
+                    address: address.to_string(),
+                })
+            },
+            |row| rows.push(&row),
+        )?;
+    }
+    rows.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_parse() {
+        assert_eq!(
+            account_range_arg("0..100"),
+            Ok(AccountRange::new(0, 100).unwrap())
+        );
+        assert_eq!(diversifier_range_arg(" 5 .. 7"), Ok(5..7));
+        assert!(account_range_arg("0..2147483649").is_err());
+        assert!(diversifier_range_arg("3..3").is_err());
+        assert!(diversifier_range_arg("7").is_err());
+        assert!(diversifier_range_arg("0..=4").is_err());
+    }
+}
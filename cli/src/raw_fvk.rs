@@ -0,0 +1,102 @@
+//! `ufvk export-raw` and `ufvk from-raw`: the Orchard full viewing key of a UFVK as
+//! 96 bytes of hex, `ak || nk || rivk`, for tooling that doesn't speak the unified
+//! encoding, and back.
+//!
+//! Raw bytes carry no network, so `from-raw` wraps them under the HRP of `--network`
+//! (or the custom network); the fingerprint is the same whichever network wraps them.
+
+use clap::Args;
+use juno_keys::Ufvk;
+use serde::Serialize;
+
+use crate::{
+    addressbook, armor, audit, network_params, resolve_network, visual, write_json_ok, AppError,
+    Cli, EntryKind, NetworkArg, Settings,
+};
+
+#[derive(Args)]
+pub(crate) struct UfvkExportRawArgs {
+    #[arg(help = "UFVK (jview1..., uview1...) or @label")]
+    pub(crate) ufvk: String,
+}
+
+#[derive(Args)]
+pub(crate) struct UfvkFromRawArgs {
+    #[arg(
+        long,
+        help = "Orchard full viewing key as hex, ak || nk || rivk (96 bytes)"
+    )]
+    pub(crate) orchard_fvk_hex: String,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp; default: profile network)"
+    )]
+    pub(crate) network: Option<NetworkArg>,
+}
+
+pub(crate) fn cmd_ufvk_export_raw(
+    cli: &Cli,
+    settings: &Settings,
+    args: &UfvkExportRawArgs,
+) -> Result<(), AppError> {
+    let ufvk = addressbook::resolve(settings, &args.ufvk, EntryKind::Ufvk)?;
+    let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+    let fvk = ufvk.to_orchard_fvk().ok_or_else(|| {
+        AppError::InvalidRequest("the UFVK has no Orchard full viewing key".into())
+    })?;
+    audit::note_ufvk(ufvk.as_str());
+
+    #[derive(Serialize)]
+    struct ExportOut {
+        orchard_fvk_hex: String,
+        ufvk_fingerprint: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        network: Option<&'static str>,
+    }
+    let data = ExportOut {
+        orchard_fvk_hex: hex::encode(fvk),
+        ufvk_fingerprint: ufvk.fingerprint(),
+        network: ufvk
+            .network()
+            .or(ufvk.zcash_network())
+            .map(|net| net.as_str()),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    println!("{}", data.orchard_fvk_hex);
+    Ok(())
+}
+
+pub(crate) fn cmd_ufvk_from_raw(
+    cli: &Cli,
+    settings: &Settings,
+    args: &UfvkFromRawArgs,
+) -> Result<(), AppError> {
+    let raw = hex::decode(args.orchard_fvk_hex.trim())
+        .map_err(|_| AppError::InvalidRequest("--orchard-fvk-hex is not valid hex".into()))?;
+    let net = resolve_network(settings, args.network)?;
+    let params = network_params(cli, net);
+    let ufvk = Ufvk::from_orchard_fvk(params.as_ref(), &raw).map_err(AppError::Keys)?;
+    audit::note_ufvk(ufvk.as_str());
+
+    #[derive(Serialize)]
+    struct FromRawOut {
+        ufvk: String,
+        ua_hrp: String,
+        ufvk_fingerprint: String,
+    }
+    let data = FromRawOut {
+        ufvk: ufvk.to_string(),
+        ua_hrp: ufvk.ua_hrp().to_string(),
+        ufvk_fingerprint: ufvk.fingerprint(),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    println!("{}", armor::wrap(cli, armor::UFVK, &data.ufvk).as_str());
+    visual::note(cli, visual::Kind::Ufvk, &data.ufvk);
+    Ok(())
+}
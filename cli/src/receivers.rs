@@ -0,0 +1,83 @@
+//! `--receivers`: which ZIP-316 items a derived UFVK, UIVK or address carries, as
+//! `orchard`, `sapling`, `p2pkh`, `p2sh` or a typecode, comma-separated.
+//!
+//! The encoder takes any set of items, sorted and checked for duplicates by
+//! `zip316::encode_unified_container`, but a Juno account only has Orchard keys so far.
+//! Asking for a receiver it can't derive fails up front rather than yielding a key or
+//! address without it.
+
+use juno_keys::zip316;
+
+use crate::AppError;
+
+/// Receivers derivation can produce today.
+const DERIVABLE: &[u64] = &[zip316::TYPECODE_ORCHARD];
+
+/// One `--receivers` entry, by name or typecode.
+pub(crate) fn receiver_arg(s: &str) -> Result<u64, String> {
+    match s.trim() {
+        "p2pkh" | "transparent" => Ok(zip316::TYPECODE_P2PKH),
+        "p2sh" => Ok(zip316::TYPECODE_P2SH),
+        "sapling" => Ok(zip316::TYPECODE_SAPLING),
+        "orchard" => Ok(zip316::TYPECODE_ORCHARD),
+        other => parse_typecode(other).ok_or_else(|| {
+            format!("expected orchard, sapling, p2pkh, p2sh or a typecode, got {s}")
+        }),
+    }
+}
+
+fn parse_typecode(s: &str) -> Option<u64> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+fn name(typecode: u64) -> String {
+    match typecode {
+        zip316::TYPECODE_P2PKH => "p2pkh".into(),
+        zip316::TYPECODE_P2SH => "p2sh".into(),
+        zip316::TYPECODE_SAPLING => "sapling".into(),
+        zip316::TYPECODE_ORCHARD => "orchard".into(),
+        _ => format!("typecode {typecode}"),
+    }
+}
+
+/// Fails unless every selected receiver can be derived; an empty selection means all.
+pub(crate) fn check(selected: &[u64]) -> Result<(), AppError> {
+    let mut seen = Vec::with_capacity(selected.len());
+    for &typecode in selected {
+        if seen.contains(&typecode) {
+            return Err(AppError::InvalidRequest(format!(
+                "--receivers names {} twice",
+                name(typecode)
+            )));
+        }
+        seen.push(typecode);
+        if !DERIVABLE.contains(&typecode) {
+            return Err(AppError::InvalidRequest(format!(
+                "{} receivers can't be derived yet; Juno accounts only have Orchard keys",
+                name(typecode)
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selections_parse_and_check() {
+        assert_eq!(receiver_arg("orchard"), Ok(zip316::TYPECODE_ORCHARD));
+        assert_eq!(receiver_arg("transparent"), Ok(zip316::TYPECODE_P2PKH));
+        assert_eq!(receiver_arg("0x10"), Ok(16));
+        assert!(receiver_arg("ironwood").is_err());
+
+        assert!(check(&[]).is_ok());
+        assert!(check(&[zip316::TYPECODE_ORCHARD]).is_ok());
+        assert!(check(&[zip316::TYPECODE_ORCHARD, zip316::TYPECODE_ORCHARD]).is_err());
+        assert!(check(&[zip316::TYPECODE_SAPLING, zip316::TYPECODE_ORCHARD]).is_err());
+    }
+}
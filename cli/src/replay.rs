@@ -0,0 +1,268 @@
+//! `audit replay`: re-derives a manifest of earlier derivations from the seed and
+//! reports any drift from what was recorded.
+//!
+//! A manifest is a text file with one derivation per line: either a wallet descriptor
+//! as `derive` prints it, or a `derive --json` line (its envelope or just its `data`),
+//! so `derive --json >> manifest.jsonl` after each ceremony builds one. Blank lines
+//! and lines starting with `#` are skipped. Each entry is derived again at its
+//! descriptor's network and path, and the descriptor's fingerprints and any recorded
+//! `ufvk`, `uivk`, `address` and `path` are compared with the result.
+
+use std::fs;
+use std::io::{self, Read as _};
+use std::path::PathBuf;
+
+use clap::Args;
+use juno_keys::{DiversifierIndex, Seed, WalletDescriptor};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::style::{self, Paint, Stream};
+use crate::{
+    audit, descriptor, network_params, resolve_seed, write_json_ok, AppError, Cli, Settings,
+};
+
+#[derive(Args)]
+pub(crate) struct ReplayArgs {
+    #[arg(
+        long,
+        help = "Manifest: a descriptor or `derive --json` line per derivation ('-' for stdin)"
+    )]
+    pub(crate) manifest: PathBuf,
+
+    #[arg(long, help = "Read seed base64 from a file")]
+    pub(crate) seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    pub(crate) seed_base64: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Entry {
+    descriptor: String,
+    diversifier_index: Option<u64>,
+    path: Option<String>,
+    ufvk: Option<String>,
+    uivk: Option<String>,
+    address: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EntryOut {
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    drift: Vec<String>,
+}
+
+// One manifest line, or `None` for a blank line or comment.
+fn parse_line(line: &str) -> Option<Result<Entry, String>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    if !line.starts_with('{') {
+        return Some(Ok(Entry {
+            descriptor: line.to_string(),
+            ..Entry::default()
+        }));
+    }
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => return Some(Err(format!("not JSON: {e}"))),
+    };
+    let data = value.get("data").unwrap_or(&value);
+    let text = |name: &str| data.get(name).and_then(Value::as_str).map(str::to_string);
+    let Some(descriptor) = text("descriptor") else {
+        return Some(Err("no descriptor member".into()));
+    };
+    Some(Ok(Entry {
+        descriptor,
+        diversifier_index: data.get("diversifier_index").and_then(Value::as_u64),
+        path: text("path"),
+        ufvk: text("ufvk"),
+        uivk: text("uivk"),
+        address: text("address"),
+    }))
+}
+
+// What differs between `entry` and its derivation from `seed`.
+fn replay(cli: &Cli, seed: &Seed, entry: &Entry) -> Result<(String, Vec<String>), AppError> {
+    let descriptor: WalletDescriptor = descriptor::parse(&entry.descriptor)?;
+    let index = DiversifierIndex::from(entry.diversifier_index.unwrap_or_default());
+    let keys = juno_keys::DerivationRequest::new(seed)
+        .network(network_params(cli, descriptor.network).as_ref())
+        .path(descriptor.path.clone())
+        .diversifier_index(index)
+        .build()
+        .map_err(AppError::Keys)?;
+
+    let mut drift = Vec::new();
+    if let Some(expected) = &descriptor.seed_fingerprint {
+        if juno_keys::seed_fingerprint(seed).map_err(AppError::Keys)? != *expected {
+            drift.push("seed fingerprint".to_string());
+        }
+    }
+    if descriptor.receiver_types != keys.ufvk.typecodes() {
+        drift.push("receiver types".to_string());
+    }
+    if let Some(expected) = &descriptor.ufvk_fingerprint {
+        if *expected != keys.ufvk.fingerprint() {
+            drift.push("ufvk fingerprint".to_string());
+        }
+    }
+    let derived = [
+        ("path", keys.path.to_string()),
+        ("ufvk", keys.ufvk.to_string()),
+        ("uivk", keys.uivk.clone()),
+        ("address", keys.address.to_string()),
+    ];
+    let recorded = [&entry.path, &entry.ufvk, &entry.uivk, &entry.address];
+    for ((name, derived), recorded) in derived.iter().zip(recorded) {
+        if recorded
+            .as_ref()
+            .is_some_and(|recorded| recorded != derived)
+        {
+            drift.push(name.to_string());
+        }
+    }
+    audit::note_ufvk(keys.ufvk.as_str());
+    Ok((keys.path.to_string(), drift))
+}
+
+pub(crate) fn cmd_audit_replay(
+    cli: &Cli,
+    settings: &Settings,
+    args: &ReplayArgs,
+) -> Result<(), AppError> {
+    let raw = if args.manifest.as_os_str() == "-" {
+        let mut s = String::new();
+        io::stdin()
+            .read_to_string(&mut s)
+            .map_err(|e| AppError::Io(format!("read stdin: {e}")))?;
+        s
+    } else {
+        fs::read_to_string(&args.manifest)
+            .map_err(|e| AppError::Io(format!("read {}: {e}", args.manifest.display())))?
+    };
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    audit::note_seed(&seed);
+
+    let entries = raw
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| parse_line(line).map(|entry| (i + 1, entry)))
+        .map(|(line, entry)| {
+            match entry.and_then(|entry| replay(cli, &seed, &entry).map_err(|e| e.message())) {
+                Ok((path, drift)) => EntryOut {
+                    line,
+                    path: Some(path),
+                    status: if drift.is_empty() { "ok" } else { "drift" },
+                    drift,
+                },
+                Err(message) => EntryOut {
+                    line,
+                    path: None,
+                    status: "invalid",
+                    drift: vec![message],
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return Err(AppError::InvalidRequest(
+            "the manifest has no entries".into(),
+        ));
+    }
+
+    #[derive(Serialize)]
+    struct ReplaySummary {
+        entries: usize,
+        ok: usize,
+        drifted: usize,
+        invalid: usize,
+    }
+    let count = |status| entries.iter().filter(|e| e.status == status).count();
+    let summary = ReplaySummary {
+        entries: entries.len(),
+        ok: count("ok"),
+        drifted: count("drift"),
+        invalid: count("invalid"),
+    };
+    let failed = summary.drifted + summary.invalid;
+
+    if cli.json {
+        if failed == 0 {
+            write_json_ok(
+                cli,
+                &serde_json::json!({
+                    "seed_fingerprint": juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?,
+                    "summary": summary,
+                    "entries": entries,
+                }),
+            )?;
+        }
+    } else {
+        for entry in &entries {
+            let paint = match entry.status {
+                "ok" => Paint::Ok,
+                _ => Paint::Fail,
+            };
+            println!(
+                "{}\t{}\t{}\t{}",
+                entry.line,
+                entry.path.as_deref().unwrap_or("-"),
+                style::paint(Stream::Stdout, paint, entry.status),
+                entry.drift.join(", ")
+            );
+        }
+        println!(
+            "{} entries: {} ok, {} drifted, {} invalid",
+            summary.entries, summary.ok, summary.drifted, summary.invalid
+        );
+    }
+
+    if failed > 0 {
+        let mut message = format!(
+            "{failed} of {} manifest entries failed replay",
+            summary.entries
+        );
+        if cli.json {
+            let lines = entries
+                .iter()
+                .filter(|e| e.status != "ok")
+                .map(|e| format!("line {}: {}", e.line, e.drift.join(", ")))
+                .collect::<Vec<_>>();
+            message = format!("{message}: {}", lines.join("; "));
+        }
+        return Err(AppError::InvalidRequest(message));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_lines_parse() {
+        assert_eq!(parse_line("  "), None);
+        assert_eq!(parse_line("# ceremony 2026-10-16"), None);
+        assert_eq!(
+            parse_line("juno(mainnet,m/32'/8133'/0',orchard)#abc"),
+            Some(Ok(Entry {
+                descriptor: "juno(mainnet,m/32'/8133'/0',orchard)#abc".into(),
+                ..Entry::default()
+            }))
+        );
+        let envelope = r#"{"version":1,"status":"ok","data":{"descriptor":"d","address":"j1","diversifier_index":3}}"#;
+        let entry = parse_line(envelope).unwrap().unwrap();
+        assert_eq!(entry.address.as_deref(), Some("j1"));
+        assert_eq!(entry.diversifier_index, Some(3));
+        assert_eq!(entry.ufvk, None);
+        assert!(parse_line(r#"{"address":"j1"}"#).unwrap().is_err());
+        assert!(parse_line("{oops").unwrap().is_err());
+    }
+}
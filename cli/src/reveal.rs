@@ -0,0 +1,124 @@
+//! `--show-for 30s`: a secret printed to a terminal is shown on the alternate screen
+//! for that long and then wiped, so it doesn't linger on screen or in scrollback for
+//! someone looking over a shoulder or a screen recording to pick up.
+//!
+//! The terminal's own screen comes back afterwards, as it does when leaving a pager.
+//! Ctrl-C (or SIGTERM) clears it early. Output that isn't a terminal is written as
+//! usual, since there is no screen to clear.
+
+use std::io::{self, IsTerminal as _, Write as _};
+use std::time::Duration;
+
+use crate::style::{self, Paint, Stream};
+use crate::{AppError, Cli};
+
+// Alternate screen on; then clear it, its scrollback, home the cursor, alternate off.
+const ENTER: &str = "\x1b[?1049h\x1b[H";
+const LEAVE: &str = "\x1b[2J\x1b[3J\x1b[H\x1b[?1049l";
+
+const MAX: Duration = Duration::from_secs(3600);
+
+/// Parses `30s`, `2m`, `1h` or plain seconds, up to an hour.
+pub(crate) fn duration_arg(s: &str) -> Result<Duration, String> {
+    parse_duration(s, MAX)
+}
+
+/// Parses `30s`, `2m`, `1h` or plain seconds, up to `max` (whole hours).
+pub(crate) fn parse_duration(s: &str, max: Duration) -> Result<Duration, String> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return Err("expected a duration such as 30s, 2m or 1h".into()),
+    };
+    let secs = number
+        .parse::<u64>()
+        .map_err(|_| "expected a duration such as 30s, 2m or 1h".to_string())?;
+    match Duration::from_secs(secs.saturating_mul(scale)) {
+        d if d.is_zero() => Err("the duration must be more than 0s".into()),
+        d if d > max => Err(format!(
+            "the duration can be at most {}h",
+            max.as_secs() / 3600
+        )),
+        d => Ok(d),
+    }
+}
+
+/// Prints `text`, a secret, with a trailing newline: for `--show-for` on a terminal,
+/// and otherwise as usual.
+pub(crate) fn print_secret(cli: &Cli, text: &str) -> Result<(), AppError> {
+    let text = text.trim_end_matches('\n');
+    let io = |e: io::Error| AppError::Io(format!("write stdout: {e}"));
+    let mut stdout = io::stdout().lock();
+    let Some(show_for) = cli.show_for.filter(|_| stdout.is_terminal()) else {
+        return writeln!(stdout, "{text}").map_err(io);
+    };
+    let footer = style::paint(
+        Stream::Stdout,
+        Paint::Secret,
+        &format!(
+            "(secret; cleared in {}s, or now with Ctrl-C)",
+            show_for.as_secs()
+        ),
+    );
+    write!(stdout, "{ENTER}{text}\n\n{footer}\n").map_err(io)?;
+    stdout.flush().map_err(io)?;
+    wait(show_for);
+    write!(stdout, "{LEAVE}").map_err(io)?;
+    stdout.flush().map_err(io)?;
+    style::note("the secret was cleared from the screen");
+    Ok(())
+}
+
+// Sleeps for `timeout`, or until SIGINT or SIGTERM, which are held back meanwhile so
+// they clear the screen instead of leaving the secret up.
+#[cfg(target_os = "linux")]
+fn wait(timeout: Duration) {
+    // SAFETY: `set` is initialized by sigemptyset before any other use, and every
+    // pointer passed is valid for the duration of its call.
+    unsafe {
+        let mut set = std::mem::MaybeUninit::<libc::sigset_t>::uninit();
+        libc::sigemptyset(set.as_mut_ptr());
+        let mut set = set.assume_init();
+        libc::sigaddset(&mut set, libc::SIGINT);
+        libc::sigaddset(&mut set, libc::SIGTERM);
+        let mut old = std::mem::MaybeUninit::<libc::sigset_t>::uninit();
+        if libc::pthread_sigmask(libc::SIG_BLOCK, &set, old.as_mut_ptr()) != 0 {
+            std::thread::sleep(timeout);
+            return;
+        }
+        let timeout = libc::timespec {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_nsec: 0,
+        };
+        // Returns early for a signal; EINTR from anything else ends the wait too.
+        libc::sigtimedwait(&set, std::ptr::null_mut(), &timeout);
+        libc::pthread_sigmask(libc::SIG_SETMASK, old.as_ptr(), std::ptr::null_mut());
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn wait(timeout: Duration) {
+    std::thread::sleep(timeout);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_parse() {
+        assert_eq!(duration_arg("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(duration_arg("45"), Ok(Duration::from_secs(45)));
+        assert_eq!(duration_arg("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(duration_arg("1h"), Ok(MAX));
+        for bad in ["", "0s", "2h", "s", "1.5s", "-3s", "10ms"] {
+            assert!(duration_arg(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! `address at-index` and `address list`: an account's addresses by diversifier index,
+//! for point-of-sale systems that hand out a fresh address per order.
+//!
+//! Both report the index each address was actually derived at. Every index yields an
+//! Orchard address, so today that is the index asked for, but a wallet that stores
+//! the reported index keeps rotating correctly if addresses ever carry receivers that
+//! skip indices. `address list` walks on from each reported index, so its rows are the
+//! same whatever `--from` a run started at.
+
+use std::path::PathBuf;
+
+use clap::Args;
+use juno_keys::{DiversifierIndex, KeysError, Ufvk, ZipPath, MAX_DIVERSIFIER_INDEX};
+use serde::Serialize;
+
+use crate::{
+    addressbook, audit, batch, diversifier_index_arg, hooks, isolate, network_params,
+    resolve_network, resolve_seed, style, visual, write_json_ok, AppError, Cli, EntryKind,
+    NetworkArg, Settings,
+};
+
+#[derive(Args)]
+pub(crate) struct SourceArgs {
+    #[arg(
+        long,
+        conflicts_with_all = ["seed_file", "seed_base64", "network", "account"],
+        help = "Derive from a UFVK (jview1... or @label) instead of the seed"
+    )]
+    pub(crate) ufvk: Option<String>,
+
+    #[arg(long, help = "Read seed base64 from a file")]
+    pub(crate) seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    pub(crate) seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets ua_hrp + coin_type; default: profile network)"
+    )]
+    pub(crate) network: Option<NetworkArg>,
+
+    #[arg(long, default_value_t = 0, help = "Account (typically 0)")]
+    pub(crate) account: u32,
+}
+
+#[derive(Args)]
+pub(crate) struct AddressAtIndexArgs {
+    #[command(flatten)]
+    pub(crate) source: SourceArgs,
+
+    #[arg(
+        long,
+        value_parser = diversifier_index_arg,
+        help = "Diversifier index to start from, in decimal or 0x hex (below 2^88)"
+    )]
+    pub(crate) index: DiversifierIndex,
+}
+
+#[derive(Args)]
+pub(crate) struct AddressListArgs {
+    #[command(flatten)]
+    pub(crate) source: SourceArgs,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = diversifier_index_arg,
+        help = "Diversifier index of the first address, in decimal or 0x hex (below 2^88)"
+    )]
+    pub(crate) from: DiversifierIndex,
+
+    #[arg(
+        long,
+        default_value_t = 20,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "How many addresses to list"
+    )]
+    pub(crate) count: u64,
+
+    #[arg(long, help = "Write one JSON object per line")]
+    pub(crate) ndjson: bool,
+}
+
+struct Source {
+    ufvk: Ufvk,
+    account: Option<u32>,
+    path: Option<String>,
+}
+
+fn resolve(cli: &Cli, settings: &Settings, args: &SourceArgs) -> Result<Source, AppError> {
+    if let Some(ufvk) = &args.ufvk {
+        let ufvk = addressbook::resolve(settings, ufvk, EntryKind::Ufvk)?;
+        let ufvk = Ufvk::parse(&ufvk).map_err(AppError::Keys)?;
+        audit::note_ufvk(ufvk.as_str());
+        return Ok(Source {
+            ufvk,
+            account: None,
+            path: None,
+        });
+    }
+    let net = resolve_network(settings, args.network)?;
+    let params = network_params(cli, net);
+    let ufvk: String = isolate::run(cli, || {
+        let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+        let ufvk = juno_keys::ufvk_from_seed(&seed, params.as_ref(), args.account)
+            .map_err(AppError::Keys)?;
+        audit::note_seed(&seed);
+        Ok(ufvk)
+    })?;
+    audit::note_ufvk(&ufvk);
+    Ok(Source {
+        ufvk: Ufvk::parse(&ufvk).map_err(AppError::Keys)?,
+        account: Some(args.account),
+        path: Some(
+            ZipPath::account(params.coin_type(), args.account)
+                .map_err(AppError::Keys)?
+                .to_string(),
+        ),
+    })
+}
+
+pub(crate) fn cmd_address_at_index(
+    cli: &Cli,
+    settings: &Settings,
+    args: &AddressAtIndexArgs,
+) -> Result<(), AppError> {
+    let source = resolve(cli, settings, &args.source)?;
+    let (index, address) = source
+        .ufvk
+        .next_address(args.index)
+        .map_err(AppError::Keys)?;
+
+    #[derive(Serialize)]
+    struct AtIndexOut {
+        address: String,
+        ua_hrp: String,
+        requested_index: u128,
+        diversifier_index: u128,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        account: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+    }
+    let data = AtIndexOut {
+        address: address.to_string(),
+        ua_hrp: source.ufvk.ua_hrp().to_string(),
+        requested_index: args.index.into(),
+        diversifier_index: index.into(),
+        account: source.account,
+        path: source.path,
+    };
+    hooks::emit(cli, settings, "address at-index", &data)?;
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    if data.diversifier_index != data.requested_index {
+        style::note(&format!(
+            "index {} has no address; using {}",
+            data.requested_index, data.diversifier_index
+        ));
+    }
+    println!("{}", data.address);
+    visual::note(cli, visual::Kind::Address, &data.address);
+    Ok(())
+}
+
+pub(crate) fn cmd_address_list(
+    cli: &Cli,
+    settings: &Settings,
+    args: &AddressListArgs,
+) -> Result<(), AppError> {
+    #[derive(Serialize)]
+    struct ListRow<'a> {
+        diversifier_index: u128,
+        address: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        account: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<&'a str>,
+    }
+    batch::Rows::check(cli, args.ndjson)?;
+    // Fail before the first row rather than partway through the list.
+    if u128::from(args.from) + u128::from(args.count - 1) > MAX_DIVERSIFIER_INDEX {
+        return Err(AppError::Keys(KeysError::DiversifierIndexInvalid));
+    }
+    let source = resolve(cli, settings, &args.source)?;
+    let generator = source.ufvk.address_generator().map_err(AppError::Keys)?;
+    let mut rows = batch::Rows::start(cli, "addresses", args.ndjson)?;
+    let mut next = args.from;
+    for i in 0..args.count {
+        let (index, address) = generator.next_address(next).map_err(AppError::Keys)?;
+        rows.push(&ListRow {
+            diversifier_index: index.into(),
+            address: address.to_string(),
+            account: source.account,
+            path: source.path.as_deref(),
+        })?;
+        if i + 1 < args.count {
+            next = DiversifierIndex::try_from(u128::from(index) + 1)
+                .map_err(|_| AppError::Keys(KeysError::DiversifierIndexInvalid))?;
+        }
+    }
+    rows.finish()
+}
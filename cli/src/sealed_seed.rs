@@ -0,0 +1,266 @@
+//! `seed encrypt` / `seed decrypt`: a seed as one printable, checksummed string that
+//! is useless without its passphrase, in the spirit of BIP-38.
+//!
+//! The format is documented at `juno_keys::encrypt_seed`.
+//!
+//! `seed new --encrypt` writes the same string (armored with `--armor`) as a seed
+//! file, and `--seed-file` and profile keystores open such files with the passphrase
+//! from `--seed-passphrase-file` or typed at the terminal.
+
+use std::fs;
+use std::io::Read as _;
+use std::path::Path;
+
+use juno_keys::{ExposeSecret as _, KeysError, Network, Seed, SeedKdf};
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+use crate::kdf::Kdf;
+use crate::outpath::{self, OutVars};
+use crate::{armor, credential, prompt};
+use crate::{
+    audit, ensure_mainnet_ok, read_passphrase_file, read_typed_passphrase, resolve_seed, reveal,
+    with_passphrase, write_json_ok, write_secret_file, AppError, Cli, SeedDecryptArgs,
+    SeedEncryptArgs, Settings,
+};
+
+// `kdf_memory_mib` / `kdf_passes` replace the library's defaults.
+const DEFAULT_KDF: Kdf = Kdf {
+    memory_kib: SeedKdf::DEFAULT.memory_kib,
+    passes: SeedKdf::DEFAULT.passes,
+};
+
+pub(crate) fn cmd_seed_encrypt(
+    cli: &Cli,
+    settings: &Settings,
+    args: &SeedEncryptArgs,
+) -> Result<(), AppError> {
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    let passphrase = new_passphrase(Some(&args.passphrase_file))?;
+    audit::note_seed(&seed);
+    let encrypted = seal(settings, &seed, &passphrase)?;
+    let seed_fingerprint = juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?;
+
+    let out = match &args.out {
+        Some(out) => {
+            let out = outpath::expand(
+                out,
+                &OutVars {
+                    fingerprint: Some(seed_fingerprint.clone()),
+                    network: settings.network,
+                    account: None,
+                },
+            )?;
+            let text = armor::wrap(cli, armor::ENCRYPTED_SEED, &encrypted);
+            write_secret_file(&out, &(text.to_string() + "\n"), args.force)?;
+            Some(out)
+        }
+        None => None,
+    };
+    if cli.json {
+        return write_json_ok(
+            cli,
+            &serde_json::json!({
+                "encrypted_seed": encrypted,
+                "seed_fingerprint": seed_fingerprint,
+                "out_path": out.map(|p| p.display().to_string()),
+            }),
+        );
+    }
+    println!(
+        "{}",
+        armor::wrap(cli, armor::ENCRYPTED_SEED, &encrypted).as_str()
+    );
+    Ok(())
+}
+
+pub(crate) fn cmd_seed_decrypt(
+    cli: &Cli,
+    settings: &Settings,
+    args: &SeedDecryptArgs,
+) -> Result<(), AppError> {
+    if args.print {
+        let net = args.network.map(Network::from).or(settings.network);
+        ensure_mainnet_ok(cli, settings, net)?;
+    }
+    let encrypted = match (&args.encrypted, &args.file) {
+        (Some(encrypted), _) => encrypted.clone(),
+        (None, Some(path)) if path.as_os_str() == "-" => {
+            let mut raw = String::new();
+            std::io::stdin()
+                .read_to_string(&mut raw)
+                .map_err(|e| AppError::Io(format!("read stdin: {e}")))?;
+            raw
+        }
+        (None, Some(path)) => fs::read_to_string(path)
+            .map_err(|e| AppError::Io(format!("read {}: {e}", path.display())))?,
+        (None, None) => {
+            return Err(AppError::InvalidRequest(
+                "missing encrypted seed (pass it or set --file)".into(),
+            ))
+        }
+    };
+    let typed = read_typed_passphrase(&Some(args.passphrase_file.clone()))?;
+    let encrypted = armor::unwrap(armor::ENCRYPTED_SEED, &encrypted)?;
+    let seed = with_passphrase(&typed, |passphrase| decrypt(&encrypted, passphrase))?;
+    audit::note_seed(&seed);
+    let seed_b64 = seed.to_base64();
+    let seed_fingerprint = juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?;
+    let out = match &args.out {
+        Some(out) => {
+            let out = outpath::expand(
+                out,
+                &OutVars {
+                    fingerprint: Some(seed_fingerprint.clone()),
+                    network: args.network.map(Network::from).or(settings.network),
+                    account: None,
+                },
+            )?;
+            write_secret_file(
+                &out,
+                &(armor::wrap(cli, armor::SEED, seed_b64.expose_secret()).to_string() + "\n"),
+                args.force,
+            )?;
+            Some(out)
+        }
+        None => None,
+    };
+    if let Some(name) = &args.credential {
+        credential::write(name, seed_b64.expose_secret(), args.force, args.ttl)?;
+    }
+
+    #[derive(Serialize)]
+    struct DecryptOut {
+        bytes: usize,
+        seed_fingerprint: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        out_path: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        credential: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed_base64: Option<String>,
+    }
+    let data = DecryptOut {
+        bytes: seed.len(),
+        seed_fingerprint,
+        out_path: out.map(|p| p.display().to_string()),
+        credential: args.credential.as_deref().map(credential::label),
+        seed_base64: args.print.then(|| seed_b64.expose_secret().to_string()),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    if let Some(seed_base64) = &data.seed_base64 {
+        reveal::print_secret(cli, &armor::wrap(cli, armor::SEED, seed_base64))?;
+    } else {
+        println!("ok, seed fingerprint {}", data.seed_fingerprint);
+    }
+    Ok(())
+}
+
+/// The passphrase to seal a seed under, from `file` or typed twice at the terminal.
+pub(crate) fn new_passphrase(file: Option<&Path>) -> Result<Zeroizing<String>, AppError> {
+    let passphrase = match file {
+        Some(file) => read_passphrase_file(&Some(file.to_path_buf()))?,
+        None => juno_keys::normalize_secret(&prompt::passphrase(
+            "Passphrase for the encrypted seed",
+            true,
+            "--passphrase-file",
+        )?),
+    };
+    if passphrase.is_empty() {
+        return Err(AppError::InvalidRequest("the passphrase is empty".into()));
+    }
+    Ok(passphrase)
+}
+
+/// `seed` encrypted under `passphrase` with the configured Argon2id settings.
+pub(crate) fn seal(settings: &Settings, seed: &Seed, passphrase: &str) -> Result<String, AppError> {
+    encrypt(seed, passphrase, Kdf::configured(settings, DEFAULT_KDF))
+}
+
+/// Whether a seed file holds an encrypted seed, plain or armored.
+pub(crate) fn is_sealed(raw: &str) -> bool {
+    juno_keys::is_encrypted_seed(raw)
+        || raw
+            .trim_start()
+            .starts_with(&format!("-----BEGIN {}-----", armor::ENCRYPTED_SEED))
+}
+
+/// The seed in an encrypted seed file, with the passphrase from
+/// `--seed-passphrase-file` or typed at the terminal.
+pub(crate) fn open(settings: &Settings, path: &Path, raw: &str) -> Result<Seed, AppError> {
+    let encrypted = armor::unwrap(armor::ENCRYPTED_SEED, raw)?;
+    let typed = match &settings.seed_passphrase_file {
+        Some(file) => read_typed_passphrase(&Some(file.clone()))?,
+        None => prompt::passphrase(
+            &format!("Passphrase for {}", path.display()),
+            false,
+            "--seed-passphrase-file",
+        )?,
+    };
+    with_passphrase(&typed, |passphrase| decrypt(&encrypted, passphrase))
+}
+
+fn encrypt(seed: &Seed, passphrase: &str, kdf: Kdf) -> Result<String, AppError> {
+    let kdf = SeedKdf {
+        memory_kib: kdf.memory_kib,
+        passes: kdf.passes,
+    };
+    juno_keys::encrypt_seed(seed, passphrase, kdf).map_err(AppError::Keys)
+}
+
+fn decrypt(encrypted: &str, passphrase: &str) -> Result<Seed, AppError> {
+    juno_keys::decrypt_seed(encrypted, passphrase).map_err(|e| match e {
+        KeysError::Bech32(e) => AppError::InvalidRequest(format!(
+            "not an encrypted seed ({e}); check it for copying mistakes"
+        )),
+        e => AppError::Keys(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_seed_needs_its_passphrase() {
+        let seed = Seed::from_bytes(&[9; 32]).unwrap();
+        let kdf = Kdf {
+            memory_kib: 256,
+            passes: 1,
+        };
+        let encrypted = encrypt(&seed, "correct horse", kdf).unwrap();
+        assert!(encrypted.starts_with("jseedenc1"));
+
+        let opened = decrypt(&encrypted.to_uppercase(), "correct horse").unwrap();
+        assert_eq!(opened.as_bytes(), seed.as_bytes());
+        assert!(decrypt(&encrypted, "battery staple")
+            .unwrap_err()
+            .message()
+            .contains("wrong passphrase"));
+
+        let last = encrypted.len() - 1;
+        let typo = format!(
+            "{}{}",
+            &encrypted[..last],
+            if encrypted.ends_with('q') { 'p' } else { 'q' }
+        );
+        assert!(decrypt(&typo, "correct horse")
+            .unwrap_err()
+            .message()
+            .contains("copying mistakes"));
+
+        // Sealed under a decomposed accent, it opens from a composed one; a seed sealed
+        // before normalization, under the composed form itself, still opens as typed.
+        let composed = "caf\u{e9}";
+        let open = |typed: &str, sealed: &str| {
+            with_passphrase(typed, |p| decrypt(sealed, p)).map(|s| s.as_bytes().to_vec())
+        };
+        let nfkd = encrypt(&seed, &juno_keys::normalize_secret(composed), kdf).unwrap();
+        assert_eq!(open(composed, &nfkd).unwrap(), seed.as_bytes());
+        let legacy = encrypt(&seed, composed, kdf).unwrap();
+        assert_eq!(open(composed, &legacy).unwrap(), seed.as_bytes());
+        assert!(open("cafe\u{301}", &legacy).is_err());
+    }
+}
@@ -0,0 +1,1575 @@
+//! `juno-keys serve`: newline-delimited JSON-RPC 2.0, on stdin/stdout or a Unix domain
+//! socket, or the same methods as a REST API over HTTP (`server` feature) or a gRPC
+//! service (`grpc` feature), for parent processes and local services that would
+//! otherwise spawn the CLI per call.
+
+use std::io::{self, BufRead, Read as _, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::Instant;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use zeroize::Zeroize as _;
+
+use juno_keys::{
+    DiversifierIndex, ErrorCode, ExposeSecret as _, KeysError, Network, OrchardKeySet, Seed, Ufvk,
+    UnifiedAddress, ZipPath,
+};
+
+use crate::{
+    audit, diversifier_index_json, ensure_mainnet_ok, json_v2, json_version, network_params,
+    resolve_network, resolve_seed, AppError, Cli, NetworkArg, SeedInput, ServeArgs, Settings,
+};
+
+// Reserved JSON-RPC 2.0 codes; application failures use the positive `ErrorCode` values.
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+// Requests are a few short strings; a longer line is not a client of ours.
+const MAX_LINE: u64 = 64 * 1024;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    // Absent for notifications, which get no response.
+    id: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<AppError> for RpcError {
+    fn from(err: AppError) -> Self {
+        let mut data = json!({ "code": err.code() });
+        if let Some(d) = err.diagnostic() {
+            data["details"] = json!({ "stage": d.stage.as_str() });
+            if let Some(offset) = d.offset {
+                data["details"]["offset"] = offset.into();
+            }
+        }
+        RpcError {
+            code: err.error_code().into(),
+            message: err.message(),
+            data: Some(data),
+        }
+    }
+}
+
+pub(crate) fn cmd_serve(cli: &Cli, settings: &Settings, args: &ServeArgs) -> Result<(), AppError> {
+    // Requests are handled on several threads, where forking isn't sound, and the seed
+    // is held in this process anyway.
+    if cli.isolate {
+        return Err(AppError::InvalidRequest(
+            "--isolate can't be used with serve".into(),
+        ));
+    }
+    // Daemons and `--stdio` sessions load the seed once, so consumers never need to
+    // read the keystore.
+    if (args.stdio || args.stdin_json) && settings.seed_input == Some(SeedInput::Stdin) {
+        return Err(AppError::InvalidRequest(
+            "--seed-stdin can't be used with --stdio or --stdin-json, which read requests from stdin"
+                .into(),
+        ));
+    }
+    let warm = if !args.stdin_json
+        && (args.seed_file.is_some()
+            || settings.keystore.is_some()
+            || settings.seed_credential.is_some()
+            || settings.seed_input.is_some())
+    {
+        let seed = resolve_seed(settings, &args.seed_file, &None)?;
+        Some(Arc::new(WarmKeys::new(seed)))
+    } else {
+        None
+    };
+    if args.stdio || args.stdin_json {
+        let server = Server {
+            cli,
+            settings: Arc::new(LiveSettings::new(settings.clone())),
+            warm,
+            interlock: true,
+            started: Instant::now(),
+            guard: Arc::default(),
+            metrics: Arc::default(),
+            signer: args
+                .sign_key
+                .as_deref()
+                .map(Signer::load)
+                .transpose()?
+                .map(Arc::new),
+        };
+        if args.stdin_json {
+            return serve_once(&server);
+        }
+        if args.envelopes {
+            return server.serve_envelopes(io::stdin().lock(), io::stdout().lock());
+        }
+        return server.serve(None, io::stdin().lock(), io::stdout().lock());
+    }
+
+    let server = Server {
+        cli,
+        settings: Arc::new(LiveSettings::new(settings.clone())),
+        warm,
+        interlock: false,
+        started: Instant::now(),
+        guard: Arc::new(Guard::new(
+            args.auth_token_file.as_deref(),
+            args.rate_limit.or(settings.rate_limit),
+        )?),
+        metrics: Arc::default(),
+        signer: args
+            .sign_key
+            .as_deref()
+            .map(Signer::load)
+            .transpose()?
+            .map(Arc::new),
+    };
+    #[cfg(unix)]
+    signals::spawn(
+        server.warm.clone(),
+        Reload {
+            source: cli.config_source(),
+            rate_limit: args.rate_limit,
+            settings: Arc::clone(&server.settings),
+            guard: Arc::clone(&server.guard),
+        },
+    )?;
+    if let Some(addr) = args.metrics {
+        let listener = tcp_listener(settings, args, addr, false)?;
+        metrics::serve(Arc::clone(&server.metrics), listener);
+    }
+    match (&args.socket, args.http, args.grpc) {
+        (Some(path), _, _) => socket::serve(&server, path, &args.allow_uid),
+        (None, Some(addr), _) => http::serve(&server, tcp_listener(settings, args, addr, true)?),
+        (None, None, Some(addr)) => grpc::serve(&server, tcp_listener(settings, args, addr, true)?),
+        (None, None, None) => Err(AppError::InvalidRequest(
+            "set --stdio, --socket, --http or --grpc".to_string(),
+        )),
+    }
+}
+
+// `--stdin-json`: one request object on stdin, one envelope on stdout, and the
+// error's code as the exit status, for callers that can't keep a pipe open.
+fn serve_once(server: &Server) -> Result<(), AppError> {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| AppError::Io(format!("read request: {e}")))?;
+    let (result, request_id) = server.answer(&input);
+    // Protocol-level failures exit as usage errors do.
+    let status = match &result {
+        Ok(_) => 0,
+        Err(err) if err.code > 0 => err.code,
+        Err(_) => ErrorCode::InvalidRequest.into(),
+    };
+    let response = server.signed(envelope(result, request_id));
+    write_line(&mut io::stdout().lock(), &response)?;
+    if status != 0 {
+        std::process::exit(status);
+    }
+    Ok(())
+}
+
+// The CLI's JSON envelope for a method's result, as `--http` and `--stdin-json`
+// answer, with the request ID at the top level.
+fn envelope(result: Result<Value, RpcError>, request_id: Option<String>) -> Value {
+    let mut envelope = match result {
+        Ok(data) => json!({ "version": json_version(), "status": "ok", "data": data }),
+        Err(RpcError { message, data, .. }) => {
+            // Protocol-level failures (negative codes) carry no string code.
+            let code = data
+                .as_ref()
+                .and_then(|d| d["code"].as_str())
+                .unwrap_or("invalid_request");
+            let mut error = error_object(code, message);
+            if let Some(details) = data.as_ref().and_then(|d| d.get("details")) {
+                error["details"] = details.clone();
+            }
+            json!({ "version": json_version(), "status": "err", "error": error })
+        }
+    };
+    if request_id.is_some() || json_v2() {
+        envelope["request_id"] = request_id.into();
+    }
+    envelope
+}
+
+// An envelope's `error`, with the numeric `exit` that v2 adds for codes that have one.
+fn error_object(code: &str, message: String) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(exit) = ErrorCode::ALL
+        .iter()
+        .find(|c| c.as_str() == code)
+        .filter(|_| json_v2())
+    {
+        error["exit"] = i32::from(*exit).into();
+    }
+    error
+}
+
+// Binds `addr`, or with `activated` takes the systemd-activated listener if there is
+// one. TCP listeners have no peer authentication, so they stay on loopback by default.
+fn tcp_listener(
+    settings: &Settings,
+    args: &ServeArgs,
+    addr: SocketAddr,
+    activated: bool,
+) -> Result<TcpListener, AppError> {
+    if settings.assert_offline {
+        return Err(AppError::NotOffline(
+            "serving TCP is forbidden by --assert-offline".to_string(),
+        ));
+    }
+    let activated = if activated {
+        systemd::tcp_listener()?
+    } else {
+        None
+    };
+    let listener = match activated {
+        Some(listener) => listener,
+        None => TcpListener::bind(addr).map_err(|e| AppError::Io(format!("bind {addr}: {e}")))?,
+    };
+    let local = listener
+        .local_addr()
+        .map_err(|e| AppError::Io(format!("listener address: {e}")))?;
+    if !local.ip().is_loopback() && !args.allow_remote {
+        return Err(AppError::InvalidRequest(format!(
+            "refusing to serve on non-loopback {local} without --allow-remote"
+        )));
+    }
+    Ok(listener)
+}
+
+struct Server<'a> {
+    cli: &'a Cli,
+    // Swapped by SIGHUP in the daemon modes.
+    settings: Arc<LiveSettings>,
+    // Used when a request carries no seed; otherwise the profile keystore is read.
+    warm: Option<Arc<WarmKeys>>,
+    // Only stdio responses can land on a terminal.
+    interlock: bool,
+    started: Instant,
+    guard: Arc<Guard>,
+    metrics: Arc<Metrics>,
+    signer: Option<Arc<Signer>>,
+}
+
+impl Server<'_> {
+    // Answers requests from `input` until it closes.
+    fn serve(
+        &self,
+        client: Option<Client>,
+        mut input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<(), AppError> {
+        while let Some(line) = read_request_line(&mut input)? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some(response) = self.handle_line(client, &line) else {
+                continue;
+            };
+            write_line(&mut output, &self.signed(response))?;
+        }
+        Ok(())
+    }
+
+    // `--stdio --envelopes`: a `{"method", "params"}` object per line, answered with
+    // an envelope line each, in order, until `input` closes.
+    fn serve_envelopes(
+        &self,
+        mut input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<(), AppError> {
+        while let Some(line) = read_request_line(&mut input)? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (result, request_id) = self.answer(&line);
+            write_line(&mut output, &self.signed(envelope(result, request_id)))?;
+        }
+        Ok(())
+    }
+
+    // One `{"method", "params"}` request, with the request ID its envelope carries.
+    fn answer(&self, input: &str) -> (Result<Value, RpcError>, Option<String>) {
+        match serde_json::from_str::<OnceRequest>(input) {
+            Ok(OnceRequest { method, mut params }) => match self.take_request_id(&mut params) {
+                Ok(request_id) => (
+                    self.dispatch(None, request_id.as_deref(), &method, params),
+                    request_id,
+                ),
+                Err(err) => (Err(err), self.cli.request_id.clone()),
+            },
+            Err(e) => (
+                Err(RpcError::new(INVALID_REQUEST, e.to_string())),
+                self.cli.request_id.clone(),
+            ),
+        }
+    }
+
+    fn signed(&self, mut response: Value) -> Value {
+        if let Some(signer) = &self.signer {
+            signer.sign(&mut response);
+        }
+        response
+    }
+
+    fn handle_line(&self, client: Option<Client>, line: &str) -> Option<Value> {
+        let value = match serde_json::from_str::<Value>(line) {
+            Ok(value) => value,
+            Err(e) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, e.to_string()),
+                ))
+            }
+        };
+        let id = value.get("id").cloned().unwrap_or(Value::Null);
+        // JSON-RPC lets servers ignore extra members; `--strict` turns them into errors.
+        let extra = value.as_object().and_then(|request| {
+            request
+                .keys()
+                .find(|key| !["jsonrpc", "method", "params", "id"].contains(&key.as_str()))
+        });
+        if let Some(key) = extra.filter(|_| self.cli.strict || self.settings.get().strict) {
+            return Some(error_response(
+                id,
+                RpcError::new(INVALID_REQUEST, format!("unknown field `{key}` (--strict)")),
+            ));
+        }
+        let request = match serde_json::from_value::<Request>(value) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(_) => {
+                return Some(error_response(
+                    id,
+                    RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+                ))
+            }
+            Err(e) => {
+                return Some(error_response(
+                    id,
+                    RpcError::new(INVALID_REQUEST, e.to_string()),
+                ))
+            }
+        };
+        let mut params = request.params;
+        let result = self.admit(client, None).and_then(|()| {
+            let request_id = self.take_request_id(&mut params)?;
+            let result = self.dispatch(client, request_id.as_deref(), &request.method, params);
+            echo_request_id(result, request_id)
+        });
+        let id = request.id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": id }),
+            Err(err) => error_response(id, err),
+        })
+    }
+
+    // Removes the optional `request_id` param, falling back to `--request-id`, so
+    // methods never see it.
+    fn take_request_id(&self, params: &mut Value) -> Result<Option<String>, RpcError> {
+        let taken = match params.as_object_mut() {
+            Some(params) => params.remove("request_id"),
+            None => None,
+        };
+        match taken {
+            None => Ok(self.cli.request_id.clone()),
+            Some(Value::String(request_id)) => Ok(Some(request_id)),
+            Some(_) => Err(RpcError::new(INVALID_PARAMS, "request_id must be a string")),
+        }
+    }
+
+    // Admits a request through `guard`, counting refusals.
+    fn admit(&self, client: Option<Client>, authorization: Option<&str>) -> Result<(), RpcError> {
+        let admitted = self.guard.admit(client, authorization);
+        if let Err(err) = &admitted {
+            self.metrics.reject(err);
+        }
+        admitted
+    }
+
+    fn dispatch(
+        &self,
+        client: Option<Client>,
+        request_id: Option<&str>,
+        method: &str,
+        mut params: Value,
+    ) -> Result<Value, RpcError> {
+        let start = Instant::now();
+        let sink = self.cli.audit.or(self.settings.get().audit);
+        let seed = take_seed(method, &mut params);
+        // Taken before `run` consumes the params.
+        let audited = sink.map(|_| {
+            (
+                audit::redact(&params),
+                self.seed_fingerprint(method, &params, seed.as_ref()),
+            )
+        });
+        // A panic fails only its request. Unwinding drops the request's seed and derived
+        // keys, which wipe themselves, and the daemon keeps serving.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.run(method, params, seed)))
+            .unwrap_or_else(|_| {
+                Err(RpcError {
+                    code: ErrorCode::Panic.into(),
+                    message: format!("internal error while handling {method}"),
+                    data: Some(json!({ "code": ErrorCode::Panic.as_str() })),
+                })
+            });
+        let outcome = result.as_ref().map(|_| ());
+        self.metrics.record(method, outcome, start.elapsed());
+        if let (Some(sink), Some((params, seed_fingerprint))) = (sink, audited) {
+            let ufvk = result
+                .as_ref()
+                .ok()
+                .and_then(|r| r.get("ufvk"))
+                .or(params.get("ufvk"));
+            let event = audit::Event {
+                operation: method,
+                outcome: match &result {
+                    Ok(_) => "ok",
+                    Err(err) => metrics::code_label(err),
+                },
+                caller: client.map_or_else(audit::local_caller, |client| client.to_string()),
+                request_id,
+                seed_fingerprint,
+                ufvk_fingerprint: ufvk
+                    .and_then(Value::as_str)
+                    .and_then(|ufvk| Ufvk::parse(ufvk).ok())
+                    .map(|ufvk| ufvk.fingerprint()),
+                backups: Vec::new(),
+                params,
+            };
+            audit::emit(sink, &event);
+        }
+        result
+    }
+
+    // The fingerprint of the seed a request derives from, when that is known without
+    // deriving: the request's own seed or the warm one.
+    fn seed_fingerprint(
+        &self,
+        method: &str,
+        params: &Value,
+        seed: Option<&Result<Seed, RpcError>>,
+    ) -> Option<String> {
+        if let Some(seed) = seed {
+            return juno_keys::seed_fingerprint(seed.as_ref().ok()?).ok();
+        }
+        let from_seed = method == "ufvk.fromSeed"
+            || (method == "address.derive" && params.get("ufvk").is_none());
+        let warm = self.warm.as_ref().filter(|_| from_seed)?;
+        warm.fingerprint().map(str::to_string)
+    }
+
+    // `seed` is the request's own seed, taken out of `params` by `take_seed`.
+    fn run(
+        &self,
+        method: &str,
+        params: Value,
+        seed: Option<Result<Seed, RpcError>>,
+    ) -> Result<Value, RpcError> {
+        match method {
+            "seed.new" => self.seed_new(parse_params(params)?),
+            "ufvk.fromSeed" => self.ufvk_from_seed(parse_params(params)?, seed),
+            "address.derive" => self.address_derive(parse_params(params)?),
+            "validate" => self.validate(parse_params(params)?),
+            "status" => self.status(parse_params(params)?),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {method}"),
+            )),
+        }
+    }
+
+    fn seed_new(&self, params: SeedNewParams) -> Result<Value, RpcError> {
+        if self.interlock {
+            let settings = self.settings.get();
+            let net = params.network.map(Into::into).or(settings.network);
+            ensure_mainnet_ok(self.cli, &settings, net)?;
+        }
+        let seed = Seed::generate(params.bytes).map_err(AppError::Keys)?;
+        Ok(json!({
+            "bytes": params.bytes,
+            "seed_fingerprint": juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?,
+            "seed_base64": seed.to_base64().expose_secret(),
+        }))
+    }
+
+    fn ufvk_from_seed(
+        &self,
+        params: UfvkFromSeedParams,
+        seed: Option<Result<Seed, RpcError>>,
+    ) -> Result<Value, RpcError> {
+        let net = resolve_network(&self.settings.get(), params.network)?;
+        // Read the keystore here rather than in `account_ufvk`, to fingerprint it.
+        let seed = match seed.transpose()? {
+            None if self.warm.is_none() => Some(resolve_seed(&self.settings.get(), &None, &None)?),
+            seed => seed,
+        };
+        let ufvk = self.account_ufvk(seed.as_ref(), net, params.account)?;
+        let params_net = network_params(self.cli, net);
+        let path =
+            ZipPath::account(params_net.coin_type(), params.account).map_err(AppError::Keys)?;
+        let seed_fingerprint = match &seed {
+            Some(seed) => Some(juno_keys::seed_fingerprint(seed).map_err(AppError::Keys)?),
+            None => self
+                .warm
+                .as_ref()
+                .and_then(|warm| warm.fingerprint())
+                .map(str::to_string),
+        };
+        Ok(json!({
+            "ufvk": ufvk.to_string(),
+            "ua_hrp": params_net.ua_hrp(),
+            "coin_type": params_net.coin_type(),
+            "account": params.account,
+            "path": path.to_string(),
+            "seed_fingerprint": seed_fingerprint,
+            "ufvk_fingerprint": ufvk.fingerprint(),
+        }))
+    }
+
+    fn address_derive(&self, params: AddressDeriveParams) -> Result<Value, RpcError> {
+        let index = diversifier_index_param(&params.diversifier_index).map_err(AppError::Keys)?;
+        let address = match (params.ufvk, &self.warm) {
+            (Some(_), _) if params.network.is_some() || params.account.is_some() => {
+                return Err(AppError::InvalidRequest(
+                    "give either ufvk or network/account, not both".to_string(),
+                )
+                .into())
+            }
+            (Some(ufvk), _) => Ufvk::parse(&ufvk).and_then(|ufvk| ufvk.address_at(index)),
+            (None, warm) => {
+                let net = resolve_network(&self.settings.get(), params.network)?;
+                let account = params.account.unwrap_or(0);
+                match warm {
+                    Some(warm) => warm.with_account(self.cli, net, account, |keys| {
+                        keys.addresses.address_at(index)
+                    })?,
+                    None => self.account_ufvk(None, net, account)?.address_at(index),
+                }
+            }
+        };
+        let address = address.map_err(AppError::Keys)?;
+        Ok(json!({
+            "address": address.to_string(),
+            "diversifier_index": diversifier_index_json(index),
+        }))
+    }
+
+    // The request's seed if it has one, else the daemon's warm keys, else the profile
+    // keystore.
+    fn account_ufvk(
+        &self,
+        seed: Option<&Seed>,
+        net: Network,
+        account: u32,
+    ) -> Result<Ufvk, AppError> {
+        let keystore;
+        let seed = match (seed, &self.warm) {
+            (Some(seed), _) => seed,
+            (None, Some(warm)) => {
+                return warm.with_account(self.cli, net, account, |keys| keys.ufvk.clone())
+            }
+            (None, None) => {
+                keystore = resolve_seed(&self.settings.get(), &None, &None)?;
+                &keystore
+            }
+        };
+        let params = network_params(self.cli, net);
+        OrchardKeySet::from_seed(seed, params.as_ref(), account)
+            .and_then(|keys| keys.ufvk(params.as_ref()))
+            .map_err(AppError::Keys)
+    }
+
+    // Everything here is safe to show to deployment tooling: the seed fingerprint
+    // identifies the loaded seed without revealing it.
+    fn status(&self, _: StatusParams) -> Result<Value, RpcError> {
+        let features: Vec<&str> = [
+            ("server", cfg!(feature = "server")),
+            ("grpc", cfg!(feature = "grpc")),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect();
+        Ok(json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "features": features,
+            "network": self.settings.get().network.map(|n| n.as_str()),
+            "zcash_compat": self.cli.zcash_compat,
+            "seed_fingerprint": self.warm.as_ref().and_then(|w| w.fingerprint()),
+            "uptime_secs": self.started.elapsed().as_secs(),
+            "signing_key": self.signer.as_deref().map(Signer::public_key),
+        }))
+    }
+
+    // Invalid input is a normal result here, not an RPC error.
+    fn validate(&self, params: ValidateParams) -> Result<Value, RpcError> {
+        let zcash_compat = self.cli.zcash_compat;
+        let network_name = |net: Option<juno_keys::Network>| net.map(|n| n.as_str());
+        let ufvk_like = params
+            .value
+            .trim()
+            .to_ascii_lowercase()
+            .rsplit_once('1')
+            .is_some_and(|(hrp, _)| hrp.contains("view"));
+        let outcome = if ufvk_like {
+            Ufvk::parse(&params.value).map(|k| {
+                let net = if zcash_compat {
+                    k.zcash_network()
+                } else {
+                    k.network()
+                };
+                ("ufvk", network_name(net))
+            })
+        } else {
+            UnifiedAddress::parse(&params.value).map(|a| {
+                let net = if zcash_compat {
+                    a.zcash_network()
+                } else {
+                    a.network()
+                };
+                ("address", network_name(net))
+            })
+        };
+        Ok(match outcome {
+            Ok((kind, network)) => json!({ "valid": true, "kind": kind, "network": network }),
+            Err(e) => {
+                let err = AppError::Keys(e);
+                json!({ "valid": false, "code": err.code(), "message": err.message() })
+            }
+        })
+    }
+}
+
+// The next line of `input` without its line ending, or `None` once it closes. A line
+// over `MAX_LINE` bytes ends the session instead of being buffered whole.
+fn read_request_line(input: &mut impl BufRead) -> Result<Option<String>, AppError> {
+    // Room for the longest line and a CRLF.
+    let limit = MAX_LINE + 2;
+    let mut line = String::new();
+    let read = input
+        .take(limit)
+        .read_line(&mut line)
+        .map_err(|e| AppError::Io(format!("read request: {e}")))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    let complete = line.ends_with('\n') || (read as u64) < limit;
+    let end = line.trim_end_matches('\n').trim_end_matches('\r').len();
+    line.truncate(end);
+    if !complete || line.len() as u64 > MAX_LINE {
+        return Err(AppError::InvalidRequest(format!(
+            "request line is over {MAX_LINE} bytes"
+        )));
+    }
+    Ok(Some(line))
+}
+
+fn write_line(output: &mut impl Write, response: &Value) -> Result<(), AppError> {
+    serde_json::to_writer(&mut *output, response)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    writeln!(output)
+        .and_then(|()| output.flush())
+        .map_err(|e| AppError::Io(format!("write response: {e}")))
+}
+
+// JSON-RPC responses can't carry extra members, so the correlation ID goes into
+// the result or the error's data.
+fn echo_request_id(
+    result: Result<Value, RpcError>,
+    request_id: Option<String>,
+) -> Result<Value, RpcError> {
+    let Some(request_id) = request_id else {
+        return result;
+    };
+    match result {
+        Ok(mut result) => {
+            if let Some(result) = result.as_object_mut() {
+                result.insert("request_id".to_string(), request_id.into());
+            }
+            Ok(result)
+        }
+        Err(mut err) => {
+            let data = err.data.get_or_insert_with(|| json!({}));
+            data["request_id"] = request_id.into();
+            Err(err)
+        }
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "error": error, "id": id })
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // Methods whose parameters are all optional may omit `params`.
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeedNewParams {
+    #[serde(default = "default_seed_bytes")]
+    bytes: usize,
+    network: Option<NetworkArg>,
+}
+
+fn default_seed_bytes() -> usize {
+    64
+}
+
+// Its `seed_base64` is taken out beforehand by `take_seed`; without one, the request
+// falls back to the daemon's seed, then the profile keystore.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UfvkFromSeedParams {
+    network: Option<NetworkArg>,
+    #[serde(default)]
+    account: u32,
+}
+
+/// Checks a request's params the way its method would, without deriving anything, for
+/// `lint --jobs`.
+pub(crate) fn check_params(method: &str, params: Value) -> Result<(), String> {
+    let mut params = params;
+    if let Some(params) = params.as_object_mut() {
+        if params
+            .remove("request_id")
+            .is_some_and(|id| !id.is_string())
+        {
+            return Err("request_id must be a string".into());
+        }
+    }
+    let account = |account: u32| {
+        juno_keys::hardened_child(account)
+            .map(|_| ())
+            .map_err(|_| format!("account {account} is not below 2^31"))
+    };
+    let parsed = |e: RpcError| e.message;
+    match method {
+        "seed.new" => {
+            let params: SeedNewParams = parse_params(params).map_err(parsed)?;
+            if !(Seed::MIN_LEN..=Seed::MAX_LEN).contains(&params.bytes) {
+                return Err(format!(
+                    "bytes must be from {} to {}",
+                    Seed::MIN_LEN,
+                    Seed::MAX_LEN
+                ));
+            }
+            Ok(())
+        }
+        "ufvk.fromSeed" => {
+            if let Some(Err(e)) = take_seed(method, &mut params) {
+                return Err(format!("seed_base64: {}", e.message));
+            }
+            let params: UfvkFromSeedParams = parse_params(params).map_err(parsed)?;
+            account(params.account)
+        }
+        "address.derive" => {
+            let params: AddressDeriveParams = parse_params(params).map_err(parsed)?;
+            diversifier_index_param(&params.diversifier_index)
+                .map_err(|_| "diversifier_index must be an integer below 2^88".to_string())?;
+            match params.ufvk {
+                Some(_) if params.network.is_some() || params.account.is_some() => {
+                    Err("give either ufvk or network/account, not both".into())
+                }
+                Some(ufvk) => Ufvk::parse(&ufvk)
+                    .map(|_| ())
+                    .map_err(|e| format!("ufvk: {}", AppError::Keys(e).message())),
+                None => params.account.map_or(Ok(()), account),
+            }
+        }
+        "validate" => parse_params::<ValidateParams>(params)
+            .map(|_| ())
+            .map_err(parsed),
+        "status" => parse_params::<StatusParams>(params)
+            .map(|_| ())
+            .map_err(parsed),
+        _ => Err(format!("unknown method: {method}")),
+    }
+}
+
+fn diversifier_index_param(index: &Option<Value>) -> Result<DiversifierIndex, KeysError> {
+    match index {
+        None => Ok(DiversifierIndex::new()),
+        Some(Value::Number(n)) => n
+            .as_u64()
+            .map(DiversifierIndex::from)
+            .ok_or(KeysError::DiversifierIndexInvalid),
+        Some(Value::String(s)) => juno_keys::parse_diversifier_index(s),
+        Some(_) => Err(KeysError::DiversifierIndexInvalid),
+    }
+}
+
+// Decodes a `ufvk.fromSeed` request's `seed_base64` once, for both its audit event and
+// its derivation, and wipes the encoded copy. Other methods, and values that aren't
+// strings, keep the param, so parsing still refuses it.
+fn take_seed(method: &str, params: &mut Value) -> Option<Result<Seed, RpcError>> {
+    if method != "ufvk.fromSeed" {
+        return None;
+    }
+    let params = params.as_object_mut()?;
+    match params.remove("seed_base64")? {
+        Value::Null => None,
+        Value::String(mut encoded) => {
+            let seed = Seed::from_base64(&encoded).map_err(|e| AppError::Keys(e).into());
+            encoded.zeroize();
+            Some(seed)
+        }
+        other => {
+            params.insert("seed_base64".into(), other);
+            None
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AddressDeriveParams {
+    // Without a UFVK, derives from the account's keys like `ufvk.fromSeed`.
+    ufvk: Option<String>,
+    network: Option<NetworkArg>,
+    account: Option<u32>,
+    // A number, or a decimal or 0x-hex string for indices past what JSON numbers hold.
+    diversifier_index: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OnceRequest {
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StatusParams {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidateParams {
+    value: String,
+}
+
+#[cfg(unix)]
+mod socket {
+    use std::fs;
+    use std::io::{self, BufReader};
+    use std::os::fd::AsRawFd as _;
+    use std::os::unix::fs::{FileTypeExt as _, PermissionsExt as _};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::thread;
+
+    use juno_keys::ErrorCode;
+    use serde_json::Value;
+
+    use super::guard::{denied, Client};
+    use super::{error_response, write_line, Server};
+    use crate::AppError;
+
+    // Connections are served by a fixed pool, as HTTP requests are; more wait to be
+    // accepted.
+    const WORKERS: usize = 4;
+
+    pub(super) fn serve(server: &Server, path: &Path, allow_uid: &[u32]) -> Result<(), AppError> {
+        let listener = match super::systemd::unix_listener()? {
+            // The socket unit owns the path and its mode.
+            Some(listener) => listener,
+            None => bind(path, allow_uid)?,
+        };
+        // SAFETY: geteuid has no preconditions and cannot fail.
+        let own_uid = unsafe { libc::geteuid() };
+        thread::scope(|scope| {
+            for _ in 0..WORKERS {
+                scope.spawn(|| {
+                    for stream in listener.incoming() {
+                        let stream = match stream {
+                            Ok(stream) => stream,
+                            Err(e) => {
+                                eprintln!("accept: {e}");
+                                continue;
+                            }
+                        };
+                        match peer_uid(&stream) {
+                            Ok(uid) if uid == own_uid || allow_uid.contains(&uid) => {
+                                if let Err(e) = serve_connection(server, stream, uid) {
+                                    eprintln!("connection from uid {uid}: {}", e.message());
+                                }
+                            }
+                            Ok(uid) => {
+                                eprintln!("rejected connection from uid {uid}");
+                                reject(stream);
+                            }
+                            Err(e) => eprintln!("peer credentials: {e}"),
+                        }
+                    }
+                });
+            }
+        });
+        Ok(())
+    }
+
+    fn bind(path: &Path, allow_uid: &[u32]) -> Result<UnixListener, AppError> {
+        // Replace a socket left behind by a previous daemon, but nothing else.
+        if let Ok(meta) = fs::symlink_metadata(path) {
+            if !meta.file_type().is_socket() {
+                return Err(AppError::Io(format!(
+                    "{} exists and is not a socket",
+                    path.display()
+                )));
+            }
+            fs::remove_file(path).map_err(|e| AppError::Io(format!("remove socket: {e}")))?;
+        }
+        let listener =
+            UnixListener::bind(path).map_err(|e| AppError::Io(format!("bind socket: {e}")))?;
+        // Other users can only connect if explicitly allowed; peer credentials are
+        // checked either way.
+        let mode = if allow_uid.is_empty() { 0o600 } else { 0o666 };
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))
+            .map_err(|e| AppError::Io(format!("chmod socket: {e}")))?;
+        Ok(listener)
+    }
+
+    fn serve_connection(server: &Server, stream: UnixStream, uid: u32) -> Result<(), AppError> {
+        let reader = stream
+            .try_clone()
+            .map_err(|e| AppError::Io(format!("clone stream: {e}")))?;
+        server.serve(Some(Client::Uid(uid)), BufReader::new(reader), stream)
+    }
+
+    fn reject(mut stream: UnixStream) {
+        let error = denied(ErrorCode::PeerNotAllowed, "peer uid is not allowed");
+        let _ = write_line(&mut stream, &error_response(Value::Null, error));
+    }
+
+    #[cfg(target_os = "linux")]
+    fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+        let mut cred = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        let mut len = size_of::<libc::ucred>() as libc::socklen_t;
+        // SAFETY: `cred` and `len` are valid for writes and `len` is the size of `cred`.
+        let rc = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                (&mut cred as *mut libc::ucred).cast(),
+                &mut len,
+            )
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(cred.uid)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+        let (mut uid, mut gid) = (0, 0);
+        // SAFETY: `uid` and `gid` are valid for writes.
+        if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(uid)
+    }
+}
+
+#[cfg(feature = "server")]
+mod http {
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+
+    use serde_json::{json, Value};
+    use tiny_http::{Header, Method, Request, Response};
+
+    use juno_keys::ErrorCode;
+
+    use super::guard::Client;
+    use super::{envelope, error_object, RpcError, Server};
+    use crate::{json_v2, json_version, AppError};
+
+    const WORKERS: usize = 4;
+    // Requests are a few short strings; anything larger is not a client of ours.
+    pub(super) const MAX_BODY: u64 = 64 * 1024;
+
+    pub(super) fn serve(server: &Server, listener: TcpListener) -> Result<(), AppError> {
+        let addr = listener
+            .local_addr()
+            .map_err(|e| AppError::Io(format!("listener address: {e}")))?;
+        let http = tiny_http::Server::from_listener(listener, None)
+            .map_err(|e| AppError::Io(format!("serve {addr}: {e}")))?;
+        eprintln!("listening on http://{addr}");
+        thread::scope(|scope| {
+            for _ in 0..WORKERS {
+                scope.spawn(|| {
+                    for request in http.incoming_requests() {
+                        handle(server, request);
+                    }
+                });
+            }
+        });
+        Ok(())
+    }
+
+    fn handle(server: &Server, mut request: Request) {
+        let client = request.remote_addr().map(|addr| Client::Ip(addr.ip()));
+        let authorization = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("authorization"))
+            .map(|header| header.value.to_string());
+        let (status, envelope) = match server.admit(client, authorization.as_deref()) {
+            Ok(()) => {
+                let length = request.body_length();
+                match read_body(request.as_reader(), length) {
+                    Ok(body) => route(server, client, request.method(), request.url(), &body),
+                    Err(response) => response,
+                }
+            }
+            Err(err) => (status(&err), envelope(Err(err), None)),
+        };
+        let header = Header::from_bytes("content-type", "application/json").expect("valid header");
+        let mut response = Response::from_string(server.signed(envelope).to_string())
+            .with_status_code(status)
+            .with_header(header);
+        let challenge = match status {
+            401 => Some(("www-authenticate", "Bearer")),
+            429 => Some(("retry-after", "1")),
+            _ => None,
+        };
+        if let Some((field, value)) = challenge {
+            response.add_header(Header::from_bytes(field, value).expect("valid header"));
+        }
+        if let Err(e) = request.respond(response) {
+            eprintln!("respond: {e}");
+        }
+    }
+
+    // Reads a request body of at most `MAX_BODY` bytes. A larger one is refused with 413,
+    // by its Content-Length or by reading one byte past the limit, rather than being
+    // truncated into a different (or still valid) request.
+    pub(super) fn read_body(
+        reader: impl Read,
+        length: Option<usize>,
+    ) -> Result<String, (u16, Value)> {
+        let too_large = || {
+            error(
+                413,
+                "invalid_request",
+                format!("request body is over {MAX_BODY} bytes"),
+            )
+        };
+        if length.is_some_and(|length| length as u64 > MAX_BODY) {
+            return Err(too_large());
+        }
+        let mut body = String::new();
+        reader
+            .take(MAX_BODY + 1)
+            .read_to_string(&mut body)
+            .map_err(|e| error(400, "invalid_request", format!("read body: {e}")))?;
+        if body.len() as u64 > MAX_BODY {
+            return Err(too_large());
+        }
+        Ok(body)
+    }
+
+    // Maps REST routes onto the JSON-RPC methods, answering in the CLI's JSON envelope.
+    pub(super) fn route(
+        server: &Server,
+        client: Option<Client>,
+        method: &Method,
+        url: &str,
+        body: &str,
+    ) -> (u16, Value) {
+        let path = url.split('?').next().unwrap_or_default();
+        let (allowed, rpc_method) = match path {
+            "/v1/health" => (Method::Get, "status"),
+            "/v1/ufvk/from-seed" => (Method::Post, "ufvk.fromSeed"),
+            "/v1/address/derive" => (Method::Post, "address.derive"),
+            "/v1/validate" => (Method::Post, "validate"),
+            _ => return error(404, "not_found", format!("no route for {path}")),
+        };
+        if *method != allowed {
+            return error(
+                405,
+                "method_not_allowed",
+                format!("use {allowed} for {path}"),
+            );
+        }
+        let parsed = if body.trim().is_empty() {
+            Ok(Value::Null)
+        } else {
+            serde_json::from_str::<Value>(body)
+        };
+        let mut params = match parsed {
+            Ok(params) => params,
+            Err(e) => return error(400, "invalid_request", e.to_string()),
+        };
+        let request_id = match server.take_request_id(&mut params) {
+            Ok(request_id) => request_id,
+            Err(e) => return error(400, "invalid_request", e.message),
+        };
+        let result = server.dispatch(client, request_id.as_deref(), rpc_method, params);
+        let status = match &result {
+            Ok(_) => 200,
+            Err(err) => status(err),
+        };
+        (status, envelope(result, request_id))
+    }
+
+    fn status(err: &RpcError) -> u16 {
+        match ErrorCode::try_from(err.code) {
+            Ok(ErrorCode::Internal) => 500,
+            Ok(ErrorCode::Unauthenticated) => 401,
+            Ok(ErrorCode::RateLimited) => 429,
+            _ => 400,
+        }
+    }
+
+    // Failures of the REST layer itself, before any method runs.
+    fn error(status: u16, code: &str, message: String) -> (u16, Value) {
+        let mut error = json!({
+            "version": json_version(),
+            "status": "err",
+            "error": error_object(code, message),
+        });
+        if json_v2() {
+            error["request_id"] = Value::Null;
+        }
+        (status, error)
+    }
+}
+
+#[cfg(not(feature = "server"))]
+mod http {
+    use std::net::TcpListener;
+
+    use super::Server;
+    use crate::AppError;
+
+    pub(super) fn serve(_: &Server, _: TcpListener) -> Result<(), AppError> {
+        Err(AppError::InvalidRequest(
+            "--http needs a build with the `server` feature".to_string(),
+        ))
+    }
+}
+
+#[cfg(feature = "grpc")]
+mod grpc;
+mod guard;
+mod metrics;
+mod reload;
+mod sign;
+#[cfg(unix)]
+mod signals;
+mod systemd;
+mod warm;
+
+use guard::{Client, Guard};
+use metrics::Metrics;
+use reload::LiveSettings;
+#[cfg(unix)]
+use reload::Reload;
+use sign::Signer;
+use warm::WarmKeys;
+
+#[cfg(not(feature = "grpc"))]
+mod grpc {
+    use std::net::TcpListener;
+
+    use super::Server;
+    use crate::AppError;
+
+    pub(super) fn serve(_: &Server, _: TcpListener) -> Result<(), AppError> {
+        Err(AppError::InvalidRequest(
+            "--grpc needs a build with the `grpc` feature".to_string(),
+        ))
+    }
+}
+
+#[cfg(not(unix))]
+mod socket {
+    use std::path::Path;
+
+    use super::Server;
+    use crate::AppError;
+
+    pub(super) fn serve(_: &Server, _: &Path, _: &[u32]) -> Result<(), AppError> {
+        Err(AppError::InvalidRequest(
+            "--socket is only supported on Unix".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser as _;
+
+    fn call(line: &str) -> Option<Value> {
+        call_with(false, line)
+    }
+
+    fn call_with(strict: bool, line: &str) -> Option<Value> {
+        let cli = Cli::parse_from(["juno-keys", "serve", "--stdio"]);
+        let settings = Settings {
+            network: Some(juno_keys::Network::Testnet),
+            strict,
+            ..Settings::default()
+        };
+        let server = Server {
+            cli: &cli,
+            settings: Arc::new(LiveSettings::new(settings)),
+            warm: None,
+            interlock: true,
+            started: Instant::now(),
+            guard: Arc::default(),
+            metrics: Arc::default(),
+            signer: None,
+        };
+        server.handle_line(None, line)
+    }
+
+    #[test]
+    fn strict_settings_reject_extra_members() {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "status",
+            "id": 1,
+            "trace": "x",
+        })
+        .to_string();
+        assert!(call(&request).expect("response").get("result").is_some());
+        let response = call_with(true, &request).expect("response");
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+        assert!(response["error"]["message"]
+            .as_str()
+            .expect("message")
+            .contains("trace"));
+    }
+
+    #[test]
+    fn serves_derivation_and_validation() {
+        let seed = Seed::from_bytes(&[7u8; 32]).expect("seed").to_base64();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "ufvk.fromSeed",
+            "params": { "seed_base64": seed.expose_secret() },
+            "id": 1,
+        });
+        let response = call(&request.to_string()).expect("response");
+        assert_eq!(response["id"], 1);
+        let ufvk = response["result"]["ufvk"]
+            .as_str()
+            .expect("ufvk")
+            .to_string();
+        assert_eq!(response["result"]["path"], "m/32'/8134'/0'");
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "address.derive",
+            "params": { "ufvk": ufvk, "diversifier_index": 2 },
+            "id": "a",
+        });
+        let address = call(&request.to_string()).expect("response")["result"]["address"].clone();
+        assert_eq!(
+            address,
+            juno_keys::ua_from_ufvk(&ufvk, 2u32).expect("address")
+        );
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "validate",
+            "params": { "value": address },
+            "id": 3,
+        });
+        let result = &call(&request.to_string()).expect("response")["result"];
+        assert_eq!(result["kind"], "address");
+        assert_eq!(result["network"], "testnet");
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "validate",
+            "params": { "value": "jview1xyz" },
+            "id": 4,
+        });
+        let result = &call(&request.to_string()).expect("response")["result"];
+        assert_eq!(result["valid"], false);
+        assert_eq!(result["code"], "encoding_invalid");
+    }
+
+    #[test]
+    fn reports_protocol_and_application_errors() {
+        let response = call("{not json").expect("response");
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = call(r#"{"jsonrpc":"2.0","method":"nope","id":1}"#).expect("response");
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response =
+            call(r#"{"jsonrpc":"2.0","method":"address.derive","params":{"x":1},"id":2}"#)
+                .expect("response");
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response = call(
+            r#"{"jsonrpc":"2.0","method":"ufvk.fromSeed","params":{"seed_base64":"AAAA"},"id":3}"#,
+        )
+        .expect("response");
+        assert_eq!(
+            response["error"]["code"],
+            juno_keys::ErrorCode::SeedInvalid as i32
+        );
+        assert_eq!(response["error"]["data"]["code"], "seed_invalid");
+
+        let response = call(
+            r#"{"jsonrpc":"2.0","method":"validate","params":{"value":"x","request_id":"job-7"},"id":4}"#,
+        )
+        .expect("response");
+        assert_eq!(response["result"]["request_id"], "job-7");
+        let response = call(
+            r#"{"jsonrpc":"2.0","method":"address.derive","params":{"ufvk":"x","request_id":"job-8"},"id":5}"#,
+        )
+        .expect("response");
+        assert_eq!(response["error"]["data"]["request_id"], "job-8");
+
+        // Notifications get no response, even on failure.
+        assert!(call(r#"{"jsonrpc":"2.0","method":"nope"}"#).is_none());
+    }
+
+    #[test]
+    fn wraps_results_in_cli_envelopes() {
+        let ok = envelope(Ok(json!({ "valid": true })), Some("job-9".to_string()));
+        assert_eq!(
+            ok,
+            json!({ "version": "v1", "status": "ok", "request_id": "job-9", "data": { "valid": true } })
+        );
+        let err = RpcError::from(AppError::Keys(juno_keys::KeysError::SeedInvalid));
+        let err = envelope(Err(err), None);
+        assert_eq!(err["error"]["code"], "seed_invalid");
+        assert!(err.get("request_id").is_none());
+        let err = envelope(Err(RpcError::new(METHOD_NOT_FOUND, "unknown")), None);
+        assert_eq!(err["error"]["code"], "invalid_request");
+    }
+
+    #[test]
+    fn derives_from_warm_keys_until_wiped() {
+        let cli = Cli::parse_from(["juno-keys", "serve", "--socket", "/unused"]);
+        let settings = Settings {
+            network: Some(juno_keys::Network::Testnet),
+            ..Settings::default()
+        };
+        let seed = Seed::from_bytes(&[7u8; 32]).expect("seed");
+        let ufvk = juno_keys::ufvk_from_seed(&seed, &juno_keys::Network::Testnet, 2).expect("ufvk");
+        let warm = Arc::new(WarmKeys::new(seed));
+        let server = Server {
+            cli: &cli,
+            settings: Arc::new(LiveSettings::new(settings)),
+            warm: Some(Arc::clone(&warm)),
+            interlock: false,
+            started: Instant::now(),
+            guard: Arc::default(),
+            metrics: Arc::default(),
+            signer: None,
+        };
+        let derive = || {
+            server.dispatch(
+                None,
+                None,
+                "address.derive",
+                json!({ "account": 2, "diversifier_index": 5 }),
+            )
+        };
+        for _ in 0..2 {
+            let result = derive().map_err(|e| e.message).expect("address");
+            assert_eq!(
+                result["address"],
+                juno_keys::ua_from_ufvk(&ufvk, 5u32).expect("address")
+            );
+        }
+        warm.wipe();
+        assert!(derive().is_err());
+    }
+
+    #[test]
+    fn refuses_to_isolate() {
+        let cli = Cli::parse_from(["juno-keys", "--isolate", "serve", "--stdio"]);
+        let crate::Command::Serve(args) = &cli.command else {
+            unreachable!("parsed serve");
+        };
+        let err = cmd_serve(&cli, &Settings::default(), args).expect_err("refused");
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+    }
+
+    #[test]
+    fn request_lines_are_bounded() {
+        let fits = "x".repeat(MAX_LINE as usize);
+        let input = format!("{fits}\r\n{{}}");
+        let mut input = input.as_bytes();
+        assert_eq!(read_request_line(&mut input).expect("fits"), Some(fits));
+        assert_eq!(
+            read_request_line(&mut input).expect("last"),
+            Some("{}".into())
+        );
+        assert_eq!(read_request_line(&mut input).expect("end"), None);
+
+        for over in [MAX_LINE + 1, MAX_LINE * 2] {
+            let over = "x".repeat(over as usize);
+            let err = read_request_line(&mut over.as_bytes()).expect_err("too long");
+            assert!(matches!(err, AppError::InvalidRequest(_)));
+        }
+    }
+
+    #[test]
+    fn answers_envelope_lines_from_the_session_seed() {
+        let cli = Cli::parse_from(["juno-keys", "serve", "--stdio", "--envelopes"]);
+        let settings = Settings {
+            network: Some(juno_keys::Network::Testnet),
+            ..Settings::default()
+        };
+        let seed = Seed::from_bytes(&[7u8; 32]).expect("seed");
+        let ufvk = juno_keys::ufvk_from_seed(&seed, &juno_keys::Network::Testnet, 0).expect("ufvk");
+        let server = Server {
+            cli: &cli,
+            settings: Arc::new(LiveSettings::new(settings)),
+            warm: Some(Arc::new(WarmKeys::new(seed))),
+            interlock: true,
+            started: Instant::now(),
+            guard: Arc::default(),
+            metrics: Arc::default(),
+            signer: None,
+        };
+        let input = [
+            r#"{"method":"ufvk.fromSeed"}"#,
+            "",
+            r#"{"method":"address.derive","params":{"diversifier_index":1,"request_id":"job-1"}}"#,
+            r#"{"jsonrpc":"2.0","method":"status","id":1}"#,
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+        server
+            .serve_envelopes(input.as_bytes(), &mut output)
+            .expect("served");
+        let lines = String::from_utf8(output).expect("utf-8");
+        let envelopes = lines
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).expect("json"))
+            .collect::<Vec<_>>();
+        assert_eq!(envelopes.len(), 3);
+        assert_eq!(envelopes[0]["data"]["ufvk"], ufvk);
+        assert_eq!(
+            envelopes[1]["data"]["address"],
+            juno_keys::ua_from_ufvk(&ufvk, 1u32).expect("address")
+        );
+        assert_eq!(envelopes[1]["request_id"], "job-1");
+        // JSON-RPC members aren't part of this protocol.
+        assert_eq!(envelopes[2]["status"], "err");
+        assert_eq!(envelopes[2]["error"]["code"], "invalid_request");
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn routes_rest_requests() {
+        use tiny_http::Method;
+
+        let cli = Cli::parse_from(["juno-keys", "serve", "--http"]);
+        let settings = Settings::default();
+        let server = Server {
+            cli: &cli,
+            settings: Arc::new(LiveSettings::new(settings)),
+            warm: Some(Arc::new(WarmKeys::new(
+                Seed::from_bytes(&[7u8; 32]).expect("seed"),
+            ))),
+            interlock: false,
+            started: Instant::now(),
+            guard: Arc::default(),
+            metrics: Arc::default(),
+            signer: None,
+        };
+        let (status, body) = http::route(
+            &server,
+            None,
+            &Method::Post,
+            "/v1/ufvk/from-seed",
+            r#"{"network":"regtest"}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(body["data"]["ua_hrp"], "jregtest");
+
+        let (status, body) = http::route(
+            &server,
+            None,
+            &Method::Post,
+            "/v1/validate",
+            r#"{"value":"j1x"}"#,
+        );
+        assert_eq!((status, &body["data"]["valid"]), (200, &json!(false)));
+        let (status, body) = http::route(&server, None, &Method::Post, "/v1/address/derive", "{}");
+        assert_eq!(
+            (status, &body["error"]["code"]),
+            (400, &json!("invalid_request"))
+        );
+        assert_eq!(
+            http::route(&server, None, &Method::Get, "/v1/validate", "").0,
+            405
+        );
+        assert_eq!(http::route(&server, None, &Method::Get, "/nope", "").0, 404);
+        let (status, body) = http::route(&server, None, &Method::Get, "/v1/health", "");
+        assert_eq!(status, 200);
+        assert_eq!(body["data"]["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn refuses_oversized_rest_bodies() {
+        let limit = http::MAX_BODY as usize;
+        let fits = format!("{{\"value\":\"{}\"}}", "x".repeat(limit - 12));
+        assert_eq!(fits.len(), limit);
+        assert_eq!(
+            http::read_body(fits.as_bytes(), Some(limit)).expect("fits"),
+            fits
+        );
+
+        // Without a Content-Length the excess is still noticed, not cut off.
+        let over = format!("{fits} ");
+        let (status, body) = http::read_body(over.as_bytes(), None).expect_err("too large");
+        assert_eq!(
+            (status, &body["error"]["code"]),
+            (413, &json!("invalid_request"))
+        );
+        let (status, _) = http::read_body(&b"{}"[..], Some(limit + 1)).expect_err("declared");
+        assert_eq!(status, 413);
+    }
+}
@@ -0,0 +1,261 @@
+//! `serve --grpc`: the JSON-RPC methods behind the typed service in
+//! `proto/juno_keys/v1/keys.proto`, plus the standard `grpc.health.v1` service.
+//!
+//! Derivation is CPU-bound, so handlers hand requests to a few worker threads that
+//! borrow the `Server` rather than running them on the async runtime.
+
+use std::net::TcpListener;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+
+use juno_keys::ErrorCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::oneshot;
+use tonic::metadata::MetadataValue;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Code, Request, Response, Status};
+
+use super::{Client, RpcError, Server, Signer};
+use crate::AppError;
+
+mod pb {
+    tonic::include_proto!("juno_keys.v1");
+}
+
+use pb::key_service_server::{KeyService, KeyServiceServer};
+
+const WORKERS: usize = 4;
+
+struct Job {
+    method: &'static str,
+    client: Option<Client>,
+    authorization: Option<String>,
+    params: Value,
+    reply: oneshot::Sender<Result<Value, RpcError>>,
+}
+
+pub(super) fn serve(server: &Server, listener: TcpListener) -> Result<(), AppError> {
+    let addr = listener
+        .local_addr()
+        .map_err(|e| AppError::Io(format!("listener address: {e}")))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| AppError::Io(format!("listener: {e}")))?;
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| AppError::Io(format!("start runtime: {e}")))?;
+    let (sender, receiver) = mpsc::channel::<Job>();
+    let receiver = Mutex::new(receiver);
+    let service = Service {
+        jobs: sender,
+        signer: server.signer.clone(),
+    };
+    thread::scope(|scope| {
+        for _ in 0..WORKERS {
+            scope.spawn(|| work(server, &receiver));
+        }
+        runtime.block_on(async {
+            let listener = tokio::net::TcpListener::from_std(listener)
+                .map_err(|e| AppError::Io(format!("listener: {e}")))?;
+            // Tonic's defaults for a bound address.
+            let incoming = TcpIncoming::from_listener(listener, false, None)
+                .map_err(|e| AppError::Io(format!("listener: {e}")))?;
+            let (mut health, health_service) = tonic_health::server::health_reporter();
+            health.set_serving::<KeyServiceServer<Service>>().await;
+            eprintln!("listening on {addr} (gRPC)");
+            tonic::transport::Server::builder()
+                .add_service(health_service)
+                .add_service(KeyServiceServer::new(service))
+                .serve_with_incoming(incoming)
+                .await
+                .map_err(|e| AppError::Io(format!("serve {addr}: {e}")))
+        })
+    })
+}
+
+fn work(server: &Server, jobs: &Mutex<mpsc::Receiver<Job>>) {
+    loop {
+        let job = jobs.lock().unwrap_or_else(PoisonError::into_inner).recv();
+        // The sender goes away with the service once the server stops.
+        let Ok(job) = job else { break };
+        let result = server
+            .admit(job.client, job.authorization.as_deref())
+            .and_then(|()| server.dispatch(job.client, None, job.method, job.params));
+        let _ = job.reply.send(result);
+    }
+}
+
+struct Service {
+    jobs: mpsc::Sender<Job>,
+    signer: Option<Arc<Signer>>,
+}
+
+impl Service {
+    // Messages share field names with the JSON-RPC params and results, so they go
+    // through `Server::dispatch` unchanged.
+    async fn call<T: Serialize, U: DeserializeOwned + prost::Message>(
+        &self,
+        method: &'static str,
+        request: Request<T>,
+    ) -> Result<Response<U>, Status> {
+        let client = request.remote_addr().map(|addr| Client::Ip(addr.ip()));
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let params = serde_json::to_value(request.into_inner())
+            .map_err(|e| Status::internal(format!("encode params: {e}")))?;
+        let (reply, result) = oneshot::channel();
+        self.jobs
+            .send(Job {
+                method,
+                client,
+                authorization,
+                params,
+                reply,
+            })
+            .map_err(|_| Status::unavailable("server is shutting down"))?;
+        let value = result
+            .await
+            .map_err(|_| Status::internal("worker stopped"))?
+            .map_err(status)?;
+        let mut response = serde_json::from_value::<U>(value)
+            .map(Response::new)
+            .map_err(|e| Status::internal(format!("decode result: {e}")))?;
+        if let Some(signer) = &self.signer {
+            let signature = signer.signature(&response.get_ref().encode_to_vec());
+            if let Ok(value) = signature.parse::<MetadataValue<_>>() {
+                response.metadata_mut().insert("juno-keys-signature", value);
+            }
+        }
+        Ok(response)
+    }
+}
+
+#[tonic::async_trait]
+impl KeyService for Service {
+    async fn ufvk_from_seed(
+        &self,
+        request: Request<pb::UfvkFromSeedRequest>,
+    ) -> Result<Response<pb::UfvkFromSeedResponse>, Status> {
+        self.call("ufvk.fromSeed", request).await
+    }
+
+    async fn derive_address(
+        &self,
+        request: Request<pb::DeriveAddressRequest>,
+    ) -> Result<Response<pb::DeriveAddressResponse>, Status> {
+        self.call("address.derive", request).await
+    }
+
+    async fn validate(
+        &self,
+        request: Request<pb::ValidateRequest>,
+    ) -> Result<Response<pb::ValidateResponse>, Status> {
+        self.call("validate", request).await
+    }
+
+    async fn status(
+        &self,
+        request: Request<pb::StatusRequest>,
+    ) -> Result<Response<pb::StatusResponse>, Status> {
+        self.call("status", request).await
+    }
+}
+
+fn status(err: RpcError) -> Status {
+    let code = match ErrorCode::try_from(err.code) {
+        Ok(ErrorCode::Internal) => Code::Internal,
+        Ok(ErrorCode::Unauthenticated) => Code::Unauthenticated,
+        Ok(ErrorCode::RateLimited) => Code::ResourceExhausted,
+        Ok(ErrorCode::IoError | ErrorCode::ConfigInvalid) => Code::FailedPrecondition,
+        _ => Code::InvalidArgument,
+    };
+    // Protocol-level failures (negative codes) carry no string code.
+    let string_code = err
+        .data
+        .as_ref()
+        .and_then(|d| d["code"].as_str())
+        .unwrap_or("invalid_request");
+    let mut status = Status::new(code, err.message);
+    if let Ok(value) = string_code.parse::<MetadataValue<_>>() {
+        status.metadata_mut().insert("juno-keys-code", value);
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use clap::Parser as _;
+    use juno_keys::Seed;
+
+    use super::*;
+    use crate::serve::{LiveSettings, WarmKeys};
+    use crate::{Cli, Settings};
+
+    #[test]
+    fn serves_typed_messages() {
+        let cli = Cli::parse_from(["juno-keys", "serve", "--grpc"]);
+        let settings = Settings::default();
+        let server = Server {
+            cli: &cli,
+            settings: Arc::new(LiveSettings::new(settings)),
+            warm: Some(Arc::new(WarmKeys::new(
+                Seed::from_bytes(&[7u8; 32]).expect("seed"),
+            ))),
+            interlock: false,
+            started: Instant::now(),
+            guard: Arc::default(),
+            metrics: Arc::default(),
+            signer: None,
+        };
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Mutex::new(receiver);
+        let service = Service {
+            jobs: sender,
+            signer: None,
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime");
+        thread::scope(|scope| {
+            scope.spawn(|| work(&server, &receiver));
+            runtime.block_on(async move {
+                let request = pb::UfvkFromSeedRequest {
+                    network: Some("regtest".to_string()),
+                    ..Default::default()
+                };
+                let ufvk = service.ufvk_from_seed(Request::new(request)).await;
+                let ufvk = ufvk.expect("ufvk").into_inner();
+                assert_eq!(ufvk.ua_hrp, "jregtest");
+
+                let request = pb::ValidateRequest { value: ufvk.ufvk };
+                let valid = service.validate(Request::new(request)).await;
+                let valid = valid.expect("validate").into_inner();
+                assert!(valid.valid);
+                assert_eq!(valid.kind.as_deref(), Some("ufvk"));
+
+                let status = service.status(Request::new(pb::StatusRequest {})).await;
+                let status = status.expect("status").into_inner();
+                assert!(status.features.iter().any(|f| f == "grpc"));
+                assert!(status.seed_fingerprint.is_some());
+
+                let request = pb::DeriveAddressRequest {
+                    ufvk: Some("jview1x".to_string()),
+                    ..Default::default()
+                };
+                let err = service.derive_address(Request::new(request)).await;
+                let err = err.expect_err("bad ufvk");
+                assert_eq!(err.code(), Code::InvalidArgument);
+                assert!(err.metadata().get("juno-keys-code").is_some());
+            });
+        });
+    }
+}
@@ -0,0 +1,206 @@
+//! Per-request admission for the daemon modes: the shared token required by
+//! `--auth-token-file` and the per-client limit set by `--rate-limit`.
+//!
+//! Socket peers are already authenticated by uid, so the token only applies to the
+//! TCP modes; the rate limit applies to every daemon mode, keyed by peer IP or uid.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::Instant;
+
+use juno_keys::{ErrorCode, ExposeSecret as _, SecretString};
+use serde_json::json;
+use subtle::ConstantTimeEq as _;
+
+use super::RpcError;
+use crate::AppError;
+
+// Buckets that have refilled are forgotten once this many clients are tracked.
+const MAX_TRACKED: usize = 4096;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(super) enum Client {
+    // Only the TCP modes have peer addresses.
+    #[cfg_attr(not(any(feature = "server", feature = "grpc")), allow(dead_code))]
+    Ip(IpAddr),
+    Uid(u32),
+}
+
+impl std::fmt::Display for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Client::Ip(ip) => write!(f, "ip:{ip}"),
+            Client::Uid(uid) => write!(f, "uid:{uid}"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct Guard {
+    token: Option<SecretString>,
+    // Replaced when a reload changes the limit.
+    limiter: RwLock<Option<RateLimiter>>,
+}
+
+impl Guard {
+    pub(super) fn new(
+        token_file: Option<&Path>,
+        rate_limit: Option<u32>,
+    ) -> Result<Self, AppError> {
+        let token = token_file.map(read_token).transpose()?;
+        Ok(Guard {
+            token,
+            limiter: RwLock::new(rate_limit.map(RateLimiter::new)),
+        })
+    }
+
+    #[cfg(test)]
+    pub(super) fn rate_limit(&self) -> Option<u32> {
+        let limiter = self.limiter.read().unwrap_or_else(PoisonError::into_inner);
+        limiter.as_ref().map(|limiter| limiter.rate as u32)
+    }
+
+    /// Changes the per-client limit. Clients keep their buckets unless it changes.
+    pub(super) fn set_rate_limit(&self, rate_limit: Option<u32>) {
+        let mut limiter = self.limiter.write().unwrap_or_else(PoisonError::into_inner);
+        let current = limiter.as_ref().map(|limiter| limiter.rate);
+        if current != rate_limit.map(f64::from) {
+            *limiter = rate_limit.map(RateLimiter::new);
+        }
+    }
+
+    /// Checks a request from `client`. `authorization` is the request's
+    /// `Authorization` header or gRPC metadata value, if any.
+    pub(super) fn admit(
+        &self,
+        client: Option<Client>,
+        authorization: Option<&str>,
+    ) -> Result<(), RpcError> {
+        // Limited first, so failed token guesses count too.
+        let limiter = self.limiter.read().unwrap_or_else(PoisonError::into_inner);
+        if let (Some(limiter), Some(client)) = (limiter.as_ref(), client) {
+            if !limiter.allow(client, Instant::now()) {
+                return Err(denied(
+                    ErrorCode::RateLimited,
+                    "rate limit exceeded; retry later",
+                ));
+            }
+        }
+        if let Some(token) = &self.token {
+            let presented = authorization
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .unwrap_or_default();
+            if !bool::from(presented.as_bytes().ct_eq(token.expose_secret().as_bytes())) {
+                return Err(denied(
+                    ErrorCode::Unauthenticated,
+                    "missing or wrong bearer token",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn denied(code: ErrorCode, message: &str) -> RpcError {
+    RpcError {
+        code: code.into(),
+        message: message.to_string(),
+        data: Some(json!({ "code": code.as_str() })),
+    }
+}
+
+fn read_token(path: &Path) -> Result<SecretString, AppError> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| AppError::Io(format!("read auth token file: {e}")))?;
+    let token = raw.trim();
+    // Long enough that guessing it over the network is hopeless.
+    if token.len() < 16 {
+        return Err(AppError::Config(
+            "auth token must be at least 16 characters".to_string(),
+        ));
+    }
+    Ok(SecretString::from(token.to_string()))
+}
+
+// A token bucket per client: `rate` requests per second, in bursts of up to `rate`.
+struct RateLimiter {
+    rate: f64,
+    buckets: Mutex<HashMap<Client, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u32) -> Self {
+        RateLimiter {
+            rate: f64::from(rate),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn allow(&self, client: Client, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        if buckets.len() >= MAX_TRACKED && !buckets.contains_key(&client) {
+            buckets.retain(|_, bucket| self.refilled(bucket, now) < self.rate);
+        }
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: self.rate,
+            updated: now,
+        });
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.updated = now;
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        (bucket.tokens + elapsed * self.rate).min(self.rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn limits_each_client_separately() {
+        let limiter = RateLimiter::new(2);
+        let (a, b) = (Client::Uid(1), Client::Ip([127, 0, 0, 1].into()));
+        let start = Instant::now();
+        assert!(limiter.allow(a, start));
+        assert!(limiter.allow(a, start));
+        assert!(!limiter.allow(a, start));
+        assert!(limiter.allow(b, start));
+        // Half a second refills one request at 2/s.
+        assert!(limiter.allow(a, start + Duration::from_millis(500)));
+        assert!(!limiter.allow(a, start + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn checks_the_bearer_token() {
+        let guard = Guard {
+            token: Some(SecretString::from("0123456789abcdef".to_string())),
+            limiter: RwLock::new(None),
+        };
+        assert!(guard.admit(None, Some("Bearer 0123456789abcdef")).is_ok());
+        for wrong in [
+            None,
+            Some("0123456789abcdef"),
+            Some("Bearer 0123456789abcde"),
+        ] {
+            let err = guard.admit(None, wrong).expect_err("rejected");
+            assert_eq!(err.code, i32::from(ErrorCode::Unauthenticated));
+        }
+    }
+}
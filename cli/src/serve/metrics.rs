@@ -0,0 +1,197 @@
+//! `serve --metrics`: request counters and latency histograms in the Prometheus text
+//! format, on a separate plain-HTTP listener so every daemon mode can expose them.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead as _, BufReader, Read as _, Write as _};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use juno_keys::ErrorCode;
+
+use super::{RpcError, INVALID_PARAMS, METHOD_NOT_FOUND, PARSE_ERROR};
+
+// Upper bounds in seconds: cached derivations take well under a millisecond, cold
+// ones a few milliseconds.
+const BUCKETS: [f64; 11] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+];
+// A scrape request is one short line plus headers.
+const MAX_REQUEST: u64 = 8 * 1024;
+
+#[derive(Default)]
+pub(super) struct Metrics {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    // By (method, code), where code is "ok" or the string error code.
+    requests: BTreeMap<(String, &'static str), u64>,
+    latency: BTreeMap<String, Histogram>,
+    // Requests turned away before reaching a method, by error code.
+    rejected: BTreeMap<&'static str, u64>,
+}
+
+#[derive(Default)]
+struct Histogram {
+    // Cumulative, like the exposition format.
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Metrics {
+    pub(super) fn record(&self, method: &str, result: Result<(), &RpcError>, took: Duration) {
+        let code = match result {
+            Ok(()) => "ok",
+            Err(err) => code_label(err),
+        };
+        // Unknown names are client input; keep them out of the label values.
+        let method = if code == "method_not_found" {
+            "unknown"
+        } else {
+            method
+        };
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        *inner
+            .requests
+            .entry((method.to_string(), code))
+            .or_default() += 1;
+        let seconds = took.as_secs_f64();
+        let histogram = inner.latency.entry(method.to_string()).or_default();
+        for (count, bound) in histogram.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= bound {
+                *count += 1;
+            }
+        }
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    pub(super) fn reject(&self, err: &RpcError) {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        *inner.rejected.entry(code_label(err)).or_default() += 1;
+    }
+
+    fn render(&self) -> String {
+        let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut out = String::new();
+        out.push_str(
+            "# HELP juno_keys_requests_total Requests handled, by method and result code.\n",
+        );
+        out.push_str("# TYPE juno_keys_requests_total counter\n");
+        for ((method, code), count) in &inner.requests {
+            let _ = writeln!(
+                out,
+                "juno_keys_requests_total{{method=\"{method}\",code=\"{code}\"}} {count}"
+            );
+        }
+        out.push_str("# HELP juno_keys_request_duration_seconds Time spent handling requests.\n");
+        out.push_str("# TYPE juno_keys_request_duration_seconds histogram\n");
+        for (method, histogram) in &inner.latency {
+            let name = "juno_keys_request_duration_seconds";
+            for (count, bound) in histogram.buckets.iter().zip(BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{{method=\"{method}\",le=\"{bound}\"}} {count}"
+                );
+            }
+            let count = histogram.count;
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{method=\"{method}\",le=\"+Inf\"}} {count}"
+            );
+            let _ = writeln!(out, "{name}_sum{{method=\"{method}\"}} {}", histogram.sum);
+            let _ = writeln!(out, "{name}_count{{method=\"{method}\"}} {count}");
+        }
+        out.push_str("# HELP juno_keys_rejected_total Requests refused by auth or rate limits.\n");
+        out.push_str("# TYPE juno_keys_rejected_total counter\n");
+        for (code, count) in &inner.rejected {
+            let _ = writeln!(out, "juno_keys_rejected_total{{code=\"{code}\"}} {count}");
+        }
+        out
+    }
+}
+
+pub(super) fn code_label(err: &RpcError) -> &'static str {
+    match err.code {
+        PARSE_ERROR => "parse_error",
+        METHOD_NOT_FOUND => "method_not_found",
+        INVALID_PARAMS => "invalid_params",
+        code => ErrorCode::try_from(code).map_or("invalid_request", |code| code.as_str()),
+    }
+}
+
+/// Answers `GET /metrics` on `listener` until the process exits.
+pub(super) fn serve(metrics: Arc<Metrics>, listener: TcpListener) {
+    if let Ok(addr) = listener.local_addr() {
+        eprintln!("metrics on http://{addr}/metrics");
+    }
+    std::thread::spawn(move || {
+        // Scrapes are infrequent, so one at a time is plenty.
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = scrape(&metrics, stream) {
+                        eprintln!("metrics: {e}");
+                    }
+                }
+                Err(e) => eprintln!("metrics accept: {e}"),
+            }
+        }
+    });
+}
+
+fn scrape(metrics: &Metrics, mut stream: TcpStream) -> std::io::Result<()> {
+    // A stalled scraper must not hold up the next one.
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers so the client sees a clean close.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let (status, body) = if request_line.starts_with("GET ") && path == "/metrics" {
+        ("200 OK", metrics.render())
+    } else {
+        ("404 Not Found", "not found\n".to_string())
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\ncontent-type: text/plain; version=0.0.4\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serve::guard::denied;
+
+    #[test]
+    fn renders_prometheus_text() {
+        let metrics = Metrics::default();
+        let took = Duration::from_millis(2);
+        metrics.record("address.derive", Ok(()), took);
+        metrics.record("address.derive", Ok(()), took);
+        let bad = RpcError::new(METHOD_NOT_FOUND, "unknown method: x");
+        metrics.record("x", Err(&bad), took);
+        metrics.reject(&denied(ErrorCode::RateLimited, "slow down"));
+        let text = metrics.render();
+        for line in [
+            "juno_keys_requests_total{method=\"address.derive\",code=\"ok\"} 2",
+            "juno_keys_requests_total{method=\"unknown\",code=\"method_not_found\"} 1",
+            "juno_keys_request_duration_seconds_bucket{method=\"address.derive\",le=\"0.001\"} 0",
+            "juno_keys_request_duration_seconds_bucket{method=\"address.derive\",le=\"0.0025\"} 2",
+            "juno_keys_request_duration_seconds_count{method=\"address.derive\"} 2",
+            "juno_keys_rejected_total{code=\"rate_limited\"} 1",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {line}");
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! SIGHUP reloads for the daemon modes: the profile's non-secret settings (default
+//! network, rate limit) are re-read and swapped in between requests. The seed loaded
+//! at startup stays resident, so a reload never needs the keystore again.
+
+// Only Unix has SIGHUP to trigger a reload.
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use super::Guard;
+use crate::{load_settings, style, ConfigSource, Settings};
+
+/// The settings requests see, replaced wholesale on reload.
+pub(super) struct LiveSettings(RwLock<Arc<Settings>>);
+
+impl LiveSettings {
+    pub(super) fn new(settings: Settings) -> Self {
+        LiveSettings(RwLock::new(Arc::new(settings)))
+    }
+
+    pub(super) fn get(&self) -> Arc<Settings> {
+        Arc::clone(&self.0.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn set(&self, settings: Settings) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(settings);
+    }
+}
+
+pub(super) struct Reload {
+    pub(super) source: ConfigSource,
+    // `--rate-limit`, which outranks the config as it did at startup.
+    pub(super) rate_limit: Option<u32>,
+    pub(super) settings: Arc<LiveSettings>,
+    pub(super) guard: Arc<Guard>,
+}
+
+impl Reload {
+    /// Re-reads the config, keeping the current settings if it no longer loads.
+    pub(super) fn run(&self) {
+        let settings = match load_settings(&self.source) {
+            Ok(settings) => settings,
+            Err(e) => {
+                eprintln!(
+                    "reload failed, keeping the current configuration: {}",
+                    e.message()
+                );
+                return;
+            }
+        };
+        let current = self.settings.get();
+        if settings.keystore != current.keystore
+            || settings.seed_credential != current.seed_credential
+        {
+            style::warning("keystore changes take effect on restart");
+        }
+        if settings.assert_offline != current.assert_offline {
+            style::warning("assert_offline changes take effect on restart");
+        }
+        // A hardened process stays hardened, so only turning it on needs a restart.
+        if settings.harden && !current.harden {
+            style::warning("harden changes take effect on restart");
+        }
+        self.guard
+            .set_rate_limit(self.rate_limit.or(settings.rate_limit));
+        self.settings.set(Settings {
+            keystore: current.keystore.clone(),
+            seed_credential: current.seed_credential.clone(),
+            assert_offline: current.assert_offline,
+            harden: current.harden,
+            ..settings
+        });
+        eprintln!("configuration reloaded");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use juno_keys::Network;
+
+    use super::*;
+
+    #[test]
+    fn reloads_until_the_config_breaks() {
+        let path =
+            std::env::temp_dir().join(format!("juno-keys-reload-{}.toml", std::process::id()));
+        fs::write(&path, "network = \"testnet\"\nrate_limit = 5\n").expect("write config");
+        let reload = Reload {
+            source: ConfigSource {
+                config: Some(path.clone()),
+                profile: None,
+                assert_offline: false,
+                strict: false,
+            },
+            rate_limit: None,
+            settings: Arc::new(LiveSettings::new(Settings::default())),
+            guard: Arc::default(),
+        };
+        reload.run();
+        assert_eq!(reload.settings.get().network, Some(Network::Testnet));
+        assert_eq!(reload.guard.rate_limit(), Some(5));
+
+        fs::write(&path, "network = \"nowhere\"\n").expect("write config");
+        reload.run();
+        fs::remove_file(&path).expect("remove config");
+        assert_eq!(reload.settings.get().network, Some(Network::Testnet));
+        assert_eq!(reload.guard.rate_limit(), Some(5));
+    }
+}
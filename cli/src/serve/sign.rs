@@ -0,0 +1,142 @@
+//! `serve --sign-key`: ed25519 signatures over every response, so consumers in other
+//! trust zones can check that results came from this service unaltered.
+//!
+//! A signed response carries a `signature` member, `{"alg":"ed25519","key":..,"sig":..}`
+//! with base64 public key and signature. The signature covers the response with that
+//! member removed, serialized as RFC 8785 canonical JSON: sorted keys and no
+//! whitespace.
+
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use ed25519_dalek::{Signer as _, SigningKey};
+use serde_json::{json, Value};
+use zeroize::Zeroizing;
+
+use crate::AppError;
+
+pub(super) struct Signer {
+    key: SigningKey,
+    // Base64 of the verifying key, as sent with each signature.
+    public: String,
+}
+
+impl Signer {
+    /// Loads a key file holding the base64 of a 32-byte ed25519 secret key.
+    pub(super) fn load(path: &Path) -> Result<Self, AppError> {
+        let raw = Zeroizing::new(
+            std::fs::read_to_string(path)
+                .map_err(|e| AppError::Io(format!("read signing key: {e}")))?,
+        );
+        let bytes = Zeroizing::new(
+            STANDARD
+                .decode(raw.trim())
+                .map_err(|e| AppError::Config(format!("signing key is not base64: {e}")))?,
+        );
+        let secret: &[u8; 32] = bytes.as_slice().try_into().map_err(|_| {
+            AppError::Config(format!("signing key must be 32 bytes, got {}", bytes.len()))
+        })?;
+        let key = SigningKey::from_bytes(secret);
+        let public = STANDARD.encode(key.verifying_key().as_bytes());
+        Ok(Signer { key, public })
+    }
+
+    pub(super) fn public_key(&self) -> &str {
+        &self.public
+    }
+
+    /// The base64 signature over `message`.
+    pub(super) fn signature(&self, message: &[u8]) -> String {
+        STANDARD.encode(self.key.sign(message).to_bytes())
+    }
+
+    /// Adds a `signature` member to a response object.
+    pub(super) fn sign(&self, response: &mut Value) {
+        if let Some(object) = response.as_object_mut() {
+            object.remove("signature");
+        }
+        let mut message = Vec::new();
+        canonical(response, &mut message);
+        let sig = self.signature(&message);
+        response["signature"] = json!({ "alg": "ed25519", "key": self.public, "sig": sig });
+    }
+}
+
+// RFC 8785 for the values responses contain: keys sorted by UTF-16 code unit (byte
+// order for the ASCII keys used here), integers, and serde_json's minimal escaping.
+fn canonical(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push(b'{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                canonical(&Value::String(key.clone()), out);
+                out.push(b':');
+                canonical(value, out);
+            }
+            out.push(b'}');
+        }
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                canonical(item, out);
+            }
+            out.push(b']');
+        }
+        scalar => out.extend_from_slice(scalar.to_string().as_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signature, Verifier as _, VerifyingKey};
+
+    use super::*;
+
+    #[test]
+    fn signs_canonical_responses() {
+        let path = std::env::temp_dir().join(format!("juno-keys-sign-{}.key", std::process::id()));
+        std::fs::write(&path, STANDARD.encode([9u8; 32])).expect("write key");
+        let signer = Signer::load(&path);
+        std::fs::remove_file(&path).expect("remove key");
+        let signer = signer.expect("signer");
+
+        let mut response =
+            json!({ "jsonrpc": "2.0", "id": 1, "result": { "z": [1, "é"], "a": true } });
+        signer.sign(&mut response);
+        let signature = response
+            .as_object_mut()
+            .and_then(|o| o.remove("signature"))
+            .expect("signature");
+        assert_eq!(signature["key"], signer.public_key());
+
+        let mut message = Vec::new();
+        canonical(&response, &mut message);
+        assert_eq!(
+            String::from_utf8(message.clone()).expect("utf-8"),
+            r#"{"id":1,"jsonrpc":"2.0","result":{"a":true,"z":[1,"é"]}}"#
+        );
+        let key: [u8; 32] = STANDARD
+            .decode(signer.public_key())
+            .expect("key")
+            .try_into()
+            .expect("32 bytes");
+        let sig: [u8; 64] = STANDARD
+            .decode(signature["sig"].as_str().expect("sig"))
+            .expect("sig")
+            .try_into()
+            .expect("64 bytes");
+        VerifyingKey::from_bytes(&key)
+            .expect("key")
+            .verify(&message, &Signature::from_bytes(&sig))
+            .expect("valid signature");
+    }
+}
@@ -0,0 +1,50 @@
+//! Signal handling for the daemon modes. SIGINT and SIGTERM wipe the warm keys and
+//! exit; SIGHUP reloads the configuration.
+
+use std::sync::Arc;
+
+use super::reload::Reload;
+use super::WarmKeys;
+use crate::AppError;
+
+/// Starts the thread that handles the daemon's signals.
+///
+/// Call before the server starts its threads: the signals are blocked in the calling
+/// thread, so every thread spawned afterwards inherits the mask and only the waiter
+/// sees them.
+pub(super) fn spawn(warm: Option<Arc<WarmKeys>>, reload: Reload) -> Result<(), AppError> {
+    // SAFETY: `set` is initialized by sigemptyset before any other use, and the
+    // pointers passed are valid for the duration of each call.
+    let set = unsafe {
+        let mut set = std::mem::MaybeUninit::<libc::sigset_t>::uninit();
+        libc::sigemptyset(set.as_mut_ptr());
+        let mut set = set.assume_init();
+        libc::sigaddset(&mut set, libc::SIGINT);
+        libc::sigaddset(&mut set, libc::SIGTERM);
+        libc::sigaddset(&mut set, libc::SIGHUP);
+        if libc::pthread_sigmask(libc::SIG_BLOCK, &set, std::ptr::null_mut()) != 0 {
+            return Err(AppError::Io("block daemon signals".to_string()));
+        }
+        set
+    };
+    std::thread::spawn(move || loop {
+        let mut signal = 0;
+        // SAFETY: `set` and `signal` are valid for the call.
+        if unsafe { libc::sigwait(&set, &mut signal) } != 0 {
+            continue;
+        }
+        if signal == libc::SIGHUP {
+            reload.run();
+            continue;
+        }
+        match &warm {
+            Some(warm) => {
+                warm.wipe();
+                eprintln!("received signal {signal}; seed wiped, exiting");
+            }
+            None => eprintln!("received signal {signal}; exiting"),
+        }
+        std::process::exit(0);
+    });
+    Ok(())
+}
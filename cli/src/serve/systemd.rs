@@ -0,0 +1,80 @@
+//! systemd socket activation: when started by a `.socket` unit, serve on the listener
+//! systemd passes in (`LISTEN_FDS`, see sd_listen_fds(3)) instead of binding one.
+
+use std::net::TcpListener;
+
+use crate::AppError;
+
+#[cfg(unix)]
+pub(super) fn unix_listener() -> Result<Option<std::os::unix::net::UnixListener>, AppError> {
+    let Some(fd) = listen_fd()? else {
+        return Ok(None);
+    };
+    let listener = std::os::unix::net::UnixListener::from(fd);
+    listener.local_addr().map_err(|e| {
+        AppError::InvalidRequest(format!("--socket needs a Unix socket from systemd: {e}"))
+    })?;
+    Ok(Some(listener))
+}
+
+#[cfg(unix)]
+pub(super) fn tcp_listener() -> Result<Option<TcpListener>, AppError> {
+    let Some(fd) = listen_fd()? else {
+        return Ok(None);
+    };
+    let listener = TcpListener::from(fd);
+    listener.local_addr().map_err(|e| {
+        AppError::InvalidRequest(format!(
+            "--http and --grpc need a TCP socket from systemd: {e}"
+        ))
+    })?;
+    Ok(Some(listener))
+}
+
+#[cfg(not(unix))]
+pub(super) fn tcp_listener() -> Result<Option<TcpListener>, AppError> {
+    Ok(None)
+}
+
+// Takes the single listener passed to this process, if any. Must run before the
+// server's threads start, since it clears the activation variables.
+#[cfg(unix)]
+fn listen_fd() -> Result<Option<std::os::fd::OwnedFd>, AppError> {
+    use std::os::fd::FromRawFd as _;
+
+    // SD_LISTEN_FDS_START: inherited descriptors follow stdin, stdout and stderr.
+    const FIRST_FD: i32 = 3;
+
+    let Ok(pid) = std::env::var("LISTEN_PID") else {
+        return Ok(None);
+    };
+    // The variables may have been inherited from a parent that was activated.
+    if pid.parse::<u32>().ok() != Some(std::process::id()) {
+        return Ok(None);
+    }
+    let count = std::env::var("LISTEN_FDS").unwrap_or_default();
+    for var in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        std::env::remove_var(var);
+    }
+    match count.parse::<i32>() {
+        Ok(0) => Ok(None),
+        Ok(1) => {
+            // SAFETY: FD_CLOEXEC only changes what children inherit.
+            if unsafe { libc::fcntl(FIRST_FD, libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+                return Err(AppError::Io(format!(
+                    "systemd listener: {}",
+                    std::io::Error::last_os_error()
+                )));
+            }
+            // SAFETY: systemd hands this process ownership of fd 3 when LISTEN_FDS=1
+            // and LISTEN_PID matches, and nothing else has claimed it.
+            Ok(Some(unsafe { std::os::fd::OwnedFd::from_raw_fd(FIRST_FD) }))
+        }
+        Ok(n) => Err(AppError::InvalidRequest(format!(
+            "systemd passed {n} sockets; configure the unit with exactly one"
+        ))),
+        Err(_) => Err(AppError::InvalidRequest(format!(
+            "LISTEN_FDS is not a number: {count:?}"
+        ))),
+    }
+}
@@ -0,0 +1,96 @@
+//! The seed a daemon loads at startup, kept resident with each account's UFVK and
+//! address generator so repeated requests skip seed decoding and ZIP-32 derivation.
+//!
+//! The seed is the only secret held: accounts are cached as viewing keys, and the
+//! spending keys derived on a cache miss are zeroized straight away. On Unix the seed
+//! is locked in memory, and SIGINT or SIGTERM wipes everything before exiting (see
+//! `signals`).
+
+use std::collections::BTreeMap;
+use std::sync::{PoisonError, RwLock};
+
+use juno_keys::{AddressGenerator, Network, OrchardKeySet, Seed, Ufvk};
+
+use crate::{network_params, style, AppError, Cli};
+
+pub(super) struct WarmKeys {
+    // `None` once wiped for shutdown.
+    inner: RwLock<Option<Inner>>,
+    fingerprint: Option<String>,
+}
+
+struct Inner {
+    seed: Seed,
+    accounts: BTreeMap<(&'static str, u32), Account>,
+}
+
+pub(super) struct Account {
+    pub(super) ufvk: Ufvk,
+    pub(super) addresses: AddressGenerator,
+}
+
+impl WarmKeys {
+    pub(super) fn new(seed: Seed) -> Self {
+        // The seed locks its own pages. Unprivileged daemons may exceed
+        // RLIMIT_MEMLOCK, which is worth a warning but not a refusal to start.
+        #[cfg(unix)]
+        if !seed.is_locked() {
+            style::warning("could not lock the seed in memory");
+        }
+        WarmKeys {
+            fingerprint: juno_keys::seed_fingerprint(&seed).ok(),
+            inner: RwLock::new(Some(Inner {
+                seed,
+                accounts: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// The seed's ZIP-32 fingerprint, kept after a wipe.
+    pub(super) fn fingerprint(&self) -> Option<&str> {
+        self.fingerprint.as_deref()
+    }
+
+    /// Runs `f` on the account's keys, deriving them on first use.
+    pub(super) fn with_account<T>(
+        &self,
+        cli: &Cli,
+        net: Network,
+        account: u32,
+        f: impl FnOnce(&Account) -> T,
+    ) -> Result<T, AppError> {
+        let key = (net.as_str(), account);
+        {
+            let inner = self.inner.read().unwrap_or_else(PoisonError::into_inner);
+            let inner = inner.as_ref().ok_or_else(shutting_down)?;
+            if let Some(account) = inner.accounts.get(&key) {
+                return Ok(f(account));
+            }
+        }
+        let mut inner = self.inner.write().unwrap_or_else(PoisonError::into_inner);
+        let inner = inner.as_mut().ok_or_else(shutting_down)?;
+        // Another request may have derived it while this one waited for the lock.
+        if !inner.accounts.contains_key(&key) {
+            let params = network_params(cli, net);
+            let ufvk = OrchardKeySet::from_seed(&inner.seed, params.as_ref(), account)
+                .and_then(|keys| keys.ufvk(params.as_ref()))
+                .map_err(AppError::Keys)?;
+            let addresses = ufvk.address_generator().map_err(AppError::Keys)?;
+            inner.accounts.insert(key, Account { ufvk, addresses });
+        }
+        Ok(f(&inner.accounts[&key]))
+    }
+
+    /// Drops (and so zeroizes) the seed once in-flight requests finish; later
+    /// requests fail.
+    pub(super) fn wipe(&self) {
+        self.inner
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+    }
+}
+
+fn shutting_down() -> AppError {
+    AppError::Io("server is shutting down".to_string())
+}
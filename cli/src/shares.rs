@@ -0,0 +1,264 @@
+//! `seed split` / `seed combine`: M-of-N backups of a seed as Shamir shares, with the
+//! library's `juno_keys::split_seed` and `combine_seed`.
+//!
+//! Each share is a bech32m string, `jshare1...`, carrying its index, the threshold and
+//! a random ID of the split, so a typo fails its checksum and shares of another split are
+//! refused rather than combined into a wrong seed. Fewer than the threshold say
+//! nothing about the seed; the threshold together are as secret as the seed itself.
+
+use std::fs;
+use std::io::Read as _;
+use std::path::PathBuf;
+
+use clap::Args;
+use juno_keys::{ExposeSecret as _, Network, SeedShare};
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+use crate::outpath::{self, OutVars};
+use crate::{
+    armor, audit, ensure_mainnet_ok, resolve_seed, reveal, write_json_ok, write_secret_file,
+    AppError, Cli, NetworkArg, Settings,
+};
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("dest").required(true).multiple(true).args(["out_dir", "print"])))]
+pub(crate) struct SeedSplitArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    pub(crate) seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    pub(crate) seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u8).range(2..),
+        help = "How many shares rebuild the seed (at least 2)"
+    )]
+    pub(crate) threshold: u8,
+
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u8).range(2..),
+        help = "How many shares to write (at most 255)"
+    )]
+    pub(crate) shares: u8,
+
+    #[arg(
+        long,
+        help = "Write each share to DIR/share-{index}-of-{shares}.txt (mode 0600 on unix; placeholders like {fingerprint} and {date} are filled in)"
+    )]
+    pub(crate) out_dir: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite share files that exist")]
+    pub(crate) force: bool,
+
+    #[arg(long, help = "Print the shares to stdout (warning: avoid logs)")]
+    pub(crate) print: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network the seed is meant for (unset counts as mainnet for the interlock)"
+    )]
+    pub(crate) network: Option<NetworkArg>,
+}
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("input").required(true).multiple(true).args(["share", "file"])))]
+pub(crate) struct SeedCombineArgs {
+    #[arg(help = "Shares (jshare1...; warning: avoid logs)")]
+    pub(crate) share: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Read shares from a file, one per line or one armored block ('-' for stdin; repeatable)"
+    )]
+    pub(crate) file: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Write the seed (base64) to a file (mode 0600 on unix; placeholders like {fingerprint} and {date} are filled in)"
+    )]
+    pub(crate) out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    pub(crate) force: bool,
+
+    #[arg(long, help = "Print seed to stdout (warning: avoid logs)")]
+    pub(crate) print: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network the seed is meant for (unset counts as mainnet for the interlock)"
+    )]
+    pub(crate) network: Option<NetworkArg>,
+}
+
+pub(crate) fn cmd_seed_split(
+    cli: &Cli,
+    settings: &Settings,
+    args: &SeedSplitArgs,
+) -> Result<(), AppError> {
+    let net = args.network.map(Network::from).or(settings.network);
+    if args.print {
+        ensure_mainnet_ok(cli, settings, net)?;
+    }
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    let shares =
+        juno_keys::split_seed(&seed, args.threshold, args.shares).map_err(AppError::Keys)?;
+    audit::note_seed(&seed);
+    let seed_fingerprint = juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?;
+    let dir = args
+        .out_dir
+        .as_ref()
+        .map(|dir| {
+            outpath::expand(
+                dir,
+                &OutVars {
+                    fingerprint: Some(seed_fingerprint.clone()),
+                    network: net,
+                    account: None,
+                },
+            )
+        })
+        .transpose()?;
+
+    #[derive(Serialize)]
+    struct ShareOut {
+        index: u8,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        out_path: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        share: Option<String>,
+    }
+    #[derive(Serialize)]
+    struct SplitOut {
+        seed_fingerprint: String,
+        split_id: String,
+        threshold: u8,
+        shares: Vec<ShareOut>,
+    }
+    let mut data = SplitOut {
+        seed_fingerprint,
+        split_id: shares[0].split_id(),
+        threshold: args.threshold,
+        shares: Vec::with_capacity(shares.len()),
+    };
+    let mut printed = Zeroizing::new(String::new());
+    for share in &shares {
+        let encoded = share.encode().map_err(AppError::Keys)?;
+        let text = armor::wrap(cli, armor::SEED_SHARE, encoded.expose_secret());
+        let out_path = match &dir {
+            Some(dir) => {
+                let path = dir.join(format!("share-{}-of-{}.txt", share.index(), args.shares));
+                write_secret_file(&path, &(text.to_string() + "\n"), args.force)?;
+                Some(path.display().to_string())
+            }
+            None => None,
+        };
+        if args.print {
+            printed.push_str(&text);
+            printed.push('\n');
+        }
+        data.shares.push(ShareOut {
+            index: share.index(),
+            out_path,
+            share: args.print.then(|| encoded.expose_secret().to_string()),
+        });
+    }
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    if args.print {
+        reveal::print_secret(cli, printed.trim_end())?;
+    } else {
+        for share in &data.shares {
+            println!("{}", share.out_path.as_deref().unwrap_or_default());
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn cmd_seed_combine(
+    cli: &Cli,
+    settings: &Settings,
+    args: &SeedCombineArgs,
+) -> Result<(), AppError> {
+    let net = args.network.map(Network::from).or(settings.network);
+    if args.print {
+        ensure_mainnet_ok(cli, settings, net)?;
+    }
+    let mut shares = args
+        .share
+        .iter()
+        .map(|share| SeedShare::parse(share).map_err(AppError::Keys))
+        .collect::<Result<Vec<_>, _>>()?;
+    for path in &args.file {
+        let raw = Zeroizing::new(if path.as_os_str() == "-" {
+            let mut raw = String::new();
+            std::io::stdin()
+                .read_to_string(&mut raw)
+                .map_err(|e| AppError::Io(format!("read stdin: {e}")))?;
+            raw
+        } else {
+            fs::read_to_string(path)
+                .map_err(|e| AppError::Io(format!("read {}: {e}", path.display())))?
+        });
+        let text = armor::unwrap(armor::SEED_SHARE, &raw)?;
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            shares.push(SeedShare::parse(line).map_err(AppError::Keys)?);
+        }
+    }
+    let seed = juno_keys::combine_seed(&shares).map_err(AppError::Keys)?;
+    audit::note_seed(&seed);
+    let seed_b64 = seed.to_base64();
+    let seed_fingerprint = juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?;
+    let out = match &args.out {
+        Some(out) => {
+            let out = outpath::expand(
+                out,
+                &OutVars {
+                    fingerprint: Some(seed_fingerprint.clone()),
+                    network: net,
+                    account: None,
+                },
+            )?;
+            write_secret_file(
+                &out,
+                &(armor::wrap(cli, armor::SEED, seed_b64.expose_secret()).to_string() + "\n"),
+                args.force,
+            )?;
+            Some(out)
+        }
+        None => None,
+    };
+
+    #[derive(Serialize)]
+    struct CombineOut {
+        bytes: usize,
+        seed_fingerprint: String,
+        split_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        out_path: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed_base64: Option<String>,
+    }
+    let data = CombineOut {
+        bytes: seed.len(),
+        seed_fingerprint,
+        split_id: shares[0].split_id(),
+        out_path: out.map(|p| p.display().to_string()),
+        seed_base64: args.print.then(|| seed_b64.expose_secret().to_string()),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    if let Some(seed_base64) = &data.seed_base64 {
+        reveal::print_secret(cli, &armor::wrap(cli, armor::SEED, seed_base64))?;
+    } else {
+        println!("ok, seed fingerprint {}", data.seed_fingerprint);
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! Human output: `label  value` lines aligned in a column, and color on a terminal.
+//!
+//! Color is used only on a stream that is a terminal, and never with `--no-color`, a
+//! non-empty `NO_COLOR` (<https://no-color.org>) or `TERM=dumb`. Piped or redirected
+//! output and `--json` are always plain, so scripts see the same bytes either way.
+
+use std::io::{self, IsTerminal as _, Write as _};
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+
+use crate::Cli;
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Turns color off for `--no-color` or `NO_COLOR`; call once, before any output.
+pub(crate) fn init(cli: &Cli) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    PLAIN.store(cli.no_color || no_color, Relaxed);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Paint {
+    Label,
+    Secret,
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Paint {
+    fn sgr(self) -> &'static str {
+        match self {
+            Paint::Label => "1",
+            Paint::Secret => "1;35",
+            Paint::Ok => "32",
+            Paint::Warn => "33",
+            Paint::Fail => "1;31",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+fn colored(stream: Stream) -> bool {
+    let terminal = match stream {
+        Stream::Stdout => io::stdout().is_terminal(),
+        Stream::Stderr => io::stderr().is_terminal(),
+    };
+    terminal && !PLAIN.load(Relaxed) && std::env::var_os("TERM").is_none_or(|t| t != "dumb")
+}
+
+/// `text` in `paint` if `stream` is colored, else as is.
+pub(crate) fn paint(stream: Stream, paint: Paint, text: &str) -> String {
+    render(colored(stream), paint, text)
+}
+
+fn render(color: bool, paint: Paint, text: &str) -> String {
+    if color {
+        format!("\x1b[{}m{text}\x1b[0m", paint.sgr())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Prints `label  value` lines to stdout with the values lined up.
+pub(crate) fn fields(rows: &[(&str, &str)]) {
+    print!("{}", aligned(colored(Stream::Stdout), rows));
+}
+
+fn aligned(color: bool, rows: &[(&str, &str)]) -> String {
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(label, value)| {
+            // Pad before painting, so escape codes don't count towards the width.
+            let label = render(color, Paint::Label, &format!("{label:<width$}"));
+            format!("{label}  {value}\n")
+        })
+        .collect()
+}
+
+/// `warning: message` on stderr.
+pub(crate) fn warning(message: &str) {
+    labelled(Paint::Warn, "warning", message);
+}
+
+/// `note: message` on stderr.
+pub(crate) fn note(message: &str) {
+    labelled(Paint::Label, "note", message);
+}
+
+/// `error: message` on stderr.
+pub(crate) fn error(message: &str) {
+    labelled(Paint::Fail, "error", message);
+}
+
+fn labelled(paint: Paint, label: &str, message: &str) {
+    let label = render(colored(Stream::Stderr), paint, &format!("{label}:"));
+    let _ = writeln!(io::stderr(), "{label} {message}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_align_without_counting_color() {
+        let rows = [("path", "m/32'/8133'/0'"), ("descriptor", "juno(...)")];
+        assert_eq!(
+            aligned(false, &rows),
+            "path        m/32'/8133'/0'\ndescriptor  juno(...)\n"
+        );
+        assert_eq!(
+            aligned(true, &rows[..1]),
+            "\x1b[1mpath\x1b[0m  m/32'/8133'/0'\n"
+        );
+        assert_eq!(render(false, Paint::Fail, "FAIL"), "FAIL");
+    }
+}
@@ -0,0 +1,492 @@
+//! `transcribe`: seeds and UFVKs laid out for copying by hand, e.g. onto a metal
+//! backup plate, and the check that reads such a copy back.
+//!
+//! A transcription is a header naming what it holds, numbered lines of up to six
+//! groups of four bech32 characters, each followed by a two-character line checksum,
+//! and a final `==` line with an eight-character checksum over everything. Seeds are
+//! their bytes in 5-bit symbols; UFVKs are the characters after the HRP. Bech32 has no
+//! `1`, `b`, `i` or `o`, and input is case-insensitive, so copies are hard to misread.
+//!
+//! ```text
+//! JKT1 SEED 32
+//! 01 qpzr y9x8 gf2t vdw0 s3jn 54kh  7d
+//! 02 ce6m ua7l qpzr y9x8 gf2t vd    ks
+//! == 4kh7 dce6
+//! ```
+
+use std::fs;
+use std::io::{IsTerminal as _, Read as _};
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use juno_keys::{ExposeSecret as _, Network, Seed, Ufvk};
+use serde::Serialize;
+use subtle::ConstantTimeEq as _;
+use zeroize::Zeroizing;
+
+use crate::{
+    audit, ensure_mainnet_ok, read_passphrase_file, resolve_seed, reveal, write_json_ok,
+    write_secret_file, AppError, BackupVerifyTranscriptionArgs, Cli, NetworkArg, Settings,
+};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GROUP: usize = 4;
+const LINE: usize = 6 * GROUP;
+
+#[derive(Subcommand)]
+pub(crate) enum TranscribeCmd {
+    /// Print a seed or UFVK in checksummed groups for copying by hand
+    #[command(name = "encode")]
+    Encode(EncodeArgs),
+    /// Check a hand-made copy and reassemble the seed or UFVK
+    #[command(name = "verify")]
+    Verify(VerifyArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct EncodeArgs {
+    #[arg(long, conflicts_with = "ufvk", help = "Read seed base64 from a file")]
+    seed_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "ufvk",
+        help = "Seed as base64 (warning: avoid logs)"
+    )]
+    seed_base64: Option<String>,
+
+    #[arg(long, help = "Transcribe this UFVK instead of a seed")]
+    ufvk: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network the seed is meant for (unset counts as mainnet for the interlock)"
+    )]
+    network: Option<NetworkArg>,
+
+    #[arg(long, help = "Write to a file (mode 0600 on unix) instead of stdout")]
+    out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct VerifyArgs {
+    #[arg(long, help = "The copied transcription ('-' for stdin)")]
+    file: PathBuf,
+
+    #[arg(
+        long,
+        help = "Write a recovered seed (base64) to a file (mode 0600 on unix)"
+    )]
+    out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    force: bool,
+}
+
+pub(crate) fn cmd_transcribe(
+    cli: &Cli,
+    settings: &Settings,
+    command: &TranscribeCmd,
+) -> Result<(), AppError> {
+    match command {
+        TranscribeCmd::Encode(args) => encode(cli, settings, args),
+        TranscribeCmd::Verify(args) => verify(cli, args),
+    }
+}
+
+fn encode(cli: &Cli, settings: &Settings, args: &EncodeArgs) -> Result<(), AppError> {
+    let (header, symbols) = match &args.ufvk {
+        Some(ufvk) => {
+            let ufvk = Ufvk::parse(ufvk).map_err(AppError::Keys)?;
+            audit::note_ufvk(ufvk.as_str());
+            let data = &ufvk.as_str()[ufvk.hrp().len() + 1..];
+            (
+                format!("JKT1 UFVK {}", ufvk.hrp().to_ascii_uppercase()),
+                Zeroizing::new(data.to_string()),
+            )
+        }
+        None => {
+            if args.out.is_none() {
+                let net = args.network.map(Network::from).or(settings.network);
+                ensure_mainnet_ok(cli, settings, net)?;
+            }
+            let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+            audit::note_seed(&seed);
+            (
+                format!("JKT1 SEED {}", seed.len()),
+                to_symbols(seed.as_bytes()),
+            )
+        }
+    };
+    let text = render(&header, &symbols);
+
+    if let Some(out) = &args.out {
+        write_secret_file(out, &text, args.force)?;
+        if cli.json {
+            return write_json_ok(
+                cli,
+                &serde_json::json!({ "out_path": out.display().to_string() }),
+            );
+        }
+        println!("wrote {}", out.display());
+        return Ok(());
+    }
+    if cli.json {
+        return write_json_ok(cli, &serde_json::json!({ "transcription": text.as_str() }));
+    }
+    reveal::print_secret(cli, &text)
+}
+
+fn verify(cli: &Cli, args: &VerifyArgs) -> Result<(), AppError> {
+    let mut raw = Zeroizing::new(String::new());
+    if args.file.as_os_str() == "-" {
+        std::io::stdin()
+            .read_to_string(&mut raw)
+            .map_err(|e| AppError::Io(format!("read stdin: {e}")))?;
+    } else {
+        *raw = fs::read_to_string(&args.file)
+            .map_err(|e| AppError::Io(format!("read {}: {e}", args.file.display())))?;
+    }
+    let (header, symbols) = parse(&raw)?;
+
+    #[derive(Serialize)]
+    struct VerifyOut {
+        kind: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed_fingerprint: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ufvk: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        out_path: Option<String>,
+    }
+    let fields = header.split_whitespace().collect::<Vec<_>>();
+    let data = match fields.as_slice() {
+        ["JKT1", "SEED", len] => {
+            let seed = seed_from(len, &symbols)?;
+            audit::note_seed(&seed);
+            if let Some(out) = &args.out {
+                write_secret_file(
+                    out,
+                    &(seed.to_base64().expose_secret().to_string() + "\n"),
+                    args.force,
+                )?;
+            }
+            VerifyOut {
+                kind: "seed",
+                seed_fingerprint: Some(juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?),
+                ufvk: None,
+                out_path: args.out.as_ref().map(|p| p.display().to_string()),
+            }
+        }
+        ["JKT1", "UFVK", hrp] => {
+            if args.out.is_some() {
+                return Err(AppError::InvalidRequest(
+                    "--out is for seeds; this transcription holds a UFVK".into(),
+                ));
+            }
+            let encoded = format!("{}1{}", hrp.to_ascii_lowercase(), symbols.as_str());
+            let ufvk = Ufvk::parse(&encoded).map_err(AppError::Keys)?;
+            audit::note_ufvk(ufvk.as_str());
+            VerifyOut {
+                kind: "ufvk",
+                seed_fingerprint: None,
+                ufvk: Some(ufvk.to_string()),
+                out_path: None,
+            }
+        }
+        _ => {
+            return Err(AppError::InvalidRequest(format!(
+                "unknown transcription header: {header}"
+            )))
+        }
+    };
+
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    match (&data.ufvk, &data.seed_fingerprint) {
+        (Some(ufvk), _) => println!("{ufvk}"),
+        (None, Some(fingerprint)) => println!("ok, seed fingerprint {fingerprint}"),
+        (None, None) => println!("ok"),
+    }
+    Ok(())
+}
+
+/// `backup verify-transcription`: the operator types back what they wrote down, and
+/// the backup only counts as done if it matches the secret exactly.
+pub(crate) fn cmd_backup_verify_transcription(
+    cli: &Cli,
+    settings: &Settings,
+    args: &BackupVerifyTranscriptionArgs,
+) -> Result<(), AppError> {
+    let (format, seed) = match &args.mnemonic_file {
+        Some(path) => {
+            let phrase = Zeroizing::new(
+                fs::read_to_string(path)
+                    .map_err(|e| AppError::Io(format!("read mnemonic file: {e}")))?,
+            );
+            let expected = words(&phrase);
+            let typed = read_typed(cli, "the recovery phrase")?;
+            let typed = words(&typed);
+            if typed.len() != expected.len() {
+                return Err(AppError::InvalidRequest(format!(
+                    "expected {} words, got {}",
+                    expected.len(),
+                    typed.len()
+                )));
+            }
+            let wrong = (0..expected.len())
+                .filter(|&i| typed[i] != expected[i])
+                .map(|i| (i + 1).to_string())
+                .collect::<Vec<_>>();
+            if !wrong.is_empty() {
+                return Err(AppError::InvalidRequest(format!(
+                    "doesn't match the recovery phrase at word {}",
+                    wrong.join(", ")
+                )));
+            }
+            let passphrase = read_passphrase_file(&args.passphrase_file)?;
+            let seed = Seed::from_mnemonic(&phrase, &passphrase).map_err(AppError::Keys)?;
+            ("mnemonic", seed)
+        }
+        None => {
+            let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+            let typed = read_typed(cli, "the transcription (from `transcribe encode`)")?;
+            let (header, symbols) = parse(&typed)?;
+            let copy = match header.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["JKT1", "SEED", len] => seed_from(len, &symbols)?,
+                _ => {
+                    return Err(AppError::InvalidRequest(
+                        "that transcription doesn't hold a seed".into(),
+                    ))
+                }
+            };
+            if !bool::from(copy.as_bytes().ct_eq(seed.as_bytes())) {
+                return Err(AppError::InvalidRequest(
+                    "the transcription is consistent but holds a different seed".into(),
+                ));
+            }
+            ("transcription", seed)
+        }
+    };
+    audit::note_seed(&seed);
+    let seed_fingerprint = juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?;
+
+    if cli.json {
+        return write_json_ok(
+            cli,
+            &serde_json::json!({
+                "verified": true,
+                "format": format,
+                "seed_fingerprint": seed_fingerprint,
+            }),
+        );
+    }
+    println!("backup verified, seed fingerprint {seed_fingerprint}");
+    Ok(())
+}
+
+// Everything on stdin up to an empty line or EOF, with a prompt when it's a terminal.
+fn read_typed(cli: &Cli, what: &str) -> Result<Zeroizing<String>, AppError> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() && !cli.json {
+        eprintln!("Type {what} from your backup, then an empty line:");
+    }
+    let mut typed = Zeroizing::new(String::new());
+    loop {
+        let mut line = Zeroizing::new(String::new());
+        let n = stdin
+            .read_line(&mut line)
+            .map_err(|e| AppError::Io(format!("read stdin: {e}")))?;
+        if n == 0 || (line.trim().is_empty() && !typed.trim().is_empty()) {
+            break;
+        }
+        typed.push_str(&line);
+    }
+    Ok(typed)
+}
+
+// Lower-cased words, skipping the numbers a sheet puts before them ("1.", "12)").
+fn words(phrase: &str) -> Vec<Zeroizing<String>> {
+    phrase
+        .split_whitespace()
+        .map(|w| w.trim_end_matches(['.', ')', ':']))
+        .filter(|w| !w.is_empty() && !w.bytes().all(|b| b.is_ascii_digit()))
+        .map(|w| Zeroizing::new(w.to_lowercase()))
+        .collect()
+}
+
+fn seed_from(len: &str, symbols: &str) -> Result<Seed, AppError> {
+    let bytes = from_symbols(symbols)
+        .filter(|b| len.parse() == Ok(b.len()))
+        .ok_or_else(|| AppError::InvalidRequest("seed length doesn't match".into()))?;
+    Seed::from_bytes(&bytes).map_err(AppError::Keys)
+}
+
+fn render(header: &str, symbols: &str) -> Zeroizing<String> {
+    let mut text = Zeroizing::new(format!("{header}\n"));
+    let lines = symbols.as_bytes().chunks(LINE).collect::<Vec<_>>();
+    for (i, line) in lines.iter().enumerate() {
+        let groups = line
+            .chunks(GROUP)
+            .map(|g| std::str::from_utf8(g).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(" ");
+        // Pad short lines so the checksums line up.
+        let width = LINE + LINE / GROUP - 1;
+        let check = checksum(header, Some(i + 1), line, 2);
+        text.push_str(&format!("{:02} {groups:<width$}  {check}\n", i + 1));
+    }
+    let check = checksum(header, None, symbols.as_bytes(), 8);
+    text.push_str(&format!("== {} {}\n", &check[..4], &check[4..]));
+    text
+}
+
+// The header and the symbols, after every checksum has passed.
+fn parse(raw: &str) -> Result<(String, Zeroizing<String>), AppError> {
+    let mut lines = raw.lines().map(str::trim).filter(|l| !l.is_empty());
+    let header = lines
+        .next()
+        .map(|h| h.split_whitespace().collect::<Vec<_>>().join(" "))
+        .ok_or_else(|| AppError::InvalidRequest("empty transcription".into()))?;
+    let header = match header.split_once(' ') {
+        Some(("jkt1" | "JKT1", rest)) => {
+            let (kind, param) = rest.split_once(' ').unwrap_or((rest, ""));
+            format!(
+                "JKT1 {} {}",
+                kind.to_ascii_uppercase(),
+                param.to_ascii_uppercase()
+            )
+        }
+        _ => header,
+    };
+
+    let mut symbols = Zeroizing::new(String::new());
+    let mut problems = Vec::new();
+    let mut footer = None;
+    let mut expected = 1;
+    for line in lines {
+        let line = line.to_ascii_lowercase();
+        let mut words = line.split_whitespace().collect::<Vec<_>>();
+        if words.first() == Some(&"==") {
+            footer = Some(words[1..].concat());
+            continue;
+        }
+        let (Some(number), Some(check)) = (words.first().copied(), words.pop()) else {
+            continue;
+        };
+        let number = match number.parse::<usize>() {
+            Ok(n) if words.len() > 1 => n,
+            _ => {
+                problems.push(format!("can't read line {line:?}"));
+                continue;
+            }
+        };
+        if number != expected {
+            problems.push(format!("line {expected:02} is missing or out of order"));
+            expected = number;
+        }
+        expected += 1;
+        let body = words[1..].concat();
+        if checksum(&header, Some(number), body.as_bytes(), 2) != check {
+            problems.push(format!("line {number:02} doesn't match its checksum"));
+        }
+        symbols.push_str(&body);
+    }
+    match footer {
+        None => problems.push("the == line is missing".into()),
+        Some(check) if problems.is_empty() => {
+            if checksum(&header, None, symbols.as_bytes(), 8) != check {
+                problems.push("the == checksum doesn't match (a line may be missing)".into());
+            }
+        }
+        Some(_) => {}
+    }
+    if !problems.is_empty() {
+        return Err(AppError::InvalidRequest(problems.join("; ")));
+    }
+    Ok((header, symbols))
+}
+
+// `len` bech32 characters of a BLAKE2b hash; `line` is None for the whole-copy check.
+fn checksum(header: &str, line: Option<usize>, symbols: &[u8], len: usize) -> String {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"JunoKeys_Transcr")
+        .to_state();
+    state.update(&(header.len() as u32).to_le_bytes());
+    state.update(header.as_bytes());
+    state.update(&(line.unwrap_or(0) as u32).to_le_bytes());
+    state.update(symbols);
+    let hash = state.finalize();
+    to_symbols(hash.as_bytes())[..len].to_string()
+}
+
+fn to_symbols(bytes: &[u8]) -> Zeroizing<String> {
+    let mut out = Zeroizing::new(String::with_capacity(bytes.len() * 8 / 5 + 1));
+    let (mut acc, mut bits) = (0u32, 0);
+    for &b in bytes {
+        acc = (acc << 8) | u32::from(b);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(char::from(CHARSET[((acc >> bits) & 31) as usize]));
+        }
+    }
+    if bits > 0 {
+        out.push(char::from(CHARSET[((acc << (5 - bits)) & 31) as usize]));
+    }
+    out
+}
+
+fn from_symbols(symbols: &str) -> Option<Zeroizing<Vec<u8>>> {
+    let mut out = Zeroizing::new(Vec::with_capacity(symbols.len() * 5 / 8));
+    let (mut acc, mut bits) = (0u32, 0);
+    for c in symbols.bytes() {
+        let v = CHARSET.iter().position(|&x| x == c)? as u32;
+        acc = ((acc << 5) | v) & 0xfff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    // Padding bits must be zero, as in bech32.
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcriptions_round_trip_and_catch_typos() {
+        let seed = (0u8..64).collect::<Vec<_>>();
+        let text = render("JKT1 SEED 64", &to_symbols(&seed));
+        let (header, symbols) = parse(&text.to_uppercase()).unwrap();
+        assert_eq!(header, "JKT1 SEED 64");
+        assert_eq!(from_symbols(&symbols).unwrap().as_slice(), seed.as_slice());
+
+        let lines = text.lines().collect::<Vec<_>>();
+        let typo = text.replacen(&lines[2][3..7], "qqqq", 1);
+        let err = parse(&typo).unwrap_err();
+        assert!(err.message().contains("line 02"), "{}", err.message());
+
+        let dropped = text.replace(&format!("{}\n", lines[3]), "");
+        assert!(parse(&dropped).is_err());
+    }
+
+    #[test]
+    fn typed_phrases_ignore_case_and_numbering() {
+        let typed = words(" 1. Abandon 2) ABANDON\n3: about 12 ");
+        let typed = typed.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+        assert_eq!(typed, ["abandon", "abandon", "about"]);
+    }
+}
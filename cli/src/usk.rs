@@ -0,0 +1,229 @@
+//! Unified spending keys (`jsecret1...`): one account's keys without the seed.
+//!
+//! `derive usk` (or `usk from-seed`) exports an account's key from the seed, for a vault that hands out
+//! account keys and keeps the seed. `ufvk from-usk` and `address from-usk` derive the
+//! viewing key and addresses from such a key where the seed never was.
+
+use std::fs;
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use juno_keys::{
+    DiversifierIndex, ExposeSecret as _, Network, OrchardKeySet, UnifiedSpendingKey, ZipPath,
+};
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+use crate::{
+    armor, audit, diversifier_index_arg, ensure_mainnet_ok, network_params, outpath,
+    resolve_network, resolve_seed, reveal, write_json_ok, write_secret_file, AppError, Cli,
+    NetworkArg, Settings,
+};
+
+#[derive(Subcommand)]
+pub(crate) enum UskCmd {
+    /// Export an account's unified spending key, as `derive usk` does
+    #[command(name = "from-seed")]
+    FromSeed(DeriveUskArgs),
+}
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("dest").required(true).multiple(true).args(["out", "print"])))]
+pub(crate) struct DeriveUskArgs {
+    #[arg(long, help = "Read seed base64 from a file")]
+    pub(crate) seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    pub(crate) seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Network selection (sets the key's HRP + coin_type; default: profile network)"
+    )]
+    pub(crate) network: Option<NetworkArg>,
+
+    #[arg(long, default_value_t = 0, help = "Account (typically 0)")]
+    pub(crate) account: u32,
+
+    #[arg(
+        long,
+        help = "Write the key to a file (mode 0600 on unix; placeholders like {fingerprint} and {date} are filled in)"
+    )]
+    pub(crate) out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    pub(crate) force: bool,
+
+    #[arg(
+        long,
+        help = "Print the key to stdout, also with --out (warning: avoid logs)"
+    )]
+    pub(crate) print: bool,
+}
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("key").required(true).args(["usk_file", "usk"])))]
+pub(crate) struct UskArgs {
+    #[arg(long, help = "Read the spending key (jsecret1...) from a file")]
+    pub(crate) usk_file: Option<PathBuf>,
+
+    #[arg(long, help = "Spending key as jsecret1... (warning: avoid logs)")]
+    pub(crate) usk: Option<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct AddressFromUskArgs {
+    #[command(flatten)]
+    pub(crate) key: UskArgs,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = diversifier_index_arg,
+        help = "Diversifier index of the address, in decimal or 0x hex (below 2^88)"
+    )]
+    pub(crate) diversifier_index: DiversifierIndex,
+}
+
+impl UskArgs {
+    fn read(&self) -> Result<UnifiedSpendingKey, AppError> {
+        let raw;
+        let body;
+        let text: &str = match (&self.usk_file, &self.usk) {
+            (Some(path), _) => {
+                raw = Zeroizing::new(
+                    fs::read_to_string(path)
+                        .map_err(|e| AppError::Io(format!("read usk file: {e}")))?,
+                );
+                body = armor::unwrap(armor::USK, &raw)?;
+                &body
+            }
+            (None, Some(usk)) => usk,
+            (None, None) => {
+                return Err(AppError::InvalidRequest(
+                    "missing spending key (set --usk-file or --usk)".into(),
+                ))
+            }
+        };
+        let usk = UnifiedSpendingKey::parse(text).map_err(AppError::Keys)?;
+        audit::note_ufvk(usk.ufvk().map_err(AppError::Keys)?.as_str());
+        Ok(usk)
+    }
+}
+
+pub(crate) fn cmd_derive_usk(
+    cli: &Cli,
+    settings: &Settings,
+    args: &DeriveUskArgs,
+) -> Result<(), AppError> {
+    let net = resolve_network(settings, args.network)?;
+    let should_print = args.print;
+    if should_print {
+        ensure_mainnet_ok(cli, settings, Some(net))?;
+    }
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    let params = network_params(cli, net);
+    let keys =
+        OrchardKeySet::from_seed(&seed, params.as_ref(), args.account).map_err(AppError::Keys)?;
+    let usk = UnifiedSpendingKey::from_keys(params.ua_hrp(), &keys).map_err(AppError::Keys)?;
+    let ufvk = usk.ufvk().map_err(AppError::Keys)?;
+    audit::note_seed(&seed);
+    audit::note_ufvk(ufvk.as_str());
+    let encoded = usk.encode();
+
+    let out = match &args.out {
+        Some(out) => {
+            let out = outpath::expand(
+                out,
+                &outpath::OutVars {
+                    fingerprint: Some(juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?),
+                    network: Some(net),
+                    account: Some(args.account),
+                },
+            )?;
+            let line = Zeroizing::new(format!(
+                "{}\n",
+                armor::wrap(cli, armor::USK, encoded.expose_secret()).as_str()
+            ));
+            write_secret_file(&out, &line, args.force)?;
+            Some(out)
+        }
+        None => None,
+    };
+
+    #[derive(Serialize)]
+    struct UskOut<'a> {
+        network: &'static str,
+        path: String,
+        ufvk: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        out_path: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        usk: Option<&'a str>,
+    }
+    let data = UskOut {
+        network: net.as_str(),
+        path: ZipPath::account(params.coin_type(), args.account)
+            .map_err(AppError::Keys)?
+            .to_string(),
+        ufvk: ufvk.to_string(),
+        out_path: out.map(|p| p.display().to_string()),
+        usk: should_print.then(|| encoded.expose_secret()),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    match (data.usk, data.out_path) {
+        (Some(usk), _) => reveal::print_secret(cli, &armor::wrap(cli, armor::USK, usk))?,
+        (None, Some(out_path)) => println!("{out_path}"),
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct FromUskOut {
+    ufvk: String,
+    ua_hrp: String,
+    network: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diversifier_index: Option<u128>,
+}
+
+fn from_usk(usk: &UnifiedSpendingKey) -> Result<FromUskOut, AppError> {
+    Ok(FromUskOut {
+        ufvk: usk.ufvk().map_err(AppError::Keys)?.to_string(),
+        ua_hrp: usk.ua_hrp().to_string(),
+        network: usk.network().as_ref().map(Network::as_str),
+        address: None,
+        diversifier_index: None,
+    })
+}
+
+pub(crate) fn cmd_ufvk_from_usk(cli: &Cli, args: &UskArgs) -> Result<(), AppError> {
+    let data = from_usk(&args.read()?)?;
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    println!("{}", armor::wrap(cli, armor::UFVK, &data.ufvk).as_str());
+    Ok(())
+}
+
+pub(crate) fn cmd_address_from_usk(cli: &Cli, args: &AddressFromUskArgs) -> Result<(), AppError> {
+    let usk = args.key.read()?;
+    let mut data = from_usk(&usk)?;
+    let address = usk
+        .ufvk()
+        .and_then(|ufvk| ufvk.address_at(args.diversifier_index))
+        .map_err(AppError::Keys)?;
+    data.address = Some(address.to_string());
+    data.diversifier_index = Some(args.diversifier_index.into());
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    println!("{address}");
+    Ok(())
+}
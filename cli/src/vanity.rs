@@ -0,0 +1,345 @@
+//! `address vanity`: search an account's addresses for one matching a prefix or a
+//! regular expression.
+//!
+//! Candidates are numbered so that n covers every account at one diversifier index
+//! before the next index. Each thread takes every `threads`-th n and stops once it
+//! passes the lowest match found so far, so the answer is the lowest matching n
+//! whatever the thread count, and reruns reproduce it. A checkpoint records the
+//! lowest n that some thread hasn't checked yet; `--resume` restarts every thread
+//! from there.
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+use std::time::{Duration, Instant};
+
+use juno_keys::{DiversifierIndex, KeysError, MAX_DIVERSIFIER_INDEX};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audit, network_params, resolve_network, resolve_seed, style, visual, write_json_ok,
+    AddressVanityArgs, AppError, Cli, Settings,
+};
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+enum Pattern {
+    Prefix(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn matches(&self, address: &str) -> bool {
+        match self {
+            Pattern::Prefix(prefix) => address.starts_with(prefix.as_str()),
+            Pattern::Regex(regex) => regex.is_match(address),
+        }
+    }
+}
+
+// Everything that decides which address the search finds; a checkpoint only resumes
+// the same search.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Search {
+    seed_fingerprint: String,
+    ua_hrp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    regex: Option<String>,
+    account: u32,
+    accounts: u32,
+    start_index: u128,
+    end_index: u128,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    version: u32,
+    #[serde(flatten)]
+    search: Search,
+    // Every candidate below this one has been checked without a match.
+    next: u64,
+    tries: u64,
+}
+
+pub(crate) fn cmd_address_vanity(
+    cli: &Cli,
+    settings: &Settings,
+    args: &AddressVanityArgs,
+) -> Result<(), AppError> {
+    let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+    let net = resolve_network(settings, args.network)?;
+    let params = network_params(cli, net);
+    let pattern = match (&args.prefix, &args.regex) {
+        (Some(prefix), _) => Pattern::Prefix(full_prefix(params.ua_hrp(), prefix)?),
+        (None, Some(regex)) => Pattern::Regex(
+            Regex::new(regex).map_err(|e| AppError::InvalidRequest(format!("--regex: {e}")))?,
+        ),
+        (None, None) => {
+            return Err(AppError::InvalidRequest(
+                "missing pattern (set --prefix or --regex)".into(),
+            ))
+        }
+    };
+    let threads = match args.threads {
+        Some(0) => {
+            return Err(AppError::InvalidRequest(
+                "--threads must be at least 1".into(),
+            ))
+        }
+        Some(n) => n as u64,
+        None => std::thread::available_parallelism().map_or(1, |n| n.get() as u64),
+    };
+    if args.accounts == 0 {
+        return Err(AppError::InvalidRequest(
+            "--accounts must be at least 1".into(),
+        ));
+    }
+    let start_index = u128::from(args.start_index);
+    let end_index = args.end_index.map_or(MAX_DIVERSIFIER_INDEX + 1, u128::from);
+    if end_index <= start_index {
+        return Err(AppError::InvalidRequest(
+            "--end-index must be above --start-index".into(),
+        ));
+    }
+    let generators = (0..args.accounts)
+        .map(|i| {
+            let account = args
+                .account
+                .checked_add(i)
+                .ok_or(KeysError::AccountInvalid)?;
+            juno_keys::DerivationRequest::new(&seed)
+                .network(params.as_ref())
+                .account(account)
+                .build()?
+                .ufvk
+                .address_generator()
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::Keys)?;
+    audit::note_seed(&seed);
+
+    let search = Search {
+        seed_fingerprint: juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?,
+        ua_hrp: params.ua_hrp().to_string(),
+        prefix: match &pattern {
+            Pattern::Prefix(prefix) => Some(prefix.clone()),
+            Pattern::Regex(_) => None,
+        },
+        regex: args.regex.clone(),
+        account: args.account,
+        accounts: args.accounts,
+        start_index,
+        end_index,
+    };
+    let (first, earlier_tries) = match &args.checkpoint {
+        Some(path) if args.resume => {
+            let checkpoint = read_checkpoint(path)?;
+            if checkpoint.search != search {
+                return Err(AppError::InvalidRequest(format!(
+                    "{} is a checkpoint for a different search",
+                    path.display()
+                )));
+            }
+            (checkpoint.next, checkpoint.tries)
+        }
+        Some(path) if path.exists() => {
+            return Err(AppError::InvalidRequest(format!(
+                "{} exists (pass --resume to continue it)",
+                path.display()
+            )))
+        }
+        _ => (0, 0),
+    };
+
+    let accounts = u64::from(args.accounts);
+    // Candidates are counted in a u64, which no search gets to the end of.
+    let total = u64::try_from(u128::from(accounts) * (end_index - start_index))
+        .unwrap_or(u64::MAX)
+        .min(u64::MAX - threads);
+    if !cli.json {
+        match &pattern {
+            // Bech32 characters after the HRP are uniform, so each costs a factor of 32.
+            Pattern::Prefix(prefix) => eprintln!(
+                "searching for {prefix} (about {:.0} addresses) on {threads} threads",
+                32f64.powi((prefix.len() - params.ua_hrp().len() - 1) as i32)
+            ),
+            Pattern::Regex(regex) => eprintln!("searching for /{regex}/ on {threads} threads"),
+        }
+        if first > 0 {
+            eprintln!("resuming after {first} of {total} candidates");
+        }
+    }
+
+    let best = AtomicU64::new(u64::MAX);
+    let tries = AtomicU64::new(0);
+    // The next candidate each thread will check, or its match.
+    let progress = (0..threads)
+        .map(|t| AtomicU64::new(first + t))
+        .collect::<Vec<_>>();
+    let checkpoint = |next: u64| match &args.checkpoint {
+        Some(path) => write_checkpoint(
+            path,
+            &Checkpoint {
+                version: 1,
+                search: search.clone(),
+                next: next.min(total),
+                tries: earlier_tries + tries.load(Relaxed),
+            },
+        ),
+        None => Ok(()),
+    };
+    checkpoint(first)?;
+
+    std::thread::scope(|scope| {
+        let workers = progress
+            .iter()
+            .map(|next| {
+                let (generators, pattern, best, tries) = (&generators, &pattern, &best, &tries);
+                scope.spawn(move || -> Result<(), KeysError> {
+                    let mut n = next.load(Relaxed);
+                    while n < total && n < best.load(Relaxed) {
+                        let index = index_at(start_index, n / accounts)?;
+                        let address = generators[(n % accounts) as usize].address_at(index)?;
+                        tries.fetch_add(1, Relaxed);
+                        if pattern.matches(address.as_str()) {
+                            best.fetch_min(n, Relaxed);
+                            break;
+                        }
+                        n += threads;
+                        next.store(n, Relaxed);
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let interval = Duration::from_secs(args.checkpoint_interval.max(1));
+        let mut last = Instant::now();
+        while !workers.iter().all(|w| w.is_finished()) {
+            std::thread::sleep(Duration::from_millis(100));
+            if last.elapsed() >= interval {
+                // A lost checkpoint costs progress, not the search.
+                if let Err(e) = checkpoint(lowest(&progress)) {
+                    style::warning(&format!("checkpoint not written: {}", e.message()));
+                }
+                last = Instant::now();
+            }
+        }
+        workers
+            .into_iter()
+            .try_for_each(|w| w.join().map_err(|_| KeysError::Internal)?)
+            .map_err(AppError::Keys)
+    })?;
+    checkpoint(lowest(&progress))?;
+
+    let n = best.into_inner();
+    if n == u64::MAX {
+        return Err(AppError::InvalidRequest(
+            "no matching address in the searched range".into(),
+        ));
+    }
+    let account = args.account + (n % accounts) as u32;
+    let diversifier_index = index_at(start_index, n / accounts).map_err(AppError::Keys)?;
+
+    #[derive(Serialize)]
+    struct VanityOut {
+        address: String,
+        account: u32,
+        diversifier_index: u128,
+        path: String,
+        tries: u64,
+    }
+    let data = VanityOut {
+        address: generators[(n % accounts) as usize]
+            .address_at(diversifier_index)
+            .map_err(AppError::Keys)?
+            .to_string(),
+        account,
+        diversifier_index: diversifier_index.into(),
+        path: format!("m/32'/{}'/{account}'", params.coin_type()),
+        tries: earlier_tries + tries.into_inner(),
+    };
+
+    if cli.json {
+        write_json_ok(cli, &data)?;
+        return Ok(());
+    }
+
+    style::fields(&[
+        ("address", &data.address),
+        ("account", &data.account.to_string()),
+        ("diversifier_index", &data.diversifier_index.to_string()),
+        ("path", &data.path),
+    ]);
+    visual::note(cli, visual::Kind::Address, &data.address);
+    Ok(())
+}
+
+fn index_at(start: u128, offset: u64) -> Result<DiversifierIndex, KeysError> {
+    DiversifierIndex::try_from(start + u128::from(offset))
+        .map_err(|_| KeysError::DiversifierIndexInvalid)
+}
+
+fn lowest(progress: &[AtomicU64]) -> u64 {
+    progress
+        .iter()
+        .map(|next| next.load(Relaxed))
+        .min()
+        .unwrap_or(0)
+}
+
+// The full address prefix: `{hrp}1` followed by bech32 data characters.
+fn full_prefix(hrp: &str, prefix: &str) -> Result<String, AppError> {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    let separator = format!("{hrp}1");
+    let data = prefix.strip_prefix(&separator).unwrap_or(&prefix);
+    if data.is_empty() {
+        return Err(AppError::InvalidRequest(format!(
+            "--prefix needs at least one character after {separator}"
+        )));
+    }
+    if let Some(c) = data.chars().find(|c| !BECH32_CHARSET.contains(*c)) {
+        return Err(AppError::InvalidRequest(format!(
+            "--prefix: {c:?} never appears in an address (bech32 uses {BECH32_CHARSET})"
+        )));
+    }
+    Ok(format!("{separator}{data}"))
+}
+
+fn read_checkpoint(path: &Path) -> Result<Checkpoint, AppError> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| AppError::Io(format!("read {}: {e}", path.display())))?;
+    serde_json::from_str::<Checkpoint>(&raw)
+        .ok()
+        .filter(|c| c.version == 1)
+        .ok_or_else(|| {
+            AppError::InvalidRequest(format!("{} is not a vanity checkpoint", path.display()))
+        })
+}
+
+// Written beside the target and renamed over it, so a crash leaves the old checkpoint.
+fn write_checkpoint(path: &Path, checkpoint: &Checkpoint) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(checkpoint)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, json + "\n")
+        .and_then(|()| fs::rename(&tmp, path))
+        .map_err(|e| AppError::Io(format!("write {}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_take_the_hrp_or_leave_it_out() {
+        assert_eq!(full_prefix("j", "J1JX").unwrap(), "j1jx");
+        assert_eq!(full_prefix("jtest", "qq").unwrap(), "jtest1qq");
+        assert!(full_prefix("j", "j1").is_err());
+        assert!(full_prefix("j", "j1bo").is_err());
+    }
+}
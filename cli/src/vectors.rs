@@ -0,0 +1,210 @@
+//! `vectors generate`: golden vectors for the JS and mobile SDKs to check themselves
+//! against, from `juno_keys::vectors`.
+//!
+//! With `--deterministic-seed` the suite only depends on the flags, so CI can commit
+//! it as a snapshot and diff a fresh run against it. With a real seed the suite holds
+//! that seed in hex, so it is a secret: `--out` files are written mode 0600 and
+//! printing it goes through the mainnet interlock.
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use juno_keys::{AccountRange, DiversifierIndex};
+use serde::Serialize;
+
+use crate::{
+    audit, diversifier_index_arg, ensure_mainnet_ok, resolve_seed, write_json_ok,
+    write_secret_file, AppError, Cli, Settings,
+};
+
+// Bumped when the layout of the suite changes, so snapshot diffs say why.
+const SUITE_VERSION: u32 = 1;
+
+#[derive(Subcommand)]
+pub(crate) enum VectorsCmd {
+    /// Emit seeds, fingerprints, UFVKs, UIVKs and addresses on every network as JSON
+    #[command(name = "generate")]
+    Generate(VectorsGenerateArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct VectorsGenerateArgs {
+    #[arg(
+        long,
+        conflicts_with_all = ["seed_file", "seed_base64"],
+        help = "Use the public test seeds of `fixtures generate` (never fund their keys)"
+    )]
+    pub(crate) deterministic_seed: bool,
+
+    #[arg(long, help = "Read seed base64 from a file")]
+    pub(crate) seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Seed as base64 (warning: avoid logs)")]
+    pub(crate) seed_base64: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "With --deterministic-seed, how many seeds (default 2)"
+    )]
+    pub(crate) seeds: Option<u32>,
+
+    #[arg(
+        long,
+        default_value_t = 2,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Accounts per seed and network, from 0"
+    )]
+    pub(crate) accounts: u32,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "0,1,2",
+        value_parser = diversifier_index_arg,
+        help = "Diversifier indices of the addresses, comma-separated"
+    )]
+    pub(crate) diversifier_indices: Vec<DiversifierIndex>,
+
+    #[arg(long, help = "Write the suite to a file instead of stdout")]
+    pub(crate) out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite --out if it exists")]
+    pub(crate) force: bool,
+}
+
+#[derive(Serialize)]
+struct AddressOut {
+    diversifier_index: u128,
+    address: String,
+}
+
+#[derive(Serialize)]
+struct AccountOut {
+    account: u32,
+    path: String,
+    ufvk: String,
+    ufvk_fingerprint: String,
+    uivk: String,
+    addresses: Vec<AddressOut>,
+}
+
+#[derive(Serialize)]
+struct NetworkOut {
+    network: &'static str,
+    ua_hrp: &'static str,
+    coin_type: u32,
+    accounts: Vec<AccountOut>,
+}
+
+#[derive(Serialize)]
+struct SeedOut {
+    seed_hex: String,
+    seed_fingerprint: String,
+    networks: Vec<NetworkOut>,
+}
+
+#[derive(Serialize)]
+struct SuiteOut {
+    version: u32,
+    deterministic: bool,
+    seeds: Vec<SeedOut>,
+}
+
+pub(crate) fn cmd_vectors(
+    cli: &Cli,
+    settings: &Settings,
+    command: &VectorsCmd,
+) -> Result<(), AppError> {
+    let VectorsCmd::Generate(args) = command;
+    if args.seeds.is_some() && !args.deterministic_seed {
+        return Err(AppError::InvalidRequest(
+            "--seeds needs --deterministic-seed".into(),
+        ));
+    }
+    if !args.deterministic_seed && args.out.is_none() {
+        ensure_mainnet_ok(cli, settings, None)?;
+    }
+    let seeds = if args.deterministic_seed {
+        (0..args.seeds.unwrap_or(2))
+            .map(juno_keys::vectors::deterministic_seed)
+            .collect()
+    } else {
+        vec![resolve_seed(settings, &args.seed_file, &args.seed_base64)?]
+    };
+    let accounts = AccountRange::from_count(0, args.accounts).map_err(AppError::Keys)?;
+    let suite = juno_keys::vectors::generate(&seeds, accounts, &args.diversifier_indices)
+        .map_err(AppError::Keys)?;
+    if !args.deterministic_seed {
+        audit::note_seed(&seeds[0]);
+    }
+
+    let data = SuiteOut {
+        version: SUITE_VERSION,
+        deterministic: args.deterministic_seed,
+        seeds: suite
+            .seeds
+            .into_iter()
+            .map(|seed| SeedOut {
+                seed_hex: seed.seed_hex,
+                seed_fingerprint: seed.seed_fingerprint,
+                networks: seed
+                    .networks
+                    .into_iter()
+                    .map(|network| NetworkOut {
+                        network: network.network.as_str(),
+                        ua_hrp: network.network.ua_hrp(),
+                        coin_type: network.network.coin_type(),
+                        accounts: network
+                            .accounts
+                            .into_iter()
+                            .map(|account| AccountOut {
+                                account: account.account,
+                                path: account.path.to_string(),
+                                ufvk: account.ufvk.to_string(),
+                                ufvk_fingerprint: account.ufvk_fingerprint,
+                                uivk: account.uivk,
+                                addresses: account
+                                    .addresses
+                                    .into_iter()
+                                    .map(|(index, address)| AddressOut {
+                                        diversifier_index: index.into(),
+                                        address: address.to_string(),
+                                    })
+                                    .collect(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    if let Some(out) = &args.out {
+        let mut contents = serde_json::to_string_pretty(&data)
+            .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+        contents.push('\n');
+        write_secret_file(out, &contents, args.force)?;
+        if cli.json {
+            #[derive(Serialize)]
+            struct WrittenOut {
+                out_path: String,
+            }
+            return write_json_ok(
+                cli,
+                &WrittenOut {
+                    out_path: out.display().to_string(),
+                },
+            );
+        }
+        println!("{}", out.display());
+        return Ok(());
+    }
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    let pretty = serde_json::to_string_pretty(&data)
+        .map_err(|e| AppError::Io(format!("json encode: {e}")))?;
+    println!("{pretty}");
+    Ok(())
+}
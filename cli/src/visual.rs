@@ -0,0 +1,235 @@
+//! Visual fingerprints of addresses and UFVKs: an OpenSSH-style randomart box, a
+//! short emoji sequence and three BIP-39 words, all from one BLAKE2b hash of the
+//! encoded string. The words are for reading aloud, e.g. on a phone call.
+//!
+//! They make a substituted address look different at a glance; comparing them is not
+//! a substitute for comparing the string when it matters. Commands that print a key
+//! for a person add them on stderr, and only when stderr is a terminal, so piped
+//! output is unchanged. `fingerprint` prints them for any address or UFVK, so the
+//! other side of a payment can compute the same picture.
+//!
+//! `fingerprint` also prints the identifiers that are safe to log in place of a key:
+//! a UFVK's BLAKE2b-256 fingerprint, and for a seed its ZIP-32 seed fingerprint
+//! (`zip32seedfp1...`). A seed's picture is drawn from that fingerprint, never from
+//! the seed.
+
+use std::io::IsTerminal as _;
+use std::path::PathBuf;
+
+use clap::Args;
+use juno_keys::{Ufvk, UnifiedAddress};
+use serde::Serialize;
+
+use crate::{audit, resolve_seed, write_json_ok, AppError, Cli, Settings};
+
+const WIDTH: usize = 17;
+const HEIGHT: usize = 9;
+// OpenSSH's symbols, by how often the bishop visited; 'S' and 'E' mark start and end.
+const SYMBOLS: &[u8] = b" .o+=*BOX@%&#/^";
+
+// 64 emoji chosen to look unlike one another, so each one carries six bits.
+const EMOJI: [&str; 64] = [
+    "🐶", "🐱", "🐭", "🐰", "🦊", "🐻", "🐼", "🐨", "🐯", "🦁", "🐮", "🐷", "🐸", "🐵", "🐔", "🐧",
+    "🐦", "🦆", "🦉", "🐴", "🦄", "🐝", "🐛", "🦋", "🐌", "🐞", "🐢", "🐍", "🐙", "🦀", "🐬", "🐳",
+    "🌵", "🌲", "🍀", "🍁", "🍄", "🌻", "🌙", "⭐", "🔥", "🌈", "⛄", "🍎", "🍋", "🍌", "🍇", "🍓",
+    "🥕", "🌽", "🍞", "🧀", "🍕", "🎂", "⚽", "🎲", "🎸", "🚀", "🚲", "⚓", "🔑", "🔔", "💡", "🎈",
+];
+
+#[derive(Args)]
+#[command(group(clap::ArgGroup::new("input").args(["value", "seed_file", "seed_base64"])))]
+pub(crate) struct FingerprintArgs {
+    #[arg(help = "Unified address (j1...) or UFVK (jview1...); without one, the seed")]
+    pub(crate) value: Option<String>,
+
+    #[arg(long, help = "Fingerprint the seed in this file")]
+    pub(crate) seed_file: Option<PathBuf>,
+
+    #[arg(long, help = "Fingerprint this seed (base64) (warning: avoid logs)")]
+    pub(crate) seed_base64: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Kind {
+    Address,
+    Ufvk,
+    Seed,
+}
+
+impl Kind {
+    fn title(self) -> &'static str {
+        match self {
+            Kind::Address => "ADDRESS",
+            Kind::Ufvk => "UFVK",
+            Kind::Seed => "SEED",
+        }
+    }
+}
+
+pub(crate) fn cmd_fingerprint(
+    cli: &Cli,
+    settings: &Settings,
+    args: &FingerprintArgs,
+) -> Result<(), AppError> {
+    // The string the picture is drawn from, and the identifier to log, if any.
+    let (kind, value, id) = match &args.value {
+        Some(value) => match UnifiedAddress::parse(value) {
+            Ok(address) => (Kind::Address, address.to_string(), None),
+            Err(_) => {
+                let ufvk = Ufvk::parse(value).map_err(AppError::Keys)?;
+                audit::note_ufvk(ufvk.as_str());
+                (Kind::Ufvk, ufvk.to_string(), Some(ufvk.fingerprint()))
+            }
+        },
+        None => {
+            let seed = resolve_seed(settings, &args.seed_file, &args.seed_base64)?;
+            audit::note_seed(&seed);
+            let id = juno_keys::seed_fingerprint(&seed).map_err(AppError::Keys)?;
+            (Kind::Seed, id.clone(), Some(id))
+        }
+    };
+
+    #[derive(Serialize)]
+    struct FingerprintOut {
+        kind: Kind,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed_fingerprint: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ufvk_fingerprint: Option<String>,
+        emoji: String,
+        words: String,
+        randomart: Vec<String>,
+    }
+    let hash = hash(&value);
+    let data = FingerprintOut {
+        kind,
+        seed_fingerprint: id.clone().filter(|_| matches!(kind, Kind::Seed)),
+        ufvk_fingerprint: id.clone().filter(|_| matches!(kind, Kind::Ufvk)),
+        emoji: emoji(&hash),
+        words: words(&hash),
+        randomart: randomart(kind, &hash),
+    };
+    if cli.json {
+        return write_json_ok(cli, &data);
+    }
+    if let Some(id) = id {
+        println!("{id}");
+    }
+    println!("{}  {}", data.words, data.emoji);
+    for line in &data.randomart {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// Shows a key's fingerprints to the person at the terminal, beside its human output.
+pub(crate) fn note(cli: &Cli, kind: Kind, value: &str) {
+    if cli.json || !std::io::stderr().is_terminal() {
+        return;
+    }
+    let hash = hash(value);
+    let art = randomart(kind, &hash);
+    eprintln!("{} {}  {}", art[0], words(&hash), emoji(&hash));
+    for line in &art[1..] {
+        eprintln!("{line}");
+    }
+}
+
+fn hash(value: &str) -> [u8; 32] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"JunoKeys_Visual_")
+        .hash(value.trim().to_ascii_lowercase().as_bytes());
+    let mut out = [0u8; 32];
+    out.copy_from_slice(hash.as_bytes());
+    out
+}
+
+fn emoji(hash: &[u8; 32]) -> String {
+    let bits = u32::from_be_bytes([0, hash[0], hash[1], hash[2]]);
+    (0..4)
+        .map(|i| EMOJI[(bits >> (18 - 6 * i)) as usize & 63])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 33 bits, after the emoji's 24, as English BIP-39 words.
+fn words(hash: &[u8; 32]) -> String {
+    let bits = u64::from_be_bytes([0, 0, 0, hash[3], hash[4], hash[5], hash[6], hash[7]]);
+    let list = bip39::Language::English.word_list();
+    (0..3)
+        .map(|i| list[(bits >> (29 - 11 * i)) as usize & 2047])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// The "drunken bishop" walk: each pair of bits moves one step diagonally, staying
+// on the board, and the box shows how often each cell was visited.
+fn randomart(kind: Kind, hash: &[u8; 32]) -> Vec<String> {
+    let mut visits = [[0usize; WIDTH]; HEIGHT];
+    let (start_x, start_y) = (WIDTH / 2, HEIGHT / 2);
+    let (mut x, mut y) = (start_x, start_y);
+    for byte in hash {
+        for step in 0..4 {
+            let bits = byte >> (2 * step);
+            x = if bits & 1 == 1 {
+                (x + 1).min(WIDTH - 1)
+            } else {
+                x.saturating_sub(1)
+            };
+            y = if bits & 2 == 2 {
+                (y + 1).min(HEIGHT - 1)
+            } else {
+                y.saturating_sub(1)
+            };
+            visits[y][x] += 1;
+        }
+    }
+
+    let mut lines = vec![border(kind.title())];
+    for (row, cells) in visits.iter().enumerate() {
+        let mut line = String::from("|");
+        for (col, &count) in cells.iter().enumerate() {
+            line.push(if (col, row) == (start_x, start_y) {
+                'S'
+            } else if (col, row) == (x, y) {
+                'E'
+            } else {
+                char::from(SYMBOLS[count.min(SYMBOLS.len() - 1)])
+            });
+        }
+        line.push('|');
+        lines.push(line);
+    }
+    lines.push(border("BLAKE2b"));
+    lines
+}
+
+fn border(title: &str) -> String {
+    let title = format!("[{title}]");
+    let left = (WIDTH - title.len()) / 2;
+    format!(
+        "+{}{title}{}+",
+        "-".repeat(left),
+        "-".repeat(WIDTH - left - title.len())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_are_stable_and_case_blind() {
+        let a = hash("j1Example");
+        assert_eq!(a, hash(" j1example\n"));
+        assert_ne!(a, hash("j1exampl3"));
+
+        let art = randomart(Kind::Address, &a);
+        assert_eq!(art.len(), HEIGHT + 2);
+        assert!(art.iter().all(|line| line.len() == WIDTH + 2));
+        assert_eq!(art[0], "+----[ADDRESS]----+");
+        assert_eq!(emoji(&a).split(' ').count(), 4);
+        assert_eq!(words(&a).split(' ').count(), 3);
+    }
+}
@@ -0,0 +1,209 @@
+//! `seed import --zcashd-dump`: the seed from a zcashd `z_exportwallet` or `dumpwallet`
+//! file, so a node operator can move to a Juno seed file without copying hex by hand.
+//!
+//! zcashd 4.7 and later write the wallet's recovery phrase in the header, and the
+//! older seed as `Legacy HDSeed` if the wallet had one:
+//!
+//! ```text
+//! # Emergency Recovery Information:
+//! # - recovery_phrase="abandon abandon ... art"
+//! # - language=English
+//! # - fingerprint=...
+//! # Legacy HDSeed=<hex> fingerprint=<hex>
+//! ```
+//!
+//! Earlier versions write only `# HDSeed=<hex> fingerprint=<hex>`. The keys listed
+//! below the header were derived from one of these seeds and are not read, except to
+//! tell which network the dump came from.
+
+use std::fs;
+use std::path::Path;
+
+use juno_keys::{Network, Seed, WalletCompat};
+use zeroize::Zeroizing;
+
+use crate::AppError;
+
+const FINGERPRINT_PERSONALIZATION: &[u8; 16] = b"Zcash_HD_Seed_FP";
+
+/// The seeds in a dump's header.
+pub(crate) struct Dump {
+    /// Seed from the recovery phrase (zcashd 4.7 and later).
+    pub(crate) mnemonic: Option<Seed>,
+    /// The pre-4.7 `HDSeed`, which older Sapling addresses came from.
+    pub(crate) legacy: Option<Seed>,
+    /// The network of the keys listed, if any are.
+    pub(crate) network: Option<Network>,
+}
+
+/// The seed to import from the dump at `path`: its recovery phrase's, or with
+/// `legacy` its `HDSeed`. Fails if the dump's keys are for another network than `net`.
+pub(crate) fn read(path: &Path, net: Network, legacy: bool) -> Result<Seed, AppError> {
+    let text = Zeroizing::new(
+        fs::read_to_string(path).map_err(|e| AppError::Io(format!("read zcashd dump: {e}")))?,
+    );
+    let dump = parse(&text)?;
+    if let Some(dumped) = dump.network.filter(|&dumped| dumped != net) {
+        return Err(AppError::InvalidRequest(format!(
+            "the zcashd dump is from a {} wallet; pass --network {}",
+            dumped.as_str(),
+            dumped.as_str()
+        )));
+    }
+    match (dump.mnemonic, dump.legacy) {
+        (_, Some(seed)) if legacy => Ok(seed),
+        (_, None) if legacy => Err(AppError::InvalidRequest(
+            "the zcashd dump has no legacy HDSeed; drop --legacy-hdseed".into(),
+        )),
+        (Some(seed), _) | (None, Some(seed)) => Ok(seed),
+        (None, None) => Err(AppError::InvalidRequest(
+            "no recovery_phrase or HDSeed in the zcashd dump; export it with z_exportwallet".into(),
+        )),
+    }
+}
+
+pub(crate) fn parse(text: &str) -> Result<Dump, AppError> {
+    let mut dump = Dump {
+        mnemonic: None,
+        legacy: None,
+        network: None,
+    };
+    let mut phrase_fingerprint = None;
+    for line in text.lines() {
+        let Some(header) = line.strip_prefix('#') else {
+            if dump.network.is_none() {
+                dump.network = key_network(line);
+            }
+            continue;
+        };
+        let header = header.trim();
+        if let Some(item) = header.strip_prefix("- ") {
+            if let Some(phrase) = item.strip_prefix("recovery_phrase=") {
+                let phrase = Zeroizing::new(phrase.trim_matches('"').to_string());
+                dump.mnemonic = Some(
+                    WalletCompat::Zcashd
+                        .seed(&phrase, "")
+                        .map_err(|_| invalid("its recovery_phrase isn't a BIP-39 phrase"))?,
+                );
+            } else if let Some(language) = item.strip_prefix("language=") {
+                if !language.eq_ignore_ascii_case("english") {
+                    return Err(invalid(&format!(
+                        "only English recovery phrases are supported, not {language}"
+                    )));
+                }
+            } else if let Some(fingerprint) = item.strip_prefix("fingerprint=") {
+                phrase_fingerprint = Some(fingerprint.trim());
+            }
+        } else if let Some(rest) = header
+            .strip_prefix("Legacy HDSeed=")
+            .or_else(|| header.strip_prefix("HDSeed="))
+        {
+            let (hex, fingerprint) = match rest.split_once(" fingerprint=") {
+                Some((hex, fingerprint)) => (hex, Some(fingerprint.trim())),
+                None => (rest, None),
+            };
+            let seed = Seed::from_hex(hex).map_err(|_| invalid("its HDSeed isn't hex"))?;
+            if fingerprint.is_some_and(|fp| !fingerprint_matches(&seed, fp)) {
+                return Err(invalid("its HDSeed doesn't match its fingerprint"));
+            }
+            dump.legacy = Some(seed);
+        }
+    }
+    if let (Some(seed), Some(fp)) = (&dump.mnemonic, phrase_fingerprint) {
+        if !fingerprint_matches(seed, fp) {
+            return Err(invalid("its recovery_phrase doesn't match its fingerprint"));
+        }
+    }
+    Ok(dump)
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::InvalidRequest(format!("zcashd dump: {message}"))
+}
+
+// The ZIP-32 seed fingerprint, which zcashd prints as `zip32seedfp1...` or as a
+// uint256 in hex (bytes reversed); plain hex is taken too.
+fn fingerprint_matches(seed: &Seed, printed: &str) -> bool {
+    let bytes = seed.as_ref();
+    let mut input = Vec::with_capacity(1 + bytes.len());
+    input.push(bytes.len() as u8);
+    input.extend_from_slice(bytes);
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(FINGERPRINT_PERSONALIZATION)
+        .hash(&input);
+    let mut reversed = hash.as_bytes().to_vec();
+    reversed.reverse();
+    let printed = printed.to_ascii_lowercase();
+    printed == hex::encode(hash.as_bytes())
+        || printed == hex::encode(reversed)
+        || juno_keys::seed_fingerprint(seed).is_ok_and(|fp| fp == printed)
+}
+
+// The network of a key line, from its Sapling spending key or address.
+fn key_network(line: &str) -> Option<Network> {
+    line.split(|c: char| c.is_whitespace() || c == '=')
+        .find_map(|word| match word {
+            _ if word.starts_with("secret-extended-key-main1") || word.starts_with("zs1") => {
+                Some(Network::Mainnet)
+            }
+            _ if word.starts_with("secret-extended-key-test1")
+                || word.starts_with("ztestsapling1") =>
+            {
+                Some(Network::Testnet)
+            }
+            _ if word.starts_with("secret-extended-key-regtest1")
+                || word.starts_with("zregtestsapling1") =>
+            {
+                Some(Network::Regtest)
+            }
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                          abandon abandon abandon about";
+
+    #[test]
+    fn dumps_yield_their_seeds() {
+        let legacy = "07".repeat(32);
+        let legacy_seed = Seed::from_hex(&legacy).unwrap();
+        let fp = juno_keys::seed_fingerprint(&legacy_seed).unwrap();
+        let text = format!(
+            "# Wallet dump created by Zcash v5.4.0\n\
+             # Emergency Recovery Information:\n\
+             # - recovery_phrase=\"{PHRASE}\"\n\
+             # - language=English\n\
+             # Legacy HDSeed={legacy} fingerprint={fp}\n\
+             \n\
+             secret-extended-key-test1qq 2023-01-01T00:00:00Z # zaddr=ztestsapling1qq\n"
+        );
+        let dump = parse(&text).unwrap();
+        assert_eq!(
+            dump.mnemonic.unwrap(),
+            WalletCompat::Zcashd.seed(PHRASE, "").unwrap()
+        );
+        assert_eq!(dump.legacy.unwrap(), legacy_seed);
+        assert_eq!(dump.network, Some(Network::Testnet));
+
+        // Pre-4.7 dumps print the fingerprint as a byte-reversed uint256.
+        let bytes = legacy_seed.as_ref();
+        let hash = blake2b_simd::Params::new()
+            .hash_length(32)
+            .personal(FINGERPRINT_PERSONALIZATION)
+            .hash(&[&[bytes.len() as u8][..], bytes].concat());
+        let mut reversed = hash.as_bytes().to_vec();
+        reversed.reverse();
+        let old = format!("# HDSeed={legacy} fingerprint={}\n", hex::encode(&reversed));
+        assert!(parse(&old).unwrap().legacy.is_some());
+
+        let damaged = old.replacen("0707", "0708", 1);
+        assert!(parse(&damaged).is_err());
+        assert!(parse("# - recovery_phrase=\"abandon about\"\n").is_err());
+        assert!(parse("# - language=Japanese\n").is_err());
+    }
+}
@@ -0,0 +1,25 @@
+// Regenerates include/juno_keys.h, which is checked in so C and C++ builds don't need
+// cbindgen.
+fn main() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("set by cargo");
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=../src/error_code.rs");
+    let mut config =
+        cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).expect("cbindgen.toml");
+    // cbindgen would also export the library's constants, so the ABI version is copied
+    // into the header by hand.
+    let source = std::fs::read_to_string(format!("{crate_dir}/src/lib.rs")).expect("src/lib.rs");
+    let version = source
+        .lines()
+        .find_map(|line| line.strip_prefix("pub const JUNO_KEYS_ABI_VERSION: u32 = "))
+        .and_then(|value| value.strip_suffix(';'))
+        .expect("JUNO_KEYS_ABI_VERSION in src/lib.rs");
+    config.after_includes = Some(format!(
+        "\n/* The ABI version this header describes, as major << 16 | minor. */\n\
+         #define JUNO_KEYS_ABI_VERSION ({version})"
+    ));
+    cbindgen::generate_with_config(&crate_dir, config)
+        .expect("generate header")
+        .write_to_file(format!("{crate_dir}/include/juno_keys.h"));
+}
@@ -0,0 +1,235 @@
+/* Generated by cbindgen from the juno-keys-ffi crate; do not edit. */
+
+#ifndef JUNO_KEYS_H
+#define JUNO_KEYS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/* The ABI version this header describes, as major << 16 | minor. */
+#define JUNO_KEYS_ABI_VERSION (1 << 16 | 10)
+
+/**
+ * Stable numeric codes for every failure reported by this crate, the CLI (as its
+ * exit status) and the C API (as `JunoKeysStatus`).
+ *
+ * Values are part of the public contract: new codes are only ever appended and
+ * existing ones are never renumbered. `as_str` gives the string codes used in JSON
+ * errors.
+ */
+typedef enum JunoKeysStatus {
+  /**
+   * Success; never produced by an error.
+   */
+  JUNO_KEYS_STATUS_OK = 0,
+  /**
+   * A bug or an unexpected failure.
+   */
+  JUNO_KEYS_STATUS_INTERNAL = 1,
+  /**
+   * Malformed arguments; matches clap's exit status for usage errors.
+   */
+  JUNO_KEYS_STATUS_INVALID_REQUEST = 2,
+  JUNO_KEYS_STATUS_SEED_INVALID = 3,
+  JUNO_KEYS_STATUS_SPENDING_KEY_INVALID = 4,
+  JUNO_KEYS_STATUS_LENGTH_OUT_OF_RANGE = 5,
+  JUNO_KEYS_STATUS_UA_HRP_INVALID = 6,
+  JUNO_KEYS_STATUS_COIN_TYPE_INVALID = 7,
+  JUNO_KEYS_STATUS_COIN_TYPE_REGISTERED = 8,
+  JUNO_KEYS_STATUS_ACCOUNT_INVALID = 9,
+  JUNO_KEYS_STATUS_PATH_INVALID = 10,
+  JUNO_KEYS_STATUS_PATH_NOT_HARDENED = 11,
+  JUNO_KEYS_STATUS_NETWORK_UNKNOWN = 12,
+  JUNO_KEYS_STATUS_ENCODING_INVALID = 13,
+  JUNO_KEYS_STATUS_IO_ERROR = 14,
+  JUNO_KEYS_STATUS_CONFIG_INVALID = 15,
+  JUNO_KEYS_STATUS_MAINNET_INTERLOCK = 16,
+  JUNO_KEYS_STATUS_OFFLINE_ASSERTION_FAILED = 17,
+  JUNO_KEYS_STATUS_SELFTEST_FAILED = 18,
+  /**
+   * A required pointer argument to the C API was NULL.
+   */
+  JUNO_KEYS_STATUS_NULL_POINTER = 19,
+  /**
+   * A string argument to the C API was not valid UTF-8.
+   */
+  JUNO_KEYS_STATUS_UTF8_INVALID = 20,
+  /**
+   * The library panicked behind the C API; this is a bug.
+   */
+  JUNO_KEYS_STATUS_PANIC = 21,
+  /**
+   * A `serve --socket` peer's uid is not allowed.
+   */
+  JUNO_KEYS_STATUS_PEER_NOT_ALLOWED = 22,
+  /**
+   * A `serve --http` or `--grpc` request lacks the configured bearer token.
+   */
+  JUNO_KEYS_STATUS_UNAUTHENTICATED = 23,
+  /**
+   * A daemon client exceeded `serve --rate-limit`.
+   */
+  JUNO_KEYS_STATUS_RATE_LIMITED = 24,
+  /**
+   * A message signature doesn't verify against the given key.
+   */
+  JUNO_KEYS_STATUS_SIGNATURE_INVALID = 25,
+  /**
+   * A FROST key generation package is malformed or fails verification.
+   */
+  JUNO_KEYS_STATUS_FROST_INVALID = 26,
+  /**
+   * A ZIP-321 payment request is malformed or asks for something invalid.
+   */
+  JUNO_KEYS_STATUS_PAYMENT_REQUEST_INVALID = 27,
+  /**
+   * A diversifier index is malformed or not below 2^88.
+   */
+  JUNO_KEYS_STATUS_DIVERSIFIER_INDEX_INVALID = 28,
+  /**
+   * A wallet descriptor is malformed or its checksum doesn't match.
+   */
+  JUNO_KEYS_STATUS_DESCRIPTOR_INVALID = 29,
+  /**
+   * `--json-version` asked for an output version this build doesn't write.
+   */
+  JUNO_KEYS_STATUS_JSON_VERSION_UNSUPPORTED = 30,
+  /**
+   * An encrypted seed is malformed or its passphrase is wrong.
+   */
+  JUNO_KEYS_STATUS_ENCRYPTED_SEED_INVALID = 31,
+  /**
+   * Seed shares are malformed, too few, or not from one split.
+   */
+  JUNO_KEYS_STATUS_SEED_SHARE_INVALID = 32,
+  /**
+   * Hardening (`--harden`) is unsupported or was refused: core dumps can't be
+   * disabled or secret memory can't be locked.
+   */
+  JUNO_KEYS_STATUS_HARDENING_UNAVAILABLE = 33,
+} JunoKeysStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * The ABI version of the loaded library. Consumers that load it dynamically should
+ * refuse to run unless `juno_keys_abi_version() >> 16 == JUNO_KEYS_ABI_VERSION >> 16`
+ * and `(juno_keys_abi_version() & 0xffff) >= (JUNO_KEYS_ABI_VERSION & 0xffff)`.
+ */
+uint32_t juno_keys_abi_version(void);
+
+/**
+ * The stable string code for `status`, e.g. `"seed_invalid"`, or NULL if `status`
+ * is not a known code. The string is static and must not be freed.
+ */
+const char *juno_keys_status_code(int status);
+
+/**
+ * A one-line description of `status`, or `"unknown error"` if it is not a known
+ * code. The string is static and must not be freed.
+ */
+const char *juno_keys_strerror(int status);
+
+/**
+ * Derives the UFVK for `account` of `seed` on `network` (`"mainnet"`, `"testnet"` or
+ * `"regtest"`).
+ *
+ * # Safety
+ *
+ * `seed` must point to `seed_len` readable bytes, `network` must be a NUL-terminated
+ * string and `out` must be writable.
+ */
+enum JunoKeysStatus juno_keys_ufvk_from_seed(const uint8_t *seed,
+                                             size_t seed_len,
+                                             const char *network,
+                                             uint32_t account,
+                                             char **out);
+
+/**
+ * Derives the unified address at `diversifier_index` for `account` of `seed`.
+ *
+ * # Safety
+ *
+ * As for [`juno_keys_ufvk_from_seed`].
+ */
+enum JunoKeysStatus juno_keys_address_from_seed(const uint8_t *seed,
+                                                size_t seed_len,
+                                                const char *network,
+                                                uint32_t account,
+                                                uint32_t diversifier_index,
+                                                char **out);
+
+/**
+ * Derives the unified address at a 64-bit `diversifier_index` for `account` of
+ * `seed`; [`juno_keys_address_from_seed`] takes the index as 32 bits.
+ *
+ * # Safety
+ *
+ * As for [`juno_keys_ufvk_from_seed`].
+ */
+enum JunoKeysStatus juno_keys_address_derive(const uint8_t *seed,
+                                             size_t seed_len,
+                                             const char *network,
+                                             uint32_t account,
+                                             uint64_t diversifier_index,
+                                             char **out);
+
+/**
+ * Derives the unified address at `diversifier_index` from a UFVK.
+ *
+ * # Safety
+ *
+ * `ufvk` must be a NUL-terminated string and `out` must be writable.
+ */
+enum JunoKeysStatus juno_keys_address_from_ufvk(const char *ufvk,
+                                                uint32_t diversifier_index,
+                                                char **out);
+
+/**
+ * Derives the UIVK for a UFVK.
+ *
+ * # Safety
+ *
+ * `ufvk` must be a NUL-terminated string and `out` must be writable.
+ */
+enum JunoKeysStatus juno_keys_uivk_from_ufvk(const char *ufvk, char **out);
+
+/**
+ * Checks that `ufvk` is a well-formed UFVK; returns `JUNO_KEYS_STATUS_OK` if so.
+ *
+ * # Safety
+ *
+ * `ufvk` must be a NUL-terminated string.
+ */
+enum JunoKeysStatus juno_keys_validate_ufvk(const char *ufvk);
+
+/**
+ * Checks that `address` is a well-formed unified address; returns
+ * `JUNO_KEYS_STATUS_OK` if so.
+ *
+ * # Safety
+ *
+ * `address` must be a NUL-terminated string.
+ */
+enum JunoKeysStatus juno_keys_validate_address(const char *address);
+
+/**
+ * Releases a string returned through an `out` parameter, wiping it first. NULL is
+ * ignored.
+ *
+ * # Safety
+ *
+ * `s` must be NULL or a string returned by this library that has not been freed.
+ */
+void juno_keys_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* JUNO_KEYS_H */
@@ -0,0 +1,390 @@
+//! C ABI for juno-keys; `include/juno_keys.h` is generated from this file.
+//!
+//! Every fallible function returns an [`ErrorCode`], exported to C as
+//! `JunoKeysStatus`; the values are shared with the CLI's exit statuses. Strings are NUL-terminated UTF-8.
+//! Strings written to `out` parameters are owned by the caller and must be released
+//! with [`juno_keys_string_free`]; on error `*out` is left untouched.
+//!
+//! The ABI is versioned as `major << 16 | minor` ([`JUNO_KEYS_ABI_VERSION`]). Adding
+//! functions or status codes bumps the minor version; removing or changing a
+//! function, or renumbering anything, bumps the major version and resets the minor.
+//! A library is compatible with a header if the majors match and the library's minor
+//! is at least the header's.
+
+#![deny(warnings)]
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, UnwindSafe};
+use std::sync::OnceLock;
+use std::{ptr, slice};
+
+use juno_keys::{DerivationRequest, ErrorCode, Network, Ufvk, UnifiedAddress};
+use zeroize::Zeroize;
+
+/// The ABI version this header describes; compare with [`juno_keys_abi_version`].
+pub const JUNO_KEYS_ABI_VERSION: u32 = 1 << 16 | 10;
+
+// NUL-terminated copies of each code's name and description, indexed by value.
+fn c_strings() -> &'static [(CString, CString)] {
+    static STRINGS: OnceLock<Vec<(CString, CString)>> = OnceLock::new();
+    STRINGS.get_or_init(|| {
+        ErrorCode::ALL
+            .iter()
+            .map(|code| {
+                let name = CString::new(code.as_str()).expect("no NUL");
+                let description = CString::new(code.description()).expect("no NUL");
+                (name, description)
+            })
+            .collect()
+    })
+}
+
+/// The ABI version of the loaded library. Consumers that load it dynamically should
+/// refuse to run unless `juno_keys_abi_version() >> 16 == JUNO_KEYS_ABI_VERSION >> 16`
+/// and `(juno_keys_abi_version() & 0xffff) >= (JUNO_KEYS_ABI_VERSION & 0xffff)`.
+#[no_mangle]
+pub extern "C" fn juno_keys_abi_version() -> u32 {
+    JUNO_KEYS_ABI_VERSION
+}
+
+/// The stable string code for `status`, e.g. `"seed_invalid"`, or NULL if `status`
+/// is not a known code. The string is static and must not be freed.
+#[no_mangle]
+pub extern "C" fn juno_keys_status_code(status: c_int) -> *const c_char {
+    match usize::try_from(status)
+        .ok()
+        .and_then(|i| c_strings().get(i))
+    {
+        Some((name, _)) => name.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// A one-line description of `status`, or `"unknown error"` if it is not a known
+/// code. The string is static and must not be freed.
+#[no_mangle]
+pub extern "C" fn juno_keys_strerror(status: c_int) -> *const c_char {
+    match usize::try_from(status)
+        .ok()
+        .and_then(|i| c_strings().get(i))
+    {
+        Some((_, description)) => description.as_ptr(),
+        None => c"unknown error".as_ptr(),
+    }
+}
+
+/// Derives the UFVK for `account` of `seed` on `network` (`"mainnet"`, `"testnet"` or
+/// `"regtest"`).
+///
+/// # Safety
+///
+/// `seed` must point to `seed_len` readable bytes, `network` must be a NUL-terminated
+/// string and `out` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn juno_keys_ufvk_from_seed(
+    seed: *const u8,
+    seed_len: usize,
+    network: *const c_char,
+    account: u32,
+    out: *mut *mut c_char,
+) -> ErrorCode {
+    ffi_call(out, || {
+        let keys = DerivationRequest::new(bytes_arg(seed, seed_len)?)
+            .network(str_arg(network)?.parse::<Network>()?)
+            .account(account)
+            .build()?;
+        Ok(keys.ufvk.to_string())
+    })
+}
+
+/// Derives the unified address at `diversifier_index` for `account` of `seed`.
+///
+/// # Safety
+///
+/// As for [`juno_keys_ufvk_from_seed`].
+#[no_mangle]
+pub unsafe extern "C" fn juno_keys_address_from_seed(
+    seed: *const u8,
+    seed_len: usize,
+    network: *const c_char,
+    account: u32,
+    diversifier_index: u32,
+    out: *mut *mut c_char,
+) -> ErrorCode {
+    ffi_call(out, || {
+        let keys = DerivationRequest::new(bytes_arg(seed, seed_len)?)
+            .network(str_arg(network)?.parse::<Network>()?)
+            .account(account)
+            .diversifier_index(diversifier_index)
+            .build()?;
+        Ok(keys.address.to_string())
+    })
+}
+
+/// Derives the unified address at a 64-bit `diversifier_index` for `account` of
+/// `seed`; [`juno_keys_address_from_seed`] takes the index as 32 bits.
+///
+/// # Safety
+///
+/// As for [`juno_keys_ufvk_from_seed`].
+#[no_mangle]
+pub unsafe extern "C" fn juno_keys_address_derive(
+    seed: *const u8,
+    seed_len: usize,
+    network: *const c_char,
+    account: u32,
+    diversifier_index: u64,
+    out: *mut *mut c_char,
+) -> ErrorCode {
+    ffi_call(out, || {
+        let keys = DerivationRequest::new(bytes_arg(seed, seed_len)?)
+            .network(str_arg(network)?.parse::<Network>()?)
+            .account(account)
+            .diversifier_index(diversifier_index)
+            .build()?;
+        Ok(keys.address.to_string())
+    })
+}
+
+/// Derives the unified address at `diversifier_index` from a UFVK.
+///
+/// # Safety
+///
+/// `ufvk` must be a NUL-terminated string and `out` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn juno_keys_address_from_ufvk(
+    ufvk: *const c_char,
+    diversifier_index: u32,
+    out: *mut *mut c_char,
+) -> ErrorCode {
+    ffi_call(out, || {
+        juno_keys::ua_from_ufvk(str_arg(ufvk)?, diversifier_index).map_err(Into::into)
+    })
+}
+
+/// Derives the UIVK for a UFVK.
+///
+/// # Safety
+///
+/// `ufvk` must be a NUL-terminated string and `out` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn juno_keys_uivk_from_ufvk(
+    ufvk: *const c_char,
+    out: *mut *mut c_char,
+) -> ErrorCode {
+    ffi_call(out, || {
+        juno_keys::uivk_from_ufvk(str_arg(ufvk)?).map_err(Into::into)
+    })
+}
+
+/// Checks that `ufvk` is a well-formed UFVK; returns `JUNO_KEYS_STATUS_OK` if so.
+///
+/// # Safety
+///
+/// `ufvk` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn juno_keys_validate_ufvk(ufvk: *const c_char) -> ErrorCode {
+    status_of(|| Ufvk::parse(str_arg(ufvk)?).map(drop).map_err(Into::into))
+}
+
+/// Checks that `address` is a well-formed unified address; returns
+/// `JUNO_KEYS_STATUS_OK` if so.
+///
+/// # Safety
+///
+/// `address` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn juno_keys_validate_address(address: *const c_char) -> ErrorCode {
+    status_of(|| {
+        UnifiedAddress::parse(str_arg(address)?)
+            .map(drop)
+            .map_err(Into::into)
+    })
+}
+
+/// Releases a string returned through an `out` parameter, wiping it first. NULL is
+/// ignored.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn juno_keys_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        CString::from_raw(s).into_bytes().zeroize();
+    }
+}
+
+// Runs `f`, writing its string to `out` on success.
+unsafe fn ffi_call(
+    out: *mut *mut c_char,
+    f: impl FnOnce() -> Result<String, ErrorCode> + UnwindSafe,
+) -> ErrorCode {
+    if out.is_null() {
+        return ErrorCode::NullPointer;
+    }
+    let result = catch_unwind(f).unwrap_or(Err(ErrorCode::Panic));
+    match result.and_then(|s| CString::new(s).map_err(|_| ErrorCode::Internal)) {
+        Ok(s) => {
+            *out = s.into_raw();
+            ErrorCode::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+fn status_of(f: impl FnOnce() -> Result<(), ErrorCode> + UnwindSafe) -> ErrorCode {
+    match catch_unwind(f) {
+        Ok(Ok(())) => ErrorCode::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => ErrorCode::Panic,
+    }
+}
+
+unsafe fn str_arg<'a>(s: *const c_char) -> Result<&'a str, ErrorCode> {
+    if s.is_null() {
+        return Err(ErrorCode::NullPointer);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| ErrorCode::Utf8Invalid)
+}
+
+unsafe fn bytes_arg<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], ErrorCode> {
+    if ptr.is_null() {
+        return Err(ErrorCode::NullPointer);
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(s: *mut c_char) -> String {
+        let owned = unsafe { CStr::from_ptr(s) }
+            .to_str()
+            .expect("utf8")
+            .to_owned();
+        unsafe { juno_keys_string_free(s) };
+        owned
+    }
+
+    #[test]
+    fn derives_and_validates_through_the_c_abi() {
+        let seed = [7u8; 32];
+        let mut ufvk = ptr::null_mut();
+        let status = unsafe {
+            juno_keys_ufvk_from_seed(seed.as_ptr(), seed.len(), c"mainnet".as_ptr(), 0, &mut ufvk)
+        };
+        assert_eq!(status, ErrorCode::Ok);
+        let ufvk = CString::new(take(ufvk)).expect("nul-free");
+        assert_eq!(
+            ufvk.to_str().expect("utf8"),
+            juno_keys::ufvk_from_seed(&seed, &Network::Mainnet, 0).expect("ufvk")
+        );
+        assert_eq!(
+            unsafe { juno_keys_validate_ufvk(ufvk.as_ptr()) },
+            ErrorCode::Ok
+        );
+
+        let (mut from_seed, mut from_ufvk) = (ptr::null_mut(), ptr::null_mut());
+        unsafe {
+            assert_eq!(
+                juno_keys_address_from_seed(
+                    seed.as_ptr(),
+                    seed.len(),
+                    c"mainnet".as_ptr(),
+                    0,
+                    3,
+                    &mut from_seed
+                ),
+                ErrorCode::Ok
+            );
+            assert_eq!(
+                juno_keys_address_from_ufvk(ufvk.as_ptr(), 3, &mut from_ufvk),
+                ErrorCode::Ok
+            );
+        }
+        let address = take(from_seed);
+        assert_eq!(address, take(from_ufvk));
+
+        let mut derived = ptr::null_mut();
+        let far = 1u64 << 40;
+        unsafe {
+            assert_eq!(
+                juno_keys_address_derive(
+                    seed.as_ptr(),
+                    seed.len(),
+                    c"mainnet".as_ptr(),
+                    0,
+                    far,
+                    &mut derived
+                ),
+                ErrorCode::Ok
+            );
+        }
+        let parsed = Ufvk::parse(ufvk.to_str().expect("utf8")).expect("parse");
+        assert_eq!(
+            take(derived),
+            parsed.address_at(far).expect("address").to_string()
+        );
+        let address = CString::new(address).expect("nul-free");
+        assert_eq!(
+            unsafe { juno_keys_validate_address(address.as_ptr()) },
+            ErrorCode::Ok
+        );
+    }
+
+    #[test]
+    fn reports_errors_as_status_codes() {
+        let mut out = ptr::null_mut();
+        let status = unsafe {
+            juno_keys_ufvk_from_seed([0u8; 8].as_ptr(), 8, c"mainnet".as_ptr(), 0, &mut out)
+        };
+        assert_eq!(status, ErrorCode::SeedInvalid);
+        assert!(out.is_null());
+
+        let status = unsafe {
+            juno_keys_ufvk_from_seed([0u8; 32].as_ptr(), 32, c"moonnet".as_ptr(), 0, &mut out)
+        };
+        assert_eq!(status, ErrorCode::NetworkUnknown);
+        assert_eq!(
+            unsafe { juno_keys_validate_ufvk(ptr::null()) },
+            ErrorCode::NullPointer
+        );
+        assert_eq!(
+            unsafe { juno_keys_validate_address(c"j1notanaddress".as_ptr()) },
+            ErrorCode::EncodingInvalid
+        );
+    }
+
+    #[test]
+    fn turns_panics_into_status_codes() {
+        let mut out = ptr::null_mut();
+        let status = unsafe { ffi_call(&mut out, || panic!("malformed input")) };
+        assert_eq!(status, ErrorCode::Panic);
+        assert!(out.is_null());
+        assert_eq!(status_of(|| panic!("malformed input")), ErrorCode::Panic);
+    }
+
+    #[test]
+    fn reports_the_header_abi_version() {
+        assert_eq!(juno_keys_abi_version(), JUNO_KEYS_ABI_VERSION);
+        let header = include_str!("../include/juno_keys.h");
+        assert!(header.contains("#define JUNO_KEYS_ABI_VERSION (1 << 16 | 10)"));
+    }
+
+    #[test]
+    fn looks_up_codes_and_descriptions() {
+        for code in ErrorCode::ALL {
+            let name = unsafe { CStr::from_ptr(juno_keys_status_code(code as c_int)) };
+            assert_eq!(name.to_str(), Ok(code.as_str()));
+            let description = unsafe { CStr::from_ptr(juno_keys_strerror(code as c_int)) };
+            assert_eq!(description.to_str(), Ok(code.description()));
+        }
+        assert!(juno_keys_status_code(-1).is_null());
+        let unknown = unsafe { CStr::from_ptr(juno_keys_strerror(99)) };
+        assert_eq!(unknown, c"unknown error");
+    }
+}
@@ -0,0 +1,79 @@
+//! N-API addon for Node; build it with `npm run build` in `node/`.
+//!
+//! Errors are thrown as JS `Error`s whose `code` is the library's string error code,
+//! e.g. `"seed_invalid"`.
+
+#![deny(warnings)]
+
+use juno_keys::{DerivationRequest, DerivedKeys, KeysError, Network, Ufvk, UnifiedAddress};
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+
+type Result<T> = napi::Result<T, String>;
+
+fn js_error(err: KeysError) -> napi::Error<String> {
+    napi::Error::new(err.code().to_string(), err.to_string())
+}
+
+fn derive(
+    seed: &[u8],
+    network: &str,
+    account: u32,
+    diversifier_index: u32,
+) -> core::result::Result<DerivedKeys, KeysError> {
+    DerivationRequest::new(seed)
+        .network(network.parse::<Network>()?)
+        .account(account)
+        .diversifier_index(diversifier_index)
+        .build()
+}
+
+/// Derives the UFVK for `account` of `seed` on `network` (`"mainnet"`, `"testnet"` or
+/// `"regtest"`).
+#[napi(catch_unwind)]
+pub fn ufvk_from_seed(seed: Buffer, network: String, account: u32) -> Result<String> {
+    derive(&seed, &network, account, 0)
+        .map(|keys| keys.ufvk.to_string())
+        .map_err(js_error)
+}
+
+/// Derives the unified address at `diversifierIndex` for `account` of `seed`.
+#[napi(catch_unwind)]
+pub fn address_from_seed(
+    seed: Buffer,
+    network: String,
+    account: u32,
+    diversifier_index: u32,
+) -> Result<String> {
+    derive(&seed, &network, account, diversifier_index)
+        .map(|keys| keys.address.to_string())
+        .map_err(js_error)
+}
+
+/// Derives the unified address at `diversifierIndex` from a UFVK.
+#[napi(catch_unwind)]
+pub fn address_from_ufvk(ufvk: String, diversifier_index: u32) -> Result<String> {
+    juno_keys::ua_from_ufvk(&ufvk, diversifier_index).map_err(js_error)
+}
+
+#[napi(catch_unwind)]
+pub fn uivk_from_ufvk(ufvk: String) -> Result<String> {
+    juno_keys::uivk_from_ufvk(&ufvk).map_err(js_error)
+}
+
+#[napi(catch_unwind)]
+pub fn ufvk_fingerprint(ufvk: String) -> Result<String> {
+    juno_keys::ufvk_fingerprint(&ufvk).map_err(js_error)
+}
+
+/// Throws unless `ufvk` is a well-formed UFVK.
+#[napi(catch_unwind)]
+pub fn validate_ufvk(ufvk: String) -> Result<()> {
+    Ufvk::parse(&ufvk).map(drop).map_err(js_error)
+}
+
+/// Throws unless `address` is a well-formed unified address.
+#[napi(catch_unwind)]
+pub fn validate_address(address: String) -> Result<()> {
+    UnifiedAddress::parse(&address).map(drop).map_err(js_error)
+}
@@ -0,0 +1,21 @@
+import assert from 'node:assert/strict';
+import { createRequire } from 'node:module';
+import test from 'node:test';
+
+const keys = createRequire(import.meta.url)('./index.js');
+const seed = Buffer.alloc(32, 7);
+
+test('derives the same address from a seed and its UFVK', () => {
+  const ufvk = keys.ufvkFromSeed(seed, 'mainnet', 0);
+  assert.ok(ufvk.startsWith('jview1'));
+  keys.validateUfvk(ufvk);
+  const address = keys.addressFromSeed(seed, 'mainnet', 0, 3);
+  assert.equal(keys.addressFromUfvk(ufvk, 3), address);
+  keys.validateAddress(address);
+});
+
+test('throws errors carrying the library error code', () => {
+  assert.throws(() => keys.ufvkFromSeed(Buffer.alloc(8), 'mainnet', 0), { code: 'seed_invalid' });
+  assert.throws(() => keys.ufvkFromSeed(seed, 'moonnet', 0), { code: 'network_unknown' });
+  assert.throws(() => keys.validateAddress('j1notanaddress'), { code: 'encoding_invalid' });
+});
@@ -0,0 +1,191 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::network::{self, Network};
+use crate::zip316::{self, TYPECODE_ORCHARD, TYPECODE_P2PKH, TYPECODE_P2SH, TYPECODE_SAPLING};
+use crate::{ufvk_hrp_from_ua_hrp, KeysError};
+
+const TRANSPARENT_RECEIVER_LEN: usize = 20;
+const SHIELDED_RECEIVER_LEN: usize = 43;
+
+/// A parsed and validated unified address.
+///
+/// Known receivers are length-checked (and Orchard receivers decoded) on construction;
+/// unknown typecodes are kept so the address re-encodes unchanged.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct UnifiedAddress {
+    encoded: String,
+    hrp: String,
+    items: Vec<(u64, Vec<u8>)>,
+}
+
+impl UnifiedAddress {
+    /// Length of a P2PKH or P2SH receiver.
+    pub const TRANSPARENT_RECEIVER_LEN: usize = TRANSPARENT_RECEIVER_LEN;
+    /// Length of a Sapling or Orchard receiver.
+    pub const SHIELDED_RECEIVER_LEN: usize = SHIELDED_RECEIVER_LEN;
+
+    /// Builds an address under `hrp` from `(typecode, receiver)` items in any order.
+    pub fn from_receivers(hrp: &str, items: &[(u64, &[u8])]) -> Result<Self, KeysError> {
+        check_ua_hrp(hrp)?;
+        let encoded = zip316::encode_unified_container(hrp, items)?;
+        Self::parse(&encoded)
+    }
+
+    pub fn parse(s: &str) -> Result<Self, KeysError> {
+        let encoded = s.trim().to_ascii_lowercase();
+        let hrp = network::encoded_hrp(&encoded)?;
+        check_ua_hrp(&hrp)?;
+        let items = zip316::decode_unified_container(&hrp, &encoded)?;
+
+        for (typecode, value) in &items {
+            let valid = match *typecode {
+                TYPECODE_P2PKH | TYPECODE_P2SH => value.len() == TRANSPARENT_RECEIVER_LEN,
+                TYPECODE_SAPLING => value.len() == SHIELDED_RECEIVER_LEN,
+                TYPECODE_ORCHARD => <&[u8; SHIELDED_RECEIVER_LEN]>::try_from(value.as_slice())
+                    .is_ok_and(|raw| {
+                        orchard::Address::from_raw_address_bytes(raw)
+                            .is_some()
+                            .into()
+                    }),
+                _ => true,
+            };
+            if !valid {
+                return Err(KeysError::EncodingInvalid);
+            }
+        }
+
+        Ok(UnifiedAddress {
+            encoded,
+            hrp,
+            items,
+        })
+    }
+
+    /// Rebuilds an address from its raw ZIP-316 encoding (see [`Self::to_bytes`]) under
+    /// `hrp`; raw bytes carry no network, so there is no `TryFrom<&[u8]>`.
+    pub fn from_bytes(hrp: &str, raw: &[u8]) -> Result<Self, KeysError> {
+        check_ua_hrp(hrp)?;
+        Self::parse(&zip316::encode_raw_container(hrp, raw)?)
+    }
+
+    /// The raw ZIP-316 encoding: the concatenated receivers, without HRP or checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let tlvs = self
+            .items
+            .iter()
+            .map(|(typecode, value)| zip316::Tlv {
+                typecode: *typecode,
+                value,
+            })
+            .collect::<Vec<_>>();
+        zip316::encode_raw_items(&tlvs)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.encoded
+    }
+
+    pub fn hrp(&self) -> &str {
+        &self.hrp
+    }
+
+    /// The Juno network, or `None` for Zcash and custom-network addresses.
+    pub fn network(&self) -> Option<Network> {
+        Network::from_hrp(&self.hrp)
+    }
+
+    /// The network for an upstream Zcash (`u`) address.
+    pub fn zcash_network(&self) -> Option<Network> {
+        Network::from_zcash_hrp(&self.hrp)
+    }
+
+    pub fn typecodes(&self) -> Vec<u64> {
+        self.items.iter().map(|(typecode, _)| *typecode).collect()
+    }
+
+    pub fn items(&self) -> &[(u64, Vec<u8>)] {
+        &self.items
+    }
+
+    pub fn orchard(&self) -> Option<orchard::Address> {
+        let raw = <&[u8; SHIELDED_RECEIVER_LEN]>::try_from(self.item(TYPECODE_ORCHARD)?).ok()?;
+        orchard::Address::from_raw_address_bytes(raw).into()
+    }
+
+    pub fn sapling(&self) -> Option<[u8; SHIELDED_RECEIVER_LEN]> {
+        self.item(TYPECODE_SAPLING)?.try_into().ok()
+    }
+
+    pub fn p2pkh(&self) -> Option<[u8; TRANSPARENT_RECEIVER_LEN]> {
+        self.item(TYPECODE_P2PKH)?.try_into().ok()
+    }
+
+    pub fn p2sh(&self) -> Option<[u8; TRANSPARENT_RECEIVER_LEN]> {
+        self.item(TYPECODE_P2SH)?.try_into().ok()
+    }
+
+    pub fn has_typecode(&self, typecode: u64) -> bool {
+        self.item(typecode).is_some()
+    }
+
+    pub fn has_orchard(&self) -> bool {
+        self.has_typecode(TYPECODE_ORCHARD)
+    }
+
+    pub fn has_sapling(&self) -> bool {
+        self.has_typecode(TYPECODE_SAPLING)
+    }
+
+    /// Whether the address has a P2PKH or P2SH receiver.
+    pub fn has_transparent(&self) -> bool {
+        self.has_typecode(TYPECODE_P2PKH) || self.has_typecode(TYPECODE_P2SH)
+    }
+
+    /// Whether every receiver is shielded (Sapling, Orchard or an unknown typecode).
+    pub fn is_shielded_only(&self) -> bool {
+        !self.has_transparent()
+    }
+
+    /// Items with typecodes this crate doesn't interpret.
+    pub fn unknown(&self) -> impl Iterator<Item = (u64, &[u8])> {
+        self.items
+            .iter()
+            .filter(|(typecode, _)| !zip316::is_known_typecode(*typecode))
+            .map(|(typecode, value)| (*typecode, value.as_slice()))
+    }
+
+    fn item(&self, typecode: u64) -> Option<&[u8]> {
+        self.items
+            .iter()
+            .find(|(tc, _)| *tc == typecode)
+            .map(|(_, value)| value.as_slice())
+    }
+}
+
+impl FromStr for UnifiedAddress {
+    type Err = KeysError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UnifiedAddress::parse(s)
+    }
+}
+
+impl fmt::Display for UnifiedAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.encoded)
+    }
+}
+
+// UA HRPs are `j*`/`u*`, excluding the viewing-key prefixes that share those letters.
+fn check_ua_hrp(hrp: &str) -> Result<(), KeysError> {
+    let is_key_hrp = ["jview", "jivk", "uview", "uivk"]
+        .iter()
+        .any(|prefix| hrp.starts_with(prefix));
+    if is_key_hrp {
+        return Err(KeysError::UAHrpInvalid);
+    }
+    ufvk_hrp_from_ua_hrp(hrp).map(|_| ())
+}
@@ -0,0 +1,39 @@
+//! Application keys: ZIP-32 arbitrary key derivation (renamed "ad-hoc" in the current
+//! ZIP) of symmetric keys from the wallet seed.
+//!
+//! The context string separates applications: keys under different contexts, or under
+//! any context versus the wallet's own Orchard keys, are unrelated. Backing up the seed
+//! backs these up too.
+
+use zeroize::Zeroizing;
+use zip32::arbitrary::SecretKey;
+
+use crate::{KeysError, Seed, ZipPath};
+
+/// Longest context string ZIP 32 allows.
+pub const APP_KEY_CONTEXT_MAX_LEN: usize = 252;
+
+/// The 32-byte key at `path` (e.g. `m` or `m/0'`) in the tree `context` names, such as
+/// a key for encrypting wallet metadata under `"juno-backup"`.
+///
+/// `context` should be unique to the application, since anyone with the seed derives
+/// the same key from the same context. It must be 1 to 252 bytes.
+///
+/// ```
+/// # use juno_keys::{app_key, Seed, ZipPath};
+/// let seed = Seed::from_bytes(&[7u8; 32])?;
+/// let key = app_key(&seed, "juno-backup", &ZipPath::parse("m/0'")?)?;
+/// assert_ne!(*key, *app_key(&seed, "juno-notes", &ZipPath::parse("m/0'")?)?);
+/// # Ok::<(), juno_keys::KeysError>(())
+/// ```
+pub fn app_key<S: AsRef<[u8]> + ?Sized>(
+    seed: &S,
+    context: &str,
+    path: &ZipPath,
+) -> Result<Zeroizing<[u8; 32]>, KeysError> {
+    let seed = seed.as_ref();
+    crate::check_len("seed", seed.len(), Seed::MIN_LEN, Seed::MAX_LEN)?;
+    crate::check_len("context", context.len(), 1, APP_KEY_CONTEXT_MAX_LEN)?;
+    let key = SecretKey::from_path(context.as_bytes(), seed, &path.child_indices());
+    Ok(Zeroizing::new(*key.data()))
+}
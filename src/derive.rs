@@ -0,0 +1,101 @@
+use alloc::string::{String, ToString};
+
+use crate::{
+    DiversifierIndex, KeysError, NetworkParams, OrchardKeySet, Ufvk, UnifiedAddress, ZipPath,
+};
+
+/// Options for deriving an account's keys from a seed.
+///
+/// ```
+/// # use juno_keys::{DerivationRequest, Network, Seed};
+/// let seed = Seed::from_bytes(&[7u8; 32])?;
+/// let keys = DerivationRequest::new(&seed)
+///     .network(Network::Mainnet)
+///     .account(3)
+///     .build()?;
+/// assert!(keys.ufvk.to_string().starts_with("jview1"));
+/// # Ok::<(), juno_keys::KeysError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DerivationRequest<'a> {
+    seed: &'a [u8],
+    network: Option<(String, u32)>,
+    account: u32,
+    path: Option<ZipPath>,
+    diversifier_index: DiversifierIndex,
+}
+
+/// Everything derived for one account by [`DerivationRequest::build`].
+#[derive(Clone, Debug)]
+pub struct DerivedKeys {
+    pub path: ZipPath,
+    pub coin_type: u32,
+    pub account: u32,
+    pub diversifier_index: DiversifierIndex,
+    pub ufvk: Ufvk,
+    pub uivk: String,
+    /// The external-scope address at `diversifier_index`.
+    pub address: UnifiedAddress,
+}
+
+impl<'a> DerivationRequest<'a> {
+    /// Starts a request for account 0, diversifier index 0; a network must be set.
+    ///
+    /// `seed` may be a [`Seed`] or raw bytes; its length is checked by [`Self::build`].
+    pub fn new<S: AsRef<[u8]> + ?Sized>(seed: &'a S) -> Self {
+        DerivationRequest {
+            seed: seed.as_ref(),
+            network: None,
+            account: 0,
+            path: None,
+            diversifier_index: DiversifierIndex::new(),
+        }
+    }
+
+    pub fn network<N: NetworkParams>(mut self, network: N) -> Self {
+        self.network = Some((network.ua_hrp().to_string(), network.coin_type()));
+        self
+    }
+
+    pub fn account(mut self, account: u32) -> Self {
+        self.account = account;
+        self
+    }
+
+    /// Derives at an explicit path instead of the network's coin type and
+    /// [`Self::account`]; the network still supplies the HRPs.
+    ///
+    /// The path must start with `m/32'/coin_type'/account'` and may continue with
+    /// further hardened levels.
+    pub fn path(mut self, path: ZipPath) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Any index below 2^88; see [`crate::parse_diversifier_index`] for text input.
+    pub fn diversifier_index(mut self, diversifier_index: impl Into<DiversifierIndex>) -> Self {
+        self.diversifier_index = diversifier_index.into();
+        self
+    }
+
+    pub fn build(&self) -> Result<DerivedKeys, KeysError> {
+        let (ua_hrp, coin_type) = self.network.as_ref().ok_or(KeysError::NetworkUnknown)?;
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => ZipPath::account(*coin_type, self.account)?,
+        };
+        let (coin_type, account) = path.account_prefix().ok_or(KeysError::PathInvalid)?;
+        let keys = OrchardKeySet::from_path(self.seed, &path)?;
+        let ufvk = Ufvk::from_orchard(ua_hrp, &keys.full_viewing_key())?;
+
+        Ok(DerivedKeys {
+            path,
+            coin_type,
+            account,
+            diversifier_index: self.diversifier_index,
+            uivk: ufvk.uivk()?,
+            address: ufvk.address_at(self.diversifier_index)?,
+            ufvk,
+        })
+    }
+}
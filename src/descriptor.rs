@@ -0,0 +1,280 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use zip32::fingerprint::SeedFingerprint;
+
+use crate::zip316::{self, TYPECODE_ORCHARD, TYPECODE_P2PKH, TYPECODE_P2SH, TYPECODE_SAPLING};
+use crate::{DerivedKeys, KeysError, Network, ZipPath};
+
+const CHECKSUM_PERSONALIZATION: &[u8; 16] = b"JunoKeys_DescSum";
+
+/// One line naming how a wallet's account is derived, in the spirit of Bitcoin's output
+/// descriptors:
+///
+/// ```text
+/// juno(mainnet,[zip32seedfp1...]m/32'/8133'/0',orchard,ufvk=<hex>)#<checksum>
+/// ```
+///
+/// The network, the ZIP-32 path and the receiver types (`orchard`, `sapling`, `p2pkh`,
+/// `p2sh` or a numeric typecode, joined with `+`) are required. The seed fingerprint in
+/// brackets and the [`crate::Ufvk::fingerprint`] after `ufvk=` are optional, and let a
+/// consumer check that it holds the right seed and derived the expected key. The
+/// checksum is 8 hex digits over the canonical form; it may be left off when parsing,
+/// and is checked when present.
+///
+/// ```
+/// # use juno_keys::{DerivationRequest, Network, Seed, WalletDescriptor};
+/// let seed = Seed::from_bytes(&[7u8; 32])?;
+/// let keys = DerivationRequest::new(&seed).network(Network::Testnet).build()?;
+/// let descriptor = WalletDescriptor::from_keys(Network::Testnet, &keys, Some(&seed))?;
+/// let line = descriptor.to_string();
+/// assert!(line.starts_with("juno(testnet,[zip32seedfp1"));
+/// assert_eq!(line.parse::<WalletDescriptor>()?, descriptor);
+/// # Ok::<(), juno_keys::KeysError>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WalletDescriptor {
+    pub network: Network,
+    pub path: ZipPath,
+    /// ZIP-316 typecodes, ascending.
+    pub receiver_types: Vec<u64>,
+    /// `zip32seedfp1...`, as from [`crate::seed_fingerprint`].
+    pub seed_fingerprint: Option<String>,
+    /// Hex, as from [`crate::Ufvk::fingerprint`].
+    pub ufvk_fingerprint: Option<String>,
+}
+
+impl WalletDescriptor {
+    /// Orchard receivers at `path`, without fingerprints.
+    pub fn new(network: Network, path: ZipPath) -> Self {
+        WalletDescriptor {
+            network,
+            path,
+            receiver_types: alloc::vec![TYPECODE_ORCHARD],
+            seed_fingerprint: None,
+            ufvk_fingerprint: None,
+        }
+    }
+
+    /// Describes `keys`, with the UFVK's receiver types and fingerprint, and the seed's
+    /// fingerprint if `seed` is given.
+    pub fn from_keys<S: AsRef<[u8]> + ?Sized>(
+        network: Network,
+        keys: &DerivedKeys,
+        seed: Option<&S>,
+    ) -> Result<Self, KeysError> {
+        Ok(WalletDescriptor {
+            network,
+            path: keys.path.clone(),
+            receiver_types: keys.ufvk.typecodes(),
+            seed_fingerprint: seed.map(crate::seed_fingerprint).transpose()?,
+            ufvk_fingerprint: Some(keys.ufvk.fingerprint()),
+        })
+    }
+
+    /// The 8 hex digits after `#`.
+    pub fn checksum(&self) -> String {
+        let body = self.body();
+        let hash = blake2b_simd::Params::new()
+            .hash_length(32)
+            .personal(CHECKSUM_PERSONALIZATION)
+            .hash(body.as_bytes());
+        hex::encode(&hash.as_bytes()[..4])
+    }
+
+    fn body(&self) -> String {
+        let mut body = alloc::format!("juno({},", self.network.as_str());
+        if let Some(fp) = &self.seed_fingerprint {
+            body.push('[');
+            body.push_str(fp);
+            body.push(']');
+        }
+        body.push_str(&self.path.to_string());
+        body.push(',');
+        let types = self
+            .receiver_types
+            .iter()
+            .map(|&typecode| match typecode_name(typecode) {
+                Some(name) => name.to_string(),
+                None => typecode.to_string(),
+            })
+            .collect::<Vec<_>>();
+        body.push_str(&types.join("+"));
+        if let Some(fp) = &self.ufvk_fingerprint {
+            body.push_str(",ufvk=");
+            body.push_str(fp);
+        }
+        body.push(')');
+        body
+    }
+}
+
+fn typecode_name(typecode: u64) -> Option<&'static str> {
+    match typecode {
+        TYPECODE_P2PKH => Some("p2pkh"),
+        TYPECODE_P2SH => Some("p2sh"),
+        TYPECODE_SAPLING => Some("sapling"),
+        TYPECODE_ORCHARD => Some("orchard"),
+        _ => None,
+    }
+}
+
+fn parse_typecode(name: &str) -> Result<u64, KeysError> {
+    match name {
+        "p2pkh" => Ok(TYPECODE_P2PKH),
+        "p2sh" => Ok(TYPECODE_P2SH),
+        "sapling" => Ok(TYPECODE_SAPLING),
+        "orchard" => Ok(TYPECODE_ORCHARD),
+        _ if !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()) => name
+            .parse()
+            .map_err(|_| KeysError::Descriptor("receiver typecode out of range")),
+        _ => Err(KeysError::Descriptor("unknown receiver type")),
+    }
+}
+
+impl FromStr for WalletDescriptor {
+    type Err = KeysError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (body, checksum) = match s.split_once('#') {
+            Some((body, checksum)) => (body, Some(checksum)),
+            None => (s, None),
+        };
+        let args = body
+            .strip_prefix("juno(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .ok_or(KeysError::Descriptor("expected juno(...)"))?;
+        let mut args = args.split(',');
+        let (Some(network), Some(path), Some(types)) = (args.next(), args.next(), args.next())
+        else {
+            return Err(KeysError::Descriptor(
+                "expected a network, a path and receiver types",
+            ));
+        };
+        let ufvk_fingerprint = match args.next() {
+            Some(arg) => {
+                let fp = arg
+                    .strip_prefix("ufvk=")
+                    .ok_or(KeysError::Descriptor("expected ufvk=<fingerprint>"))?;
+                if fp.len() != 64 || !fp.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+                    return Err(KeysError::Descriptor(
+                        "UFVK fingerprint is not 64 lowercase hex digits",
+                    ));
+                }
+                Some(fp.to_string())
+            }
+            None => None,
+        };
+        if args.next().is_some() {
+            return Err(KeysError::Descriptor("too many arguments"));
+        }
+
+        let network = network.parse()?;
+        let (seed_fingerprint, path) = match path.strip_prefix('[') {
+            Some(rest) => {
+                let (fp, path) = rest
+                    .split_once(']')
+                    .ok_or(KeysError::Descriptor("unclosed ["))?;
+                let fp = SeedFingerprint::from_str(fp)
+                    .map_err(|_| KeysError::Descriptor("seed fingerprint is invalid"))?;
+                (Some(fp.to_string()), path)
+            }
+            None => (None, path),
+        };
+        let path = ZipPath::parse(path)?;
+        if path.account_prefix().is_none() {
+            return Err(KeysError::PathInvalid);
+        }
+
+        let mut receiver_types = types
+            .split('+')
+            .map(parse_typecode)
+            .collect::<Result<Vec<_>, _>>()?;
+        receiver_types.sort_unstable();
+        let items = receiver_types
+            .iter()
+            .map(|&typecode| (typecode, [0u8; 0]))
+            .collect::<Vec<_>>();
+        zip316::validate_container(&items)
+            .map_err(|_| KeysError::Descriptor("receiver types can't form a unified address"))?;
+
+        let descriptor = WalletDescriptor {
+            network,
+            path,
+            receiver_types,
+            seed_fingerprint,
+            ufvk_fingerprint,
+        };
+        if checksum.is_some_and(|checksum| checksum != descriptor.checksum()) {
+            return Err(KeysError::Descriptor("checksum mismatch"));
+        }
+        Ok(descriptor)
+    }
+}
+
+impl fmt::Display for WalletDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.body(), self.checksum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DerivationRequest, Seed};
+
+    #[test]
+    fn descriptors_round_trip_and_catch_damage() {
+        let seed = Seed::from_bytes(&[7u8; 32]).unwrap();
+        let keys = DerivationRequest::new(&seed)
+            .network(Network::Mainnet)
+            .account(2)
+            .build()
+            .unwrap();
+        let descriptor = WalletDescriptor::from_keys(Network::Mainnet, &keys, Some(&seed)).unwrap();
+        let line = descriptor.to_string();
+        assert!(line.contains("]m/32'/8133'/2',orchard,ufvk="), "{line}");
+        assert_eq!(line.parse::<WalletDescriptor>().unwrap(), descriptor);
+
+        // Without the checksum, and with other hardened markers.
+        let bare = WalletDescriptor::new(Network::Testnet, ZipPath::account(8134, 0).unwrap());
+        assert_eq!(
+            "juno(test,m/32h/8134h/0h,orchard)"
+                .parse::<WalletDescriptor>()
+                .unwrap(),
+            bare
+        );
+        let mixed = "juno(regtest,m/32'/8135'/0'/1',p2pkh+orchard+65530)"
+            .parse::<WalletDescriptor>()
+            .unwrap();
+        assert_eq!(
+            mixed.receiver_types,
+            [TYPECODE_P2PKH, TYPECODE_ORCHARD, 65530]
+        );
+        assert!(mixed
+            .to_string()
+            .starts_with("juno(regtest,m/32'/8135'/0'/1',p2pkh+orchard+65530)#"));
+
+        let damaged = line.replacen("8133'/2'", "8133'/3'", 1);
+        assert!(matches!(
+            damaged.parse::<WalletDescriptor>(),
+            Err(KeysError::Descriptor("checksum mismatch"))
+        ));
+        for bad in [
+            "juno(mainnet,m/32'/8133'/0')",
+            "juno(mainnet,m/32'/8133'/0',p2pkh)",
+            "juno(mainnet,m/32'/8133'/0',orchard+orchard)",
+            "juno(mainnet,m/32'/8133'/0',tor)",
+            "juno(mainnet,[zip32seedfp1qq]m/32'/8133'/0',orchard)",
+            "juno(mainnet,m/32'/8133'/0',orchard,ufvk=00)",
+            "juno(mainnet,m/32'/8133'/0',orchard,ufvk=00,x)",
+            "btc(mainnet,m/32'/8133'/0',orchard)",
+            "juno(mainnet,m/44'/8133'/0',orchard)",
+        ] {
+            assert!(bad.parse::<WalletDescriptor>().is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Diversifier indices: the 88-bit counter behind an account's external addresses.
+//!
+//! APIs take anything convertible to [`DiversifierIndex`], so a `u32` or `u64` works
+//! for the common low indices and `[u8; 11]` or [`parse_diversifier_index`] reach the
+//! rest. JSON and CLI output give the index as a number (`u128::from(index)`).
+
+use crate::KeysError;
+
+pub use zip32::DiversifierIndex;
+
+/// The highest diversifier index, `2^88 - 1`.
+pub const MAX_DIVERSIFIER_INDEX: u128 = (1 << 88) - 1;
+
+/// Parses a diversifier index written in decimal, or in hex with a `0x` prefix.
+///
+/// ```
+/// # use juno_keys::{parse_diversifier_index, DiversifierIndex};
+/// assert_eq!(parse_diversifier_index("7")?, DiversifierIndex::from(7u32));
+/// let last = parse_diversifier_index("0xffffffffffffffffffffff")?;
+/// assert_eq!(u128::from(last), (1 << 88) - 1);
+/// assert!(parse_diversifier_index("0x10000000000000000000000").is_err());
+/// # Ok::<(), juno_keys::KeysError>(())
+/// ```
+pub fn parse_diversifier_index(s: &str) -> Result<DiversifierIndex, KeysError> {
+    let s = s.trim();
+    // Integer parsing takes a leading `+`, which isn't how an index is written.
+    if s.starts_with('+') {
+        return Err(KeysError::DiversifierIndexInvalid);
+    }
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) if !hex.starts_with('+') => u128::from_str_radix(hex, 16).ok(),
+        Some(_) => None,
+        None => s.parse::<u128>().ok(),
+    }
+    .and_then(|value| DiversifierIndex::try_from(value).ok())
+    .ok_or(KeysError::DiversifierIndexInvalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use orchard::keys::Scope;
+
+    use super::*;
+    use crate::{Network, OrchardKeySet};
+
+    #[test]
+    fn indices_parse_in_decimal_and_hex() {
+        let parse = |s: &str| parse_diversifier_index(s).map(u128::from).ok();
+        assert_eq!(parse("0"), Some(0));
+        assert_eq!(parse("4294967296"), Some(1 << 32));
+        assert_eq!(parse("0x100000000"), Some(1 << 32));
+        assert_eq!(
+            parse("0XFFFFFFFFFFFFFFFFFFFFFF"),
+            Some(MAX_DIVERSIFIER_INDEX)
+        );
+        assert_eq!(
+            parse(&MAX_DIVERSIFIER_INDEX.to_string()),
+            Some(MAX_DIVERSIFIER_INDEX)
+        );
+        for bad in [
+            "",
+            "-1",
+            "+1",
+            "0x",
+            "0x+1",
+            "1e3",
+            "0x10000000000000000000000",
+        ] {
+            assert!(
+                matches!(
+                    parse_diversifier_index(bad),
+                    Err(KeysError::DiversifierIndexInvalid)
+                ),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn wide_indices_round_trip() {
+        let ufvk = OrchardKeySet::from_seed(&[7u8; 32], &Network::Mainnet, 0)
+            .and_then(|keys| keys.ufvk(&Network::Mainnet))
+            .expect("ufvk");
+        let last = DiversifierIndex::try_from(MAX_DIVERSIFIER_INDEX).expect("index");
+        let wide = parse_diversifier_index("0x123456789abcdef0123456").expect("index");
+        for index in [wide, last] {
+            let address = ufvk.address_at(index).expect("address");
+            assert_eq!(ufvk.diversifier_index_of(&address), Some(index));
+            let locator = ufvk.address_locator().expect("locator");
+            assert_eq!(locator.locate(&address), Some((Scope::External, index)));
+        }
+        // The iterator stops after the last index rather than wrapping to 0.
+        let tail = ufvk
+            .addresses_from(Scope::External, last)
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(tail.len(), 1);
+        assert_eq!(tail[0].1, ufvk.address_at(last).expect("address"));
+    }
+}
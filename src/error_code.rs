@@ -0,0 +1,205 @@
+/// Stable numeric codes for every failure reported by this crate, the CLI (as its
+/// exit status) and the C API (as `JunoKeysStatus`).
+///
+/// Values are part of the public contract: new codes are only ever appended and
+/// existing ones are never renumbered. `as_str` gives the string codes used in JSON
+/// errors.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// Success; never produced by an error.
+    Ok = 0,
+    /// A bug or an unexpected failure.
+    Internal = 1,
+    /// Malformed arguments; matches clap's exit status for usage errors.
+    InvalidRequest = 2,
+    SeedInvalid = 3,
+    SpendingKeyInvalid = 4,
+    LengthOutOfRange = 5,
+    UaHrpInvalid = 6,
+    CoinTypeInvalid = 7,
+    CoinTypeRegistered = 8,
+    AccountInvalid = 9,
+    PathInvalid = 10,
+    PathNotHardened = 11,
+    NetworkUnknown = 12,
+    EncodingInvalid = 13,
+    IoError = 14,
+    ConfigInvalid = 15,
+    MainnetInterlock = 16,
+    OfflineAssertionFailed = 17,
+    SelftestFailed = 18,
+    /// A required pointer argument to the C API was NULL.
+    NullPointer = 19,
+    /// A string argument to the C API was not valid UTF-8.
+    Utf8Invalid = 20,
+    /// The library panicked behind the C API; this is a bug.
+    Panic = 21,
+    /// A `serve --socket` peer's uid is not allowed.
+    PeerNotAllowed = 22,
+    /// A `serve --http` or `--grpc` request lacks the configured bearer token.
+    Unauthenticated = 23,
+    /// A daemon client exceeded `serve --rate-limit`.
+    RateLimited = 24,
+    /// A message signature doesn't verify against the given key.
+    SignatureInvalid = 25,
+    /// A FROST key generation package is malformed or fails verification.
+    FrostInvalid = 26,
+    /// A ZIP-321 payment request is malformed or asks for something invalid.
+    PaymentRequestInvalid = 27,
+    /// A diversifier index is malformed or not below 2^88.
+    DiversifierIndexInvalid = 28,
+    /// A wallet descriptor is malformed or its checksum doesn't match.
+    DescriptorInvalid = 29,
+    /// `--json-version` asked for an output version this build doesn't write.
+    JsonVersionUnsupported = 30,
+    /// An encrypted seed is malformed or its passphrase is wrong.
+    EncryptedSeedInvalid = 31,
+    /// Seed shares are malformed, too few, or not from one split.
+    SeedShareInvalid = 32,
+    /// Hardening (`--harden`) is unsupported or was refused: core dumps can't be
+    /// disabled or secret memory can't be locked.
+    HardeningUnavailable = 33,
+}
+
+impl ErrorCode {
+    /// Every code, in numeric order.
+    pub const ALL: [ErrorCode; 34] = [
+        ErrorCode::Ok,
+        ErrorCode::Internal,
+        ErrorCode::InvalidRequest,
+        ErrorCode::SeedInvalid,
+        ErrorCode::SpendingKeyInvalid,
+        ErrorCode::LengthOutOfRange,
+        ErrorCode::UaHrpInvalid,
+        ErrorCode::CoinTypeInvalid,
+        ErrorCode::CoinTypeRegistered,
+        ErrorCode::AccountInvalid,
+        ErrorCode::PathInvalid,
+        ErrorCode::PathNotHardened,
+        ErrorCode::NetworkUnknown,
+        ErrorCode::EncodingInvalid,
+        ErrorCode::IoError,
+        ErrorCode::ConfigInvalid,
+        ErrorCode::MainnetInterlock,
+        ErrorCode::OfflineAssertionFailed,
+        ErrorCode::SelftestFailed,
+        ErrorCode::NullPointer,
+        ErrorCode::Utf8Invalid,
+        ErrorCode::Panic,
+        ErrorCode::PeerNotAllowed,
+        ErrorCode::Unauthenticated,
+        ErrorCode::RateLimited,
+        ErrorCode::SignatureInvalid,
+        ErrorCode::FrostInvalid,
+        ErrorCode::PaymentRequestInvalid,
+        ErrorCode::DiversifierIndexInvalid,
+        ErrorCode::DescriptorInvalid,
+        ErrorCode::JsonVersionUnsupported,
+        ErrorCode::EncryptedSeedInvalid,
+        ErrorCode::SeedShareInvalid,
+        ErrorCode::HardeningUnavailable,
+    ];
+
+    /// The string code, e.g. `"seed_invalid"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Ok => "ok",
+            ErrorCode::Internal => "internal",
+            ErrorCode::InvalidRequest => "invalid_request",
+            ErrorCode::SeedInvalid => "seed_invalid",
+            ErrorCode::SpendingKeyInvalid => "spending_key_invalid",
+            ErrorCode::LengthOutOfRange => "length_out_of_range",
+            ErrorCode::UaHrpInvalid => "ua_hrp_invalid",
+            ErrorCode::CoinTypeInvalid => "coin_type_invalid",
+            ErrorCode::CoinTypeRegistered => "coin_type_registered",
+            ErrorCode::AccountInvalid => "account_invalid",
+            ErrorCode::PathInvalid => "path_invalid",
+            ErrorCode::PathNotHardened => "path_not_hardened",
+            ErrorCode::NetworkUnknown => "network_unknown",
+            ErrorCode::EncodingInvalid => "encoding_invalid",
+            ErrorCode::IoError => "io_error",
+            ErrorCode::ConfigInvalid => "config_invalid",
+            ErrorCode::MainnetInterlock => "mainnet_interlock",
+            ErrorCode::OfflineAssertionFailed => "offline_assertion_failed",
+            ErrorCode::SelftestFailed => "selftest_failed",
+            ErrorCode::NullPointer => "null_pointer",
+            ErrorCode::Utf8Invalid => "utf8_invalid",
+            ErrorCode::Panic => "panic",
+            ErrorCode::PeerNotAllowed => "peer_not_allowed",
+            ErrorCode::Unauthenticated => "unauthenticated",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::SignatureInvalid => "signature_invalid",
+            ErrorCode::FrostInvalid => "frost_invalid",
+            ErrorCode::PaymentRequestInvalid => "payment_request_invalid",
+            ErrorCode::DiversifierIndexInvalid => "diversifier_index_invalid",
+            ErrorCode::DescriptorInvalid => "descriptor_invalid",
+            ErrorCode::JsonVersionUnsupported => "json_version_unsupported",
+            ErrorCode::EncryptedSeedInvalid => "encrypted_seed_invalid",
+            ErrorCode::SeedShareInvalid => "seed_share_invalid",
+            ErrorCode::HardeningUnavailable => "hardening_unavailable",
+        }
+    }
+
+    /// A one-line description, for `strerror`-style reporting.
+    pub fn description(&self) -> &'static str {
+        match self {
+            ErrorCode::Ok => "success",
+            ErrorCode::Internal => "internal error",
+            ErrorCode::InvalidRequest => "invalid request",
+            ErrorCode::SeedInvalid => "seed is malformed or outside 32..=252 bytes",
+            ErrorCode::SpendingKeyInvalid => "spending key is malformed",
+            ErrorCode::LengthOutOfRange => "input length is out of range",
+            ErrorCode::UaHrpInvalid => "unified address HRP is invalid",
+            ErrorCode::CoinTypeInvalid => "coin type is not below 2^31",
+            ErrorCode::CoinTypeRegistered => "coin type belongs to another SLIP-44 chain",
+            ErrorCode::AccountInvalid => "account is not below 2^31",
+            ErrorCode::PathInvalid => "derivation path is malformed",
+            ErrorCode::PathNotHardened => "derivation path has a non-hardened level",
+            ErrorCode::NetworkUnknown => "network is unknown",
+            ErrorCode::EncodingInvalid => "key or address encoding is invalid",
+            ErrorCode::IoError => "I/O error",
+            ErrorCode::ConfigInvalid => "configuration is invalid",
+            ErrorCode::MainnetInterlock => "refusing to print mainnet secrets to a terminal",
+            ErrorCode::OfflineAssertionFailed => "host is not offline",
+            ErrorCode::SelftestFailed => "known-answer tests failed",
+            ErrorCode::NullPointer => "required pointer argument is NULL",
+            ErrorCode::Utf8Invalid => "string argument is not valid UTF-8",
+            ErrorCode::Panic => "library panicked",
+            ErrorCode::PeerNotAllowed => "socket peer is not allowed",
+            ErrorCode::Unauthenticated => "server credentials are missing or wrong",
+            ErrorCode::RateLimited => "too many requests from this client",
+            ErrorCode::SignatureInvalid => "signature does not verify",
+            ErrorCode::FrostInvalid => "FROST key generation package is invalid",
+            ErrorCode::PaymentRequestInvalid => "payment request is invalid",
+            ErrorCode::DiversifierIndexInvalid => {
+                "diversifier index is malformed or not below 2^88"
+            }
+            ErrorCode::DescriptorInvalid => "wallet descriptor is invalid",
+            ErrorCode::JsonVersionUnsupported => "JSON output version is not supported",
+            ErrorCode::EncryptedSeedInvalid => {
+                "encrypted seed is malformed or its passphrase is wrong"
+            }
+            ErrorCode::SeedShareInvalid => "seed shares are malformed, too few or mismatched",
+            ErrorCode::HardeningUnavailable => "memory hardening is unavailable",
+        }
+    }
+}
+
+impl TryFrom<i32> for ErrorCode {
+    type Error = i32;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        usize::try_from(value)
+            .ok()
+            .and_then(|i| ErrorCode::ALL.get(i).copied())
+            .ok_or(value)
+    }
+}
+
+impl From<ErrorCode> for i32 {
+    fn from(code: ErrorCode) -> Self {
+        code as i32
+    }
+}
@@ -0,0 +1,1019 @@
+//! FROST key generation for Orchard spend authority, so a t-of-n group of signer
+//! machines jointly holds an account's `ask` and no single machine can spend.
+//!
+//! Shares come from either [`trusted_dealer`], where one machine samples the key and
+//! splits it, or the three-part distributed key generation in [`dkg`] (the Pedersen
+//! DKG with proofs of knowledge from RFC 9591), where the key never exists in one
+//! place. Both give each signer a [`KeyPackage`] and everyone the same
+//! [`PublicKeyPackage`], whose group verifying key is the account's `ak`.
+//!
+//! The rest of the group FVK, `nk` and `rivk`, is viewing material rather than spend
+//! authority. It is derived from a viewing seed that the dealer samples, or that the
+//! DKG participants contribute to over their private round-2 channels, so observers of
+//! the public round-1 broadcast can't view the account.
+//!
+//! Signers then produce spend authorization signatures with the two rounds in
+//! [`sign`]. Identifiers run from 1 to `max_signers`.
+//!
+//! # Relation to RFC 9591 and `reddsa`
+//!
+//! This is not the RFC 9591 ciphersuite, and its packages do not interoperate with
+//! the Zcash Foundation's FROST tooling (`reddsa::frost::redpallas`, `frost-client`).
+//! The protocol follows RFC 9591's structure: Shamir shares with Feldman
+//! commitments, Schnorr proofs of knowledge in the DKG, hedged nonces, and per-signer
+//! binding factors over the sorted commitment list. Its hashes, though, are
+//! personalized BLAKE2b with this crate's own tags: `JunoKeys_FrostPK` for the DKG proofs,
+//! `JunoKeys_FrostNC` for nonces, `JunoKeys_FrostBF` for binding factors, and
+//! `JunoKeys_FrostVK`/`JunoKeys_FrostVS` for the viewing seed. The signature
+//! challenge is RedPallas's own `Zcash_RedPallasH`, so aggregated signatures are
+//! ordinary Orchard spend authorization signatures that verify under `rk` with
+//! [`orchard::primitives::redpallas`]; only the signers' intermediate messages differ.
+//!
+//! The crate keeps its own implementation because a key package here is an Orchard
+//! account, not just a group key. The DKG also has to agree on `nk` and `rivk`
+//! through private round-2 contributions, and on the sign of `ak`, and the generic
+//! FROST crates model neither; layering that onto `reddsa` would still need custom
+//! round-2 payloads. It also keeps `frost-core` and `frost-rerandomized` out of the
+//! dependency tree of every crate that only wants key derivation. Binding `rk`, with
+//! the spend's `alpha`, into the binding factors is the same rerandomized FROST
+//! construction `reddsa` uses, so the security argument carries over even though the
+//! encodings differ.
+//!
+//! This code has not had an independent audit. Deployments that need to interoperate
+//! with other FROST signers, or that want a reviewed implementation, should use
+//! `reddsa::frost::redpallas` instead.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
+use group::ff::{Field, FromUniformBytes, PrimeField};
+use group::{Group, GroupEncoding};
+use orchard::keys::FullViewingKey;
+use orchard::primitives::redpallas::{self, SpendAuth};
+use pasta_curves::pallas;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::{KeysError, NetworkParams, Ufvk, ORCHARD_FVK_LEN};
+
+/// A signer's index in the group, from 1 to `max_signers`.
+pub type Identifier = u16;
+
+/// One signer's secret share of the group's spend authorizing key.
+///
+/// `Debug` is redacted.
+#[derive(Clone)]
+pub struct KeyPackage {
+    pub identifier: Identifier,
+    pub min_signers: u16,
+    /// The signer's share of `ask`, a little-endian Pallas scalar.
+    pub signing_share: Zeroizing<[u8; 32]>,
+    /// `[signing_share]` times the spend authorization basepoint.
+    pub verifying_share: [u8; 32],
+    /// The group's Orchard full viewing key, `ak || nk || rivk`.
+    pub full_viewing_key: [u8; ORCHARD_FVK_LEN],
+}
+
+impl fmt::Debug for KeyPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPackage")
+            .field("identifier", &self.identifier)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The group's public keys, identical for every signer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PublicKeyPackage {
+    pub min_signers: u16,
+    pub verifying_shares: BTreeMap<Identifier, [u8; 32]>,
+    /// The group's Orchard full viewing key, `ak || nk || rivk`.
+    pub full_viewing_key: [u8; ORCHARD_FVK_LEN],
+}
+
+impl PublicKeyPackage {
+    /// The group verifying key, Orchard's spend validating key `ak`.
+    pub fn verifying_key(&self) -> [u8; 32] {
+        let mut ak = [0u8; 32];
+        ak.copy_from_slice(&self.full_viewing_key[..32]);
+        ak
+    }
+
+    /// The group's UFVK under `network`'s HRPs.
+    pub fn ufvk<N: NetworkParams + ?Sized>(&self, network: &N) -> Result<Ufvk, KeysError> {
+        let fvk = FullViewingKey::from_bytes(&self.full_viewing_key).ok_or(KeysError::Internal)?;
+        Ufvk::from_orchard(network.ua_hrp(), &fvk)
+    }
+}
+
+/// Samples a fresh spend authorizing key and viewing seed and splits the key into
+/// `max_signers` shares, any `min_signers` of which can sign.
+///
+/// The dealer sees the whole key; use [`dkg`] where no machine should.
+pub fn trusted_dealer<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    min_signers: u16,
+    max_signers: u16,
+) -> Result<(Vec<KeyPackage>, PublicKeyPackage), KeysError> {
+    check_params(min_signers, max_signers)?;
+    let coefficients = (0..min_signers)
+        .map(|_| pallas::Scalar::random(&mut *rng))
+        .collect::<Vec<_>>();
+    let commitment = coefficients
+        .iter()
+        .map(|a| basepoint() * a)
+        .collect::<Vec<_>>();
+    let mut viewing_seed = Zeroizing::new([0u8; 32]);
+    rng.fill_bytes(viewing_seed.as_mut());
+
+    let (public, negate) = public_package(min_signers, max_signers, &commitment, &viewing_seed)?;
+    let shares = (1..=max_signers)
+        .map(|id| key_package(id, evaluate(&coefficients, id), negate, &public))
+        .collect();
+    Ok((shares, public))
+}
+
+fn check_params(min_signers: u16, max_signers: u16) -> Result<(), KeysError> {
+    if min_signers < 2 || min_signers > max_signers {
+        return Err(frost_error(None, "need 2 <= min_signers <= max_signers"));
+    }
+    Ok(())
+}
+
+fn frost_error(culprit: Option<Identifier>, reason: &'static str) -> KeysError {
+    KeysError::Frost { culprit, reason }
+}
+
+// reddsa keeps SpendAuth's basepoint private; it is the verification key of scalar 1.
+fn basepoint() -> pallas::Point {
+    let mut one = [0u8; 32];
+    one[0] = 1;
+    let key = redpallas::SigningKey::<SpendAuth>::try_from(one).expect("1 is a scalar");
+    let bytes: [u8; 32] = redpallas::VerificationKey::from(&key).into();
+    pallas::Point::from_bytes(&bytes).expect("basepoint")
+}
+
+fn scalar_from(bytes: &[u8; 32], culprit: Option<Identifier>) -> Result<pallas::Scalar, KeysError> {
+    Option::from(pallas::Scalar::from_repr(*bytes))
+        .ok_or(frost_error(culprit, "non-canonical scalar"))
+}
+
+fn point_from(bytes: &[u8; 32], culprit: Option<Identifier>) -> Result<pallas::Point, KeysError> {
+    Option::<pallas::Point>::from(pallas::Point::from_bytes(bytes))
+        .filter(|p| !bool::from(p.is_identity()))
+        .ok_or(frost_error(culprit, "invalid point"))
+}
+
+// f(x) for the polynomial with `coefficients`, constant term first.
+fn evaluate(coefficients: &[pallas::Scalar], x: Identifier) -> pallas::Scalar {
+    let x = pallas::Scalar::from(u64::from(x));
+    coefficients
+        .iter()
+        .rev()
+        .fold(pallas::Scalar::ZERO, |acc, a| acc * x + a)
+}
+
+// The same evaluation in the exponent, on a commitment to the coefficients.
+fn evaluate_commitment(commitment: &[pallas::Point], x: Identifier) -> pallas::Point {
+    let x = pallas::Scalar::from(u64::from(x));
+    commitment
+        .iter()
+        .rev()
+        .fold(pallas::Point::identity(), |acc, c| acc * x + c)
+}
+
+fn blake2b_512(personal: &[u8; 16], parts: &[&[u8]]) -> [u8; 64] {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(64)
+        .personal(personal)
+        .to_state();
+    for part in parts {
+        state.update(part);
+    }
+    let mut out = [0u8; 64];
+    out.copy_from_slice(state.finalize().as_bytes());
+    out
+}
+
+// Builds the public package for the group polynomial `commitment`. Orchard needs `ak`
+// with a positive y sign, so if the group key has the other sign the whole sharing is
+// negated; the returned flag tells callers to negate their shares too.
+fn public_package(
+    min_signers: u16,
+    max_signers: u16,
+    commitment: &[pallas::Point],
+    viewing_seed: &[u8; 32],
+) -> Result<(PublicKeyPackage, bool), KeysError> {
+    let negate = commitment[0].to_bytes()[31] & 0x80 != 0;
+    let commitment = commitment
+        .iter()
+        .map(|c| if negate { -c } else { *c })
+        .collect::<Vec<_>>();
+    let verifying_shares = (1..=max_signers)
+        .map(|id| (id, evaluate_commitment(&commitment, id).to_bytes()))
+        .collect();
+
+    let nk =
+        pallas::Base::from_uniform_bytes(&blake2b_512(b"JunoKeys_FrostVK", &[&[0], viewing_seed]));
+    let rivk = pallas::Scalar::from_uniform_bytes(&blake2b_512(
+        b"JunoKeys_FrostVK",
+        &[&[1], viewing_seed],
+    ));
+    let mut full_viewing_key = [0u8; ORCHARD_FVK_LEN];
+    full_viewing_key[..32].copy_from_slice(&commitment[0].to_bytes());
+    full_viewing_key[32..64].copy_from_slice(&nk.to_repr());
+    full_viewing_key[64..].copy_from_slice(&rivk.to_repr());
+    // Rejects the negligible cases of an identity `ak` or an invalid derived ivk.
+    FullViewingKey::from_bytes(&full_viewing_key).ok_or(KeysError::Internal)?;
+
+    let public = PublicKeyPackage {
+        min_signers,
+        verifying_shares,
+        full_viewing_key,
+    };
+    Ok((public, negate))
+}
+
+fn key_package(
+    identifier: Identifier,
+    share: pallas::Scalar,
+    negate: bool,
+    public: &PublicKeyPackage,
+) -> KeyPackage {
+    let share = if negate { -share } else { share };
+    KeyPackage {
+        identifier,
+        min_signers: public.min_signers,
+        signing_share: Zeroizing::new(share.to_repr()),
+        verifying_share: public.verifying_shares[&identifier],
+        full_viewing_key: public.full_viewing_key,
+    }
+}
+
+/// Distributed key generation in three parts, run by every participant.
+///
+/// 1. [`part1`]: sample a polynomial and broadcast its [`Round1Package`] to everyone,
+///    keeping the [`Round1Secret`].
+/// 2. [`part2`]: with everyone else's round-1 packages, produce one [`Round2Package`]
+///    per participant and send each to its recipient over a private, authenticated
+///    channel.
+/// 3. [`part3`]: with the round-1 packages and the round-2 packages addressed to you,
+///    verify everything and derive your [`KeyPackage`] and the [`PublicKeyPackage`].
+///
+/// Every participant should then compare the group UFVK (or its fingerprint) out of
+/// band: a participant who sent inconsistent packages to different peers makes them
+/// disagree rather than produce a weakened key.
+pub mod dkg {
+    use super::*;
+
+    const SECRET_HEADER_LEN: usize = 6 + 32;
+
+    /// A participant's state between the parts; keep it secret and delete it after
+    /// [`part3`]. `Debug` is redacted.
+    pub struct Round1Secret {
+        identifier: Identifier,
+        min_signers: u16,
+        max_signers: u16,
+        coefficients: Zeroizing<Vec<[u8; 32]>>,
+        viewing_contribution: Zeroizing<[u8; 32]>,
+    }
+
+    impl Round1Secret {
+        pub fn identifier(&self) -> Identifier {
+            self.identifier
+        }
+
+        /// `identifier || min_signers || max_signers` (little-endian u16s), the
+        /// viewing contribution, then the polynomial's coefficients.
+        pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+            let mut out = Zeroizing::new(Vec::with_capacity(
+                SECRET_HEADER_LEN + 32 * self.coefficients.len(),
+            ));
+            out.extend_from_slice(&self.identifier.to_le_bytes());
+            out.extend_from_slice(&self.min_signers.to_le_bytes());
+            out.extend_from_slice(&self.max_signers.to_le_bytes());
+            out.extend_from_slice(self.viewing_contribution.as_ref());
+            for coefficient in self.coefficients.iter() {
+                out.extend_from_slice(coefficient);
+            }
+            out
+        }
+
+        pub fn from_bytes(bytes: &[u8]) -> Result<Self, KeysError> {
+            let malformed = || frost_error(None, "malformed round-1 secret");
+            let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+            if bytes.len() < SECRET_HEADER_LEN {
+                return Err(malformed());
+            }
+            let (identifier, min_signers, max_signers) = (u16_at(0), u16_at(2), u16_at(4));
+            check_params(min_signers, max_signers)?;
+            let rest = &bytes[SECRET_HEADER_LEN..];
+            if identifier == 0
+                || identifier > max_signers
+                || rest.len() != 32 * usize::from(min_signers)
+            {
+                return Err(malformed());
+            }
+            let mut viewing_contribution = Zeroizing::new([0u8; 32]);
+            viewing_contribution.copy_from_slice(&bytes[6..SECRET_HEADER_LEN]);
+            let mut coefficients = Zeroizing::new(Vec::with_capacity(rest.len() / 32));
+            for chunk in rest.chunks_exact(32) {
+                let coefficient = <[u8; 32]>::try_from(chunk).map_err(|_| malformed())?;
+                scalar_from(&coefficient, None)?;
+                coefficients.push(coefficient);
+            }
+            Ok(Round1Secret {
+                identifier,
+                min_signers,
+                max_signers,
+                coefficients,
+                viewing_contribution,
+            })
+        }
+
+        fn polynomial(&self) -> Vec<pallas::Scalar> {
+            self.coefficients
+                .iter()
+                .map(|c| scalar_from(c, None).expect("checked on construction"))
+                .collect()
+        }
+    }
+
+    impl fmt::Debug for Round1Secret {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Round1Secret")
+                .field("identifier", &self.identifier)
+                .finish_non_exhaustive()
+        }
+    }
+
+    /// A participant's public commitment, broadcast to everyone.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Round1Package {
+        pub identifier: Identifier,
+        /// Commitments to the polynomial's `min_signers` coefficients.
+        pub commitment: Vec<[u8; 32]>,
+        /// Schnorr proof of knowledge of the constant term, `R || mu`.
+        pub proof: [u8; 64],
+    }
+
+    /// A share of one participant's polynomial for another; send it privately.
+    /// `Debug` is redacted.
+    #[derive(Clone)]
+    pub struct Round2Package {
+        pub sender: Identifier,
+        pub recipient: Identifier,
+        pub signing_share: Zeroizing<[u8; 32]>,
+        pub viewing_contribution: Zeroizing<[u8; 32]>,
+    }
+
+    impl fmt::Debug for Round2Package {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Round2Package")
+                .field("sender", &self.sender)
+                .field("recipient", &self.recipient)
+                .finish_non_exhaustive()
+        }
+    }
+
+    /// Starts key generation as `identifier` of `max_signers`.
+    pub fn part1<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        identifier: Identifier,
+        min_signers: u16,
+        max_signers: u16,
+    ) -> Result<(Round1Secret, Round1Package), KeysError> {
+        check_params(min_signers, max_signers)?;
+        if identifier == 0 || identifier > max_signers {
+            return Err(frost_error(None, "identifier must be in 1..=max_signers"));
+        }
+        let coefficients = (0..min_signers)
+            .map(|_| pallas::Scalar::random(&mut *rng))
+            .collect::<Vec<_>>();
+        let commitment = coefficients
+            .iter()
+            .map(|a| (basepoint() * a).to_bytes())
+            .collect::<Vec<_>>();
+        let k = pallas::Scalar::random(&mut *rng);
+        let r = (basepoint() * k).to_bytes();
+        let mu = k + coefficients[0] * challenge(identifier, &commitment[0], &r);
+        let mut proof = [0u8; 64];
+        proof[..32].copy_from_slice(&r);
+        proof[32..].copy_from_slice(&mu.to_repr());
+
+        let mut viewing_contribution = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(viewing_contribution.as_mut());
+        let secret = Round1Secret {
+            identifier,
+            min_signers,
+            max_signers,
+            coefficients: Zeroizing::new(coefficients.iter().map(|a| a.to_repr()).collect()),
+            viewing_contribution,
+        };
+        let package = Round1Package {
+            identifier,
+            commitment,
+            proof,
+        };
+        Ok((secret, package))
+    }
+
+    /// Checks everyone else's round-1 packages and computes the round-2 package for
+    /// each of them.
+    pub fn part2(
+        secret: &Round1Secret,
+        round1: &[Round1Package],
+    ) -> Result<Vec<Round2Package>, KeysError> {
+        check_round1(secret, round1)?;
+        let polynomial = secret.polynomial();
+        Ok(round1
+            .iter()
+            .map(|package| Round2Package {
+                sender: secret.identifier,
+                recipient: package.identifier,
+                signing_share: Zeroizing::new(evaluate(&polynomial, package.identifier).to_repr()),
+                viewing_contribution: secret.viewing_contribution.clone(),
+            })
+            .collect())
+    }
+
+    /// Verifies the round-2 packages addressed to this participant against the
+    /// senders' commitments and derives the key packages.
+    pub fn part3(
+        secret: &Round1Secret,
+        round1: &[Round1Package],
+        round2: &[Round2Package],
+    ) -> Result<(KeyPackage, PublicKeyPackage), KeysError> {
+        let commitments = check_round1(secret, round1)?;
+        let me = secret.identifier;
+        let polynomial = secret.polynomial();
+        let mut share = evaluate(&polynomial, me);
+        let mut contributions = BTreeMap::from([(me, secret.viewing_contribution.clone())]);
+        for package in round2 {
+            let sender = package.sender;
+            let commitment = commitments.get(&sender).ok_or(frost_error(
+                Some(sender),
+                "round-2 package from an unknown sender",
+            ))?;
+            if package.recipient != me {
+                return Err(frost_error(
+                    Some(sender),
+                    "round-2 package for another participant",
+                ));
+            }
+            if contributions
+                .insert(sender, package.viewing_contribution.clone())
+                .is_some()
+            {
+                return Err(frost_error(Some(sender), "duplicate round-2 package"));
+            }
+            let value = scalar_from(&package.signing_share, Some(sender))?;
+            if basepoint() * value != evaluate_commitment(commitment, me) {
+                return Err(frost_error(
+                    Some(sender),
+                    "share does not match the commitment",
+                ));
+            }
+            share += value;
+        }
+        if contributions.len() != usize::from(secret.max_signers) {
+            return Err(frost_error(None, "missing round-2 packages"));
+        }
+
+        let mut group = polynomial
+            .iter()
+            .map(|a| basepoint() * a)
+            .collect::<Vec<_>>();
+        for commitment in commitments.values() {
+            for (sum, c) in group.iter_mut().zip(commitment) {
+                *sum += c;
+            }
+        }
+        let mut state = blake2b_simd::Params::new()
+            .hash_length(32)
+            .personal(b"JunoKeys_FrostVS")
+            .to_state();
+        for (id, contribution) in &contributions {
+            state.update(&id.to_le_bytes());
+            state.update(contribution.as_ref());
+        }
+        let mut viewing_seed = Zeroizing::new([0u8; 32]);
+        viewing_seed.copy_from_slice(state.finalize().as_bytes());
+
+        let (public, negate) = public_package(
+            secret.min_signers,
+            secret.max_signers,
+            &group,
+            &viewing_seed,
+        )?;
+        Ok((key_package(me, share, negate, &public), public))
+    }
+
+    fn challenge(identifier: Identifier, constant: &[u8; 32], r: &[u8; 32]) -> pallas::Scalar {
+        pallas::Scalar::from_uniform_bytes(&blake2b_512(
+            b"JunoKeys_FrostPK",
+            &[&identifier.to_le_bytes(), constant, r],
+        ))
+    }
+
+    // Checks that `round1` holds one valid package from every other participant, and
+    // returns their commitments.
+    fn check_round1(
+        secret: &Round1Secret,
+        round1: &[Round1Package],
+    ) -> Result<BTreeMap<Identifier, Vec<pallas::Point>>, KeysError> {
+        let mut commitments = BTreeMap::new();
+        for package in round1 {
+            let id = package.identifier;
+            if id == 0 || id > secret.max_signers || id == secret.identifier {
+                return Err(frost_error(Some(id), "unexpected round-1 identifier"));
+            }
+            if package.commitment.len() != usize::from(secret.min_signers) {
+                return Err(frost_error(Some(id), "commitment has the wrong length"));
+            }
+            let commitment = package
+                .commitment
+                .iter()
+                .map(|c| point_from(c, Some(id)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut r = [0u8; 32];
+            r.copy_from_slice(&package.proof[..32]);
+            let mut mu = [0u8; 32];
+            mu.copy_from_slice(&package.proof[32..]);
+            let (r_point, mu) = (point_from(&r, Some(id))?, scalar_from(&mu, Some(id))?);
+            let c = challenge(id, &package.commitment[0], &r);
+            if basepoint() * mu != r_point + commitment[0] * c {
+                return Err(frost_error(Some(id), "invalid proof of knowledge"));
+            }
+            if commitments.insert(id, commitment).is_some() {
+                return Err(frost_error(Some(id), "duplicate round-1 package"));
+            }
+        }
+        if commitments.len() + 1 != usize::from(secret.max_signers) {
+            return Err(frost_error(None, "missing round-1 packages"));
+        }
+        Ok(commitments)
+    }
+}
+
+/// Threshold signing in two rounds, then aggregation by anyone.
+///
+/// 1. [`round1`]: each signer commits to fresh nonces, keeping the [`SigningNonces`]
+///    and sending its [`SigningCommitments`] to the other signers.
+/// 2. [`round2`]: with at least `min_signers` commitments, the message and the
+///    randomizer, every signer builds the same [`SigningPackage`] and produces a
+///    [`SignatureShare`]. Nonces must be deleted after one use; signing two packages
+///    with the same nonces reveals the signer's share.
+/// 3. [`aggregate`]: whoever collects the shares combines them into a RedPallas
+///    spend authorization signature.
+///
+/// The signature validates under `ak` plus `[randomizer]` times the basepoint, which
+/// is Orchard's `rk` for a spend randomized by `alpha`; a zero randomizer gives a
+/// signature under `ak` itself.
+pub mod sign {
+    use super::*;
+
+    const NONCES_LEN: usize = 2 + 64;
+
+    /// A signer's round-1 nonces; keep them secret and use them once. `Debug` is
+    /// redacted.
+    pub struct SigningNonces {
+        identifier: Identifier,
+        hiding: Zeroizing<[u8; 32]>,
+        binding: Zeroizing<[u8; 32]>,
+    }
+
+    impl SigningNonces {
+        pub fn identifier(&self) -> Identifier {
+            self.identifier
+        }
+
+        /// The commitments to these nonces, as sent in round 1.
+        pub fn commitments(&self) -> SigningCommitments {
+            let commit = |nonce: &[u8; 32]| {
+                (basepoint() * scalar_from(nonce, None).expect("checked on construction"))
+                    .to_bytes()
+            };
+            SigningCommitments {
+                identifier: self.identifier,
+                hiding: commit(&self.hiding),
+                binding: commit(&self.binding),
+            }
+        }
+
+        /// `identifier` (little-endian u16), then the hiding and binding nonces.
+        pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+            let mut out = Zeroizing::new(Vec::with_capacity(NONCES_LEN));
+            out.extend_from_slice(&self.identifier.to_le_bytes());
+            out.extend_from_slice(self.hiding.as_ref());
+            out.extend_from_slice(self.binding.as_ref());
+            out
+        }
+
+        pub fn from_bytes(bytes: &[u8]) -> Result<Self, KeysError> {
+            if bytes.len() != NONCES_LEN {
+                return Err(frost_error(None, "malformed signing nonces"));
+            }
+            let mut hiding = Zeroizing::new([0u8; 32]);
+            hiding.copy_from_slice(&bytes[2..34]);
+            let mut binding = Zeroizing::new([0u8; 32]);
+            binding.copy_from_slice(&bytes[34..]);
+            scalar_from(&hiding, None)?;
+            scalar_from(&binding, None)?;
+            Ok(SigningNonces {
+                identifier: u16::from_le_bytes([bytes[0], bytes[1]]),
+                hiding,
+                binding,
+            })
+        }
+    }
+
+    impl fmt::Debug for SigningNonces {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("SigningNonces")
+                .field("identifier", &self.identifier)
+                .finish_non_exhaustive()
+        }
+    }
+
+    /// A signer's public commitments to its nonces, sent to the other signers.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct SigningCommitments {
+        pub identifier: Identifier,
+        pub hiding: [u8; 32],
+        pub binding: [u8; 32],
+    }
+
+    /// What every signer in a session must agree on before round 2.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct SigningPackage {
+        /// One entry per participating signer, in any order.
+        pub commitments: Vec<SigningCommitments>,
+        /// The bytes the signature covers, such as a transaction's sighash.
+        pub message: Vec<u8>,
+        /// The spend's `alpha`, a little-endian Pallas scalar.
+        pub randomizer: [u8; 32],
+    }
+
+    /// One signer's contribution to the signature.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct SignatureShare {
+        pub identifier: Identifier,
+        /// A little-endian Pallas scalar.
+        pub share: [u8; 32],
+    }
+
+    /// Samples fresh nonces for `key`'s signer.
+    pub fn round1<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        key: &KeyPackage,
+    ) -> (SigningNonces, SigningCommitments) {
+        // Hedged as in RFC 9591: a weak RNG alone doesn't leak the share.
+        let mut nonce = || {
+            let mut random = Zeroizing::new([0u8; 32]);
+            rng.fill_bytes(random.as_mut());
+            let wide = Zeroizing::new(blake2b_512(
+                b"JunoKeys_FrostNC",
+                &[random.as_ref(), key.signing_share.as_ref()],
+            ));
+            Zeroizing::new(pallas::Scalar::from_uniform_bytes(&wide).to_repr())
+        };
+        let nonces = SigningNonces {
+            identifier: key.identifier,
+            hiding: nonce(),
+            binding: nonce(),
+        };
+        let commitments = nonces.commitments();
+        (nonces, commitments)
+    }
+
+    /// Signs `package` as `key`'s signer, consuming `nonces`.
+    pub fn round2(
+        package: &SigningPackage,
+        nonces: SigningNonces,
+        key: &KeyPackage,
+    ) -> Result<SignatureShare, KeysError> {
+        let me = key.identifier;
+        if nonces.identifier != me {
+            return Err(frost_error(None, "nonces belong to another signer"));
+        }
+        let mut ak = [0u8; 32];
+        ak.copy_from_slice(&key.full_viewing_key[..32]);
+        let session = Session::new(package, key.min_signers, &ak)?;
+        if !package.commitments.contains(&nonces.commitments()) {
+            return Err(frost_error(
+                None,
+                "signing package lacks this signer's commitments",
+            ));
+        }
+        let share = scalar_from(&key.signing_share, None)?;
+        let hiding = scalar_from(&nonces.hiding, None)?;
+        let binding = scalar_from(&nonces.binding, None)?;
+        let z = hiding + binding * session.binding[&me] + session.lambda(me) * share * session.c;
+        Ok(SignatureShare {
+            identifier: me,
+            share: z.to_repr(),
+        })
+    }
+
+    /// Combines one share per signer in `package` into a signature `R || s`.
+    ///
+    /// If the result doesn't verify, each share is checked against its signer's
+    /// verifying share so the error can name the culprit.
+    pub fn aggregate(
+        package: &SigningPackage,
+        shares: &[SignatureShare],
+        public: &PublicKeyPackage,
+    ) -> Result<[u8; 64], KeysError> {
+        let session = Session::new(package, public.min_signers, &public.verifying_key())?;
+        let mut values = BTreeMap::new();
+        for share in shares {
+            let id = share.identifier;
+            if !session.commitments.contains_key(&id) {
+                return Err(frost_error(
+                    Some(id),
+                    "share from a signer not in the package",
+                ));
+            }
+            if values
+                .insert(id, scalar_from(&share.share, Some(id))?)
+                .is_some()
+            {
+                return Err(frost_error(Some(id), "duplicate signature share"));
+            }
+        }
+        if values.len() != session.commitments.len() {
+            return Err(frost_error(None, "missing signature shares"));
+        }
+
+        let alpha = scalar_from(&package.randomizer, None)?;
+        let s = values.values().sum::<pallas::Scalar>() + session.c * alpha;
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&session.r.to_bytes());
+        signature[32..].copy_from_slice(&s.to_repr());
+        let key = redpallas::VerificationKey::<SpendAuth>::try_from(session.rk.to_bytes())
+            .map_err(|_| KeysError::Internal)?;
+        if key.verify(&package.message, &signature.into()).is_ok() {
+            return Ok(signature);
+        }
+
+        let mut invalid = Vec::new();
+        for (id, z) in &values {
+            let (hiding, binding) = session.commitments[id];
+            let verifying_share = public
+                .verifying_shares
+                .get(id)
+                .ok_or(frost_error(Some(*id), "unknown signer"))?;
+            let expected = hiding
+                + binding * session.binding[id]
+                + point_from(verifying_share, Some(*id))? * (session.lambda(*id) * session.c);
+            if basepoint() * z != expected {
+                invalid.push(*id);
+            }
+        }
+        match invalid[..] {
+            [] => Err(KeysError::Internal),
+            // Every share failing points at this package, not at the signers.
+            _ if invalid.len() == values.len() => Err(frost_error(
+                None,
+                "shares were made for a different signing package",
+            )),
+            [culprit, ..] => Err(frost_error(Some(culprit), "invalid signature share")),
+        }
+    }
+
+    // The values every signer derives from a signing package.
+    struct Session {
+        commitments: BTreeMap<Identifier, (pallas::Point, pallas::Point)>,
+        binding: BTreeMap<Identifier, pallas::Scalar>,
+        rk: pallas::Point,
+        r: pallas::Point,
+        c: pallas::Scalar,
+    }
+
+    impl Session {
+        fn new(
+            package: &SigningPackage,
+            min_signers: u16,
+            ak: &[u8; 32],
+        ) -> Result<Self, KeysError> {
+            let mut commitments = BTreeMap::new();
+            for c in &package.commitments {
+                let id = c.identifier;
+                if id == 0 {
+                    return Err(frost_error(Some(id), "identifier must be nonzero"));
+                }
+                let points = (
+                    point_from(&c.hiding, Some(id))?,
+                    point_from(&c.binding, Some(id))?,
+                );
+                if commitments.insert(id, points).is_some() {
+                    return Err(frost_error(Some(id), "duplicate signing commitments"));
+                }
+            }
+            if commitments.len() < usize::from(min_signers) {
+                return Err(frost_error(None, "fewer than min_signers commitments"));
+            }
+
+            let alpha = scalar_from(&package.randomizer, None)?;
+            let rk = point_from(ak, None)? + basepoint() * alpha;
+            let rk_bytes = rk.to_bytes();
+            let length = (package.message.len() as u64).to_le_bytes();
+            let mut sorted = package.commitments.iter().collect::<Vec<_>>();
+            sorted.sort_by_key(|c| c.identifier);
+            let ids = sorted
+                .iter()
+                .map(|c| c.identifier.to_le_bytes())
+                .collect::<Vec<_>>();
+            let mut parts: Vec<&[u8]> = Vec::from([&rk_bytes[..], &length, &package.message]);
+            for (id, c) in ids.iter().zip(&sorted) {
+                parts.extend([&id[..], &c.hiding, &c.binding]);
+            }
+            let prefix = blake2b_512(b"JunoKeys_FrostBF", &parts);
+
+            let binding = sorted
+                .iter()
+                .zip(&ids)
+                .map(|(c, id)| {
+                    let wide = blake2b_512(b"JunoKeys_FrostBF", &[&prefix, id]);
+                    (c.identifier, pallas::Scalar::from_uniform_bytes(&wide))
+                })
+                .collect::<BTreeMap<_, _>>();
+            let r = commitments
+                .iter()
+                .map(|(id, (hiding, binding_point))| hiding + binding_point * binding[id])
+                .sum::<pallas::Point>();
+            let c = pallas::Scalar::from_uniform_bytes(&blake2b_512(
+                b"Zcash_RedPallasH",
+                &[&r.to_bytes(), &rk_bytes, &package.message],
+            ));
+            Ok(Session {
+                commitments,
+                binding,
+                rk,
+                r,
+                c,
+            })
+        }
+
+        // The Lagrange coefficient at zero for `signer` among this session's signers.
+        fn lambda(&self, signer: Identifier) -> pallas::Scalar {
+            let x = pallas::Scalar::from(u64::from(signer));
+            let (num, den) = self
+                .commitments
+                .keys()
+                .filter(|id| **id != signer)
+                .map(|id| pallas::Scalar::from(u64::from(*id)))
+                .fold(
+                    (pallas::Scalar::ONE, pallas::Scalar::ONE),
+                    |(num, den), xj| (num * xj, den * (xj - x)),
+                );
+            num * den.invert().expect("identifiers are distinct")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+
+    use super::*;
+    use crate::Network;
+
+    // Interpolates f(0) from `shares`, checking that it matches `ak`.
+    fn reconstructs_ak(shares: &[KeyPackage], ak: [u8; 32]) -> bool {
+        let xs = shares
+            .iter()
+            .map(|s| pallas::Scalar::from(u64::from(s.identifier)))
+            .collect::<Vec<_>>();
+        let mut secret = pallas::Scalar::ZERO;
+        for (i, share) in shares.iter().enumerate() {
+            let mut lambda = pallas::Scalar::ONE;
+            for (j, xj) in xs.iter().enumerate() {
+                if i != j {
+                    lambda *= xj * (xj - xs[i]).invert().unwrap();
+                }
+            }
+            secret += lambda * scalar_from(&share.signing_share, None).expect("share");
+        }
+        (basepoint() * secret).to_bytes() == ak
+    }
+
+    #[test]
+    fn dealer_shares_reconstruct_the_group_key() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let (shares, public) = trusted_dealer(&mut rng, 2, 3).expect("dealer");
+        assert_eq!(shares.len(), 3);
+        let ak = public.verifying_key();
+        assert!(reconstructs_ak(&shares[..2], ak));
+        assert!(reconstructs_ak(&shares[1..], ak));
+        assert!(!reconstructs_ak(&shares[..1], ak));
+        public.ufvk(&Network::Testnet).expect("group ufvk");
+        assert!(trusted_dealer(&mut rng, 1, 3).is_err());
+    }
+
+    #[test]
+    fn dkg_agrees_on_the_group_key() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(9);
+        let (secrets, round1): (Vec<_>, Vec<_>) = (1..=3)
+            .map(|id| dkg::part1(&mut rng, id, 2, 3).expect("part1"))
+            .unzip();
+        let others = |id: Identifier| {
+            round1
+                .iter()
+                .filter(|p| p.identifier != id)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let round2 = secrets
+            .iter()
+            .flat_map(|s| dkg::part2(s, &others(s.identifier())).expect("part2"))
+            .collect::<Vec<_>>();
+        let inbox = |id: Identifier| {
+            round2
+                .iter()
+                .filter(|p| p.recipient == id)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let results = secrets
+            .iter()
+            .map(|s| {
+                let s = dkg::Round1Secret::from_bytes(&s.to_bytes()).expect("round trip");
+                dkg::part3(&s, &others(s.identifier()), &inbox(s.identifier())).expect("part3")
+            })
+            .collect::<Vec<_>>();
+        let public = results[0].1.clone();
+        assert!(results.iter().all(|(_, p)| *p == public));
+        let shares = results.into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+        assert!(reconstructs_ak(&shares[..2], public.verifying_key()));
+        public.ufvk(&Network::Mainnet).expect("group ufvk");
+
+        let mut tampered = inbox(1);
+        tampered[0].signing_share = Zeroizing::new(pallas::Scalar::ONE.to_repr());
+        let err = dkg::part3(&secrets[0], &others(1), &tampered).expect_err("bad share");
+        assert!(matches!(
+            err,
+            KeysError::Frost {
+                culprit: Some(_),
+                ..
+            }
+        ));
+        let mut forged = others(1);
+        forged[0].proof[40] ^= 1;
+        assert!(dkg::part2(&secrets[0], &forged).is_err());
+    }
+
+    #[test]
+    fn threshold_signatures_verify_under_the_group_key() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let (shares, public) = trusted_dealer(&mut rng, 2, 3).expect("dealer");
+        let signers = [&shares[0], &shares[2]];
+        let session = |rng: &mut rand::rngs::StdRng, message: &[u8], randomizer| {
+            let (nonces, commitments): (Vec<_>, Vec<_>) =
+                signers.iter().map(|key| sign::round1(rng, key)).unzip();
+            let package = sign::SigningPackage {
+                commitments,
+                message: message.to_vec(),
+                randomizer,
+            };
+            let shares = nonces
+                .into_iter()
+                .zip(signers)
+                .map(|(n, key)| {
+                    let n = sign::SigningNonces::from_bytes(&n.to_bytes()).expect("round trip");
+                    sign::round2(&package, n, key).expect("round2")
+                })
+                .collect::<Vec<_>>();
+            (package, shares)
+        };
+
+        let digest = crate::message::message_digest(b"juno");
+        let (package, sig_shares) = session(&mut rng, &digest, [0u8; 32]);
+        let signature = sign::aggregate(&package, &sig_shares, &public).expect("aggregate");
+        let ufvk = public.ufvk(&Network::Mainnet).expect("group ufvk");
+        ufvk.verify_message(b"juno", &signature)
+            .expect("verifies under ak");
+
+        let alpha = pallas::Scalar::from(5u64);
+        let (package, mut sig_shares) = session(&mut rng, &[9u8; 32], alpha.to_repr());
+        let signature = sign::aggregate(&package, &sig_shares, &public).expect("aggregate");
+        let rk = redpallas::VerificationKey::<SpendAuth>::try_from(public.verifying_key())
+            .expect("ak")
+            .randomize(&alpha);
+        rk.verify(&[9u8; 32], &signature.into())
+            .expect("verifies under rk");
+
+        sig_shares[1].share = pallas::Scalar::ONE.to_repr();
+        assert!(matches!(
+            sign::aggregate(&package, &sig_shares, &public),
+            Err(KeysError::Frost {
+                culprit: Some(3),
+                ..
+            })
+        ));
+        assert!(sign::aggregate(&package, &sig_shares[..1], &public).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Conversions to the `orchard` crate's public types, for services that already use
+//! them; enabled by the `interop` feature.
+//!
+//! Going the other way needs an HRP, so use [`Ufvk::from_orchard`] and
+//! [`UnifiedAddress::from_receivers`].
+
+use orchard::keys::{FullViewingKey, SpendingKey};
+
+use crate::{KeysError, OrchardKeySet, Ufvk, UnifiedAddress};
+
+/// Fails with `EncodingInvalid` if the key has no Orchard item.
+impl TryFrom<&Ufvk> for FullViewingKey {
+    type Error = KeysError;
+
+    fn try_from(ufvk: &Ufvk) -> Result<Self, Self::Error> {
+        ufvk.orchard().ok_or(KeysError::EncodingInvalid)
+    }
+}
+
+/// Fails with `EncodingInvalid` if the address has no Orchard receiver.
+impl TryFrom<&UnifiedAddress> for orchard::Address {
+    type Error = KeysError;
+
+    fn try_from(address: &UnifiedAddress) -> Result<Self, Self::Error> {
+        address.orchard().ok_or(KeysError::EncodingInvalid)
+    }
+}
+
+impl From<&OrchardKeySet> for FullViewingKey {
+    fn from(keys: &OrchardKeySet) -> Self {
+        keys.full_viewing_key()
+    }
+}
+
+impl From<&OrchardKeySet> for SpendingKey {
+    fn from(keys: &OrchardKeySet) -> Self {
+        keys.spending_key()
+    }
+}
+
+// Fallible because a hardened process must be able to lock the copy; see
+// `secret::harden`.
+impl TryFrom<&SpendingKey> for OrchardKeySet {
+    type Error = KeysError;
+
+    fn try_from(sk: &SpendingKey) -> Result<Self, Self::Error> {
+        OrchardKeySet::from_orchard(sk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Network;
+
+    #[test]
+    fn converts_to_orchard_types() {
+        let keys = OrchardKeySet::from_seed(&[7u8; 32][..], &Network::Mainnet, 0).expect("keys");
+        let ufvk = keys.ufvk(&Network::Mainnet).expect("ufvk");
+        let fvk = FullViewingKey::try_from(&ufvk).expect("fvk");
+        assert_eq!(fvk, FullViewingKey::from(&keys));
+        assert_eq!(
+            OrchardKeySet::try_from(&SpendingKey::from(&keys)).expect("from sk"),
+            keys
+        );
+
+        let address = ufvk.address_at(0u32).expect("address");
+        assert_eq!(
+            orchard::Address::try_from(&address).expect("receiver"),
+            fvk.address_at(0u32, orchard::keys::Scope::External)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use core::fmt;
+
+use orchard::keys::{FullViewingKey, IncomingViewingKey, OutgoingViewingKey, Scope, SpendingKey};
+use zcash_spec::{PrfExpand, VariableLengthSlice};
+use zeroize::Zeroizing;
+use zip32::hardened_only::{Context, HardenedOnlyKey};
+
+use crate::secret::SecretBuf;
+use crate::{
+    AccountRange, ChildIndex, KeysError, NetworkParams, Seed, Ufvk, ZipPath, ORCHARD_FVK_LEN,
+};
+
+const ORCHARD_SK_LEN: usize = 32;
+const ORCHARD_IVK_LEN: usize = 64;
+const ORCHARD_OVK_LEN: usize = 32;
+
+// Orchard's ZIP-32 derivation context; `orchard` only exposes it for account paths.
+struct OrchardZip32;
+
+impl Context for OrchardZip32 {
+    const MKG_DOMAIN: [u8; 16] = *b"ZcashIP32Orchard";
+    const CKD_DOMAIN: PrfExpand<([u8; 32], [u8; 4], [u8; 1], VariableLengthSlice)> =
+        PrfExpand::ORCHARD_ZIP32_CHILD;
+}
+
+/// The Orchard keys for one account, derived together from a single spending key.
+///
+/// Every component is held as bytes that are zeroized on drop, the spending key in a
+/// [`SecretBuf`]; accessors rebuild the `orchard` types on demand. `Debug` is redacted.
+/// Copies go through [`Self::try_clone`], which can fail in a hardened process.
+pub struct OrchardKeySet {
+    sk: SecretBuf,
+    fvk: Zeroizing<[u8; ORCHARD_FVK_LEN]>,
+    external_ivk: Zeroizing<[u8; ORCHARD_IVK_LEN]>,
+    internal_ivk: Zeroizing<[u8; ORCHARD_IVK_LEN]>,
+    external_ovk: Zeroizing<[u8; ORCHARD_OVK_LEN]>,
+    internal_ovk: Zeroizing<[u8; ORCHARD_OVK_LEN]>,
+}
+
+impl OrchardKeySet {
+    /// Length of the raw spending key, the key set's byte representation.
+    pub const SPENDING_KEY_LEN: usize = ORCHARD_SK_LEN;
+
+    /// Derives the ZIP-32 account key set for `network`'s coin type.
+    ///
+    /// `seed` may be a [`Seed`] or raw bytes.
+    pub fn from_seed<S: AsRef<[u8]> + ?Sized, N: NetworkParams + ?Sized>(
+        seed: &S,
+        network: &N,
+        account: u32,
+    ) -> Result<Self, KeysError> {
+        Self::from_path(seed, &ZipPath::account(network.coin_type(), account)?)
+    }
+
+    /// Derives the key set at any hardened `path`, including levels past the account.
+    ///
+    /// Fails with `SeedInvalid` if any key along the path is not a valid Orchard
+    /// spending key, matching `orchard`'s own account derivation.
+    pub fn from_path<S: AsRef<[u8]> + ?Sized>(seed: &S, path: &ZipPath) -> Result<Self, KeysError> {
+        let seed = seed.as_ref();
+        crate::check_len("seed", seed.len(), Seed::MIN_LEN, Seed::MAX_LEN)?;
+        let mut key = HardenedOnlyKey::<OrchardZip32>::master(&[seed]);
+        let mut sk = Self::spending_key_of(&key)?;
+        for index in path.child_indices() {
+            key = key.derive_child(index);
+            sk = Self::spending_key_of(&key)?;
+        }
+        Self::from_orchard(&sk)
+    }
+
+    /// Derives the key sets of `accounts` for `network`'s coin type, in order.
+    ///
+    /// The master and coin-type keys are derived once, here, so each account costs one
+    /// child derivation; the seed isn't borrowed past this call.
+    pub fn from_seed_accounts<S: AsRef<[u8]> + ?Sized, N: NetworkParams + ?Sized>(
+        seed: &S,
+        network: &N,
+        accounts: AccountRange,
+    ) -> Result<impl Iterator<Item = Result<Self, KeysError>>, KeysError> {
+        let seed = seed.as_ref();
+        crate::check_len("seed", seed.len(), Seed::MIN_LEN, Seed::MAX_LEN)?;
+        let path = ZipPath::account(network.coin_type(), accounts.start())?;
+        let mut key = HardenedOnlyKey::<OrchardZip32>::master(&[seed]);
+        Self::spending_key_of(&key)?;
+        // m/32'/coin_type', the path above every account.
+        for index in &path.child_indices()[..2] {
+            key = key.derive_child(*index);
+            Self::spending_key_of(&key)?;
+        }
+        Ok(accounts.into_iter().map(move |account| {
+            let sk = Self::spending_key_of(&key.derive_child(ChildIndex::hardened(account)))?;
+            Self::from_orchard(&sk)
+        }))
+    }
+
+    fn spending_key_of(key: &HardenedOnlyKey<OrchardZip32>) -> Result<SpendingKey, KeysError> {
+        Option::from(SpendingKey::from_bytes(*key.parts().0)).ok_or(KeysError::SeedInvalid)
+    }
+
+    /// Builds the key set for raw 32-byte Orchard spending key material.
+    pub fn from_spending_key(spending_key: &[u8]) -> Result<Self, KeysError> {
+        crate::check_len(
+            "spending_key",
+            spending_key.len(),
+            ORCHARD_SK_LEN,
+            ORCHARD_SK_LEN,
+        )?;
+        let bytes = Zeroizing::new(
+            <[u8; ORCHARD_SK_LEN]>::try_from(spending_key)
+                .map_err(|_| KeysError::SpendingKeyInvalid)?,
+        );
+        let sk = Option::<SpendingKey>::from(SpendingKey::from_bytes(*bytes))
+            .ok_or(KeysError::SpendingKeyInvalid)?;
+        Self::from_orchard(&sk)
+    }
+
+    pub(crate) fn from_orchard(sk: &SpendingKey) -> Result<Self, KeysError> {
+        let fvk = FullViewingKey::from(sk);
+        Ok(OrchardKeySet {
+            sk: SecretBuf::from_slice(sk.to_bytes())?,
+            fvk: Zeroizing::new(fvk.to_bytes()),
+            external_ivk: Zeroizing::new(fvk.to_ivk(Scope::External).to_bytes()),
+            internal_ivk: Zeroizing::new(fvk.to_ivk(Scope::Internal).to_bytes()),
+            external_ovk: Zeroizing::new(*fvk.to_ovk(Scope::External).as_ref()),
+            internal_ovk: Zeroizing::new(*fvk.to_ovk(Scope::Internal).as_ref()),
+        })
+    }
+
+    /// A copy of the key set, failing with `hardening_unavailable` in a hardened process
+    /// if the copy of the spending key can't be locked.
+    pub fn try_clone(&self) -> Result<Self, KeysError> {
+        Ok(OrchardKeySet {
+            sk: self.sk.try_clone()?,
+            fvk: self.fvk.clone(),
+            external_ivk: self.external_ivk.clone(),
+            internal_ivk: self.internal_ivk.clone(),
+            external_ovk: self.external_ovk.clone(),
+            internal_ovk: self.internal_ovk.clone(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Zeroizing<[u8; ORCHARD_SK_LEN]> {
+        let mut sk = Zeroizing::new([0u8; ORCHARD_SK_LEN]);
+        sk.copy_from_slice(&self.sk);
+        sk
+    }
+
+    pub fn spending_key(&self) -> SpendingKey {
+        Option::from(SpendingKey::from_bytes(*self.to_bytes())).expect("checked on construction")
+    }
+
+    pub fn full_viewing_key(&self) -> FullViewingKey {
+        FullViewingKey::from_bytes(&self.fvk).expect("checked on construction")
+    }
+
+    pub fn incoming_viewing_key(&self, scope: Scope) -> IncomingViewingKey {
+        let bytes = match scope {
+            Scope::External => &self.external_ivk,
+            Scope::Internal => &self.internal_ivk,
+        };
+        Option::from(IncomingViewingKey::from_bytes(bytes)).expect("checked on construction")
+    }
+
+    pub fn outgoing_viewing_key(&self, scope: Scope) -> OutgoingViewingKey {
+        match scope {
+            Scope::External => OutgoingViewingKey::from(*self.external_ovk),
+            Scope::Internal => OutgoingViewingKey::from(*self.internal_ovk),
+        }
+    }
+
+    /// The diversifier key `dk`, i.e. the first half of the scope's encoded IVK.
+    pub fn diversifier_key(&self, scope: Scope) -> Zeroizing<[u8; 32]> {
+        let ivk = match scope {
+            Scope::External => &self.external_ivk,
+            Scope::Internal => &self.internal_ivk,
+        };
+        let mut dk = Zeroizing::new([0u8; 32]);
+        dk.copy_from_slice(&ivk[..32]);
+        dk
+    }
+
+    /// The UFVK for this key set under `network`'s HRPs.
+    pub fn ufvk<N: NetworkParams + ?Sized>(&self, network: &N) -> Result<Ufvk, KeysError> {
+        Ufvk::from_orchard(network.ua_hrp(), &self.full_viewing_key())
+    }
+}
+
+impl TryFrom<&[u8]> for OrchardKeySet {
+    type Error = KeysError;
+
+    fn try_from(spending_key: &[u8]) -> Result<Self, Self::Error> {
+        OrchardKeySet::from_spending_key(spending_key)
+    }
+}
+
+// The other components are all derived from the spending key.
+impl PartialEq for OrchardKeySet {
+    fn eq(&self, other: &Self) -> bool {
+        crate::ct_eq(&self.sk[..], &other.sk[..])
+    }
+}
+
+impl Eq for OrchardKeySet {}
+
+impl fmt::Debug for OrchardKeySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OrchardKeySet(..)")
+    }
+}
@@ -1,88 +1,308 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(warnings)]
 
+extern crate alloc;
+
+mod address;
+mod app_key;
+mod derive;
+mod descriptor;
+mod diversifier;
+mod error_code;
+pub mod frost;
+#[cfg(feature = "interop")]
+pub mod interop;
+mod keyset;
+mod message;
+mod network;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+mod path;
+mod sealed;
+pub mod secret;
+mod seed;
+pub mod selftest;
+mod shard;
+pub mod slip44;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod ufvk;
+mod usk;
+pub mod vectors;
 pub mod zip316;
+pub mod zip321;
 
-use base64::Engine as _;
-use orchard::keys::{FullViewingKey, SpendingKey};
-use rand::RngCore as _;
-use thiserror::Error;
-use zeroize::Zeroize;
+pub use address::UnifiedAddress;
+pub use app_key::{app_key, APP_KEY_CONTEXT_MAX_LEN};
+/// A BIP-39 wordlist; every standard list is built in.
+pub use bip39::Language as MnemonicLanguage;
+pub use derive::{DerivationRequest, DerivedKeys};
+pub use descriptor::WalletDescriptor;
+pub use diversifier::{parse_diversifier_index, DiversifierIndex, MAX_DIVERSIFIER_INDEX};
+pub use error_code::ErrorCode;
+pub use keyset::OrchardKeySet;
+pub use message::{message_digest, ownership_statement, MessageSignature};
+pub use network::{
+    detect_network, detect_zcash_network, CustomNetwork, Network, NetworkParams, ZcashNetwork,
+};
+pub use path::{hardened_child, AccountRange, ChildIndex, HardeningPolicy, ZipPath, ZIP32_PURPOSE};
+#[cfg(feature = "std")]
+pub use sealed::encrypt_seed;
+pub use sealed::{decrypt_seed, encrypt_seed_with, is_encrypted_seed, SeedKdf, ENCRYPTED_SEED_HRP};
+pub use secrecy::{ExposeSecret, SecretString};
+#[cfg(feature = "secret-accounting")]
+pub use secret::accounting as secret_accounting;
+#[cfg(feature = "std")]
+pub use seed::generate_mnemonic;
+pub use seed::{generate_mnemonic_with, normalize_secret, Seed, WalletCompat};
+#[cfg(feature = "std")]
+pub use shard::split_seed;
+pub use shard::{combine_seed, split_seed_with, SeedShare, SEED_SHARE_HRP};
+pub use ufvk::{AddressGenerator, AddressLocator, Ufvk};
+pub use usk::UnifiedSpendingKey;
+
+// Services share keys across threads and tasks; keep every public key type usable there.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Seed>();
+    assert_send_sync::<Ufvk>();
+    assert_send_sync::<UnifiedAddress>();
+    assert_send_sync::<AddressGenerator>();
+    assert_send_sync::<AddressLocator>();
+    assert_send_sync::<OrchardKeySet>();
+    assert_send_sync::<UnifiedSpendingKey>();
+    assert_send_sync::<DerivedKeys>();
+    assert_send_sync::<DerivationRequest<'static>>();
+    assert_send_sync::<ZipPath>();
+    assert_send_sync::<Network>();
+    assert_send_sync::<KeysError>();
+};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
 use zeroize::Zeroizing;
 
-const TYPECODE_ORCHARD: u64 = 3;
+// Key types serialize as their canonical string encodings and re-validate on the way in.
+#[cfg(feature = "serde")]
+macro_rules! serde_via_str {
+    ($($ty:ty),*) => {$(
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "serde")]
+serde_via_str!(Network, Ufvk, UnifiedAddress, WalletDescriptor, ZipPath);
+use thiserror::Error;
+
 const ORCHARD_FVK_LEN: usize = 96;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum Network {
-    Mainnet,
-    Testnet,
-    Regtest,
+const UFVK_FP_PERSONALIZATION: &[u8; 16] = b"JunoKeys_UFVK_FP";
+
+/// Result of re-encoding a Zcash unified encoding under Juno HRPs.
+#[derive(Clone, Debug)]
+pub struct ConvertedEncoding {
+    pub network: Network,
+    pub is_ufvk: bool,
+    pub typecodes: Vec<u64>,
+    pub encoded: String,
 }
 
-impl Network {
-    pub fn ua_hrp(&self) -> &'static str {
-        match self {
-            Network::Mainnet => "j",
-            Network::Testnet => "jtest",
-            Network::Regtest => "jregtest",
-        }
-    }
+/// Re-encodes a Zcash UA/UFVK under the matching Juno HRP, preserving every item as-is.
+///
+/// The underlying keys are unchanged (they stay bound to Zcash's coin type); only the
+/// encoding moves to Juno's prefixes.
+pub fn convert_from_zcash(encoded: &str) -> Result<ConvertedEncoding, KeysError> {
+    let encoded = encoded.trim().to_ascii_lowercase();
+    let hrp = network::encoded_hrp(&encoded)?;
+    let network = Network::from_zcash_hrp(&hrp).ok_or(KeysError::NetworkUnknown)?;
+    let is_ufvk = hrp == network.zcash_ufvk_hrp();
+    let juno_hrp = if is_ufvk {
+        network.ufvk_hrp()
+    } else {
+        network.ua_hrp()
+    };
 
-    pub fn coin_type(&self) -> u32 {
-        match self {
-            Network::Mainnet => 8133,
-            Network::Testnet => 8134,
-            Network::Regtest => 8135,
-        }
-    }
+    let items = zip316::decode_unified_container(&hrp, &encoded)?;
+    let tlvs = items
+        .iter()
+        .map(|(typecode, value)| zip316::Tlv {
+            typecode: *typecode,
+            value,
+        })
+        .collect::<Vec<_>>();
+    let encoded = zip316::encode_tlv_container(juno_hrp, &tlvs).map_err(|_| KeysError::Internal)?;
+
+    Ok(ConvertedEncoding {
+        network,
+        is_ufvk,
+        typecodes: items.iter().map(|(typecode, _)| *typecode).collect(),
+        encoded,
+    })
 }
 
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum KeysError {
     #[error("seed_invalid")]
     SeedInvalid,
+    #[error("base64_invalid: {0}")]
+    Base64(#[cfg_attr(feature = "std", source)] base64::DecodeError),
+    #[error("hex_invalid: {0}")]
+    Hex(#[cfg_attr(feature = "std", source)] hex::FromHexError),
+    #[error("mnemonic_invalid: {0}")]
+    Mnemonic(#[cfg_attr(feature = "std", source)] bip39::Error),
+    /// `what` names the input (`"seed"`, `"spending_key"`).
+    #[error("{what} length {len} is outside {min}..={max}")]
+    LengthOutOfRange {
+        what: &'static str,
+        len: usize,
+        min: usize,
+        max: usize,
+    },
     #[error("ua_hrp_invalid")]
     UAHrpInvalid,
     #[error("coin_type_invalid")]
     CoinTypeInvalid,
+    #[error("coin_type_registered")]
+    CoinTypeRegistered,
     #[error("account_invalid")]
     AccountInvalid,
+    /// A ZIP-32 path is malformed or has the wrong shape.
+    #[error("path_invalid")]
+    PathInvalid,
+    /// A ZIP-32 path requests non-hardened derivation, which Orchard doesn't allow.
+    #[error("path_not_hardened")]
+    NonHardenedPath,
+    #[error("spending_key_invalid")]
+    SpendingKeyInvalid,
+    #[error("network_unknown")]
+    NetworkUnknown,
+    #[error("encoding_invalid")]
+    EncodingInvalid,
+    /// A container's bech32m layer (charset, checksum or HRP) is invalid.
+    #[error("encoding_invalid: {0}")]
+    Bech32(#[source] zip316::Zip316Error),
+    /// A container failed to decode past the bech32m layer; see
+    /// [`zip316::Zip316Error::diagnostic`].
+    #[error("encoding_invalid: {0}")]
+    Parse(#[source] zip316::Zip316Error),
+    /// A message signature doesn't verify against the given key.
+    #[error("signature_invalid")]
+    SignatureInvalid,
+    /// A FROST key generation input is malformed or fails verification; `culprit` is
+    /// the participant it came from, when known.
+    #[error("frost_invalid: {reason}{}", culprit.map(|id| alloc::format!(" (participant {id})")).unwrap_or_default())]
+    Frost {
+        culprit: Option<u16>,
+        reason: &'static str,
+    },
+    /// A ZIP-321 payment request or one of its parameters is invalid.
+    #[error("payment_request_invalid: {0}")]
+    PaymentRequest(&'static str),
+    /// A diversifier index is malformed or not below 2^88.
+    #[error("diversifier_index_invalid")]
+    DiversifierIndexInvalid,
+    /// A wallet descriptor is malformed or its checksum doesn't match.
+    #[error("descriptor_invalid: {0}")]
+    Descriptor(&'static str),
+    /// An encrypted seed (`jseedenc1...`) is malformed or its passphrase is wrong.
+    #[error("encrypted_seed_invalid: {0}")]
+    EncryptedSeed(&'static str),
+    /// Seed shares (`jshare1...`) are malformed, too few, or not from one split.
+    #[error("seed_share_invalid: {0}")]
+    SeedShare(&'static str),
+    /// [`secret::harden`] was refused, or a hardened process couldn't lock a secret.
+    #[error("hardening_unavailable: {0}")]
+    HardeningUnavailable(&'static str),
     #[error("internal")]
     Internal,
 }
 
 impl KeysError {
     pub fn code(&self) -> &'static str {
+        self.error_code().as_str()
+    }
+
+    pub fn error_code(&self) -> ErrorCode {
         match self {
-            KeysError::SeedInvalid => "seed_invalid",
-            KeysError::UAHrpInvalid => "ua_hrp_invalid",
-            KeysError::CoinTypeInvalid => "coin_type_invalid",
-            KeysError::AccountInvalid => "account_invalid",
-            KeysError::Internal => "internal",
+            // Seeds are the only base64/hex/mnemonic inputs, so keep their stable code.
+            KeysError::SeedInvalid
+            | KeysError::Base64(_)
+            | KeysError::Hex(_)
+            | KeysError::Mnemonic(_)
+            | KeysError::LengthOutOfRange { what: "seed", .. } => ErrorCode::SeedInvalid,
+            KeysError::LengthOutOfRange {
+                what: "spending_key",
+                ..
+            } => ErrorCode::SpendingKeyInvalid,
+            KeysError::LengthOutOfRange { .. } => ErrorCode::LengthOutOfRange,
+            KeysError::UAHrpInvalid => ErrorCode::UaHrpInvalid,
+            KeysError::CoinTypeInvalid => ErrorCode::CoinTypeInvalid,
+            KeysError::CoinTypeRegistered => ErrorCode::CoinTypeRegistered,
+            KeysError::AccountInvalid => ErrorCode::AccountInvalid,
+            KeysError::PathInvalid => ErrorCode::PathInvalid,
+            KeysError::NonHardenedPath => ErrorCode::PathNotHardened,
+            KeysError::SpendingKeyInvalid => ErrorCode::SpendingKeyInvalid,
+            KeysError::NetworkUnknown => ErrorCode::NetworkUnknown,
+            KeysError::EncodingInvalid | KeysError::Bech32(_) | KeysError::Parse(_) => {
+                ErrorCode::EncodingInvalid
+            }
+            KeysError::SignatureInvalid => ErrorCode::SignatureInvalid,
+            KeysError::Frost { .. } => ErrorCode::FrostInvalid,
+            KeysError::PaymentRequest(_) => ErrorCode::PaymentRequestInvalid,
+            KeysError::DiversifierIndexInvalid => ErrorCode::DiversifierIndexInvalid,
+            KeysError::Descriptor(_) => ErrorCode::DescriptorInvalid,
+            KeysError::EncryptedSeed(_) => ErrorCode::EncryptedSeedInvalid,
+            KeysError::SeedShare(_) => ErrorCode::SeedShareInvalid,
+            KeysError::HardeningUnavailable(_) => ErrorCode::HardeningUnavailable,
+            KeysError::Internal => ErrorCode::Internal,
         }
     }
 }
 
-pub fn generate_seed_base64(bytes: usize) -> Result<Zeroizing<String>, KeysError> {
-    if !(32..=252).contains(&bytes) {
-        return Err(KeysError::SeedInvalid);
+impl From<KeysError> for ErrorCode {
+    fn from(err: KeysError) -> Self {
+        err.error_code()
     }
+}
 
-    let mut seed = Zeroizing::new(vec![0u8; bytes]);
-    rand::rngs::OsRng.fill_bytes(seed.as_mut_slice());
-
-    let b64 = base64::engine::general_purpose::STANDARD.encode(seed.as_slice());
-    Ok(Zeroizing::new(b64))
+impl From<zip316::Zip316Error> for KeysError {
+    fn from(e: zip316::Zip316Error) -> Self {
+        match e.diagnostic() {
+            Some(d) if d.stage == zip316::ParseStage::Bech32 => KeysError::Bech32(e),
+            _ => KeysError::Parse(e),
+        }
+    }
 }
 
-pub fn decode_seed_base64(seed_base64: &str) -> Result<Zeroizing<Vec<u8>>, KeysError> {
-    let bytes = base64::engine::general_purpose::STANDARD
-        .decode(seed_base64.trim())
-        .map_err(|_| KeysError::SeedInvalid)?;
-    if !(32..=252).contains(&bytes.len()) {
-        return Err(KeysError::SeedInvalid);
+pub(crate) fn check_len(
+    what: &'static str,
+    len: usize,
+    min: usize,
+    max: usize,
+) -> Result<(), KeysError> {
+    if !(min..=max).contains(&len) {
+        return Err(KeysError::LengthOutOfRange {
+            what,
+            len,
+            min,
+            max,
+        });
     }
-    Ok(Zeroizing::new(bytes))
+    Ok(())
 }
 
 pub fn ufvk_hrp_from_ua_hrp(ua_hrp: &str) -> Result<String, KeysError> {
@@ -90,97 +310,1078 @@ pub fn ufvk_hrp_from_ua_hrp(ua_hrp: &str) -> Result<String, KeysError> {
     if hrp.is_empty() {
         return Err(KeysError::UAHrpInvalid);
     }
-    if hrp == "j" {
-        return Ok("jview".to_string());
-    }
-    let Some(suffix) = hrp.strip_prefix('j') else {
+    // Juno HRPs are `j*`; `u*` is accepted for Zcash compatibility (`u` -> `uview`).
+    let (prefix, suffix) = if let Some(suffix) = hrp.strip_prefix('j') {
+        ("jview", suffix)
+    } else if let Some(suffix) = hrp.strip_prefix('u') {
+        ("uview", suffix)
+    } else {
         return Err(KeysError::UAHrpInvalid);
     };
-    if suffix.is_empty() {
-        return Ok("jview".to_string());
+    Ok(format!("{prefix}{suffix}"))
+}
+
+pub fn uivk_hrp_from_ua_hrp(ua_hrp: &str) -> Result<String, KeysError> {
+    let ufvk_hrp = ufvk_hrp_from_ua_hrp(ua_hrp)?;
+    Ok(ufvk_hrp.replacen("view", "ivk", 1))
+}
+
+/// The [`UnifiedSpendingKey`] HRP for a UA HRP: `j` -> `jsecret`, `utest` -> `usecrettest`.
+pub fn usk_hrp_from_ua_hrp(ua_hrp: &str) -> Result<String, KeysError> {
+    let ufvk_hrp = ufvk_hrp_from_ua_hrp(ua_hrp)?;
+    Ok(ufvk_hrp.replacen("view", "secret", 1))
+}
+
+pub fn ua_hrp_from_usk_hrp(usk_hrp: &str) -> Result<String, KeysError> {
+    let hrp = usk_hrp.trim();
+    for prefix in ["jsecret", "usecret"] {
+        if let Some(suffix) = hrp.strip_prefix(prefix) {
+            return Ok(format!("{}{suffix}", &prefix[..1]));
+        }
     }
-    Ok(format!("jview{suffix}"))
+    Err(KeysError::UAHrpInvalid)
 }
 
-pub fn ufvk_from_seed_base64(
-    seed_base64: &str,
-    ua_hrp: &str,
-    coin_type: u32,
+pub fn ua_hrp_from_ufvk_hrp(ufvk_hrp: &str) -> Result<String, KeysError> {
+    let hrp = ufvk_hrp.trim();
+    for prefix in ["jview", "uview"] {
+        if let Some(suffix) = hrp.strip_prefix(prefix) {
+            return Ok(format!("{}{suffix}", &prefix[..1]));
+        }
+    }
+    Err(KeysError::UAHrpInvalid)
+}
+
+/// The keys at an arbitrary ZIP-32 path, for reproducing another wallet's scheme.
+///
+/// A [`ChildIndex`] is always hardened, as Orchard requires; build one with
+/// [`hardened_child`]. To take a path as text such as `m/32h/8133h/0h/5h`, use
+/// [`ZipPath::parse`], which fails with [`KeysError::NonHardenedPath`] on an unmarked
+/// level, and [`OrchardKeySet::from_path`].
+pub fn derive_path<S: AsRef<[u8]> + ?Sized>(
+    seed: &S,
+    path: &[ChildIndex],
+) -> Result<OrchardKeySet, KeysError> {
+    OrchardKeySet::from_path(seed, &ZipPath::from_child_indices(path))
+}
+
+/// Shorthand for a [`DerivationRequest`] that only needs the UFVK.
+///
+/// `seed` may be a [`Seed`] or raw bytes, so callers holding bytes needn't copy them.
+pub fn ufvk_from_seed<S: AsRef<[u8]> + ?Sized, N: NetworkParams + ?Sized>(
+    seed: &S,
+    network: &N,
     account: u32,
 ) -> Result<String, KeysError> {
-    if coin_type >= 0x8000_0000 {
-        return Err(KeysError::CoinTypeInvalid);
+    let keys = DerivationRequest::new(seed)
+        .network(network)
+        .account(account)
+        .build()?;
+    Ok(keys.ufvk.to_string())
+}
+
+/// The UFVK of each account in `accounts`, in order, for provisioning accounts in bulk.
+///
+/// The seed is read once and the ZIP-32 master and coin-type keys are derived once;
+/// see [`OrchardKeySet::from_seed_accounts`].
+pub fn derive_ufvk_batch<S: AsRef<[u8]> + ?Sized, N: NetworkParams + ?Sized>(
+    seed: &S,
+    network: &N,
+    accounts: AccountRange,
+) -> Result<Vec<Ufvk>, KeysError> {
+    OrchardKeySet::from_seed_accounts(seed, network, accounts)?
+        .map(|keys| keys?.ufvk(network))
+        .collect()
+}
+
+/// The external-scope address at each of `diversifier_indices` for each account in
+/// `accounts`, account by account, as `(account, diversifier index, address)`.
+///
+/// Each account's keys are derived once, as [`derive_ufvk_batch`] derives them, and
+/// its addresses come from one [`AddressGenerator`].
+pub fn derive_address_batch<S: AsRef<[u8]> + ?Sized, N: NetworkParams + ?Sized>(
+    seed: &S,
+    network: &N,
+    accounts: AccountRange,
+    diversifier_indices: Range<u64>,
+) -> Result<Vec<(u32, DiversifierIndex, UnifiedAddress)>, KeysError> {
+    let mut batch = Vec::new();
+    let keys = OrchardKeySet::from_seed_accounts(seed, network, accounts)?;
+    for (account, keys) in accounts.into_iter().zip(keys) {
+        let generator = keys?.ufvk(network)?.address_generator()?;
+        for j in diversifier_indices.clone() {
+            batch.push((account, DiversifierIndex::from(j), generator.address_at(j)?));
+        }
     }
-    if account >= 0x8000_0000 {
-        return Err(KeysError::AccountInvalid);
+    Ok(batch)
+}
+
+/// The account in `accounts` whose Orchard viewing key is `ufvk`'s, to check that a
+/// distributed UFVK came from `seed` without printing anything secret.
+///
+/// Keys are compared rather than encodings, so the UFVK's HRP doesn't matter; the
+/// coin type is `network`'s. Accounts are derived as [`derive_ufvk_batch`] does.
+pub fn find_ufvk_account<S: AsRef<[u8]> + ?Sized, N: NetworkParams + ?Sized>(
+    seed: &S,
+    network: &N,
+    ufvk: &Ufvk,
+    accounts: AccountRange,
+) -> Result<Option<u32>, KeysError> {
+    let Some(fvk) = ufvk.orchard() else {
+        return Ok(None);
+    };
+    let keys = OrchardKeySet::from_seed_accounts(seed, network, accounts)?;
+    for (account, keys) in accounts.into_iter().zip(keys) {
+        if ct_eq(&keys?.full_viewing_key().to_bytes(), &fvk.to_bytes()) {
+            return Ok(Some(account));
+        }
     }
+    Ok(None)
+}
 
-    let ufvk_hrp = ufvk_hrp_from_ua_hrp(ua_hrp)?;
+/// The account in `accounts` that derives `address`, with the address's scope and
+/// diversifier index.
+///
+/// The index is decrypted from the address's Orchard receiver, so there is no
+/// diversifier window to search; see [`AddressLocator::locate`].
+pub fn find_address_account<S: AsRef<[u8]> + ?Sized, N: NetworkParams + ?Sized>(
+    seed: &S,
+    network: &N,
+    address: &UnifiedAddress,
+    accounts: AccountRange,
+) -> Result<Option<(u32, orchard::keys::Scope, DiversifierIndex)>, KeysError> {
+    let keys = OrchardKeySet::from_seed_accounts(seed, network, accounts)?;
+    for (account, keys) in accounts.into_iter().zip(keys) {
+        let locator = keys?.ufvk(network)?.address_locator()?;
+        if let Some((scope, index)) = locator.locate(address) {
+            return Ok(Some((account, scope, index)));
+        }
+    }
+    Ok(None)
+}
 
-    let mut seed = decode_seed_base64(seed_base64)?;
-    let account = zip32::AccountId::try_from(account).map_err(|_| KeysError::AccountInvalid)?;
-    let sk = SpendingKey::from_zip32_seed(seed.as_slice(), coin_type, account)
-        .map_err(|_| KeysError::SeedInvalid)?;
-    seed.zeroize();
+/// The seed of a BIP-39 (ZIP-339) recovery phrase in any standard wordlist, with an
+/// optional passphrase; see [`Seed::from_mnemonic_in`].
+pub fn seed_from_mnemonic(phrase: &str, passphrase: &str) -> Result<Seed, KeysError> {
+    Seed::from_mnemonic_in(None, phrase, passphrase)
+}
 
-    let fvk = FullViewingKey::from(&sk);
-    let fvk_bytes = fvk.to_bytes();
-    if fvk_bytes.len() != ORCHARD_FVK_LEN {
-        return Err(KeysError::Internal);
-    }
+/// Builds the UFVK for raw 32-byte Orchard spending key material, bypassing ZIP-32.
+///
+/// For keys that didn't come from a seed on this machine, e.g. ones exported by a
+/// hardware device; addresses and the UIVK come from the returned [`Ufvk`].
+pub fn ufvk_from_spending_key<N: NetworkParams + ?Sized>(
+    spending_key: &[u8],
+    network: &N,
+) -> Result<Ufvk, KeysError> {
+    OrchardKeySet::from_spending_key(spending_key)?.ufvk(network)
+}
+
+/// Derives the external-scope unified address at `diversifier_index` from a UFVK.
+pub fn ua_from_ufvk(
+    ufvk: &str,
+    diversifier_index: impl Into<DiversifierIndex>,
+) -> Result<String, KeysError> {
+    Ok(Ufvk::parse(ufvk)?
+        .address_at(diversifier_index)?
+        .to_string())
+}
 
-    zip316::encode_unified_container(&ufvk_hrp, TYPECODE_ORCHARD, &fvk_bytes)
-        .map_err(|_| KeysError::Internal)
+/// Derives the external-scope unified address at `diversifier_index` of `account`,
+/// for a base64 seed as `seed new` writes it.
+pub fn ua_from_seed_base64<N: NetworkParams + ?Sized>(
+    seed_base64: &str,
+    network: &N,
+    account: u32,
+    diversifier_index: impl Into<DiversifierIndex>,
+) -> Result<String, KeysError> {
+    let seed = Seed::from_base64(seed_base64)?;
+    let keys = DerivationRequest::new(&seed)
+        .network(network)
+        .account(account)
+        .diversifier_index(diversifier_index)
+        .build()?;
+    Ok(keys.address.to_string())
+}
+
+/// The encoded unified spending key of `account`, for a base64 seed as `seed new`
+/// writes it; see [`UnifiedSpendingKey`].
+pub fn usk_from_seed_base64<N: NetworkParams + ?Sized>(
+    seed_base64: &str,
+    network: &N,
+    account: u32,
+) -> Result<Zeroizing<String>, KeysError> {
+    let seed = Seed::from_base64(seed_base64)?;
+    let keys = OrchardKeySet::from_seed(&seed, network, account)?;
+    let usk = UnifiedSpendingKey::from_keys(network.ua_hrp(), &keys)?;
+    Ok(Zeroizing::new(usk.encode().expose_secret().to_string()))
+}
+
+/// Derives the external-scope unified incoming viewing key from a UFVK.
+pub fn uivk_from_ufvk(ufvk: &str) -> Result<String, KeysError> {
+    Ufvk::parse(ufvk)?.uivk()
+}
+
+/// ZIP-32 seed fingerprint, bech32m-encoded (`zip32seedfp1...`).
+pub fn seed_fingerprint<S: AsRef<[u8]> + ?Sized>(seed: &S) -> Result<String, KeysError> {
+    check_len("seed", seed.as_ref().len(), Seed::MIN_LEN, Seed::MAX_LEN)?;
+    zip32::fingerprint::SeedFingerprint::from_seed(seed.as_ref())
+        .map(|fp| fp.to_string())
+        .ok_or(KeysError::SeedInvalid)
+}
+
+/// Compares key material (seeds, FVK bytes, encoded keys) in constant time.
+///
+/// Only the lengths may leak; a length mismatch returns `false` immediately.
+pub fn ct_eq<A: AsRef<[u8]> + ?Sized, B: AsRef<[u8]> + ?Sized>(a: &A, b: &B) -> bool {
+    subtle::ConstantTimeEq::ct_eq(a.as_ref(), b.as_ref()).into()
+}
+
+/// Hex BLAKE2b-256 over the decoded UFVK container items, independent of the HRP.
+pub fn ufvk_fingerprint(ufvk: &str) -> Result<String, KeysError> {
+    Ok(Ufvk::parse(ufvk)?.fingerprint())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::zip316::TYPECODE_ORCHARD;
+
+    struct RawParams(&'static str, u32);
+
+    impl NetworkParams for RawParams {
+        fn ua_hrp(&self) -> &str {
+            self.0
+        }
+
+        fn coin_type(&self) -> u32 {
+            self.1
+        }
+    }
 
     #[test]
-    fn seed_roundtrip_default_len() {
-        let seed_b64 = generate_seed_base64(64).expect("seed");
-        let seed = decode_seed_base64(&seed_b64).expect("decode");
+    fn seed_constructors() {
+        let seed = Seed::generate(64).expect("seed");
         assert_eq!(seed.len(), 64);
+        let decoded = Seed::from_base64(seed.to_base64().expose_secret()).expect("base64");
+        assert_eq!(decoded.as_bytes(), seed.as_bytes());
+        let decoded = Seed::from_hex(&hex::encode(seed.as_bytes())).expect("hex");
+        assert_eq!(decoded.as_bytes(), seed.as_bytes());
+        assert!(matches!(
+            Seed::from_bytes(&[0u8; 16]),
+            Err(KeysError::LengthOutOfRange {
+                what: "seed",
+                len: 16,
+                min: 32,
+                max: 252,
+            })
+        ));
+        assert!(matches!(
+            Seed::generate(253),
+            Err(KeysError::LengthOutOfRange { len: 253, .. })
+        ));
+        let err = Seed::from_base64("not base64!").expect_err("base64");
+        assert!(matches!(err, KeysError::Base64(_)));
+        assert_eq!(err.code(), "seed_invalid");
+        assert!(core::error::Error::source(&err).is_some());
+        assert!(matches!(Seed::from_hex("zz"), Err(KeysError::Hex(_))));
+        assert_eq!(format!("{seed:?}"), "Seed(64 bytes)");
+        let b64 = seed.to_base64();
+        assert!(!format!("{b64:?}").contains(b64.expose_secret()));
+
+        use rand::SeedableRng as _;
+        let a = Seed::generate_with(&mut rand::rngs::StdRng::seed_from_u64(7), 32).expect("a");
+        let b = Seed::generate_with(&mut rand::rngs::StdRng::seed_from_u64(7), 32).expect("b");
+        assert_eq!(a.as_bytes(), b.as_bytes());
+        assert!(Seed::generate_with(&mut rand::rngs::OsRng, 16).is_err());
+
+        // BIP-39 reference vector (all-zero entropy, passphrase "TREZOR").
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                      abandon abandon about";
+        let seed = Seed::from_mnemonic(phrase, "TREZOR").expect("mnemonic");
+        assert_eq!(
+            hex::encode(seed.as_bytes()),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        assert!(matches!(
+            Seed::from_mnemonic("abandon abandon", ""),
+            Err(KeysError::Mnemonic(_))
+        ));
+        // Composed and decomposed accents, and full-width letters, derive alike.
+        assert_eq!(
+            Seed::from_mnemonic(phrase, "caf\u{e9}").expect("nfc"),
+            Seed::from_mnemonic(phrase, "cafe\u{301}").expect("nfd")
+        );
+        assert_eq!(
+            Seed::from_mnemonic(phrase, "\u{ff34}REZOR").expect("full-width"),
+            seed
+        );
+        assert_eq!(
+            seed_from_mnemonic(phrase, "TREZOR").expect("any list"),
+            seed
+        );
+
+        // Generated phrases in other lists come back through language detection.
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for language in [MnemonicLanguage::Japanese, MnemonicLanguage::Spanish] {
+            let phrase = generate_mnemonic_with(&mut rng, 24, language).expect("phrase");
+            assert_eq!(phrase.expose_secret().split_whitespace().count(), 24);
+            let any = seed_from_mnemonic(phrase.expose_secret(), "").expect("detected");
+            let given = Seed::from_mnemonic_in(Some(language), phrase.expose_secret(), "");
+            assert_eq!(given.expect("given"), any);
+            assert!(Seed::from_mnemonic(phrase.expose_secret(), "").is_err());
+        }
+        assert!(generate_mnemonic_with(&mut rng, 13, MnemonicLanguage::English).is_err());
+
+        let shouted = "  ABANDON abandon\tabandon abandon abandon abandon abandon abandon abandon \
+                       abandon abandon About\n";
+        let ywallet = WalletCompat::Ywallet
+            .seed(shouted, "TREZOR")
+            .expect("ywallet");
+        assert_eq!(ywallet, seed);
+        assert!(WalletCompat::Zingo.seed(phrase, "TREZOR").is_err());
+        assert!(WalletCompat::Zcashd.seed(phrase, "TREZOR").is_err());
+        assert_eq!(WalletCompat::Ywallet.zip32_account(2).ok(), Some(2));
+        assert!(matches!(
+            WalletCompat::Zingo.zip32_account(1),
+            Err(KeysError::AccountInvalid)
+        ));
     }
 
     #[test]
     fn derives_ufvk_prefixes() {
-        let seed = [7u8; 64];
-        let seed_b64 = base64::engine::general_purpose::STANDARD.encode(seed);
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
 
-        let ufvk_main = ufvk_from_seed_base64(
-            &seed_b64,
-            Network::Mainnet.ua_hrp(),
-            Network::Mainnet.coin_type(),
-            0,
-        )
-        .expect("ufvk main");
+        let ufvk_main = ufvk_from_seed(&seed, &Network::Mainnet, 0).expect("ufvk main");
         assert!(ufvk_main.starts_with("jview1"));
 
-        let ufvk_regtest = ufvk_from_seed_base64(
-            &seed_b64,
-            Network::Regtest.ua_hrp(),
-            Network::Regtest.coin_type(),
-            0,
-        )
-        .expect("ufvk regtest");
+        let ufvk_regtest = ufvk_from_seed(&seed, &Network::Regtest, 0).expect("ufvk regtest");
         assert!(ufvk_regtest.starts_with("jviewregtest1"));
     }
 
+    #[test]
+    fn detects_network_from_prefix() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        for net in Network::ALL {
+            let ufvk = ufvk_from_seed(&seed, &net, 0).expect("ufvk");
+            assert_eq!(detect_network(&ufvk).expect("detect"), net);
+        }
+        assert_eq!(
+            detect_network("jtest1qqqq").expect("ua prefix"),
+            Network::Testnet
+        );
+        let err = detect_network("uview1qqqq").expect_err("err");
+        assert!(matches!(err, KeysError::NetworkUnknown));
+    }
+
+    #[test]
+    fn derives_zcash_compat_ufvk() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let net = Network::Testnet;
+        let ufvk = ufvk_from_seed(&seed, &ZcashNetwork(net), 0).expect("ufvk");
+        assert!(ufvk.starts_with("uviewtest1"));
+        assert_eq!(detect_zcash_network(&ufvk).expect("detect"), net);
+        assert!(detect_network(&ufvk).is_err());
+    }
+
+    #[test]
+    fn converts_zcash_ufvk_to_juno() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let zcash = ufvk_from_seed(&seed, &RawParams("uregtest", 8135), 0).expect("zcash");
+        let juno = ufvk_from_seed(&seed, &Network::Regtest, 0).expect("juno");
+
+        let converted = convert_from_zcash(&zcash).expect("convert");
+        assert_eq!(converted.network, Network::Regtest);
+        assert!(converted.is_ufvk);
+        assert_eq!(converted.typecodes, vec![TYPECODE_ORCHARD]);
+        assert_eq!(converted.encoded, juno);
+
+        let err = convert_from_zcash(&juno).expect_err("err");
+        assert!(matches!(err, KeysError::NetworkUnknown));
+    }
+
     #[test]
     fn ufvk_from_seed_rejects_invalid_coin_type() {
-        let seed = [7u8; 64];
-        let seed_b64 = base64::engine::general_purpose::STANDARD.encode(seed);
-        let err = ufvk_from_seed_base64(&seed_b64, "j", 0x8000_0000, 0).expect_err("err");
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let err = ufvk_from_seed(&seed, &RawParams("j", 0x8000_0000), 0).expect_err("err");
+        assert!(matches!(err, KeysError::CoinTypeInvalid));
+        let err = CustomNetwork::new("jdev", 0x8000_0000).expect_err("err");
         assert!(matches!(err, KeysError::CoinTypeInvalid));
     }
 
     #[test]
     fn ufvk_from_seed_rejects_invalid_ua_hrp() {
-        let seed = [7u8; 64];
-        let seed_b64 = base64::engine::general_purpose::STANDARD.encode(seed);
-        let err = ufvk_from_seed_base64(&seed_b64, "x", 8133, 0).expect_err("err");
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let err = ufvk_from_seed(&seed, &RawParams("x", 8133), 0).expect_err("err");
         assert!(matches!(err, KeysError::UAHrpInvalid));
+        let err = CustomNetwork::new("jwaytoolongsuffix", 8133).expect_err("err");
+        assert!(matches!(err, KeysError::UAHrpInvalid));
+
+        // `jsecret` plus a 9-character suffix fills the 16-byte HRP limit.
+        let longest = CustomNetwork::new("jabcdefghi", 8133).expect("longest HRP");
+        let keys = OrchardKeySet::from_seed(&seed, &longest, 0).expect("keys");
+        let usk = UnifiedSpendingKey::from_keys(longest.ua_hrp(), &keys).expect("usk");
+        assert!(usk
+            .encode()
+            .expose_secret()
+            .starts_with("jsecretabcdefghi1"));
+        let err = CustomNetwork::new("jabcdefghij", 8133).expect_err("err");
+        assert!(matches!(err, KeysError::UAHrpInvalid));
+    }
+
+    #[test]
+    fn derives_addresses_and_uivk_from_ufvk() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let ufvk = ufvk_from_seed(&seed, &Network::Testnet, 0).expect("ufvk");
+
+        let ua0 = ua_from_ufvk(&ufvk, 0u32).expect("ua0");
+        let ua1 = ua_from_ufvk(&ufvk, 1u32).expect("ua1");
+        assert!(ua0.starts_with("jtest1"));
+        assert_ne!(ua0, ua1);
+        assert_eq!(detect_network(&ua0).expect("detect"), Network::Testnet);
+        let b64 = seed.to_base64();
+        let from_seed = ua_from_seed_base64(b64.expose_secret(), &Network::Testnet, 0, 1u32);
+        assert_eq!(from_seed.expect("ua from seed"), ua1);
+        let usk = usk_from_seed_base64(b64.expose_secret(), &Network::Testnet, 0).expect("usk");
+        assert!(usk.starts_with("jsecrettest1"));
+        let usk = UnifiedSpendingKey::parse(&usk).expect("parse usk");
+        assert_eq!(usk.ufvk().expect("usk ufvk").as_str(), ufvk);
+
+        let uivk = uivk_from_ufvk(&ufvk).expect("uivk");
+        assert!(uivk.starts_with("jivktest1"));
+
+        let err = ua_from_ufvk(&ua0, 0u32).expect_err("err");
+        assert!(matches!(err, KeysError::UAHrpInvalid));
+    }
+
+    #[test]
+    fn fingerprints_are_stable() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let fp = seed_fingerprint(&seed).expect("seed fp");
+        assert!(fp.starts_with("zip32seedfp1"));
+
+        let ufvk0 = ufvk_from_seed(&seed, &Network::Mainnet, 0).expect("ufvk0");
+        let ufvk1 = ufvk_from_seed(&seed, &Network::Mainnet, 1).expect("ufvk1");
+        let fp0 = ufvk_fingerprint(&ufvk0).expect("fp0");
+        assert_eq!(fp0.len(), 64);
+        assert_eq!(fp0, ufvk_fingerprint(&ufvk0).expect("fp0 again"));
+        assert_ne!(fp0, ufvk_fingerprint(&ufvk1).expect("fp1"));
+    }
+
+    #[test]
+    fn flags_registered_slip44_coin_types() {
+        assert_eq!(slip44::lookup(133).expect("zec").symbol, "ZEC");
+        for net in Network::ALL {
+            assert!(slip44::lookup(net.coin_type()).is_none());
+        }
+
+        let custom = CustomNetwork::new("jdev", 60).expect("custom");
+        let hit = custom.slip44_collision().expect("collision");
+        assert_eq!(hit.symbol, "ETH");
+        assert!(matches!(
+            slip44::check_coin_type(60, true),
+            Err(KeysError::CoinTypeRegistered)
+        ));
+        assert!(slip44::check_coin_type(8199, true).expect("free").is_none());
+    }
+
+    #[test]
+    fn parses_networks_and_custom_params() {
+        for net in Network::ALL {
+            assert_eq!(net.to_string().parse::<Network>().expect("parse"), net);
+        }
+        assert!(matches!(
+            "devnet".parse::<Network>(),
+            Err(KeysError::NetworkUnknown)
+        ));
+
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let custom = CustomNetwork::new("jdev", 8133).expect("custom");
+        let ufvk = ufvk_from_seed(&seed, &custom, 0).expect("ufvk");
+        assert!(ufvk.starts_with("jviewdev1"));
+    }
+
+    #[test]
+    fn ufvk_type_roundtrips_and_knows_its_network() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let encoded = ufvk_from_seed(&seed, &Network::Testnet, 0).expect("ufvk");
+        let ufvk: Ufvk = encoded.parse().expect("parse");
+        assert_eq!(ufvk.to_string(), encoded);
+        assert_eq!(ufvk.network(), Some(Network::Testnet));
+        assert_eq!(ufvk.zcash_network(), None);
+        assert_eq!(ufvk.ua_hrp(), "jtest");
+        assert_eq!(ufvk.typecodes(), vec![TYPECODE_ORCHARD]);
+        assert!(ufvk.orchard().is_some());
+        assert_eq!(
+            Ufvk::parse(&encoded.to_ascii_uppercase()).expect("upper"),
+            ufvk
+        );
+
+        let raw = ufvk.to_orchard_fvk().expect("orchard item");
+        assert_eq!(
+            Ufvk::from_orchard_fvk(&Network::Testnet, &raw).expect("wrap"),
+            ufvk
+        );
+        assert_eq!(
+            Ufvk::from_orchard_fvk(&Network::Mainnet, &raw)
+                .expect("wrap")
+                .fingerprint(),
+            ufvk.fingerprint()
+        );
+        assert!(matches!(
+            Ufvk::from_orchard_fvk(&Network::Testnet, &raw[..95]),
+            Err(KeysError::LengthOutOfRange { len: 95, .. })
+        ));
+        assert!(matches!(
+            Ufvk::from_orchard_fvk(&Network::Testnet, &[0xff; 96]),
+            Err(KeysError::EncodingInvalid)
+        ));
+
+        let ua = ua_from_ufvk(&encoded, 0u32).expect("ua");
+        assert!(matches!(Ufvk::parse(&ua), Err(KeysError::UAHrpInvalid)));
+    }
+
+    #[test]
+    fn unified_address_type_exposes_receivers() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let ufvk = ufvk_from_seed(&seed, &Network::Mainnet, 0).expect("ufvk");
+        let encoded = ua_from_ufvk(&ufvk, 0u32).expect("ua");
+        let ua: UnifiedAddress = encoded.parse().expect("parse");
+        assert_eq!(ua.to_string(), encoded);
+        assert_eq!(ua.network(), Some(Network::Mainnet));
+        assert!(ua.orchard().is_some());
+        assert_eq!(ua.sapling(), None);
+        assert_eq!(ua.unknown().count(), 0);
+
+        let orchard = ua.orchard().expect("orchard").to_raw_address_bytes();
+        let p2pkh = [1u8; 20];
+        let future = [2u8; 8];
+        let multi = UnifiedAddress::from_receivers(
+            "jtest",
+            &[(0x05, &future), (TYPECODE_ORCHARD, &orchard), (0, &p2pkh)],
+        )
+        .expect("multi");
+        assert_eq!(multi.typecodes(), vec![0, TYPECODE_ORCHARD, 0x05]);
+        assert_eq!(multi.p2pkh(), Some(p2pkh));
+        assert_eq!(
+            multi.unknown().collect::<Vec<_>>(),
+            vec![(0x05, &future[..])]
+        );
+        assert_eq!(multi.network(), Some(Network::Testnet));
+
+        assert!(matches!(
+            UnifiedAddress::parse(&ufvk),
+            Err(KeysError::UAHrpInvalid)
+        ));
+        assert!(matches!(
+            UnifiedAddress::from_receivers("j", &[(TYPECODE_ORCHARD, &[0u8; 42])]),
+            Err(KeysError::EncodingInvalid)
+        ));
+    }
+
+    #[test]
+    fn unified_address_receiver_introspection() {
+        let orchard = ufvk_from_seed(&[7u8; 64][..], &Network::Mainnet, 0)
+            .and_then(|ufvk| ua_from_ufvk(&ufvk, 0u32))
+            .and_then(|ua| UnifiedAddress::parse(&ua))
+            .expect("orchard ua");
+        assert!(orchard.has_orchard() && !orchard.has_sapling());
+        assert!(!orchard.has_transparent() && orchard.is_shielded_only());
+
+        let orchard_raw = orchard.orchard().expect("receiver").to_raw_address_bytes();
+        let mixed = UnifiedAddress::from_receivers(
+            "j",
+            &[
+                (zip316::TYPECODE_P2SH, &[1u8; 20]),
+                (TYPECODE_ORCHARD, &orchard_raw),
+            ],
+        )
+        .expect("mixed ua");
+        assert!(mixed.has_transparent() && !mixed.is_shielded_only());
+        assert!(mixed.has_typecode(zip316::TYPECODE_P2SH));
+        assert!(!mixed.has_typecode(zip316::TYPECODE_P2PKH));
+    }
+
+    #[test]
+    fn derivation_request_builds_all_artifacts() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let keys = DerivationRequest::new(&seed)
+            .network(Network::Regtest)
+            .account(2)
+            .diversifier_index(5u32)
+            .build()
+            .expect("derive");
+        let ufvk = ufvk_from_seed(&seed, &Network::Regtest, 2).expect("ufvk");
+        assert_eq!(keys.ufvk.to_string(), ufvk);
+        assert_eq!(keys.coin_type, 8135);
+        assert_eq!(keys.uivk, uivk_from_ufvk(&ufvk).expect("uivk"));
+        assert_eq!(
+            keys.address.to_string(),
+            ua_from_ufvk(&ufvk, 5u32).expect("ua")
+        );
+
+        assert!(matches!(
+            DerivationRequest::new(&seed).build(),
+            Err(KeysError::NetworkUnknown)
+        ));
+    }
+
+    #[test]
+    fn raw_byte_seeds_match_seed_type() {
+        let bytes = [7u8; 64];
+        let seed = Seed::from_bytes(&bytes).expect("seed");
+        assert_eq!(
+            ufvk_from_seed(&bytes[..], &Network::Mainnet, 0).expect("bytes"),
+            ufvk_from_seed(&seed, &Network::Mainnet, 0).expect("seed")
+        );
+        assert_eq!(
+            seed_fingerprint(&bytes[..]).expect("bytes fp"),
+            seed_fingerprint(&seed).expect("seed fp")
+        );
+        assert!(matches!(
+            ufvk_from_seed(&bytes[..16], &Network::Mainnet, 0),
+            Err(KeysError::LengthOutOfRange { what: "seed", .. })
+        ));
+        assert!(matches!(
+            seed_fingerprint(&bytes[..16]),
+            Err(KeysError::LengthOutOfRange { what: "seed", .. })
+        ));
+    }
+
+    #[test]
+    fn derives_from_raw_spending_key() {
+        let seed = [7u8; 64];
+        let sk = orchard::keys::SpendingKey::from_zip32_seed(
+            &seed,
+            Network::Mainnet.coin_type(),
+            zip32::AccountId::ZERO,
+        )
+        .expect("sk");
+        let ufvk = ufvk_from_spending_key(sk.to_bytes(), &Network::Mainnet).expect("ufvk");
+        assert_eq!(
+            ufvk.to_string(),
+            ufvk_from_seed(&seed[..], &Network::Mainnet, 0).expect("seed ufvk")
+        );
+        assert!(ufvk.address_at(0u32).is_ok());
+
+        assert!(matches!(
+            ufvk_from_spending_key(&[0u8; 31], &Network::Mainnet),
+            Err(KeysError::LengthOutOfRange {
+                what: "spending_key",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn byte_conversions_roundtrip() {
+        let seed = Seed::try_from(&[7u8; 64][..]).expect("seed");
+        assert_eq!(seed.to_bytes().as_slice(), &[7u8; 64]);
+        assert!(Seed::try_from(&[7u8; 16][..]).is_err());
+
+        let keys = OrchardKeySet::from_seed(&seed, &Network::Mainnet, 0).expect("keys");
+        let sk = keys.to_bytes();
+        assert_eq!(sk.len(), OrchardKeySet::SPENDING_KEY_LEN);
+        assert_eq!(OrchardKeySet::try_from(&sk[..]).expect("from sk"), keys);
+
+        let ufvk = keys.ufvk(&Network::Mainnet).expect("ufvk");
+        let raw = ufvk.to_bytes();
+        assert_eq!(raw.len(), 2 + Ufvk::ORCHARD_FVK_LEN);
+        assert_eq!(Ufvk::from_bytes("jview", &raw).expect("ufvk bytes"), ufvk);
+        assert_eq!(
+            Ufvk::from_bytes("jviewtest", &raw)
+                .expect("testnet")
+                .network(),
+            Some(Network::Testnet)
+        );
+        assert!(Ufvk::from_bytes("jview", &raw[..40]).is_err());
+
+        let address = ufvk.address_at(0u32).expect("address");
+        let raw = address.to_bytes();
+        assert_eq!(raw.len(), 2 + UnifiedAddress::SHIELDED_RECEIVER_LEN);
+        assert_eq!(
+            UnifiedAddress::from_bytes("j", &raw).expect("ua bytes"),
+            address
+        );
+        assert!(UnifiedAddress::from_bytes("jview", &raw).is_err());
+    }
+
+    #[test]
+    fn constant_time_comparisons() {
+        assert!(ct_eq("jview1abc", b"jview1abc"));
+        assert!(!ct_eq("jview1abc", "jview1abd"));
+        assert!(!ct_eq(&[0u8; 32][..], &[0u8; 31][..]));
+
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        assert_eq!(seed, Seed::from_bytes(&[7u8; 64]).expect("same"));
+        assert_ne!(seed, Seed::from_bytes(&[8u8; 64]).expect("other"));
+
+        let ufvk = ufvk_from_seed(&seed, &Network::Mainnet, 0).expect("ufvk");
+        assert_eq!(
+            Ufvk::parse(&ufvk).expect("lower"),
+            Ufvk::parse(&ufvk.to_ascii_uppercase()).expect("upper")
+        );
+        assert_eq!(
+            OrchardKeySet::from_seed(&seed, &Network::Mainnet, 0).expect("a"),
+            OrchardKeySet::from_seed(&seed, &Network::Mainnet, 0).expect("b")
+        );
+    }
+
+    #[test]
+    fn ufvk_addresses_iterate_lazily() {
+        use orchard::keys::{DiversifierIndex, Scope};
+
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let ufvk: Ufvk = ufvk_from_seed(&seed, &Network::Mainnet, 0)
+            .expect("ufvk")
+            .parse()
+            .expect("parse");
+        let external = ufvk.addresses(Scope::External).take(3).collect::<Vec<_>>();
+        assert_eq!(external.len(), 3);
+        for (i, (j, address)) in external.iter().enumerate() {
+            assert_eq!(*j, DiversifierIndex::from(i as u32));
+            assert_eq!(*address, ufvk.address_at(i as u32).expect("address"));
+        }
+        let generator = ufvk.address_generator().expect("generator");
+        assert_eq!(generator.address_at(2u32).expect("address"), external[2].1);
+        assert_eq!(
+            ufvk.next_address(2u32).expect("next"),
+            (DiversifierIndex::from(2u32), external[2].1.clone())
+        );
+        let (_, internal) = ufvk.addresses(Scope::Internal).next().expect("internal");
+        assert_ne!(internal, external[0].1);
+        assert_eq!(
+            ufvk.addresses(Scope::External).nth(20).expect("lazy").0,
+            20u32.into()
+        );
+
+        let locator = ufvk.address_locator().expect("locator");
+        assert_eq!(
+            locator.locate(&external[2].1),
+            Some((Scope::External, 2u32.into()))
+        );
+        assert_eq!(
+            locator.locate(&internal),
+            Some((Scope::Internal, 0u32.into()))
+        );
+        let stranger = ufvk_from_seed(&seed, &Network::Mainnet, 1).expect("ufvk");
+        let stranger = ua_from_ufvk(&stranger, 0u32)
+            .expect("address")
+            .parse()
+            .expect("parse");
+        assert_eq!(locator.locate(&stranger), None);
+    }
+
+    #[test]
+    fn app_keys_match_zip32_arbitrary_vectors() {
+        // zcash-test-vectors zip_0032_arbitrary.py, first and second vectors.
+        let seed: Vec<u8> = (0u8..32).collect();
+        let context = "Zcash test vectors";
+        let master = app_key(&seed, context, &ZipPath::parse("m").expect("path")).expect("key");
+        assert_eq!(
+            hex::encode(*master),
+            "e9da8806409dc3c3ebd1fc2a71c879c13dd7aa93ede803bf1a83414b9d3b158a"
+        );
+        let child = app_key(&seed, context, &ZipPath::parse("m/1'").expect("path")).expect("key");
+        assert_eq!(
+            hex::encode(*child),
+            "e8409aaa832cc2378f2badeb77150562153742fee876dcf4783a6ccd119da66a"
+        );
+
+        assert!(matches!(
+            app_key(&seed, "", &ZipPath::default()),
+            Err(KeysError::LengthOutOfRange {
+                what: "context",
+                ..
+            })
+        ));
+        assert!(app_key(&[0u8; 16], context, &ZipPath::default()).is_err());
+    }
+
+    #[test]
+    fn zip_paths_parse_and_display() {
+        let path: ZipPath = "m/32'/8133'/0'".parse().expect("path");
+        assert_eq!(path.indices(), &[32, 8133, 0]);
+        assert_eq!(path.as_account(), Some((8133, 0)));
+        assert_eq!(path, ZipPath::account(8133, 0).expect("account path"));
+        assert_eq!(
+            ZipPath::parse("m/32h/8133H/7'").expect("h").to_string(),
+            "m/32'/8133'/7'"
+        );
+        assert_eq!(ZipPath::parse("m").expect("root").depth(), 0);
+        for bad in [
+            "",
+            "32'/8133'/0'",
+            "m/32'//0'",
+            "m/x",
+            "m/+1'",
+            "m/2147483648'",
+        ] {
+            assert!(
+                matches!(ZipPath::parse(bad), Err(KeysError::PathInvalid)),
+                "{bad}"
+            );
+        }
+        assert!(matches!(
+            ZipPath::parse("m/32'/8133/0'"),
+            Err(KeysError::NonHardenedPath)
+        ));
+        assert_eq!(
+            ZipPath::parse_with_policy("m/32/8133'/0", HardeningPolicy::HardenUnmarked)
+                .expect("implicitly hardened"),
+            path
+        );
+        assert_eq!(
+            ZipPath::parse("m/44'/0'/0'").expect("bip44").as_account(),
+            None
+        );
+
+        let seed = [7u8; 64];
+        let keys = DerivationRequest::new(&seed[..])
+            .network(Network::Testnet)
+            .path(path.clone())
+            .build()
+            .expect("keys");
+        assert_eq!((keys.coin_type, keys.account), (8133, 0));
+        assert_eq!(keys.path, path);
+        assert_eq!(
+            keys.ufvk.orchard(),
+            ufvk_from_seed(&seed[..], &Network::Mainnet, 0)
+                .expect("ufvk")
+                .parse::<Ufvk>()
+                .expect("parse")
+                .orchard()
+        );
+        let deeper = DerivationRequest::new(&seed[..])
+            .network(Network::Mainnet)
+            .path(ZipPath::parse("m/32'/8133'/0'/7'").expect("deeper"))
+            .build()
+            .expect("deeper keys");
+        assert_eq!((deeper.coin_type, deeper.account), (8133, 0));
+        assert_ne!(deeper.ufvk.orchard(), keys.ufvk.orchard());
+        assert_eq!(
+            OrchardKeySet::from_path(&seed[..], &deeper.path)
+                .expect("key set")
+                .full_viewing_key(),
+            deeper.ufvk.orchard().expect("orchard fvk")
+        );
+
+        let err = DerivationRequest::new(&seed[..])
+            .network(Network::Mainnet)
+            .path(ZipPath::parse("m/44'/0'/0'").expect("bip44"))
+            .build()
+            .expect_err("non-zip32 path");
+        assert_eq!(err.code(), "path_invalid");
+    }
+
+    #[test]
+    fn error_codes_are_stable() {
+        for (i, code) in ErrorCode::ALL.iter().enumerate() {
+            assert_eq!(i32::from(*code), i as i32);
+            assert_eq!(ErrorCode::try_from(i as i32), Ok(*code));
+        }
+        assert_eq!(ErrorCode::try_from(-1), Err(-1));
+        assert_eq!(ErrorCode::try_from(ErrorCode::ALL.len() as i32), Err(34));
+        // Pinned: these values are compiled into C callers and scripts.
+        assert_eq!(ErrorCode::InvalidRequest as i32, 2);
+        assert_eq!(ErrorCode::SeedInvalid as i32, 3);
+        assert_eq!(ErrorCode::Panic as i32, 21);
+
+        let names = ErrorCode::ALL
+            .iter()
+            .map(ErrorCode::as_str)
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+        assert_eq!(
+            KeysError::NonHardenedPath.error_code(),
+            ErrorCode::PathNotHardened
+        );
+        assert_eq!(KeysError::NonHardenedPath.code(), "path_not_hardened");
+    }
+
+    #[test]
+    fn account_ranges_are_bounded() {
+        let range = AccountRange::new(5, 8).expect("range");
+        assert_eq!(range.len(), 3);
+        assert!(range.contains(7) && !range.contains(8));
+        assert_eq!(range.into_iter().collect::<Vec<_>>(), [5, 6, 7]);
+        assert!(AccountRange::from_count(AccountRange::MAX_ACCOUNT, 1).is_ok());
+        assert!(matches!(
+            AccountRange::from_count(AccountRange::MAX_ACCOUNT, 2),
+            Err(KeysError::AccountInvalid)
+        ));
+        assert!(AccountRange::from_count(u32::MAX, 1).is_err());
+        assert!(AccountRange::new(3, 2).is_err());
+
+        assert_eq!(hardened_child(7).expect("child").index(), 0x8000_0007);
+        assert!(matches!(
+            hardened_child(0x8000_0000),
+            Err(KeysError::PathInvalid)
+        ));
+        assert_eq!(
+            ZipPath::account(8133, 0).expect("path").child_indices()[1],
+            ChildIndex::hardened(8133)
+        );
+    }
+
+    #[test]
+    fn paths_derive_past_the_account() {
+        let seed = [7u8; 32];
+        let path = ZipPath::parse("m/32h/8133h/0h/5h").expect("path");
+        assert_eq!(ZipPath::from_child_indices(&path.child_indices()), path);
+        let keys = derive_path(&seed, &path.child_indices()).expect("keys");
+        assert_eq!(keys, OrchardKeySet::from_path(&seed, &path).expect("keys"));
+        assert_ne!(
+            keys,
+            OrchardKeySet::from_seed(&seed, &Network::Mainnet, 0).expect("keys")
+        );
+        assert!(matches!(
+            ZipPath::parse("m/32h/8133h/0h/5"),
+            Err(KeysError::NonHardenedPath)
+        ));
+    }
+
+    #[test]
+    fn accounts_are_found_from_their_keys() {
+        let seed = [7u8; 32];
+        let accounts = AccountRange::new(0, 5).expect("range");
+        let ufvk = Ufvk::parse(&ufvk_from_seed(&seed, &Network::Testnet, 3).expect("ufvk"))
+            .expect("parse");
+        assert_eq!(
+            find_ufvk_account(&seed, &Network::Testnet, &ufvk, accounts).expect("find"),
+            Some(3)
+        );
+        // Only the HRP differs from the testnet key.
+        let fork = CustomNetwork::new("jdev", Network::Testnet.coin_type()).expect("network");
+        let fork = Ufvk::parse(&ufvk_from_seed(&seed, &fork, 3).expect("ufvk")).expect("parse");
+        assert_eq!(
+            find_ufvk_account(&seed, &Network::Testnet, &fork, accounts).expect("find"),
+            Some(3)
+        );
+        let few = AccountRange::new(0, 3).expect("range");
+        assert_eq!(
+            find_ufvk_account(&seed, &Network::Testnet, &ufvk, few).expect("find"),
+            None
+        );
+        assert_eq!(
+            find_ufvk_account(&[8u8; 32], &Network::Testnet, &ufvk, accounts).expect("find"),
+            None
+        );
+
+        let address = ufvk.address_at(77u32).expect("address");
+        let (account, scope, index) =
+            find_address_account(&seed, &Network::Testnet, &address, accounts)
+                .expect("find")
+                .expect("found");
+        assert_eq!((account, u128::from(index)), (3, 77));
+        assert_eq!(scope, orchard::keys::Scope::External);
+        assert!(
+            find_address_account(&seed, &Network::Testnet, &address, few)
+                .expect("find")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn batches_match_single_derivations() {
+        let seed = [7u8; 32];
+        let accounts = AccountRange::new(2, 5).expect("range");
+        let ufvks = derive_ufvk_batch(&seed, &Network::Testnet, accounts).expect("ufvks");
+        assert_eq!(ufvks.len(), 3);
+        for (account, ufvk) in accounts.into_iter().zip(&ufvks) {
+            assert_eq!(
+                ufvk.to_string(),
+                ufvk_from_seed(&seed, &Network::Testnet, account).expect("ufvk")
+            );
+        }
+
+        let addresses =
+            derive_address_batch(&seed, &Network::Testnet, accounts, 9..11).expect("addresses");
+        assert_eq!(addresses.len(), 6);
+        let (account, index, address) = &addresses[3];
+        assert_eq!((*account, u128::from(*index)), (3, 10));
+        assert_eq!(
+            address.to_string(),
+            ua_from_ufvk(ufvks[1].as_str(), 10u64).expect("address")
+        );
+        assert!(matches!(
+            derive_ufvk_batch(&[0u8; 16], &Network::Testnet, accounts),
+            Err(KeysError::LengthOutOfRange { what: "seed", .. })
+        ));
+    }
+
+    #[test]
+    fn orchard_key_set_matches_individual_derivations() {
+        use orchard::keys::Scope;
+
+        let seed = [7u8; 64];
+        let keys = OrchardKeySet::from_seed(&seed[..], &Network::Mainnet, 0).expect("keys");
+        let sk = keys.spending_key();
+        let fvk = orchard::keys::FullViewingKey::from(&sk);
+        assert_eq!(keys.full_viewing_key(), fvk);
+        for scope in [Scope::External, Scope::Internal] {
+            let ivk = fvk.to_ivk(scope);
+            assert_eq!(keys.incoming_viewing_key(scope), ivk);
+            assert_eq!(
+                keys.outgoing_viewing_key(scope).as_ref(),
+                fvk.to_ovk(scope).as_ref()
+            );
+            assert_eq!(keys.diversifier_key(scope)[..], ivk.to_bytes()[..32]);
+        }
+        assert_ne!(
+            keys.diversifier_key(Scope::External),
+            keys.diversifier_key(Scope::Internal)
+        );
+
+        let from_sk = OrchardKeySet::from_spending_key(sk.to_bytes()).expect("from sk");
+        assert_eq!(from_sk.full_viewing_key(), fvk);
+        assert_eq!(
+            keys.ufvk(&Network::Mainnet).expect("ufvk").to_string(),
+            ufvk_from_seed(&seed[..], &Network::Mainnet, 0).expect("seed ufvk")
+        );
+        assert_eq!(format!("{keys:?}"), "OrchardKeySet(..)");
+    }
+
+    #[cfg(feature = "secret-accounting")]
+    #[test]
+    fn derivation_keeps_one_copy_of_the_seed() {
+        let (before, live) = (secret_accounting::allocations(), secret_accounting::live());
+        let seed = Seed::from_base64(&("BwcH".repeat(21) + "Bw==")).expect("seed");
+        let keys = DerivationRequest::new(&seed)
+            .network(Network::Testnet)
+            .build()
+            .expect("derive");
+        ufvk_from_seed(&seed, &Network::Testnet, 1).expect("ufvk");
+        assert_eq!(secret_accounting::allocations() - before, 1);
+        drop((seed, keys));
+        assert_eq!(secret_accounting::live(), live);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn key_types_serialize_as_strings() {
+        let seed = Seed::from_bytes(&[7u8; 64]).expect("seed");
+        let keys = DerivationRequest::new(&seed)
+            .network(Network::Testnet)
+            .build()
+            .expect("derive");
+
+        let json = serde_json::to_string(&(Network::Testnet, &keys.ufvk, &keys.address))
+            .expect("serialize");
+        let (net, ufvk, ua): (Network, Ufvk, UnifiedAddress) =
+            serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(net, Network::Testnet);
+        assert_eq!(ufvk, keys.ufvk);
+        assert_eq!(ua, keys.address);
+
+        assert!(serde_json::from_str::<Ufvk>("\"jview1bogus\"").is_err());
+    }
+
+    #[test]
+    fn seeds_parse_from_base64() {
+        let seed = Seed::from_bytes(&[7u8; 32]).expect("seed");
+        let parsed: Seed = seed.to_base64().expose_secret().parse().expect("parse");
+        assert_eq!(parsed, seed);
+        assert!("AAAA".parse::<Seed>().is_err());
     }
 }
@@ -0,0 +1,133 @@
+use orchard::keys::SpendAuthorizingKey;
+use orchard::primitives::redpallas::{self, SpendAuth};
+use pasta_curves::pallas;
+use rand_core::{CryptoRng, RngCore};
+
+use alloc::format;
+use alloc::string::String;
+
+use crate::{KeysError, OrchardKeySet, Ufvk, UnifiedAddress};
+
+const MESSAGE_PERSONALIZATION: &[u8; 16] = b"JunoKeys_Message";
+
+/// A RedPallas spend-authorization signature over [`message_digest`], with the key
+/// that validates it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MessageSignature {
+    /// The account's Orchard spend validating key `ak`, the first 32 bytes of its
+    /// full viewing key.
+    pub spend_validating_key: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+/// BLAKE2b-256 of `message` personalized with `"JunoKeys_Message"`, so a message
+/// signature can never be mistaken for a transaction's spend authorization.
+pub fn message_digest(message: &[u8]) -> [u8; 32] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(MESSAGE_PERSONALIZATION)
+        .hash(message);
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(hash.as_bytes());
+    digest
+}
+
+/// The text an address-ownership proof signs, binding `address` to a verifier's
+/// `challenge`. Verifiers rebuild it and check the signature with
+/// [`Ufvk::verify_message`] and the address with [`Ufvk::diversifier_index_of`].
+pub fn ownership_statement(address: &UnifiedAddress, challenge: &str) -> String {
+    format!("Juno address ownership\naddress: {address}\nchallenge: {challenge}\n")
+}
+
+impl OrchardKeySet {
+    /// Signs `message` with the account's spend authorizing key, proving control of
+    /// the wallet to anyone holding its UFVK.
+    #[cfg(feature = "std")]
+    pub fn sign_message(&self, message: &[u8]) -> MessageSignature {
+        self.sign_message_with(&mut rand::rngs::OsRng, message)
+    }
+
+    /// [`Self::sign_message`] with the signing nonce drawn from `rng`.
+    pub fn sign_message_with<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        message: &[u8],
+    ) -> MessageSignature {
+        // Randomizing by zero yields the unrandomized key, so `ak` validates it.
+        let key =
+            SpendAuthorizingKey::from(&self.spending_key()).randomize(&pallas::Scalar::from(0));
+        let signature = key.sign(rng, &message_digest(message));
+        MessageSignature {
+            spend_validating_key: redpallas::VerificationKey::from(&key).into(),
+            signature: (&signature).into(),
+        }
+    }
+}
+
+impl Ufvk {
+    /// Checks a [`OrchardKeySet::sign_message`] signature against this key's `ak`.
+    ///
+    /// Fails with `EncodingInvalid` if the key has no Orchard item and
+    /// `SignatureInvalid` if the signature doesn't verify.
+    pub fn verify_message(&self, message: &[u8], signature: &[u8; 64]) -> Result<(), KeysError> {
+        let fvk = self.orchard().ok_or(KeysError::EncodingInvalid)?;
+        let mut ak = [0u8; 32];
+        ak.copy_from_slice(&fvk.to_bytes()[..32]);
+        let key = redpallas::VerificationKey::<SpendAuth>::try_from(ak)
+            .map_err(|_| KeysError::Internal)?;
+        key.verify(&message_digest(message), &(*signature).into())
+            .map_err(|_| KeysError::SignatureInvalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+
+    use super::*;
+    use crate::Network;
+
+    #[test]
+    fn signatures_verify_against_the_ufvk() {
+        let keys = OrchardKeySet::from_seed(&[7u8; 32], &Network::Mainnet, 0).expect("keys");
+        let ufvk = keys.ufvk(&Network::Mainnet).expect("ufvk");
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let signed = keys.sign_message_with(&mut rng, b"juno");
+        assert_eq!(
+            &signed.spend_validating_key[..],
+            &keys.full_viewing_key().to_bytes()[..32]
+        );
+        ufvk.verify_message(b"juno", &signed.signature)
+            .expect("valid signature");
+
+        assert!(matches!(
+            ufvk.verify_message(b"june", &signed.signature),
+            Err(KeysError::SignatureInvalid)
+        ));
+        let address = ufvk.address_at(5u32).expect("address");
+        assert_eq!(
+            ufvk.diversifier_index_of(&address)
+                .and_then(|j| u32::try_from(j).ok()),
+            Some(5)
+        );
+        let other = OrchardKeySet::from_seed(&[7u8; 32], &Network::Mainnet, 1)
+            .and_then(|keys| keys.ufvk(&Network::Mainnet))
+            .expect("ufvk");
+        assert!(other.verify_message(b"juno", &signed.signature).is_err());
+        assert_eq!(other.diversifier_index_of(&address), None);
+    }
+
+    #[test]
+    fn statements_bind_address_and_challenge() {
+        let ufvk = OrchardKeySet::from_seed(&[7u8; 32], &Network::Testnet, 0)
+            .and_then(|keys| keys.ufvk(&Network::Testnet))
+            .expect("ufvk");
+        let address = ufvk.address_at(0u32).expect("address");
+        let statement = ownership_statement(&address, "listing #1");
+        assert_eq!(
+            statement,
+            format!("Juno address ownership\naddress: {address}\nchallenge: listing #1\n")
+        );
+        assert_ne!(statement, ownership_statement(&address, "listing #2"));
+    }
+}
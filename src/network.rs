@@ -0,0 +1,209 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+
+use crate::slip44::{self, Slip44Entry};
+use crate::{usk_hrp_from_ua_hrp, KeysError};
+
+// ZIP-316 pads containers with the HRP, so the longest HRP we can emit is 16 bytes.
+// The USK HRP (`jsecret...`) is the longest one derived from a UA HRP.
+const MAX_HRP_LEN: usize = 16;
+
+/// Parameters that select the encoding prefix and ZIP-32 coin type for a network.
+pub trait NetworkParams {
+    fn ua_hrp(&self) -> &str;
+    fn coin_type(&self) -> u32;
+}
+
+impl<T: NetworkParams + ?Sized> NetworkParams for &T {
+    fn ua_hrp(&self) -> &str {
+        (**self).ua_hrp()
+    }
+
+    fn coin_type(&self) -> u32 {
+        (**self).coin_type()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+impl Network {
+    pub const ALL: [Network; 3] = [Network::Mainnet, Network::Testnet, Network::Regtest];
+
+    pub fn ua_hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "j",
+            Network::Testnet => "jtest",
+            Network::Regtest => "jregtest",
+        }
+    }
+
+    pub fn coin_type(&self) -> u32 {
+        match self {
+            Network::Mainnet => 8133,
+            Network::Testnet => 8134,
+            Network::Regtest => 8135,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Regtest => "regtest",
+        }
+    }
+
+    pub fn ufvk_hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "jview",
+            Network::Testnet => "jviewtest",
+            Network::Regtest => "jviewregtest",
+        }
+    }
+
+    /// Maps a UA or UFVK human-readable part back to its network.
+    pub fn from_hrp(hrp: &str) -> Option<Network> {
+        Network::ALL
+            .into_iter()
+            .find(|n| n.ua_hrp() == hrp || n.ufvk_hrp() == hrp)
+    }
+
+    // Upstream Zcash parameters, used only for cross-validation against Zcash tooling.
+    pub fn zcash_ua_hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "u",
+            Network::Testnet => "utest",
+            Network::Regtest => "uregtest",
+        }
+    }
+
+    pub fn zcash_ufvk_hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "uview",
+            Network::Testnet => "uviewtest",
+            Network::Regtest => "uviewregtest",
+        }
+    }
+
+    pub fn zcash_coin_type(&self) -> u32 {
+        match self {
+            Network::Mainnet => 133,
+            Network::Testnet | Network::Regtest => 1,
+        }
+    }
+
+    pub fn from_zcash_hrp(hrp: &str) -> Option<Network> {
+        Network::ALL
+            .into_iter()
+            .find(|n| n.zcash_ua_hrp() == hrp || n.zcash_ufvk_hrp() == hrp)
+    }
+}
+
+impl NetworkParams for Network {
+    fn ua_hrp(&self) -> &str {
+        Network::ua_hrp(self)
+    }
+
+    fn coin_type(&self) -> u32 {
+        Network::coin_type(self)
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Network {
+    type Err = KeysError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mainnet" | "main" => Ok(Network::Mainnet),
+            "testnet" | "test" => Ok(Network::Testnet),
+            "regtest" => Ok(Network::Regtest),
+            _ => Err(KeysError::NetworkUnknown),
+        }
+    }
+}
+
+/// A network's upstream Zcash parameters (`u`/`uview` HRPs, coin type 133 or 1).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ZcashNetwork(pub Network);
+
+impl NetworkParams for ZcashNetwork {
+    fn ua_hrp(&self) -> &str {
+        self.0.zcash_ua_hrp()
+    }
+
+    fn coin_type(&self) -> u32 {
+        self.0.zcash_coin_type()
+    }
+}
+
+/// Network parameters for forks and devnets that aren't one of the built-in networks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CustomNetwork {
+    ua_hrp: String,
+    coin_type: u32,
+}
+
+impl CustomNetwork {
+    pub fn new(ua_hrp: &str, coin_type: u32) -> Result<Self, KeysError> {
+        let ua_hrp = ua_hrp.trim();
+        let usk_hrp = usk_hrp_from_ua_hrp(ua_hrp)?;
+        if usk_hrp.len() > MAX_HRP_LEN
+            || ua_hrp
+                .bytes()
+                .any(|b| !(b.is_ascii_lowercase() || b.is_ascii_digit()))
+        {
+            return Err(KeysError::UAHrpInvalid);
+        }
+        if coin_type >= crate::path::HARDENED {
+            return Err(KeysError::CoinTypeInvalid);
+        }
+        Ok(CustomNetwork {
+            ua_hrp: ua_hrp.to_string(),
+            coin_type,
+        })
+    }
+
+    /// Returns the registered SLIP-44 coin this network's coin type collides with, if any.
+    pub fn slip44_collision(&self) -> Option<&'static Slip44Entry> {
+        slip44::lookup(self.coin_type)
+    }
+}
+
+impl NetworkParams for CustomNetwork {
+    fn ua_hrp(&self) -> &str {
+        &self.ua_hrp
+    }
+
+    fn coin_type(&self) -> u32 {
+        self.coin_type
+    }
+}
+
+/// Detects the network of an encoded UA/UFVK from its bech32m prefix.
+pub fn detect_network(encoded: &str) -> Result<Network, KeysError> {
+    Network::from_hrp(&encoded_hrp(encoded)?).ok_or(KeysError::NetworkUnknown)
+}
+
+/// Like [`detect_network`], but for upstream Zcash (`u`/`uview`) prefixes.
+pub fn detect_zcash_network(encoded: &str) -> Result<Network, KeysError> {
+    Network::from_zcash_hrp(&encoded_hrp(encoded)?).ok_or(KeysError::NetworkUnknown)
+}
+
+pub(crate) fn encoded_hrp(encoded: &str) -> Result<String, KeysError> {
+    let Some((hrp, _)) = encoded.trim().rsplit_once('1') else {
+        return Err(KeysError::NetworkUnknown);
+    };
+    Ok(hrp.to_ascii_lowercase())
+}
@@ -0,0 +1,93 @@
+//! Tokio wrappers for the operations slow enough to stall an async executor: BIP-39
+//! seed stretching (2048 rounds of PBKDF2-HMAC-SHA512), account derivation and batch
+//! address derivation. Each one runs on tokio's blocking pool.
+//!
+//! Parsing, encoding and validation take microseconds and can be called directly from
+//! async code. Every key type is `Send + Sync`, so keys can live in shared state such
+//! as an axum `State`:
+//!
+//! ```
+//! # use juno_keys::{nonblocking, Network, Seed};
+//! # async fn handler(seed: Seed) -> Result<(), juno_keys::KeysError> {
+//! let keys = nonblocking::derive(seed, |request| request.network(Network::Mainnet).account(3))
+//!     .await?;
+//! let batch = nonblocking::addresses(keys.ufvk, 0..100).await?;
+//! # assert_eq!(batch.len(), 100);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use secrecy::{ExposeSecret as _, SecretString};
+
+use crate::{DerivationRequest, DerivedKeys, KeysError, Seed, Ufvk, UnifiedAddress};
+
+/// Runs `f` on tokio's blocking thread pool and waits for it.
+///
+/// A panic in `f` is resumed in the caller. If the runtime shuts down before `f`
+/// starts, the result is [`KeysError::Internal`].
+pub async fn run<T, F>(f: F) -> Result<T, KeysError>
+where
+    F: FnOnce() -> Result<T, KeysError> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) => match e.try_into_panic() {
+            Ok(panic) => std::panic::resume_unwind(panic),
+            Err(_) => Err(KeysError::Internal),
+        },
+    }
+}
+
+/// [`Seed::from_mnemonic`] off the async executor.
+pub async fn seed_from_mnemonic(
+    phrase: SecretString,
+    passphrase: SecretString,
+) -> Result<Seed, KeysError> {
+    run(move || Seed::from_mnemonic(phrase.expose_secret(), passphrase.expose_secret())).await
+}
+
+/// Builds the [`DerivationRequest`] that `configure` sets up for `seed`, off the async
+/// executor.
+pub async fn derive<F>(seed: Seed, configure: F) -> Result<DerivedKeys, KeysError>
+where
+    F: for<'a> FnOnce(DerivationRequest<'a>) -> DerivationRequest<'a> + Send + 'static,
+{
+    run(move || configure(DerivationRequest::new(&seed)).build()).await
+}
+
+/// The external-scope addresses at each diversifier index in `indices`, in order.
+pub async fn addresses(ufvk: Ufvk, indices: Range<u32>) -> Result<Vec<UnifiedAddress>, KeysError> {
+    run(move || {
+        let generator = ufvk.address_generator()?;
+        indices.map(|j| generator.address_at(j)).collect()
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Network;
+
+    #[tokio::test]
+    async fn matches_the_blocking_api() {
+        let seed = Seed::from_bytes(&[7u8; 32]).expect("seed");
+        let expected = DerivationRequest::new(&seed)
+            .network(Network::Testnet)
+            .account(2)
+            .build()
+            .expect("derive");
+        let keys = derive(seed, |request| request.network(Network::Testnet).account(2))
+            .await
+            .expect("derive");
+        assert_eq!(keys.ufvk, expected.ufvk);
+
+        let batch = addresses(keys.ufvk.clone(), 3..6).await.expect("addresses");
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch[0], expected.ufvk.address_at(3u32).expect("address"));
+    }
+}
@@ -0,0 +1,217 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use core::str::FromStr;
+
+pub use zip32::ChildIndex;
+
+use crate::KeysError;
+
+/// The ZIP-32 purpose level, `32'`.
+pub const ZIP32_PURPOSE: u32 = 32;
+
+// Indices at or above this collide with the hardened bit.
+pub(crate) const HARDENED: u32 = 0x8000_0000;
+
+/// The hardened child index for `index`, which must be below 2^31.
+///
+/// Unlike `ChildIndex::hardened`, this returns `PathInvalid` instead of panicking.
+pub fn hardened_child(index: u32) -> Result<ChildIndex, KeysError> {
+    if index >= HARDENED {
+        return Err(KeysError::PathInvalid);
+    }
+    Ok(ChildIndex::hardened(index))
+}
+
+/// A half-open range of ZIP-32 account indices, checked against the 2^31 bound once
+/// so callers can enumerate accounts without repeating it.
+///
+/// ```
+/// # use juno_keys::AccountRange;
+/// let accounts = AccountRange::from_count(0, 3)?;
+/// assert_eq!(accounts.into_iter().collect::<Vec<_>>(), [0, 1, 2]);
+/// # Ok::<(), juno_keys::KeysError>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct AccountRange {
+    start: u32,
+    end: u32,
+}
+
+impl AccountRange {
+    /// The largest valid account index.
+    pub const MAX_ACCOUNT: u32 = HARDENED - 1;
+
+    /// Accounts `start..end`; fails with `AccountInvalid` if `end` exceeds 2^31 or
+    /// `start > end`.
+    pub fn new(start: u32, end: u32) -> Result<Self, KeysError> {
+        if start > end || end > HARDENED {
+            return Err(KeysError::AccountInvalid);
+        }
+        Ok(AccountRange { start, end })
+    }
+
+    /// `count` accounts starting at `start`.
+    pub fn from_count(start: u32, count: u32) -> Result<Self, KeysError> {
+        let end = start.checked_add(count).ok_or(KeysError::AccountInvalid)?;
+        Self::new(start, end)
+    }
+
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+
+    pub fn len(&self) -> u32 {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, account: u32) -> bool {
+        (self.start..self.end).contains(&account)
+    }
+}
+
+impl IntoIterator for AccountRange {
+    type Item = u32;
+    type IntoIter = Range<u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.start..self.end
+    }
+}
+
+/// How [`ZipPath::parse_with_policy`] treats levels without a hardened marker.
+///
+/// Orchard has no non-hardened derivation, so an unmarked level can never be derived
+/// as written; the policy decides whether that is an error or a typo to correct.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum HardeningPolicy {
+    /// Reject unmarked levels with [`KeysError::NonHardenedPath`].
+    #[default]
+    HardenedOnly,
+    /// Derive unmarked levels hardened, for paths copied from tools that omit markers.
+    HardenUnmarked,
+}
+
+/// A ZIP-32 derivation path such as `m/32'/8133'/0'`.
+///
+/// Orchard only defines hardened derivation, so every level is hardened. Indices are
+/// stored without the hardened bit; `Display` prints `'` markers.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct ZipPath(Vec<u32>);
+
+impl ZipPath {
+    /// The account path `m/32'/coin_type'/account'`.
+    pub fn account(coin_type: u32, account: u32) -> Result<Self, KeysError> {
+        if coin_type >= HARDENED {
+            return Err(KeysError::CoinTypeInvalid);
+        }
+        if account >= HARDENED {
+            return Err(KeysError::AccountInvalid);
+        }
+        Ok(ZipPath(alloc::vec![ZIP32_PURPOSE, coin_type, account]))
+    }
+
+    /// Builds a path from unhardened index values; each is derived hardened.
+    pub fn from_indices(indices: &[u32]) -> Result<Self, KeysError> {
+        if indices.iter().any(|&i| i >= HARDENED) {
+            return Err(KeysError::PathInvalid);
+        }
+        Ok(ZipPath(indices.to_vec()))
+    }
+
+    /// Parses a path whose levels must all be marked hardened (`'`, `h` or `H`).
+    pub fn parse(s: &str) -> Result<Self, KeysError> {
+        Self::parse_with_policy(s, HardeningPolicy::HardenedOnly)
+    }
+
+    pub fn parse_with_policy(s: &str, policy: HardeningPolicy) -> Result<Self, KeysError> {
+        let mut levels = s.trim().split('/');
+        if levels.next() != Some("m") {
+            return Err(KeysError::PathInvalid);
+        }
+        let indices = levels
+            .map(|level| {
+                let digits = match level.strip_suffix(['\'', 'h', 'H']) {
+                    Some(digits) => digits,
+                    None if policy == HardeningPolicy::HardenUnmarked => level,
+                    None => {
+                        // Only report the policy error for levels that are otherwise valid.
+                        parse_index(level)?;
+                        return Err(KeysError::NonHardenedPath);
+                    }
+                };
+                parse_index(digits)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_indices(&indices)
+    }
+
+    /// Index values, without the hardened bit.
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// The levels as (hardened) ZIP-32 child indices.
+    pub fn child_indices(&self) -> Vec<ChildIndex> {
+        self.0.iter().map(|&i| ChildIndex::hardened(i)).collect()
+    }
+
+    /// The path through `indices`, the inverse of [`Self::child_indices`].
+    pub fn from_child_indices(indices: &[ChildIndex]) -> Self {
+        ZipPath(indices.iter().map(|i| i.index() - HARDENED).collect())
+    }
+
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+
+    /// `(coin_type, account)` if this is exactly `m/32'/coin_type'/account'`.
+    pub fn as_account(&self) -> Option<(u32, u32)> {
+        match self.0.as_slice() {
+            [ZIP32_PURPOSE, coin_type, account] => Some((*coin_type, *account)),
+            _ => None,
+        }
+    }
+
+    /// `(coin_type, account)` if this path starts with `m/32'/coin_type'/account'`,
+    /// possibly followed by deeper levels.
+    pub fn account_prefix(&self) -> Option<(u32, u32)> {
+        match self.0.as_slice() {
+            [ZIP32_PURPOSE, coin_type, account, ..] => Some((*coin_type, *account)),
+            _ => None,
+        }
+    }
+}
+
+fn parse_index(digits: &str) -> Result<u32, KeysError> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(KeysError::PathInvalid);
+    }
+    digits.parse::<u32>().map_err(|_| KeysError::PathInvalid)
+}
+
+impl FromStr for ZipPath {
+    type Err = KeysError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ZipPath::parse(s)
+    }
+}
+
+impl fmt::Display for ZipPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        for index in &self.0 {
+            write!(f, "/{index}'")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead as _, KeyInit as _, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::{zip316, KeysError, Seed};
+
+/// The bech32m HRP of [`encrypt_seed`] strings.
+pub const ENCRYPTED_SEED_HRP: &str = "jseedenc";
+
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = 3 + SALT_LEN;
+const TAG_LEN: usize = 16;
+
+/// Argon2id settings for [`encrypt_seed`]; each string records its own, so
+/// [`decrypt_seed`] needs none.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SeedKdf {
+    /// Memory in KiB: a power of two from 8 KiB to 4 GiB.
+    pub memory_kib: u32,
+    /// Passes over the memory, from 1 to [`Self::MAX_PASSES`].
+    pub passes: u32,
+}
+
+impl SeedKdf {
+    /// 64 MiB and three passes: slow enough to blunt guessing, fast enough for a laptop.
+    pub const DEFAULT: SeedKdf = SeedKdf {
+        memory_kib: 64 * 1024,
+        passes: 3,
+    };
+    pub const MAX_MEMORY_KIB: u32 = 4 * 1024 * 1024;
+    pub const MAX_PASSES: u32 = 16;
+
+    // Memory is recorded as its log2, so only powers of two round-trip; a forged
+    // string must not be able to ask for more than 4 GiB or 16 passes.
+    fn check(&self) -> Result<(), KeysError> {
+        if !self.memory_kib.is_power_of_two()
+            || !(8..=Self::MAX_MEMORY_KIB).contains(&self.memory_kib)
+            || !(1..=Self::MAX_PASSES).contains(&self.passes)
+        {
+            return Err(KeysError::EncryptedSeed("bad KDF parameters"));
+        }
+        Ok(())
+    }
+
+    fn cipher(&self, passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305, KeysError> {
+        self.check()?;
+        let params = Params::new(self.memory_kib, self.passes, 1, Some(32))
+            .map_err(|_| KeysError::EncryptedSeed("bad KDF parameters"))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+            .map_err(|_| KeysError::Internal)?;
+        Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_ref())))
+    }
+}
+
+impl Default for SeedKdf {
+    fn default() -> Self {
+        SeedKdf::DEFAULT
+    }
+}
+
+/// Encrypts `seed` under `passphrase` as one printable string, `jseedenc1...`, with a
+/// fresh salt from the OS RNG; see [`encrypt_seed_with`].
+#[cfg(feature = "std")]
+pub fn encrypt_seed(seed: &Seed, passphrase: &str, kdf: SeedKdf) -> Result<String, KeysError> {
+    encrypt_seed_with(&mut rand::rngs::OsRng, seed, passphrase, kdf)
+}
+
+/// Encrypts `seed` under `passphrase`, drawing the salt from `rng`.
+///
+/// The string is bech32m under [`ENCRYPTED_SEED_HRP`], so a copying mistake fails its
+/// checksum rather than looking like a wrong passphrase. Its payload is
+///
+/// ```text
+/// version (1) | log2 Argon2id memory in KiB (1) | Argon2id passes (1) | salt (16)
+///   | ChaCha20-Poly1305 ciphertext of the seed, with its tag (len + 16)
+/// ```
+///
+/// The key is Argon2id of the passphrase over the salt, so it is never reused and the
+/// nonce is fixed at zero. The HRP and header are authenticated with the seed. The
+/// passphrase is used as given; pass what people type through [`crate::normalize_secret`].
+///
+/// ```
+/// # use juno_keys::{decrypt_seed, encrypt_seed, Seed, SeedKdf};
+/// let seed = Seed::from_bytes(&[7u8; 32])?;
+/// let kdf = SeedKdf { memory_kib: 256, passes: 1 };
+/// let encrypted = encrypt_seed(&seed, "correct horse", kdf)?;
+/// assert!(encrypted.starts_with("jseedenc1"));
+/// assert_eq!(decrypt_seed(&encrypted, "correct horse")?, seed);
+/// assert!(decrypt_seed(&encrypted, "battery staple").is_err());
+/// # Ok::<(), juno_keys::KeysError>(())
+/// ```
+pub fn encrypt_seed_with<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    seed: &Seed,
+    passphrase: &str,
+    kdf: SeedKdf,
+) -> Result<String, KeysError> {
+    kdf.check()?;
+    let mut payload = Vec::with_capacity(HEADER_LEN + seed.len() + TAG_LEN);
+    payload.extend_from_slice(&[VERSION, kdf.memory_kib.ilog2() as u8, kdf.passes as u8]);
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    payload.extend_from_slice(&salt);
+
+    let ciphertext = kdf
+        .cipher(passphrase, &salt)?
+        .encrypt(
+            &Nonce::default(),
+            Payload {
+                msg: seed.as_bytes(),
+                aad: &aad(&payload),
+            },
+        )
+        .map_err(|_| KeysError::Internal)?;
+    payload.extend_from_slice(&ciphertext);
+    Ok(zip316::bech32m_encode(ENCRYPTED_SEED_HRP, &payload)?)
+}
+
+/// The seed in an [`encrypt_seed`] string.
+///
+/// Fails with [`KeysError::Bech32`] if the string is damaged, and with
+/// [`KeysError::EncryptedSeed`] if it isn't an encrypted seed or the passphrase is wrong.
+pub fn decrypt_seed(encrypted: &str, passphrase: &str) -> Result<Seed, KeysError> {
+    let (hrp, payload) = zip316::bech32m_decode(encrypted)?;
+    if hrp != ENCRYPTED_SEED_HRP || payload.len() <= HEADER_LEN + TAG_LEN {
+        return Err(KeysError::EncryptedSeed("not a jseedenc1 string"));
+    }
+    if payload[0] != VERSION {
+        return Err(KeysError::EncryptedSeed("unsupported version"));
+    }
+    let (header, ciphertext) = payload.split_at(HEADER_LEN);
+    let (m_cost_log2, passes) = (header[1], header[2]);
+    if m_cost_log2 >= 32 {
+        return Err(KeysError::EncryptedSeed("bad KDF parameters"));
+    }
+    let kdf = SeedKdf {
+        memory_kib: 1 << m_cost_log2,
+        passes: passes.into(),
+    };
+    let plaintext = Zeroizing::new(
+        kdf.cipher(passphrase, &header[3..])?
+            .decrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad(header),
+                },
+            )
+            .map_err(|_| KeysError::EncryptedSeed("wrong passphrase"))?,
+    );
+    Seed::from_bytes(&plaintext)
+}
+
+/// Whether `text` looks like an [`encrypt_seed`] string, going by its HRP alone.
+pub fn is_encrypted_seed(text: &str) -> bool {
+    let text = text.trim();
+    text.get(..ENCRYPTED_SEED_HRP.len() + 1)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("jseedenc1"))
+}
+
+fn aad(header: &[u8]) -> Vec<u8> {
+    [ENCRYPTED_SEED_HRP.as_bytes(), header].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng as _;
+
+    const FAST: SeedKdf = SeedKdf {
+        memory_kib: 256,
+        passes: 1,
+    };
+
+    #[test]
+    fn encrypted_seeds_round_trip() {
+        let seed = Seed::from_bytes(&[9; 64]).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let encrypted = encrypt_seed_with(&mut rng, &seed, "correct horse", FAST).unwrap();
+        assert!(is_encrypted_seed(&encrypted.to_uppercase()));
+        assert!(!is_encrypted_seed("jseed"));
+        assert_eq!(
+            decrypt_seed(&encrypted.to_uppercase(), "correct horse").unwrap(),
+            seed
+        );
+        assert!(matches!(
+            decrypt_seed(&encrypted, "battery staple"),
+            Err(KeysError::EncryptedSeed("wrong passphrase"))
+        ));
+
+        // The header is authenticated: a string claiming other KDF settings won't open.
+        let (_, mut payload) = zip316::bech32m_decode(&encrypted).unwrap();
+        payload[2] = 2;
+        let forged = zip316::bech32m_encode(ENCRYPTED_SEED_HRP, &payload).unwrap();
+        assert!(decrypt_seed(&forged, "correct horse").is_err());
+        payload[1] = 40;
+        let forged = zip316::bech32m_encode(ENCRYPTED_SEED_HRP, &payload).unwrap();
+        assert!(matches!(
+            decrypt_seed(&forged, "correct horse"),
+            Err(KeysError::EncryptedSeed("bad KDF parameters"))
+        ));
+
+        let last = encrypted.len() - 1;
+        let typo = format!(
+            "{}{}",
+            &encrypted[..last],
+            if encrypted.ends_with('q') { 'p' } else { 'q' }
+        );
+        assert!(matches!(
+            decrypt_seed(&typo, "correct horse"),
+            Err(KeysError::Bech32(_))
+        ));
+        let other = zip316::bech32m_encode("jseed", &payload).unwrap();
+        assert_eq!(
+            decrypt_seed(&other, "").unwrap_err().code(),
+            "encrypted_seed_invalid"
+        );
+        let slow = SeedKdf {
+            memory_kib: 1000,
+            passes: 1,
+        };
+        assert!(encrypt_seed_with(&mut rng, &seed, "x", slow).is_err());
+    }
+}
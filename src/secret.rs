@@ -0,0 +1,271 @@
+//! The buffers seeds and spending keys live in, and the process-wide hardening mode.
+//!
+//! A [`SecretBuf`] is allocated once at its final size and filled in place, so decoding
+//! leaves no stray copies. With `std` on Unix its pages are locked out of swap (best
+//! effort: `RLIMIT_MEMLOCK` may refuse), and dropping it zeroizes then unlocks them.
+//!
+//! After [`harden`], locking is no longer best effort: a buffer that can't be locked
+//! fails to allocate with `hardening_unavailable`, and the process writes no core
+//! dumps.
+
+use alloc::boxed::Box;
+use alloc::vec;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use zeroize::Zeroize as _;
+
+use crate::KeysError;
+
+static HARDENED: AtomicBool = AtomicBool::new(false);
+
+/// Disables core dumps and makes every later [`SecretBuf`] (so every [`Seed`] and
+/// spending key) fail to allocate unless its pages can be locked.
+///
+/// On Unix this sets `RLIMIT_CORE` to zero and, on Linux, clears the dumpable flag
+/// with `prctl(PR_SET_DUMPABLE, 0)`, which also keeps other processes of the same
+/// user from attaching to this one or reading its memory. It then checks that a
+/// buffer can be locked. Without `std`, or on other platforms, it fails with
+/// `hardening_unavailable`, as it does if any step is refused; the process is then
+/// only partly hardened and should not go on to load secrets.
+///
+/// Hardening lasts for the life of the process. Call it before any secret is loaded.
+///
+/// [`Seed`]: crate::Seed
+pub fn harden() -> Result<(), KeysError> {
+    os::disable_core_dumps()?;
+    let probe = vec![0u8; 1];
+    if !os::lock(&probe) {
+        return Err(KeysError::HardeningUnavailable(
+            "memory can't be locked (see RLIMIT_MEMLOCK)",
+        ));
+    }
+    os::unlock(&probe);
+    HARDENED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Whether [`harden`] has succeeded in this process.
+pub fn is_hardened() -> bool {
+    HARDENED.load(Ordering::SeqCst)
+}
+
+/// A zeroized-on-drop byte buffer for secrets, locked in memory where the OS allows.
+///
+/// `Debug` is redacted. Once the process is [hardened](harden), every buffer is locked
+/// or fails to allocate.
+pub struct SecretBuf {
+    buf: Box<[u8]>,
+    // Bytes in use; decoders allocate their upper bound and fill a prefix.
+    len: usize,
+    locked: bool,
+    // Seeds are counted by `accounting`; other secrets aren't.
+    #[cfg(feature = "secret-accounting")]
+    seed: bool,
+}
+
+impl SecretBuf {
+    /// A buffer of `len` zero bytes.
+    pub fn zeroed(len: usize) -> Result<Self, KeysError> {
+        Self::new(len, false)
+    }
+
+    /// As [`Self::zeroed`], for the one buffer of a [`Seed`](crate::Seed).
+    pub(crate) fn seed(len: usize) -> Result<Self, KeysError> {
+        Self::new(len, true)
+    }
+
+    fn new(len: usize, seed: bool) -> Result<Self, KeysError> {
+        let buf = vec![0u8; len].into_boxed_slice();
+        let locked = os::lock(&buf);
+        #[cfg(feature = "secret-accounting")]
+        if seed {
+            accounting::allocated();
+        }
+        #[cfg(not(feature = "secret-accounting"))]
+        let _ = seed;
+        let secret = SecretBuf {
+            buf,
+            len,
+            locked,
+            #[cfg(feature = "secret-accounting")]
+            seed,
+        };
+        if !locked && is_hardened() && len > 0 {
+            return Err(KeysError::HardeningUnavailable(
+                "a secret buffer can't be locked in memory",
+            ));
+        }
+        Ok(secret)
+    }
+
+    /// A locked copy of `bytes`.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, KeysError> {
+        let mut secret = Self::zeroed(bytes.len())?;
+        secret.copy_from_slice(bytes);
+        Ok(secret)
+    }
+
+    /// A copy in a buffer of its own; like [`Self::zeroed`], it fails in a hardened
+    /// process if the copy can't be locked.
+    pub fn try_clone(&self) -> Result<Self, KeysError> {
+        #[cfg(feature = "secret-accounting")]
+        let seed = self.seed;
+        #[cfg(not(feature = "secret-accounting"))]
+        let seed = false;
+        let mut copy = Self::new(self.len, seed)?;
+        copy.copy_from_slice(self);
+        Ok(copy)
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.buf[len.min(self.len)..self.len].zeroize();
+        self.len = len.min(self.len);
+    }
+
+    /// Whether the buffer's pages are locked in memory, so they can't be swapped out.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl Deref for SecretBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl DerefMut for SecretBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[..self.len]
+    }
+}
+
+impl Drop for SecretBuf {
+    fn drop(&mut self) {
+        self.buf.zeroize();
+        // Locks don't nest, so unlocking would also unpin any other secret sharing a
+        // page. Hardened processes keep their pages locked instead.
+        if self.locked && !is_hardened() {
+            os::unlock(&self.buf);
+        }
+        #[cfg(feature = "secret-accounting")]
+        if self.seed {
+            accounting::dropped();
+        }
+    }
+}
+
+impl fmt::Debug for SecretBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretBuf(..)")
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+mod os {
+    use crate::KeysError;
+
+    pub(super) fn lock(buf: &[u8]) -> bool {
+        // SAFETY: the range is a live allocation that outlives the lock; mlock only
+        // pins its pages.
+        !buf.is_empty() && unsafe { libc::mlock(buf.as_ptr().cast(), buf.len()) } == 0
+    }
+
+    pub(super) fn unlock(buf: &[u8]) {
+        // SAFETY: as for `lock`; the pages were locked by it.
+        unsafe { libc::munlock(buf.as_ptr().cast(), buf.len()) };
+    }
+
+    pub(super) fn disable_core_dumps() -> Result<(), KeysError> {
+        let no_core = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: setrlimit only reads the struct.
+        if unsafe { libc::setrlimit(libc::RLIMIT_CORE, &no_core) } != 0 {
+            return Err(KeysError::HardeningUnavailable(
+                "core dumps can't be disabled",
+            ));
+        }
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        // SAFETY: PR_SET_DUMPABLE takes one integer argument and touches no memory.
+        if unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 0) } != 0 {
+            return Err(KeysError::HardeningUnavailable(
+                "the process can't be made non-dumpable",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(all(feature = "std", unix)))]
+mod os {
+    use crate::KeysError;
+
+    pub(super) fn lock(_: &[u8]) -> bool {
+        false
+    }
+
+    pub(super) fn unlock(_: &[u8]) {}
+
+    pub(super) fn disable_core_dumps() -> Result<(), KeysError> {
+        Err(KeysError::HardeningUnavailable(
+            "not supported on this platform",
+        ))
+    }
+}
+
+/// Counts of seed buffers, for checking that a code path makes no more than it needs
+/// (feature `secret-accounting`).
+///
+/// Counts are per thread: a buffer is counted by the thread that allocates it and the
+/// thread that drops it, so tests running in parallel don't see each other's seeds.
+#[cfg(feature = "secret-accounting")]
+pub mod accounting {
+    use std::cell::Cell;
+
+    std::thread_local! {
+        static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+        static DROPPED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub(crate) fn allocated() {
+        ALLOCATED.with(|n| n.set(n.get() + 1));
+    }
+
+    pub(crate) fn dropped() {
+        DROPPED.with(|n| n.set(n.get() + 1));
+    }
+
+    /// Seed buffers this thread has allocated.
+    pub fn allocations() -> usize {
+        ALLOCATED.with(Cell::get)
+    }
+
+    /// Seed buffers this thread has allocated and not yet dropped (negative if it
+    /// dropped buffers other threads made).
+    pub fn live() -> isize {
+        ALLOCATED.with(Cell::get) as isize - DROPPED.with(Cell::get) as isize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffers_copy_truncate_and_redact() {
+        let mut secret = SecretBuf::from_slice(&[1, 2, 3, 4]).expect("buffer");
+        let copy = secret.try_clone().expect("copy");
+        secret.truncate(2);
+        assert_eq!(&secret[..], &[1, 2]);
+        assert_eq!(&copy[..], &[1, 2, 3, 4]);
+        assert_eq!(format!("{copy:?}"), "SecretBuf(..)");
+        assert!(SecretBuf::zeroed(0).expect("empty").is_empty());
+        assert!(!is_hardened());
+    }
+}
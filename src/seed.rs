@@ -0,0 +1,273 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use base64::Engine as _;
+use rand_core::{CryptoRng, RngCore};
+use secrecy::SecretString;
+use subtle::{Choice, ConstantTimeEq};
+use unicode_normalization::UnicodeNormalization as _;
+use zeroize::Zeroizing;
+
+use crate::secret::SecretBuf;
+use crate::KeysError;
+use crate::MnemonicLanguage;
+
+/// A ZIP-32 seed, zeroized on drop.
+///
+/// Every constructor enforces the ZIP-32 length bounds, so a `Seed` can always be used
+/// for derivation. The bytes live in one buffer, decoded into place and never copied
+/// by the library; with `std` on Unix it is also locked out of swap where the OS
+/// allows (see [`Seed::is_locked`]), and in a [hardened](crate::secret::harden) process
+/// a seed that can't be locked fails to construct. Borrow it with [`Seed::as_bytes`] rather than
+/// [`Seed::to_bytes`], which makes a copy.
+pub struct Seed(SecretBuf);
+
+impl Seed {
+    pub const MIN_LEN: usize = 32;
+    pub const MAX_LEN: usize = 252;
+
+    /// Draws a fresh `len`-byte seed from the OS RNG.
+    #[cfg(feature = "std")]
+    pub fn generate(len: usize) -> Result<Self, KeysError> {
+        Self::generate_with(&mut rand::rngs::OsRng, len)
+    }
+
+    /// Draws a fresh `len`-byte seed from `rng`, e.g. a seeded RNG in tests or a
+    /// hardware entropy source.
+    pub fn generate_with<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        len: usize,
+    ) -> Result<Self, KeysError> {
+        Self::check_len(len)?;
+        let mut bytes = SecretBuf::seed(len)?;
+        rng.fill_bytes(&mut bytes);
+        Ok(Seed(bytes))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KeysError> {
+        Self::check_len(bytes.len())?;
+        let mut seed = SecretBuf::seed(bytes.len())?;
+        seed.copy_from_slice(bytes);
+        Ok(Seed(seed))
+    }
+
+    pub fn from_base64(s: &str) -> Result<Self, KeysError> {
+        let s = s.trim();
+        let mut bytes = SecretBuf::seed(base64::decoded_len_estimate(s.len()))?;
+        let len = base64::engine::general_purpose::STANDARD
+            .decode_slice(s, &mut bytes)
+            .map_err(|e| match e {
+                base64::DecodeSliceError::DecodeError(e) => KeysError::Base64(e),
+                base64::DecodeSliceError::OutputSliceTooSmall => KeysError::SeedInvalid,
+            })?;
+        bytes.truncate(len);
+        Self::check_len(len)?;
+        Ok(Seed(bytes))
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, KeysError> {
+        let s = s.trim();
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        let mut bytes = SecretBuf::seed(s.len() / 2)?;
+        hex::decode_to_slice(s, &mut bytes).map_err(KeysError::Hex)?;
+        Self::check_len(bytes.len())?;
+        Ok(Seed(bytes))
+    }
+
+    /// The 64-byte BIP-39 seed for an English `phrase` and optional `passphrase`.
+    ///
+    /// Both are NFKD-normalized first, as BIP-39 specifies (see [`normalize_secret`]).
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, KeysError> {
+        Self::from_mnemonic_in(Some(MnemonicLanguage::English), phrase, passphrase)
+    }
+
+    /// As [`Seed::from_mnemonic`], for a phrase from `language`'s wordlist, or with
+    /// `None` from whichever standard wordlist holds its words.
+    ///
+    /// A phrase whose words are in several lists is taken in the first list (English
+    /// first) under which its checksum holds.
+    pub fn from_mnemonic_in(
+        language: Option<MnemonicLanguage>,
+        phrase: &str,
+        passphrase: &str,
+    ) -> Result<Self, KeysError> {
+        let phrase = normalize_secret(phrase);
+        let parse = |language| bip39::Mnemonic::parse_in_normalized(language, &phrase);
+        let mnemonic = match language.map_or_else(|| bip39::Mnemonic::language_of(&*phrase), Ok) {
+            Ok(language) => parse(language),
+            Err(bip39::Error::AmbiguousLanguages(languages)) => languages
+                .iter()
+                .find_map(|language| parse(language).ok())
+                .ok_or(bip39::Error::AmbiguousLanguages(languages)),
+            Err(e) => Err(e),
+        }
+        .map_err(KeysError::Mnemonic)?;
+        let bytes = Zeroizing::new(mnemonic.to_seed_normalized(&normalize_secret(passphrase)));
+        Self::from_bytes(bytes.as_slice())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// A zeroizing copy of the bytes, for callers that must own them.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.0.to_vec())
+    }
+
+    /// Whether the seed's pages are locked in memory, so they can't be swapped out.
+    pub fn is_locked(&self) -> bool {
+        self.0.is_locked()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The standard base64 encoding; call `expose_secret` to read it.
+    pub fn to_base64(&self) -> SecretString {
+        let encoded =
+            Zeroizing::new(base64::engine::general_purpose::STANDARD.encode(self.as_bytes()));
+        SecretString::from(encoded.as_str())
+    }
+
+    fn check_len(len: usize) -> Result<(), KeysError> {
+        crate::check_len("seed", len, Self::MIN_LEN, Self::MAX_LEN)
+    }
+}
+
+impl TryFrom<&[u8]> for Seed {
+    type Error = KeysError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Seed::from_bytes(bytes)
+    }
+}
+
+/// Parses base64, as `seed new` writes it; see [`Seed::from_base64`]. There is no
+/// `Display`: use [`Seed::to_base64`], which keeps the encoding secret.
+impl FromStr for Seed {
+    type Err = KeysError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Seed::from_base64(s)
+    }
+}
+
+impl AsRef<[u8]> for Seed {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl ConstantTimeEq for Seed {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.as_bytes().ct_eq(other.as_bytes())
+    }
+}
+
+impl PartialEq for Seed {
+    fn eq(&self, other: &Self) -> bool {
+        crate::ct_eq(self, other)
+    }
+}
+
+impl Eq for Seed {}
+
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Seed({} bytes)", self.len())
+    }
+}
+
+/// A fresh `words`-word BIP-39 recovery phrase in `language`, from the OS RNG.
+///
+/// `words` is 12, 15, 18, 21 or 24 (128 to 256 bits of entropy); anything else is
+/// `Mnemonic`. The phrase's seed is [`Seed::from_mnemonic_in`] with any passphrase.
+#[cfg(feature = "std")]
+pub fn generate_mnemonic(
+    words: usize,
+    language: MnemonicLanguage,
+) -> Result<SecretString, KeysError> {
+    generate_mnemonic_with(&mut rand::rngs::OsRng, words, language)
+}
+
+/// As [`generate_mnemonic`], drawing the entropy from `rng`.
+pub fn generate_mnemonic_with<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    words: usize,
+    language: MnemonicLanguage,
+) -> Result<SecretString, KeysError> {
+    if !matches!(words, 12 | 15 | 18 | 21 | 24) {
+        return Err(KeysError::Mnemonic(bip39::Error::BadWordCount(words)));
+    }
+    let mut entropy = Zeroizing::new([0u8; 32]);
+    let entropy = &mut entropy[..words / 3 * 4];
+    rng.fill_bytes(entropy);
+    let mnemonic =
+        bip39::Mnemonic::from_entropy_in(language, entropy).map_err(KeysError::Mnemonic)?;
+    let phrase = Zeroizing::new(alloc::string::ToString::to_string(&mnemonic));
+    Ok(SecretString::from(phrase.as_str()))
+}
+
+/// `text` in Unicode NFKD form, the form every human-entered secret (recovery phrase
+/// or passphrase) is used in.
+///
+/// The same passphrase can arrive as different code points: an `é` typed on macOS is
+/// often `e` plus a combining accent, and on Linux a single precomposed character.
+/// Normalizing makes both derive the same keys.
+///
+/// ```
+/// # use juno_keys::normalize_secret;
+/// assert_eq!(*normalize_secret("caf\u{e9}"), *normalize_secret("cafe\u{301}"));
+/// ```
+pub fn normalize_secret(text: &str) -> Zeroizing<alloc::string::String> {
+    let mut normalized = Zeroizing::new(alloc::string::String::with_capacity(text.len()));
+    normalized.extend(text.nfkd());
+    normalized
+}
+
+/// How another Zcash wallet turns its recovery phrase into Orchard keys, so users
+/// migrating into Juno tooling import the keys they already had.
+///
+/// These wallets derive under Zcash's coin types (133 and 1), so pair the account
+/// with [`crate::ZcashNetwork`] rather than the Juno network.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WalletCompat {
+    /// Zingo, which has no BIP-39 passphrase and keeps every Orchard address in
+    /// account 0, giving out diversified addresses rather than new accounts.
+    Zingo,
+    /// Ywallet, whose accounts on one seed are ZIP-32 accounts 0, 1, 2, ... and which
+    /// accepts a BIP-39 passphrase.
+    Ywallet,
+    /// zcashd 4.7 and later, whose one recovery phrase has no BIP-39 passphrase and
+    /// whose accounts are ZIP-32 accounts 0, 1, 2, ...
+    Zcashd,
+}
+
+impl WalletCompat {
+    /// The seed the wallet derives from `phrase`. Like the wallets, this ignores case
+    /// and extra whitespace; a passphrase for a wallet without one is `SeedInvalid`.
+    pub fn seed(self, phrase: &str, passphrase: &str) -> Result<Seed, KeysError> {
+        if self != WalletCompat::Ywallet && !passphrase.is_empty() {
+            return Err(KeysError::SeedInvalid);
+        }
+        let words = Zeroizing::new(phrase.split_whitespace().collect::<Vec<_>>().join(" "));
+        Seed::from_mnemonic(&Zeroizing::new(words.to_lowercase()), passphrase)
+    }
+
+    /// The ZIP-32 account holding the Orchard keys of the wallet's account `account`,
+    /// numbered from 0 as in the wallet. Zingo has only account 0; any other is
+    /// `AccountInvalid`.
+    pub fn zip32_account(self, account: u32) -> Result<u32, KeysError> {
+        match self {
+            WalletCompat::Zingo if account != 0 => Err(KeysError::AccountInvalid),
+            WalletCompat::Zingo | WalletCompat::Ywallet | WalletCompat::Zcashd => Ok(account),
+        }
+    }
+}
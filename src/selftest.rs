@@ -0,0 +1,142 @@
+//! Known-answer tests that re-run the derivation and encoding paths at runtime.
+//!
+//! The ZIP-32, Orchard key component and F4Jumble vectors come from
+//! zcash-test-vectors; the Juno vector was produced by this crate and pins its own
+//! HRPs and coin type against regressions.
+
+use alloc::vec::Vec;
+
+use orchard::keys::Scope;
+
+use crate::{zip316, DerivationRequest, KeysError, Network, OrchardKeySet, ZipPath};
+
+/// The outcome of one known-answer test.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub passed: bool,
+}
+
+// orchard_zip32.py: spending keys for seed 0x00..0x1f at each path.
+const ZIP32_ORCHARD: &[(&str, &str, &str)] = &[
+    (
+        "zip32_orchard_m",
+        "m",
+        "7eee3c1017870990a3dd6891b82f80be8976c1e7dc20d60817a5e88e8b2cd4b8",
+    ),
+    (
+        "zip32_orchard_m_1h",
+        "m/1'",
+        "98d703fcb40504c95b3b6ed10ecd50082cff97dfd1dd9aa0913c78f977c962af",
+    ),
+    (
+        "zip32_orchard_m_1h_2h",
+        "m/1'/2'",
+        "99afd8894baad58784d0ec08f5148ee2c2a17b2b294b08ef9e0a0cf14bcc0920",
+    ),
+    (
+        "zip32_orchard_m_1h_2h_3h",
+        "m/1'/2'/3'",
+        "96439ea348a4b2ce4ec7beb4543c70274c8f76495d60c5fa5f018b68f3c32367",
+    ),
+];
+
+// orchard_key_components.py, first vector.
+const KEYS_SK: &str = "5d7a8f739a2d9e945b0ce152a8049e294c4d6e66b164939daffa2ef6ee692148";
+const KEYS_DK: &str = "31d6a685be570f9faf3ca8b052e887840b2c9f8d67224ca82aefb9e2ee5bedaf";
+const KEYS_IVK: &str = "85c8b5cd1ac3ec3ad7092132f97f0178b075c81a139fd460bbe0dfcd75514724";
+const KEYS_OVK: &str = "bcc7065e59910b35993f59505be209b14bf02488750bbc8b1acdcf108c362004";
+const KEYS_INTERNAL_DK: &str = "6d61a03f746ba93b932402ac1071fc2759d4f4d684b2c5056d5b177af0fa8aa9";
+const KEYS_INTERNAL_IVK: &str = "906e2d20d00dc0bf7c520687d9df3ce9814d30ee05c215f8764a32c362f9262f";
+const KEYS_INTERNAL_OVK: &str = "d7268bebbee692286252ac60bd4df405ea499d697c454773c5c43cb170930123";
+const KEYS_DEFAULT_ADDRESS: &str =
+    "8ff3386971cb64b8e7789908dd8ebd7de92a68e586a34db8fea999efd2016fae76750afae7ee941646bcb9";
+
+// f4jumble test_vectors.py, first (48-byte) vector.
+const F4JUMBLE_NORMAL: &str = "5d7a8f739a2d9e945b0ce152a8049e294c4d6e66b164939daffa2ef6ee6921481cdd86b3cc4318d9614fc820905d042b";
+const F4JUMBLE_JUMBLED: &str = "0304d029141b995da5387c125970673504d6c764d91ea6c082123770c7139ccd88ee27368cd0c0921a0444c8e5858d22";
+
+// Seed 0x00..0x1f, mainnet, account 0, diversifier index 0.
+const JUNO_UFVK: &str = "jview193j7puy8rp26j4sr3sk7504n6cjq8z0lul6nthz5sqxs50hdspwez2284edxjnzy9l3ta9c2ttzj0qqm42ml356yztdl6zayw80f2depm6eelvq6pt2u87evf85wkpxfyek63np7kdnxlga6wckp53lq9qj0ms6zrm2f89cvxgach66m5zn86xq2ldukz";
+const JUNO_UIVK: &str = "jivk17lu2j3r5q8r8g0w33tfqq0p0a95p6eudk6jdwhx2wsf72cxyp289uqtlyv2avv5s5zl7qlf0ss5ks5zccmhl4l5nt444zfynx6klzjatdsmt38nlmfvkhtzhngzlqwzgxvnqwgj0cr";
+const JUNO_ADDRESS: &str = "j1aczdmeatkqpea5r9efg3x3skk67hxlc5wewgh3hq86jem8ssnx52yjn3c22f2hltdcf3dqdlkks4jpag8eeetgxxgxfvxpvnkc2ew64l";
+
+/// Runs every known-answer test; a deployment is sound only if all of them pass.
+pub fn run() -> Vec<Check> {
+    let mut checks = ZIP32_ORCHARD
+        .iter()
+        .map(|&(name, path, sk)| check(name, || zip32_orchard(path, sk)))
+        .collect::<Vec<_>>();
+    checks.push(check("orchard_key_components", orchard_key_components));
+    checks.push(check("f4jumble", f4jumble));
+    checks.push(check("juno_mainnet_account", juno_mainnet_account));
+    checks
+}
+
+fn check(name: &'static str, test: impl FnOnce() -> Result<bool, KeysError>) -> Check {
+    Check {
+        name,
+        passed: matches!(test(), Ok(true)),
+    }
+}
+
+fn seed() -> [u8; 32] {
+    core::array::from_fn(|i| i as u8)
+}
+
+fn zip32_orchard(path: &str, sk: &str) -> Result<bool, KeysError> {
+    let keys = OrchardKeySet::from_path(&seed(), &ZipPath::parse(path)?)?;
+    Ok(hex::encode(keys.to_bytes().as_slice()) == sk)
+}
+
+fn orchard_key_components() -> Result<bool, KeysError> {
+    let sk = hex::decode(KEYS_SK).map_err(|_| KeysError::Internal)?;
+    let keys = OrchardKeySet::from_spending_key(&sk)?;
+    let scope_matches = |scope, dk: &str, ivk: &str, ovk: &str| {
+        hex::encode(keys.incoming_viewing_key(scope).to_bytes()) == [dk, ivk].concat()
+            && hex::encode(keys.diversifier_key(scope).as_slice()) == dk
+            && hex::encode(keys.outgoing_viewing_key(scope).as_ref()) == ovk
+    };
+    let address = keys
+        .full_viewing_key()
+        .address_at(0u32, Scope::External)
+        .to_raw_address_bytes();
+    Ok(scope_matches(Scope::External, KEYS_DK, KEYS_IVK, KEYS_OVK)
+        && scope_matches(
+            Scope::Internal,
+            KEYS_INTERNAL_DK,
+            KEYS_INTERNAL_IVK,
+            KEYS_INTERNAL_OVK,
+        )
+        && hex::encode(address) == KEYS_DEFAULT_ADDRESS)
+}
+
+fn f4jumble() -> Result<bool, KeysError> {
+    let normal = hex::decode(F4JUMBLE_NORMAL).map_err(|_| KeysError::Internal)?;
+    let jumbled = zip316::f4jumble(&normal)?;
+    Ok(hex::encode(&jumbled) == F4JUMBLE_JUMBLED && zip316::f4jumble_inv(&jumbled)? == normal)
+}
+
+fn juno_mainnet_account() -> Result<bool, KeysError> {
+    let keys = DerivationRequest::new(&seed())
+        .network(Network::Mainnet)
+        .build()?;
+    Ok(keys.ufvk.as_str() == JUNO_UFVK
+        && keys.uivk == JUNO_UIVK
+        && keys.address.as_str() == JUNO_ADDRESS
+        && crate::ua_from_ufvk(JUNO_UFVK, 0u32)? == JUNO_ADDRESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_known_answers_pass() {
+        let checks = run();
+        assert_eq!(checks.len(), ZIP32_ORCHARD.len() + 3);
+        for c in &checks {
+            assert!(c.passed, "{}", c.name);
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use rand_core::{CryptoRng, RngCore};
+use secrecy::SecretString;
+use zeroize::Zeroizing;
+
+use crate::{zip316, KeysError, Seed};
+
+/// The bech32m HRP of [`SeedShare`] strings.
+pub const SEED_SHARE_HRP: &str = "jshare";
+
+const VERSION: u8 = 2;
+const ID_LEN: usize = 4;
+const HEADER_LEN: usize = 1 + ID_LEN + 2;
+const DIGEST_LEN: usize = 4;
+const DIGEST_PERSONALIZATION: &[u8; 16] = b"JunoKeys_ShareDg";
+
+/// One share of a seed split M-of-N by [`split_seed`]: any `threshold` shares of the
+/// split rebuild the seed with [`combine_seed`], and fewer say nothing about it.
+///
+/// A share prints as one bech32m string, `jshare1...`, whose checksum catches copying
+/// mistakes. Its payload is
+///
+/// ```text
+/// version (1) | split ID (4) | threshold (1) | index (1) | share of each seed byte
+///     | share of each digest byte (4)
+/// ```
+///
+/// The split ID is random, drawn afresh for every split, and only tells shares of
+/// different splits apart. What [`combine_seed`] rebuilds is checked against a 4-byte
+/// BLAKE2b digest of the seed that is split along with it, as SLIP-39 does, so no
+/// share carries anything computed from the seed in the clear.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SeedShare {
+    id: [u8; ID_LEN],
+    threshold: u8,
+    index: u8,
+    value: Zeroizing<Vec<u8>>,
+}
+
+impl SeedShare {
+    /// Decodes a `jshare1...` string, in either case.
+    pub fn parse(encoded: &str) -> Result<Self, KeysError> {
+        let (hrp, payload) = zip316::bech32m_decode(encoded.trim())?;
+        let payload = Zeroizing::new(payload);
+        if hrp != SEED_SHARE_HRP || payload.len() < HEADER_LEN + Seed::MIN_LEN + DIGEST_LEN {
+            return Err(KeysError::SeedShare("not a jshare1 string"));
+        }
+        if payload[0] != VERSION {
+            return Err(KeysError::SeedShare("unsupported version"));
+        }
+        let (threshold, index) = (payload[1 + ID_LEN], payload[2 + ID_LEN]);
+        if threshold < 2 || index == 0 || payload.len() > HEADER_LEN + Seed::MAX_LEN + DIGEST_LEN {
+            return Err(KeysError::SeedShare("malformed share"));
+        }
+        let mut id = [0u8; ID_LEN];
+        id.copy_from_slice(&payload[1..1 + ID_LEN]);
+        Ok(SeedShare {
+            id,
+            threshold,
+            index,
+            value: Zeroizing::new(payload[HEADER_LEN..].to_vec()),
+        })
+    }
+
+    /// The `jshare1...` string; as secret as the seed once `threshold` are together.
+    pub fn encode(&self) -> Result<SecretString, KeysError> {
+        let mut payload = Zeroizing::new(Vec::with_capacity(HEADER_LEN + self.value.len()));
+        payload.push(VERSION);
+        payload.extend_from_slice(&self.id);
+        payload.extend_from_slice(&[self.threshold, self.index]);
+        payload.extend_from_slice(&self.value);
+        Ok(zip316::bech32m_encode(SEED_SHARE_HRP, &payload)?.into())
+    }
+
+    /// How many shares of the split rebuild the seed.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// This share's x-coordinate, from 1 to the number of shares.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// The split this share belongs to, as 8 hex digits.
+    pub fn split_id(&self) -> alloc::string::String {
+        hex::encode(self.id)
+    }
+}
+
+impl fmt::Debug for SeedShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SeedShare({}, {} of {}-of-N)",
+            self.split_id(),
+            self.index,
+            self.threshold
+        )
+    }
+}
+
+/// Splits `seed` into `shares` shares, any `threshold` of which rebuild it, with
+/// coefficients from the OS RNG; see [`split_seed_with`].
+#[cfg(feature = "std")]
+pub fn split_seed(seed: &Seed, threshold: u8, shares: u8) -> Result<Vec<SeedShare>, KeysError> {
+    split_seed_with(&mut rand::rngs::OsRng, seed, threshold, shares)
+}
+
+/// Splits `seed` with Shamir's scheme over GF(2^8), one random polynomial of degree
+/// `threshold - 1` per byte of the seed and of its digest, drawing the split ID and
+/// the coefficients from `rng`.
+///
+/// `threshold` must be at least 2 and at most `shares`; share `i` is the polynomials
+/// evaluated at `i`, from 1 to `shares`.
+///
+/// ```
+/// # use juno_keys::{combine_seed, split_seed, Seed};
+/// let seed = Seed::from_bytes(&[7u8; 32])?;
+/// let shares = split_seed(&seed, 2, 3)?;
+/// assert_eq!(combine_seed(&shares[1..])?, seed);
+/// assert!(combine_seed(&shares[..1]).is_err());
+/// # Ok::<(), juno_keys::KeysError>(())
+/// ```
+pub fn split_seed_with<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    seed: &Seed,
+    threshold: u8,
+    shares: u8,
+) -> Result<Vec<SeedShare>, KeysError> {
+    if threshold < 2 || shares < threshold {
+        return Err(KeysError::SeedShare(
+            "the threshold must be from 2 to the number of shares",
+        ));
+    }
+    let mut secret = Zeroizing::new(Vec::with_capacity(seed.len() + DIGEST_LEN));
+    secret.extend_from_slice(seed.as_bytes());
+    secret.extend_from_slice(&digest(seed.as_bytes()));
+    let mut id = [0u8; ID_LEN];
+    rng.fill_bytes(&mut id);
+    // Coefficient k of every byte's polynomial, for k from 1 to threshold - 1.
+    let mut coefficients =
+        Zeroizing::new(alloc::vec![0u8; (threshold as usize - 1) * secret.len()]);
+    rng.fill_bytes(&mut coefficients);
+    Ok((1..=shares)
+        .map(|x| {
+            let value = secret
+                .iter()
+                .enumerate()
+                .map(|(i, &s)| {
+                    let highest_first = coefficients.chunks(secret.len()).rev();
+                    let y = highest_first.fold(0, |y, c| mul(y, x) ^ c[i]);
+                    mul(y, x) ^ s
+                })
+                .collect();
+            SeedShare {
+                id,
+                threshold,
+                index: x,
+                value: Zeroizing::new(value),
+            }
+        })
+        .collect())
+}
+
+/// Rebuilds the seed from at least `threshold` shares of one split, in any order.
+///
+/// Fails with [`KeysError::SeedShare`] if the shares are from different splits, too
+/// few, or disagree (a share was altered, or two differ at the same index).
+pub fn combine_seed(shares: &[SeedShare]) -> Result<Seed, KeysError> {
+    let first = shares.first().ok_or(KeysError::SeedShare("no shares"))?;
+    let mut points: Vec<&SeedShare> = Vec::with_capacity(first.threshold.into());
+    for share in shares {
+        if share.id != first.id
+            || share.threshold != first.threshold
+            || share.value.len() != first.value.len()
+        {
+            return Err(KeysError::SeedShare("the shares are from different splits"));
+        }
+        match points.iter().find(|p| p.index == share.index) {
+            Some(p) if !crate::ct_eq(&*p.value, &*share.value) => {
+                return Err(KeysError::SeedShare("two shares have the same index"))
+            }
+            Some(_) => {}
+            None if points.len() < first.threshold.into() => points.push(share),
+            None => {}
+        }
+    }
+    if points.len() < first.threshold.into() {
+        return Err(KeysError::SeedShare("fewer shares than the threshold"));
+    }
+
+    let points = points
+        .iter()
+        .map(|share| (share.index, share.value.as_slice()))
+        .collect::<Vec<_>>();
+    let secret = interpolate(&points, 0);
+    let (seed, check) = secret.split_at(secret.len() - DIGEST_LEN);
+    if !crate::ct_eq(&digest(seed), check) {
+        return Err(KeysError::SeedShare(
+            "the shares don't rebuild the seed they were split from",
+        ));
+    }
+    Seed::from_bytes(seed)
+}
+
+// The polynomials through `points` (x, y of each byte), evaluated at `x`. The
+// x-coordinates are public, so only the products with y need to be constant-time.
+fn interpolate(points: &[(u8, &[u8])], x: u8) -> Zeroizing<Vec<u8>> {
+    let mut out = Zeroizing::new(alloc::vec![0u8; points[0].1.len()]);
+    for (i, &(xi, ys)) in points.iter().enumerate() {
+        let basis = points
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(1, |basis, (_, &(xj, _))| {
+                mul(basis, mul(x ^ xj, inv(xi ^ xj)))
+            });
+        for (out, &y) in out.iter_mut().zip(ys) {
+            *out ^= mul(basis, y);
+        }
+    }
+    out
+}
+
+fn digest(seed: &[u8]) -> [u8; DIGEST_LEN] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(DIGEST_PERSONALIZATION)
+        .hash(seed);
+    let mut digest = [0u8; DIGEST_LEN];
+    digest.copy_from_slice(&hash.as_bytes()[..DIGEST_LEN]);
+    digest
+}
+
+// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1, without branches or
+// table lookups that depend on the operands.
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+// a^254, the inverse of a nonzero a.
+fn inv(a: u8) -> u8 {
+    let (mut result, mut square) = (1, a);
+    for bit in 0..8 {
+        if 254 >> bit & 1 == 1 {
+            result = mul(result, square);
+        }
+        square = mul(square, square);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::SeedableRng as _;
+
+    #[test]
+    fn field_inverses_are_inverses() {
+        for a in 1..=255u8 {
+            assert_eq!(mul(a, inv(a)), 1);
+        }
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+    }
+
+    #[test]
+    fn shares_round_trip_as_strings() {
+        let seed = Seed::from_bytes(&[3; 64]).expect("seed");
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let shares = split_seed_with(&mut rng, &seed, 3, 5).expect("split");
+        let encoded = shares[4].encode().expect("encode");
+        let text = secrecy::ExposeSecret::expose_secret(&encoded);
+        assert!(text.starts_with("jshare1"));
+        let parsed = SeedShare::parse(&text.to_uppercase()).expect("parse");
+        assert_eq!(parsed, shares[4]);
+        assert_eq!((parsed.threshold(), parsed.index()), (3, 5));
+        assert!(!format!("{parsed:?}").contains(&hex::encode(&*parsed.value)));
+
+        let typo = format!("{}q", &text[..text.len() - 1]);
+        assert!(typo == text || SeedShare::parse(&typo).is_err());
+        assert!(split_seed_with(&mut rng, &seed, 1, 5).is_err());
+        assert!(split_seed_with(&mut rng, &seed, 4, 3).is_err());
+    }
+
+    #[test]
+    fn bad_share_sets_are_refused() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(2);
+        let seed = Seed::from_bytes(&[5; 32]).expect("seed");
+        let other = Seed::from_bytes(&[6; 32]).expect("seed");
+        let shares = split_seed_with(&mut rng, &seed, 2, 3).expect("split");
+        let others = split_seed_with(&mut rng, &other, 2, 3).expect("split");
+        let code = |shares: &[SeedShare]| combine_seed(shares).expect_err("refused").code();
+
+        assert_eq!(code(&[]), "seed_share_invalid");
+        assert_eq!(
+            code(&[shares[0].clone(), others[1].clone()]),
+            "seed_share_invalid"
+        );
+        assert_eq!(
+            code(&[shares[0].clone(), shares[0].clone()]),
+            "seed_share_invalid"
+        );
+        let mut altered = shares[1].clone();
+        altered.value[0] ^= 1;
+        assert_eq!(code(&[shares[0].clone(), altered]), "seed_share_invalid");
+        // A repeated share is ignored.
+        let repeated = [shares[2].clone(), shares[2].clone(), shares[0].clone()];
+        assert_eq!(combine_seed(&repeated).expect("combine"), seed);
+    }
+
+    #[test]
+    fn split_ids_are_fresh_for_every_split() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let seed = Seed::from_bytes(&[5; 32]).expect("seed");
+        let first = split_seed_with(&mut rng, &seed, 2, 2).expect("split");
+        let again = split_seed_with(&mut rng, &seed, 2, 2).expect("split");
+        assert_ne!(first[0].split_id(), again[0].split_id());
+        assert_eq!(first[0].split_id(), first[1].split_id());
+        // Shares of two splits of one seed still don't mix.
+        assert!(combine_seed(&[first[0].clone(), again[1].clone()]).is_err());
+        assert_eq!(combine_seed(&again).expect("combine"), seed);
+    }
+
+    // A split and a shuffled subset of its shares: `enough` to rebuild it, or fewer.
+    fn arb_split(enough: bool) -> impl Strategy<Value = (Vec<u8>, u8, u8, u64, Vec<usize>)> {
+        (
+            prop::collection::vec(any::<u8>(), Seed::MIN_LEN..=64),
+            2..=6u8,
+        )
+            .prop_flat_map(|(seed, threshold)| {
+                (Just(seed), Just(threshold), threshold..=8u8, any::<u64>())
+            })
+            .prop_flat_map(move |(seed, threshold, count, rng_seed)| {
+                let sizes = if enough {
+                    threshold as usize..=count as usize
+                } else {
+                    1..=threshold as usize - 1
+                };
+                let picks =
+                    prop::sample::subsequence((0..count as usize).collect::<Vec<_>>(), sizes);
+                (
+                    Just(seed),
+                    Just(threshold),
+                    Just(count),
+                    Just(rng_seed),
+                    picks.prop_shuffle(),
+                )
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn any_threshold_subset_rebuilds_the_seed(
+            (bytes, threshold, count, rng_seed, picks) in arb_split(true),
+        ) {
+            let seed = Seed::from_bytes(&bytes).expect("seed");
+            let mut rng = rand::rngs::StdRng::seed_from_u64(rng_seed);
+            let shares = split_seed_with(&mut rng, &seed, threshold, count).expect("split");
+            let subset = picks.iter().map(|&i| shares[i].clone()).collect::<Vec<_>>();
+            prop_assert_eq!(combine_seed(&subset).expect("combine"), seed);
+        }
+
+        #[test]
+        fn sub_threshold_subsets_rebuild_nothing(
+            (bytes, threshold, count, rng_seed, picks) in arb_split(false),
+            other in any::<u8>(),
+        ) {
+            let seed = Seed::from_bytes(&bytes).expect("seed");
+            let mut rng = rand::rngs::StdRng::seed_from_u64(rng_seed);
+            let shares = split_seed_with(&mut rng, &seed, threshold, count).expect("split");
+            let subset = picks.iter().map(|&i| shares[i].clone()).collect::<Vec<_>>();
+            prop_assert!(combine_seed(&subset).is_err());
+
+            // The subset fits every secret (seed and digest) equally well: for any
+            // guess, some share completes it to that guess.
+            let guess = alloc::vec![other; bytes.len() + DIGEST_LEN];
+            let mut points = subset
+                .iter()
+                .map(|share| (share.index, share.value.as_slice()))
+                .collect::<Vec<_>>();
+            let last = shares
+                .iter()
+                .map(SeedShare::index)
+                .find(|x| points.iter().all(|&(picked, _)| picked != *x))
+                .expect("an unpicked share");
+            points.push((0, &guess));
+            let completion = interpolate(&points, last);
+            points.pop();
+            points.push((last, &completion));
+            prop_assert_eq!(&*interpolate(&points, 0), &guess);
+        }
+    }
+}
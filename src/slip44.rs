@@ -0,0 +1,94 @@
+use crate::KeysError;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Slip44Entry {
+    pub coin_type: u32,
+    pub symbol: &'static str,
+    pub name: &'static str,
+}
+
+const fn entry(coin_type: u32, symbol: &'static str, name: &'static str) -> Slip44Entry {
+    Slip44Entry {
+        coin_type,
+        symbol,
+        name,
+    }
+}
+
+// Widely used registrations from SLIP-0044, sorted by coin type. This is a curated
+// subset of the registry, not a full mirror.
+const REGISTERED: &[Slip44Entry] = &[
+    entry(0, "BTC", "Bitcoin"),
+    entry(1, "", "Testnet (all coins)"),
+    entry(2, "LTC", "Litecoin"),
+    entry(3, "DOGE", "Dogecoin"),
+    entry(4, "RDD", "Reddcoin"),
+    entry(5, "DASH", "Dash"),
+    entry(6, "PPC", "Peercoin"),
+    entry(7, "NMC", "Namecoin"),
+    entry(20, "DGB", "DigiByte"),
+    entry(22, "MONA", "Monacoin"),
+    entry(28, "VTC", "Vertcoin"),
+    entry(42, "DCR", "Decred"),
+    entry(43, "XEM", "NEM"),
+    entry(57, "SYS", "Syscoin"),
+    entry(60, "ETH", "Ether"),
+    entry(61, "ETC", "Ether Classic"),
+    entry(77, "XVG", "Verge"),
+    entry(118, "ATOM", "Atom"),
+    entry(119, "PIVX", "PIVX"),
+    entry(121, "ZEN", "Horizen"),
+    entry(128, "XMR", "Monero"),
+    entry(133, "ZEC", "Zcash"),
+    entry(134, "LSK", "Lisk"),
+    entry(135, "STEEM", "Steem"),
+    entry(136, "FIRO", "Firo"),
+    entry(141, "KMD", "Komodo"),
+    entry(144, "XRP", "Ripple"),
+    entry(145, "BCH", "Bitcoin Cash"),
+    entry(147, "ZCL", "Zclassic"),
+    entry(148, "XLM", "Stellar Lumens"),
+    entry(156, "BTG", "Bitcoin Gold"),
+    entry(165, "XNO", "Nano"),
+    entry(175, "RVN", "Ravencoin"),
+    entry(194, "EOS", "EOS"),
+    entry(195, "TRX", "Tron"),
+    entry(283, "ALGO", "Algorand"),
+    entry(330, "LUNA", "Terra"),
+    entry(354, "DOT", "Polkadot"),
+    entry(397, "NEAR", "NEAR Protocol"),
+    entry(434, "KSM", "Kusama"),
+    entry(461, "FIL", "Filecoin"),
+    entry(501, "SOL", "Solana"),
+    entry(529, "SCRT", "Secret Network"),
+    entry(637, "APT", "Aptos"),
+    entry(714, "BNB", "Binance"),
+    entry(784, "SUI", "Sui"),
+    entry(818, "VET", "VeChain Token"),
+    entry(966, "MATIC", "Polygon"),
+    entry(1729, "XTZ", "Tezos"),
+    entry(1815, "ADA", "Cardano"),
+    entry(5353, "HNS", "Handshake"),
+    entry(9000, "AVAX", "Avalanche"),
+];
+
+/// Looks up a coin type in the bundled SLIP-44 table.
+pub fn lookup(coin_type: u32) -> Option<&'static Slip44Entry> {
+    REGISTERED
+        .binary_search_by_key(&coin_type, |e| e.coin_type)
+        .ok()
+        .map(|i| &REGISTERED[i])
+}
+
+/// Checks a custom coin type for collisions with registered coins.
+///
+/// Returns the colliding entry so callers can warn; under `strict` a collision is an error.
+pub fn check_coin_type(
+    coin_type: u32,
+    strict: bool,
+) -> Result<Option<&'static Slip44Entry>, KeysError> {
+    match lookup(coin_type) {
+        Some(_) if strict => Err(KeysError::CoinTypeRegistered),
+        found => Ok(found),
+    }
+}
@@ -0,0 +1,100 @@
+//! Proptest strategies for property-testing code built on this crate.
+//!
+//! Enabled by the `testing` feature; not meant for production builds.
+
+use alloc::vec::Vec;
+
+use proptest::collection::{btree_map, vec};
+use proptest::prelude::*;
+
+use crate::zip316::{TYPECODE_ORCHARD, TYPECODE_P2PKH, TYPECODE_P2SH, TYPECODE_SAPLING};
+use crate::{AccountRange, Network, Seed, ZipPath};
+
+/// Seeds of every length ZIP-32 allows.
+pub fn arb_seed() -> impl Strategy<Value = Seed> {
+    vec(any::<u8>(), Seed::MIN_LEN..=Seed::MAX_LEN)
+        .prop_map(|bytes| Seed::from_bytes(&bytes).expect("length in range"))
+}
+
+/// Valid (non-hardened) ZIP-32 account indices.
+pub fn arb_account() -> impl Strategy<Value = u32> {
+    0..=AccountRange::MAX_ACCOUNT
+}
+
+pub fn arb_network() -> impl Strategy<Value = Network> {
+    prop::sample::select(Network::ALL.to_vec())
+}
+
+/// Account paths `m/32'/coin_type'/account'` for any coin type.
+pub fn arb_account_path() -> impl Strategy<Value = ZipPath> {
+    (0..crate::path::HARDENED, arb_account())
+        .prop_map(|(coin_type, account)| ZipPath::account(coin_type, account).expect("in range"))
+}
+
+/// Canonical unified-container items: ascending unique typecodes, at most one
+/// transparent receiver and at least one other item. Values are arbitrary bytes, so
+/// known typecodes don't necessarily decode as receivers or keys.
+pub fn arb_container_items() -> impl Strategy<Value = Vec<(u64, Vec<u8>)>> {
+    let transparent = prop_oneof![
+        Just(None),
+        Just(Some(TYPECODE_P2PKH)),
+        Just(Some(TYPECODE_P2SH))
+    ];
+    let other_typecodes = prop_oneof![
+        Just(TYPECODE_SAPLING),
+        Just(TYPECODE_ORCHARD),
+        4u64..=0xffff,
+    ];
+    (
+        transparent,
+        vec(any::<u8>(), 0..64),
+        // Non-transparent values of 32+ bytes keep the payload above F4Jumble's minimum.
+        btree_map(other_typecodes, vec(any::<u8>(), 32..128), 1..4),
+    )
+        .prop_map(|(transparent, transparent_value, others)| {
+            transparent
+                .map(|typecode| (typecode, transparent_value))
+                .into_iter()
+                .chain(others)
+                .collect()
+        })
+}
+
+impl Arbitrary for Network {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Network>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        arb_network().boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zip316;
+
+    proptest! {
+        #[test]
+        fn container_items_roundtrip(items in arb_container_items()) {
+            let borrowed = items
+                .iter()
+                .map(|(typecode, value)| (*typecode, value.as_slice()))
+                .collect::<Vec<_>>();
+            let encoded = zip316::encode_unified_container("jtest", &borrowed).expect("encode");
+            prop_assert_eq!(zip316::decode_unified_container("jtest", &encoded).expect("decode"), items);
+        }
+
+        #[test]
+        fn account_paths_roundtrip(path in arb_account_path()) {
+            prop_assert_eq!(path.to_string().parse::<ZipPath>().expect("parse"), path);
+        }
+
+        #[test]
+        fn seeds_have_valid_lengths(seed in arb_seed(), network in any::<Network>()) {
+            prop_assert!((Seed::MIN_LEN..=Seed::MAX_LEN).contains(&seed.len()));
+            prop_assert!(crate::seed_fingerprint(&seed).is_ok());
+            prop_assert!(Network::ALL.contains(&network));
+        }
+    }
+}
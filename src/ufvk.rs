@@ -0,0 +1,344 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+use orchard::keys::{DiversifierIndex, FullViewingKey, IncomingViewingKey, Scope};
+
+use crate::network::{self, Network};
+use crate::zip316::{self, TYPECODE_ORCHARD};
+use crate::{
+    ua_hrp_from_ufvk_hrp, ufvk_hrp_from_ua_hrp, uivk_hrp_from_ua_hrp, KeysError, NetworkParams,
+    UnifiedAddress, ORCHARD_FVK_LEN, UFVK_FP_PERSONALIZATION,
+};
+
+/// A parsed and validated unified full viewing key.
+///
+/// Parsing checks the HRP, the container encoding and the Orchard item (if any);
+/// `Display` yields the lowercase encoding, which re-parses to an equal value.
+/// Equality compares the encodings in constant time.
+#[derive(Clone, Debug)]
+pub struct Ufvk {
+    encoded: String,
+    hrp: String,
+    ua_hrp: String,
+    items: Vec<(u64, Vec<u8>)>,
+}
+
+impl Ufvk {
+    /// Length of an Orchard full viewing key item.
+    pub const ORCHARD_FVK_LEN: usize = ORCHARD_FVK_LEN;
+
+    pub fn parse(s: &str) -> Result<Self, KeysError> {
+        let encoded = s.trim().to_ascii_lowercase();
+        let hrp = network::encoded_hrp(&encoded)?;
+        let ua_hrp = ua_hrp_from_ufvk_hrp(&hrp)?;
+        let items = zip316::decode_unified_container(&hrp, &encoded)?;
+
+        let ufvk = Ufvk {
+            encoded,
+            hrp,
+            ua_hrp,
+            items,
+        };
+        if ufvk.has_item(TYPECODE_ORCHARD) && ufvk.orchard().is_none() {
+            return Err(KeysError::EncodingInvalid);
+        }
+        Ok(ufvk)
+    }
+
+    /// Wraps an Orchard full viewing key for the network whose UA HRP is `ua_hrp`.
+    pub fn from_orchard(ua_hrp: &str, fvk: &FullViewingKey) -> Result<Self, KeysError> {
+        let ufvk_hrp = ufvk_hrp_from_ua_hrp(ua_hrp)?;
+        let encoded =
+            zip316::encode_single_item_container(&ufvk_hrp, TYPECODE_ORCHARD, &fvk.to_bytes())
+                .map_err(|_| KeysError::Internal)?;
+        Ufvk::parse(&encoded)
+    }
+
+    /// Wraps raw Orchard full viewing key bytes, `ak || nk || rivk`, for `network`.
+    pub fn from_orchard_fvk<N: NetworkParams + ?Sized>(
+        network: &N,
+        fvk: &[u8],
+    ) -> Result<Self, KeysError> {
+        let raw =
+            <&[u8; ORCHARD_FVK_LEN]>::try_from(fvk).map_err(|_| KeysError::LengthOutOfRange {
+                what: "orchard_fvk",
+                len: fvk.len(),
+                min: ORCHARD_FVK_LEN,
+                max: ORCHARD_FVK_LEN,
+            })?;
+        let fvk = FullViewingKey::from_bytes(raw).ok_or(KeysError::EncodingInvalid)?;
+        Ufvk::from_orchard(network.ua_hrp(), &fvk)
+    }
+
+    /// Rebuilds a key from its raw ZIP-316 encoding (see [`Self::to_bytes`]) under the
+    /// UFVK HRP `hrp`; raw bytes carry no network, so there is no `TryFrom<&[u8]>`.
+    pub fn from_bytes(hrp: &str, raw: &[u8]) -> Result<Self, KeysError> {
+        Ufvk::parse(&zip316::encode_raw_container(hrp, raw)?)
+    }
+
+    /// The raw ZIP-316 encoding: the concatenated items, without HRP or checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        zip316::encode_raw_items(&self.tlvs())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.encoded
+    }
+
+    pub fn hrp(&self) -> &str {
+        &self.hrp
+    }
+
+    /// The HRP of addresses derived from this key (`jview` -> `j`).
+    pub fn ua_hrp(&self) -> &str {
+        &self.ua_hrp
+    }
+
+    /// The Juno network, or `None` for Zcash and custom-network keys.
+    pub fn network(&self) -> Option<Network> {
+        Network::from_hrp(&self.hrp)
+    }
+
+    /// The network for an upstream Zcash (`uview`) key.
+    pub fn zcash_network(&self) -> Option<Network> {
+        Network::from_zcash_hrp(&self.hrp)
+    }
+
+    pub fn typecodes(&self) -> Vec<u64> {
+        self.items.iter().map(|(typecode, _)| *typecode).collect()
+    }
+
+    pub fn items(&self) -> &[(u64, Vec<u8>)] {
+        &self.items
+    }
+
+    pub fn orchard(&self) -> Option<FullViewingKey> {
+        self.items
+            .iter()
+            .find(|(typecode, _)| *typecode == TYPECODE_ORCHARD)
+            .and_then(|(_, value)| <&[u8; ORCHARD_FVK_LEN]>::try_from(value.as_slice()).ok())
+            .and_then(FullViewingKey::from_bytes)
+    }
+
+    /// The raw bytes of the Orchard item, `ak || nk || rivk`, if the key has one.
+    pub fn to_orchard_fvk(&self) -> Option<[u8; ORCHARD_FVK_LEN]> {
+        self.orchard().map(|fvk| fvk.to_bytes())
+    }
+
+    /// The external-scope Orchard address at `diversifier_index`.
+    pub fn address_at(
+        &self,
+        diversifier_index: impl Into<DiversifierIndex>,
+    ) -> Result<UnifiedAddress, KeysError> {
+        self.address_generator()?.address_at(diversifier_index)
+    }
+
+    /// The first diversifier index at or after `from` that yields an external-scope
+    /// address, and that address; see [`AddressGenerator::next_address`].
+    pub fn next_address(
+        &self,
+        from: impl Into<DiversifierIndex>,
+    ) -> Result<(DiversifierIndex, UnifiedAddress), KeysError> {
+        self.address_generator()?.next_address(from)
+    }
+
+    /// Prepares the key for deriving many external-scope addresses.
+    pub fn address_generator(&self) -> Result<AddressGenerator, KeysError> {
+        let fvk = self.orchard().ok_or(KeysError::EncodingInvalid)?;
+        Ok(AddressGenerator {
+            ua_hrp: self.ua_hrp.clone(),
+            ivk: fvk.to_ivk(Scope::External),
+        })
+    }
+
+    /// The diversifier index of `address` if it is one of this key's external-scope
+    /// Orchard addresses, or `None` if it isn't (or either lacks an Orchard item).
+    pub fn diversifier_index_of(&self, address: &UnifiedAddress) -> Option<DiversifierIndex> {
+        self.orchard()?
+            .to_ivk(Scope::External)
+            .diversifier_index(&address.orchard()?)
+    }
+
+    /// Prepares the key for finding the scope and index of many addresses.
+    pub fn address_locator(&self) -> Result<AddressLocator, KeysError> {
+        let fvk = self.orchard().ok_or(KeysError::EncodingInvalid)?;
+        Ok(AddressLocator {
+            ua_hrp: self.ua_hrp.clone(),
+            external: fvk.to_ivk(Scope::External),
+            internal: fvk.to_ivk(Scope::Internal),
+        })
+    }
+
+    /// Lazily derives `scope`'s addresses from diversifier index 0 upwards.
+    ///
+    /// Empty if the key has no Orchard item.
+    pub fn addresses(
+        &self,
+        scope: Scope,
+    ) -> impl Iterator<Item = (DiversifierIndex, UnifiedAddress)> + '_ {
+        self.addresses_from(scope, DiversifierIndex::new())
+    }
+
+    /// As [`Self::addresses`], starting at `start` and ending after index `2^88 - 1`.
+    pub fn addresses_from(
+        &self,
+        scope: Scope,
+        start: impl Into<DiversifierIndex>,
+    ) -> impl Iterator<Item = (DiversifierIndex, UnifiedAddress)> + '_ {
+        let fvk = self.orchard();
+        core::iter::successors(Some(start.into()), |j| {
+            let mut next = *j;
+            next.increment().ok().map(|()| next)
+        })
+        .map_while(move |j| {
+            let address = self.orchard_address(fvk.as_ref()?, j, scope).ok()?;
+            Some((j, address))
+        })
+    }
+
+    fn orchard_address(
+        &self,
+        fvk: &FullViewingKey,
+        diversifier_index: DiversifierIndex,
+        scope: Scope,
+    ) -> Result<UnifiedAddress, KeysError> {
+        let address = fvk.address_at(diversifier_index, scope);
+        UnifiedAddress::from_receivers(
+            &self.ua_hrp,
+            &[(TYPECODE_ORCHARD, &address.to_raw_address_bytes())],
+        )
+    }
+
+    /// The external-scope unified incoming viewing key.
+    pub fn uivk(&self) -> Result<String, KeysError> {
+        let fvk = self.orchard().ok_or(KeysError::EncodingInvalid)?;
+        let uivk_hrp = uivk_hrp_from_ua_hrp(&self.ua_hrp)?;
+        let ivk = fvk.to_ivk(Scope::External);
+        zip316::encode_single_item_container(&uivk_hrp, TYPECODE_ORCHARD, &ivk.to_bytes())
+            .map_err(|_| KeysError::Internal)
+    }
+
+    /// Hex BLAKE2b-256 over the container items, independent of the HRP.
+    pub fn fingerprint(&self) -> String {
+        let mut state = blake2b_simd::Params::new()
+            .hash_length(32)
+            .personal(UFVK_FP_PERSONALIZATION)
+            .to_state();
+        for (typecode, value) in &self.items {
+            state.update(&typecode.to_le_bytes());
+            state.update(&(value.len() as u64).to_le_bytes());
+            state.update(value);
+        }
+        hex::encode(state.finalize().as_bytes())
+    }
+
+    fn tlvs(&self) -> Vec<zip316::Tlv<'_>> {
+        self.items
+            .iter()
+            .map(|(typecode, value)| zip316::Tlv {
+                typecode: *typecode,
+                value,
+            })
+            .collect()
+    }
+
+    fn has_item(&self, typecode: u64) -> bool {
+        self.items.iter().any(|(tc, _)| *tc == typecode)
+    }
+}
+
+/// A UFVK's external-scope incoming viewing key, from [`Ufvk::address_generator`].
+///
+/// Computing the IVK dominates a single [`Ufvk::address_at`]; holding it makes each
+/// further address one diversifier hash and scalar multiplication.
+#[derive(Clone, Debug)]
+pub struct AddressGenerator {
+    ua_hrp: String,
+    ivk: IncomingViewingKey,
+}
+
+impl AddressGenerator {
+    /// The same address as [`Ufvk::address_at`].
+    pub fn address_at(
+        &self,
+        diversifier_index: impl Into<DiversifierIndex>,
+    ) -> Result<UnifiedAddress, KeysError> {
+        let address = self.ivk.address_at(diversifier_index.into());
+        UnifiedAddress::from_receivers(
+            &self.ua_hrp,
+            &[(TYPECODE_ORCHARD, &address.to_raw_address_bytes())],
+        )
+    }
+
+    /// The first diversifier index at or after `from` that yields an address, and
+    /// that address.
+    ///
+    /// Unlike Sapling, Orchard accepts every diversifier, so for the Orchard-only
+    /// addresses derived here this is always `from`. Wallets should still record the
+    /// index returned rather than the one asked for, so they keep working once
+    /// addresses carry receivers that skip indices.
+    pub fn next_address(
+        &self,
+        from: impl Into<DiversifierIndex>,
+    ) -> Result<(DiversifierIndex, UnifiedAddress), KeysError> {
+        let index = from.into();
+        Ok((index, self.address_at(index)?))
+    }
+}
+
+/// A UFVK's incoming viewing keys for both scopes, from [`Ufvk::address_locator`].
+#[derive(Clone, Debug)]
+pub struct AddressLocator {
+    ua_hrp: String,
+    external: IncomingViewingKey,
+    internal: IncomingViewingKey,
+}
+
+impl AddressLocator {
+    /// The scope and diversifier index of `address` if the key derives its Orchard
+    /// receiver, external scope first. `None` for addresses on another network.
+    pub fn locate(&self, address: &UnifiedAddress) -> Option<(Scope, DiversifierIndex)> {
+        if address.hrp() != self.ua_hrp {
+            return None;
+        }
+        let receiver = address.orchard()?;
+        [
+            (Scope::External, &self.external),
+            (Scope::Internal, &self.internal),
+        ]
+        .into_iter()
+        .find_map(|(scope, ivk)| Some((scope, ivk.diversifier_index(&receiver)?)))
+    }
+}
+
+// Every other field is derived from `encoded`, so it alone decides equality.
+impl PartialEq for Ufvk {
+    fn eq(&self, other: &Self) -> bool {
+        crate::ct_eq(&self.encoded, &other.encoded)
+    }
+}
+
+impl Eq for Ufvk {}
+
+impl Hash for Ufvk {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.encoded.hash(state);
+    }
+}
+
+impl FromStr for Ufvk {
+    type Err = KeysError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ufvk::parse(s)
+    }
+}
+
+impl fmt::Display for Ufvk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.encoded)
+    }
+}
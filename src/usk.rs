@@ -0,0 +1,152 @@
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use secrecy::SecretString;
+use zeroize::Zeroizing;
+
+use crate::network::{self, Network};
+use crate::zip316::{self, TYPECODE_ORCHARD};
+use crate::{ua_hrp_from_usk_hrp, usk_hrp_from_ua_hrp, KeysError, OrchardKeySet, Ufvk};
+
+/// An account's spending key as one string, for vaults that hand out account keys
+/// rather than the seed.
+///
+/// The encoding is a ZIP-316 unified container (F4Jumble and Bech32m, as for UFVKs)
+/// holding the Orchard spending key under typecode 3, with the HRP `jsecret` on
+/// mainnet, `jsecrettest` and `jsecretregtest` elsewhere, and `usecret*` for keys
+/// under Zcash's coin types. `Debug` is redacted and there is no `Display`; use
+/// [`Self::encode`].
+///
+/// ```
+/// # use juno_keys::{Network, OrchardKeySet, UnifiedSpendingKey};
+/// let keys = OrchardKeySet::from_seed(&[7u8; 32], &Network::Testnet, 0)?;
+/// let usk = UnifiedSpendingKey::from_keys(Network::Testnet.ua_hrp(), &keys)?;
+/// # use juno_keys::ExposeSecret as _;
+/// let encoded = usk.encode();
+/// assert!(encoded.expose_secret().starts_with("jsecrettest1"));
+/// let parsed: UnifiedSpendingKey = encoded.expose_secret().parse()?;
+/// assert_eq!(parsed.ufvk()?, keys.ufvk(&Network::Testnet)?);
+/// # Ok::<(), juno_keys::KeysError>(())
+/// ```
+#[doc(alias = "AccountKey")]
+#[derive(Eq, PartialEq)]
+pub struct UnifiedSpendingKey {
+    ua_hrp: String,
+    keys: OrchardKeySet,
+}
+
+impl UnifiedSpendingKey {
+    /// Wraps `keys` for the network whose UA HRP is `ua_hrp`.
+    pub fn from_keys(ua_hrp: &str, keys: &OrchardKeySet) -> Result<Self, KeysError> {
+        usk_hrp_from_ua_hrp(ua_hrp)?;
+        Ok(UnifiedSpendingKey {
+            ua_hrp: ua_hrp.trim().into(),
+            keys: keys.try_clone()?,
+        })
+    }
+
+    /// A copy of the key, failing as [`OrchardKeySet::try_clone`] does.
+    pub fn try_clone(&self) -> Result<Self, KeysError> {
+        Ok(UnifiedSpendingKey {
+            ua_hrp: self.ua_hrp.clone(),
+            keys: self.keys.try_clone()?,
+        })
+    }
+
+    pub fn parse(s: &str) -> Result<Self, KeysError> {
+        let encoded = Zeroizing::new(s.trim().to_ascii_lowercase());
+        let hrp = network::encoded_hrp(&encoded)?;
+        let ua_hrp = ua_hrp_from_usk_hrp(&hrp)?;
+        let items = zip316::decode_unified_container(&hrp, &encoded)?;
+        let mut items = items
+            .into_iter()
+            .map(|(typecode, value)| (typecode, Zeroizing::new(value)));
+        // Only Orchard keys are derived here, so that is all a key may hold.
+        let (Some((TYPECODE_ORCHARD, sk)), None) = (items.next(), items.next()) else {
+            return Err(KeysError::SpendingKeyInvalid);
+        };
+        Ok(UnifiedSpendingKey {
+            ua_hrp,
+            keys: OrchardKeySet::from_spending_key(&sk)?,
+        })
+    }
+
+    pub fn encode(&self) -> SecretString {
+        let hrp = usk_hrp_from_ua_hrp(&self.ua_hrp).expect("checked on construction");
+        let sk = self.keys.to_bytes();
+        let encoded = Zeroizing::new(
+            zip316::encode_single_item_container(&hrp, TYPECODE_ORCHARD, sk.as_slice())
+                .expect("a single Orchard item always encodes"),
+        );
+        SecretString::from(encoded.as_str())
+    }
+
+    pub fn keys(&self) -> &OrchardKeySet {
+        &self.keys
+    }
+
+    pub fn ua_hrp(&self) -> &str {
+        &self.ua_hrp
+    }
+
+    /// The Juno network, or `None` for Zcash and custom-network keys.
+    pub fn network(&self) -> Option<Network> {
+        Network::ALL.into_iter().find(|n| n.ua_hrp() == self.ua_hrp)
+    }
+
+    /// The UFVK of the same account and network.
+    pub fn ufvk(&self) -> Result<Ufvk, KeysError> {
+        Ufvk::from_orchard(&self.ua_hrp, &self.keys.full_viewing_key())
+    }
+}
+
+impl FromStr for UnifiedSpendingKey {
+    type Err = KeysError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UnifiedSpendingKey::parse(s)
+    }
+}
+
+impl fmt::Debug for UnifiedSpendingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UnifiedSpendingKey({}, ..)", self.ua_hrp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExposeSecret as _, ZcashNetwork};
+
+    #[test]
+    fn spending_keys_round_trip_per_network() {
+        for network in Network::ALL {
+            let keys = OrchardKeySet::from_seed(&[7u8; 32], &network, 1).unwrap();
+            let usk = UnifiedSpendingKey::from_keys(network.ua_hrp(), &keys).unwrap();
+            let encoded = usk.encode();
+            let parsed =
+                UnifiedSpendingKey::parse(&encoded.expose_secret().to_uppercase()).unwrap();
+            assert_eq!(parsed, usk);
+            assert_eq!(parsed.network(), Some(network));
+            assert_eq!(parsed.ufvk().unwrap(), keys.ufvk(&network).unwrap());
+        }
+
+        let zcash = ZcashNetwork(Network::Mainnet);
+        let keys = OrchardKeySet::from_seed(&[7u8; 32], &zcash, 0).unwrap();
+        let usk = UnifiedSpendingKey::from_keys("u", &keys).unwrap();
+        assert!(usk.encode().expose_secret().starts_with("usecret1"));
+        assert_eq!(format!("{usk:?}"), "UnifiedSpendingKey(u, ..)");
+
+        // A UFVK's container under the spending key HRP is not a spending key.
+        let fvk = keys.full_viewing_key().to_bytes();
+        let wrong =
+            zip316::encode_single_item_container("jsecret", TYPECODE_ORCHARD, &fvk).unwrap();
+        assert!(UnifiedSpendingKey::parse(&wrong).is_err());
+        assert!(matches!(
+            UnifiedSpendingKey::parse(keys.ufvk(&zcash).unwrap().as_str()),
+            Err(KeysError::UAHrpInvalid)
+        ));
+    }
+}
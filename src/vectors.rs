@@ -0,0 +1,146 @@
+//! Golden vectors for other implementations: seeds, fingerprints, UFVKs, UIVKs and
+//! addresses on every network, for a few accounts and diversifier indices each.
+//!
+//! A suite depends only on its seeds, accounts and indices, so one generated from
+//! [`deterministic_seed`]s can be committed as a snapshot and regenerated to diff.
+//! Those seeds come from a public label; never fund their keys.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    seed_fingerprint, AccountRange, DerivationRequest, DiversifierIndex, KeysError, Network, Seed,
+    Ufvk, UnifiedAddress, ZipPath,
+};
+
+const DETERMINISTIC_SEED_PERSONALIZATION: &[u8; 16] = b"JunoKeysFixtures";
+
+/// The `index`th public 64-byte test seed, the same as `fixtures generate` uses.
+pub fn deterministic_seed(index: u32) -> Seed {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(64)
+        .personal(DETERMINISTIC_SEED_PERSONALIZATION)
+        .hash(&index.to_le_bytes());
+    Seed::from_bytes(hash.as_bytes()).expect("64 bytes is a valid seed length")
+}
+
+/// Vectors for each seed, in the order given.
+#[derive(Clone, Debug)]
+pub struct Suite {
+    pub seeds: Vec<SeedVectors>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SeedVectors {
+    pub seed_hex: String,
+    pub seed_fingerprint: String,
+    /// In [`Network::ALL`] order.
+    pub networks: Vec<NetworkVectors>,
+}
+
+#[derive(Clone, Debug)]
+pub struct NetworkVectors {
+    pub network: Network,
+    pub accounts: Vec<AccountVectors>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AccountVectors {
+    pub account: u32,
+    pub path: ZipPath,
+    pub ufvk: Ufvk,
+    pub ufvk_fingerprint: String,
+    pub uivk: String,
+    /// External-scope addresses, in the order the indices were given.
+    pub addresses: Vec<(DiversifierIndex, UnifiedAddress)>,
+}
+
+/// Derives the vectors of `seeds` for `accounts`, with addresses at the diversifier
+/// `indices`, on every network.
+pub fn generate(
+    seeds: &[Seed],
+    accounts: AccountRange,
+    indices: &[DiversifierIndex],
+) -> Result<Suite, KeysError> {
+    let seeds = seeds
+        .iter()
+        .map(|seed| {
+            let networks = Network::ALL
+                .iter()
+                .map(|&network| {
+                    let accounts = accounts
+                        .into_iter()
+                        .map(|account| account_vectors(seed, network, account, indices))
+                        .collect::<Result<_, _>>()?;
+                    Ok(NetworkVectors { network, accounts })
+                })
+                .collect::<Result<_, KeysError>>()?;
+            Ok(SeedVectors {
+                seed_hex: hex::encode(seed.as_bytes()),
+                seed_fingerprint: seed_fingerprint(seed)?,
+                networks,
+            })
+        })
+        .collect::<Result<_, KeysError>>()?;
+    Ok(Suite { seeds })
+}
+
+fn account_vectors(
+    seed: &Seed,
+    network: Network,
+    account: u32,
+    indices: &[DiversifierIndex],
+) -> Result<AccountVectors, KeysError> {
+    let keys = DerivationRequest::new(seed)
+        .network(network)
+        .account(account)
+        .build()?;
+    let generator = keys.ufvk.address_generator()?;
+    let addresses = indices
+        .iter()
+        .map(|&index| Ok((index, generator.address_at(index)?)))
+        .collect::<Result<_, KeysError>>()?;
+    Ok(AccountVectors {
+        account,
+        path: keys.path,
+        ufvk_fingerprint: keys.ufvk.fingerprint(),
+        ufvk: keys.ufvk,
+        uivk: keys.uivk,
+        addresses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suites_cover_every_network_and_are_reproducible() {
+        let seeds = [deterministic_seed(0), deterministic_seed(1)];
+        let accounts = AccountRange::new(0, 2).unwrap();
+        let indices = [DiversifierIndex::from(0u32), DiversifierIndex::from(7u32)];
+        let suite = generate(&seeds, accounts, &indices).unwrap();
+
+        assert_eq!(suite.seeds.len(), 2);
+        let first = &suite.seeds[0];
+        assert_eq!(first.seed_hex.len(), 128);
+        assert_eq!(
+            first.networks.iter().map(|n| n.network).collect::<Vec<_>>(),
+            Network::ALL
+        );
+        let testnet = &first.networks[1].accounts[1];
+        assert_eq!(testnet.account, 1);
+        assert_eq!(testnet.ufvk.network(), Some(Network::Testnet));
+        assert_eq!(
+            testnet.addresses[1].1,
+            testnet.ufvk.address_at(7u32).unwrap()
+        );
+
+        let again = generate(&seeds[..1], accounts, &indices).unwrap();
+        assert_eq!(
+            again.seeds[0].networks[2].accounts[0].ufvk,
+            first.networks[2].accounts[0].ufvk
+        );
+        assert_ne!(first.seed_fingerprint, suite.seeds[1].seed_fingerprint);
+    }
+}
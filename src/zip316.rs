@@ -1,6 +1,8 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use bech32::primitives::checksum::Checksum;
 use bech32::primitives::decode::CheckedHrpstring;
-use bech32::Hrp;
+use bech32::{Fe32, Hrp};
 use thiserror::Error;
 
 const BECH32_GEN: [u32; 5] = [
@@ -23,7 +25,23 @@ impl Checksum for Bech32mUnlimited {
     const TARGET_RESIDUE: u32 = 0x2bc8_30a3;
 }
 
+pub const TYPECODE_P2PKH: u64 = 0x00;
+pub const TYPECODE_P2SH: u64 = 0x01;
+pub const TYPECODE_SAPLING: u64 = 0x02;
+pub const TYPECODE_ORCHARD: u64 = 0x03;
+
+/// Whether this crate understands items of `typecode`.
+pub fn is_known_typecode(typecode: u64) -> bool {
+    matches!(
+        typecode,
+        TYPECODE_P2PKH | TYPECODE_P2SH | TYPECODE_SAPLING | TYPECODE_ORCHARD
+    )
+}
+
 const PADDING_LEN: usize = 16;
+// F4Jumble is defined for messages of at most 2^22 + 64 bytes (ZIP-316).
+const MAX_JUMBLE_LEN: usize = 4_194_368;
+const MIN_JUMBLE_LEN: usize = 48;
 
 #[derive(Debug, Error)]
 pub enum Zip316Error {
@@ -33,18 +51,158 @@ pub enum Zip316Error {
     InvalidHrp,
     #[error("payload_too_short")]
     PayloadTooShort,
+    #[error("payload_too_long")]
+    PayloadTooLong,
+    #[error("no_items")]
+    NoItems,
+    #[error("duplicate_typecode")]
+    DuplicateTypecode,
+    #[error("typecode_order")]
+    TypecodeOrder,
+    #[error("transparent_conflict")]
+    TransparentConflict,
+    #[error("no_shielded_item")]
+    NoShieldedItem,
     #[error("f4jumble_failed")]
     F4JumbleFailed,
     #[error("bech32_encode_failed")]
     Bech32EncodeFailed,
+    /// `offset` is the character index of the first invalid character, if any.
     #[error("bech32_decode_failed")]
-    Bech32DecodeFailed,
+    Bech32DecodeFailed { offset: Option<usize> },
     #[error("hrp_mismatch")]
     HrpMismatch,
+    /// `offset` is the byte index into the unjumbled payload.
     #[error("padding_invalid")]
-    PaddingInvalid,
+    PaddingInvalid { offset: usize },
+    /// `offset` is the byte index into the unjumbled payload.
     #[error("tlv_invalid")]
-    TlvInvalid,
+    TlvInvalid { stage: ParseStage, offset: usize },
+}
+
+/// The decoding step at which a container was rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParseStage {
+    Bech32,
+    Jumble,
+    Padding,
+    Typecode,
+    Length,
+}
+
+impl ParseStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParseStage::Bech32 => "bech32",
+            ParseStage::Jumble => "jumble",
+            ParseStage::Padding => "padding",
+            ParseStage::Typecode => "typecode",
+            ParseStage::Length => "length",
+        }
+    }
+}
+
+/// Where a decode failed, for error reports.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ParseDiagnostic {
+    pub stage: ParseStage,
+    pub offset: Option<usize>,
+}
+
+impl Zip316Error {
+    /// The stage and offset of a decode failure; `None` for errors that aren't parse failures.
+    pub fn diagnostic(&self) -> Option<ParseDiagnostic> {
+        let (stage, offset) = match *self {
+            Zip316Error::Bech32DecodeFailed { offset } => (ParseStage::Bech32, offset),
+            Zip316Error::HrpMismatch => (ParseStage::Bech32, Some(0)),
+            Zip316Error::PayloadTooShort
+            | Zip316Error::PayloadTooLong
+            | Zip316Error::F4JumbleFailed => (ParseStage::Jumble, None),
+            Zip316Error::PaddingInvalid { offset } => (ParseStage::Padding, Some(offset)),
+            Zip316Error::TlvInvalid { stage, offset } => (stage, Some(offset)),
+            Zip316Error::NoItems
+            | Zip316Error::DuplicateTypecode
+            | Zip316Error::TypecodeOrder
+            | Zip316Error::TransparentConflict
+            | Zip316Error::NoShieldedItem => (ParseStage::Typecode, None),
+            _ => return None,
+        };
+        Some(ParseDiagnostic { stage, offset })
+    }
+}
+
+/// How the decoder treats defects that don't prevent recovering the items.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DecodeMode {
+    /// Reject any defect (the default, and what encoders must produce).
+    #[default]
+    Strict,
+    /// Report defects as warnings, for triaging keys from buggy third-party wallets.
+    Lenient,
+}
+
+impl DecodeMode {
+    fn check(
+        self,
+        warning: DecodeWarning,
+        err: Zip316Error,
+        warnings: &mut Vec<DecodeWarning>,
+    ) -> Result<(), Zip316Error> {
+        match self {
+            DecodeMode::Strict => Err(err),
+            DecodeMode::Lenient => {
+                warnings.push(warning);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DecodeWarning {
+    /// The padding does not start with the HRP.
+    HrpPaddingMismatch,
+    /// The padding bytes after the HRP are not all zero.
+    NonZeroPadding,
+    /// `len` bytes after the last complete item could not be parsed as an item.
+    TrailingData { len: usize },
+    /// An item this crate doesn't understand; it is kept in `items` as-is.
+    UnknownTypecode { typecode: u64, value: Vec<u8> },
+    /// The items break a [`validate_container`] rule; `reason` is the error code.
+    NonCanonical { reason: String },
+}
+
+impl DecodeWarning {
+    pub fn code(&self) -> &'static str {
+        match self {
+            DecodeWarning::HrpPaddingMismatch => "hrp_padding_mismatch",
+            DecodeWarning::NonZeroPadding => "nonzero_padding",
+            DecodeWarning::TrailingData { .. } => "trailing_data",
+            DecodeWarning::UnknownTypecode { .. } => "unknown_typecode",
+            DecodeWarning::NonCanonical { .. } => "non_canonical",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DecodedContainer {
+    pub items: Vec<(u64, Vec<u8>)>,
+    pub warnings: Vec<DecodeWarning>,
+}
+
+impl DecodedContainer {
+    /// Re-encodes every item, unknown ones included, byte-for-byte in decoded order.
+    pub fn reencode(&self, hrp: &str) -> Result<String, Zip316Error> {
+        let tlvs = self
+            .items
+            .iter()
+            .map(|(typecode, value)| Tlv {
+                typecode: *typecode,
+                value,
+            })
+            .collect::<Vec<_>>();
+        encode_tlv_container(hrp, &tlvs)
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -53,6 +211,42 @@ pub struct Tlv<'a> {
     pub value: &'a [u8],
 }
 
+/// Applies F4Jumble to `message` (48..=4194368 bytes).
+pub fn f4jumble(message: &[u8]) -> Result<Vec<u8>, Zip316Error> {
+    let mut out = message.to_vec();
+    f4jumble_mut(&mut out)?;
+    Ok(out)
+}
+
+/// Inverts F4Jumble on `message` (48..=4194368 bytes).
+pub fn f4jumble_inv(message: &[u8]) -> Result<Vec<u8>, Zip316Error> {
+    let mut out = message.to_vec();
+    f4jumble_inv_mut(&mut out)?;
+    Ok(out)
+}
+
+/// Applies F4Jumble in place, without copying large payloads.
+pub fn f4jumble_mut(message: &mut [u8]) -> Result<(), Zip316Error> {
+    check_jumble_len(message.len())?;
+    f4jumble::f4jumble_mut(message).map_err(|_| Zip316Error::F4JumbleFailed)
+}
+
+/// Inverts F4Jumble in place, without copying large payloads.
+pub fn f4jumble_inv_mut(message: &mut [u8]) -> Result<(), Zip316Error> {
+    check_jumble_len(message.len())?;
+    f4jumble::f4jumble_inv_mut(message).map_err(|_| Zip316Error::F4JumbleFailed)
+}
+
+fn check_jumble_len(len: usize) -> Result<(), Zip316Error> {
+    if len < MIN_JUMBLE_LEN {
+        return Err(Zip316Error::PayloadTooShort);
+    }
+    if len > MAX_JUMBLE_LEN {
+        return Err(Zip316Error::PayloadTooLong);
+    }
+    Ok(())
+}
+
 fn write_compact_size(n: u64, out: &mut Vec<u8>) {
     if n <= 252 {
         out.push(n as u8);
@@ -68,48 +262,70 @@ fn write_compact_size(n: u64, out: &mut Vec<u8>) {
     }
 }
 
-fn read_compact_size(input: &mut &[u8]) -> Result<u64, Zip316Error> {
-    let first = *input.first().ok_or(Zip316Error::TlvInvalid)?;
+fn read_compact_size(input: &mut &[u8]) -> Option<u64> {
+    let first = *input.first()?;
     *input = &input[1..];
 
     match first {
-        n @ 0..=252 => Ok(n as u64),
+        n @ 0..=252 => Some(n as u64),
         253 => {
             if input.len() < 2 {
-                return Err(Zip316Error::TlvInvalid);
+                return None;
             }
             let v = u16::from_le_bytes([input[0], input[1]]) as u64;
             *input = &input[2..];
-            Ok(v)
+            Some(v)
         }
         254 => {
             if input.len() < 4 {
-                return Err(Zip316Error::TlvInvalid);
+                return None;
             }
             let v = u32::from_le_bytes([input[0], input[1], input[2], input[3]]) as u64;
             *input = &input[4..];
-            Ok(v)
+            Some(v)
         }
         255 => {
             if input.len() < 8 {
-                return Err(Zip316Error::TlvInvalid);
+                return None;
             }
             let v = u64::from_le_bytes([
                 input[0], input[1], input[2], input[3], input[4], input[5], input[6], input[7],
             ]);
             *input = &input[8..];
-            Ok(v)
+            Some(v)
         }
     }
 }
 
+/// Plain bech32m encoding of `data` under `hrp`, without ZIP-316 padding or jumbling.
+pub fn bech32m_encode(hrp: &str, data: &[u8]) -> Result<String, Zip316Error> {
+    let hrp = Hrp::parse(hrp).map_err(|_| Zip316Error::InvalidHrp)?;
+    bech32::encode::<Bech32mUnlimited>(hrp, data).map_err(|_| Zip316Error::Bech32EncodeFailed)
+}
+
+/// Decodes a plain bech32m string into its HRP and data bytes.
+pub fn bech32m_decode(s: &str) -> Result<(String, Vec<u8>), Zip316Error> {
+    let s = s.trim();
+    let checked = CheckedHrpstring::new::<Bech32mUnlimited>(s).map_err(|_| bech32_error(s))?;
+    Ok((checked.hrp().to_lowercase(), checked.byte_iter().collect()))
+}
+
+// Points at the first character outside the bech32 alphabet after the separator, if any.
+fn bech32_error(s: &str) -> Zip316Error {
+    let offset = s.rfind('1').and_then(|sep| {
+        s[sep + 1..]
+            .char_indices()
+            .find(|(_, c)| Fe32::from_char(*c).is_err())
+            .map(|(i, _)| sep + 1 + i)
+    });
+    Zip316Error::Bech32DecodeFailed { offset }
+}
+
 fn encode_zip316_bech32m(hrp: &str, raw_items_tlv: &[u8]) -> Result<String, Zip316Error> {
     if hrp.len() > PADDING_LEN {
         return Err(Zip316Error::HrpTooLong);
     }
-    if raw_items_tlv.len() + PADDING_LEN < 48 {
-        return Err(Zip316Error::PayloadTooShort);
-    }
+    check_jumble_len(raw_items_tlv.len() + PADDING_LEN)?;
 
     let mut padded = Vec::with_capacity(raw_items_tlv.len() + PADDING_LEN);
     padded.extend_from_slice(raw_items_tlv);
@@ -118,32 +334,51 @@ fn encode_zip316_bech32m(hrp: &str, raw_items_tlv: &[u8]) -> Result<String, Zip3
     padding[..hrp.len()].copy_from_slice(hrp.as_bytes());
     padded.extend_from_slice(&padding);
 
-    let jumbled = f4jumble::f4jumble(&padded).map_err(|_| Zip316Error::F4JumbleFailed)?;
+    f4jumble_mut(&mut padded)?;
 
     let hrp = Hrp::parse(hrp).map_err(|_| Zip316Error::InvalidHrp)?;
-    bech32::encode::<Bech32mUnlimited>(hrp, &jumbled).map_err(|_| Zip316Error::Bech32EncodeFailed)
+    bech32::encode::<Bech32mUnlimited>(hrp, &padded).map_err(|_| Zip316Error::Bech32EncodeFailed)
 }
 
-fn decode_zip316_bech32m(hrp_expected: &str, s: &str) -> Result<Vec<u8>, Zip316Error> {
-    let checked = CheckedHrpstring::new::<Bech32mUnlimited>(s)
-        .map_err(|_| Zip316Error::Bech32DecodeFailed)?;
+fn decode_zip316_bech32m(
+    hrp_expected: &str,
+    s: &str,
+    mode: DecodeMode,
+    warnings: &mut Vec<DecodeWarning>,
+) -> Result<Vec<u8>, Zip316Error> {
+    if hrp_expected.len() > PADDING_LEN {
+        return Err(Zip316Error::HrpTooLong);
+    }
+
+    let checked = CheckedHrpstring::new::<Bech32mUnlimited>(s).map_err(|_| bech32_error(s))?;
 
     if checked.hrp().as_str() != hrp_expected {
         return Err(Zip316Error::HrpMismatch);
     }
 
     let mut bytes = checked.byte_iter().collect::<Vec<_>>();
-    f4jumble::f4jumble_inv_mut(&mut bytes).map_err(|_| Zip316Error::F4JumbleFailed)?;
+    f4jumble_inv_mut(&mut bytes)?;
     if bytes.len() < PADDING_LEN {
-        return Err(Zip316Error::PaddingInvalid);
+        return Err(Zip316Error::PaddingInvalid { offset: 0 });
     }
 
-    let padding = &bytes[bytes.len() - PADDING_LEN..];
-    if !padding[..hrp_expected.len()].eq(hrp_expected.as_bytes()) {
-        return Err(Zip316Error::PaddingInvalid);
+    let padding_start = bytes.len() - PADDING_LEN;
+    let padding = &bytes[padding_start..];
+    if let Some(i) = padding
+        .iter()
+        .zip(hrp_expected.as_bytes())
+        .position(|(a, b)| a != b)
+    {
+        let err = Zip316Error::PaddingInvalid {
+            offset: padding_start + i,
+        };
+        mode.check(DecodeWarning::HrpPaddingMismatch, err, warnings)?;
     }
-    if padding[hrp_expected.len()..].iter().any(|b| *b != 0) {
-        return Err(Zip316Error::PaddingInvalid);
+    if let Some(i) = padding[hrp_expected.len()..].iter().position(|b| *b != 0) {
+        let err = Zip316Error::PaddingInvalid {
+            offset: padding_start + hrp_expected.len() + i,
+        };
+        mode.check(DecodeWarning::NonZeroPadding, err, warnings)?;
     }
 
     bytes.truncate(bytes.len() - PADDING_LEN);
@@ -151,40 +386,437 @@ fn decode_zip316_bech32m(hrp_expected: &str, s: &str) -> Result<Vec<u8>, Zip316E
 }
 
 pub fn encode_tlv_container(hrp: &str, items: &[Tlv<'_>]) -> Result<String, Zip316Error> {
+    encode_zip316_bech32m(hrp, &encode_raw_items(items))
+}
+
+/// The raw ZIP-316 encoding of `items`: concatenated TLVs, before HRP padding,
+/// F4Jumble and bech32m.
+pub fn encode_raw_items(items: &[Tlv<'_>]) -> Vec<u8> {
     let mut payload = Vec::new();
     for item in items {
         write_compact_size(item.typecode, &mut payload);
         write_compact_size(item.value.len() as u64, &mut payload);
         payload.extend_from_slice(item.value);
     }
-    encode_zip316_bech32m(hrp, &payload)
+    payload
+}
+
+/// Encodes a raw ZIP-316 encoding under `hrp`, after checking it parses canonically.
+pub fn encode_raw_container(hrp: &str, raw: &[u8]) -> Result<String, Zip316Error> {
+    decode_raw_items(raw)?;
+    encode_zip316_bech32m(hrp, raw)
+}
+
+/// Parses a raw ZIP-316 encoding back into canonical `(typecode, value)` items.
+pub fn decode_raw_items(raw: &[u8]) -> Result<Vec<(u64, Vec<u8>)>, Zip316Error> {
+    let mut rest = raw;
+    let mut items = Vec::new();
+    while !rest.is_empty() {
+        let offset = raw.len() - rest.len();
+        items.push(read_tlv_item(&mut rest, offset)?);
+    }
+    validate_container(&items)?;
+    Ok(items)
+}
+
+/// Encodes `(typecode, value)` items as a unified container.
+///
+/// Items are emitted in ascending typecode order regardless of input order; a container
+/// must hold at least one item and no typecode more than once.
+pub fn encode_unified_container(hrp: &str, items: &[(u64, &[u8])]) -> Result<String, Zip316Error> {
+    let mut items = items.to_vec();
+    items.sort_by_key(|&(typecode, _)| typecode);
+    validate_container(&items)?;
+
+    let tlvs = items
+        .iter()
+        .map(|&(typecode, value)| Tlv { typecode, value })
+        .collect::<Vec<_>>();
+    encode_tlv_container(hrp, &tlvs)
 }
 
-pub fn encode_unified_container(
+/// Checks the ZIP-316 canonicality rules on a container's items, in encoded order.
+///
+/// A container needs at least one item, strictly ascending typecodes, at least one
+/// item that isn't a transparent receiver, and at most one of P2PKH and P2SH.
+pub fn validate_container<V: AsRef<[u8]>>(items: &[(u64, V)]) -> Result<(), Zip316Error> {
+    if items.is_empty() {
+        return Err(Zip316Error::NoItems);
+    }
+    for w in items.windows(2) {
+        if w[0].0 == w[1].0 {
+            return Err(Zip316Error::DuplicateTypecode);
+        }
+        if w[0].0 > w[1].0 {
+            return Err(Zip316Error::TypecodeOrder);
+        }
+    }
+
+    let has = |tc: u64| items.iter().any(|(typecode, _)| *typecode == tc);
+    if has(TYPECODE_P2PKH) && has(TYPECODE_P2SH) {
+        return Err(Zip316Error::TransparentConflict);
+    }
+    if items
+        .iter()
+        .all(|(typecode, _)| matches!(*typecode, TYPECODE_P2PKH | TYPECODE_P2SH))
+    {
+        return Err(Zip316Error::NoShieldedItem);
+    }
+    Ok(())
+}
+
+/// [`encode_unified_container`] for a container of one item.
+pub fn encode_single_item_container(
     hrp: &str,
     typecode: u64,
     value: &[u8],
 ) -> Result<String, Zip316Error> {
-    let items = [Tlv { typecode, value }];
-    encode_tlv_container(hrp, &items)
+    encode_unified_container(hrp, &[(typecode, value)])
+}
+
+/// Decodes a unified container (UA/UFVK/UIVK) into its `(typecode, value)` items.
+///
+/// Reverses the bech32m checksum, F4Jumble and HRP padding; items are returned in
+/// encoded order.
+pub fn decode_unified_container(
+    hrp_expected: &str,
+    s: &str,
+) -> Result<Vec<(u64, Vec<u8>)>, Zip316Error> {
+    decode_tlv_container(hrp_expected, s)
 }
 
 pub fn decode_tlv_container(
     hrp_expected: &str,
     s: &str,
 ) -> Result<Vec<(u64, Vec<u8>)>, Zip316Error> {
-    let bytes = decode_zip316_bech32m(hrp_expected, s)?;
+    decode_unified_container_with(hrp_expected, s, DecodeMode::Strict).map(|d| d.items)
+}
+
+/// Decodes a unified container, either rejecting or reporting recoverable defects.
+pub fn decode_unified_container_with(
+    hrp_expected: &str,
+    s: &str,
+    mode: DecodeMode,
+) -> Result<DecodedContainer, Zip316Error> {
+    let mut warnings = Vec::new();
+    let bytes = decode_zip316_bech32m(hrp_expected, s, mode, &mut warnings)?;
     let mut rest = bytes.as_slice();
-    let mut out = Vec::new();
+    let mut items = Vec::new();
     while !rest.is_empty() {
-        let typecode = read_compact_size(&mut rest)?;
-        let len = read_compact_size(&mut rest)? as usize;
-        if rest.len() < len {
-            return Err(Zip316Error::TlvInvalid);
+        let item_start = rest;
+        match read_tlv_item(&mut rest, bytes.len() - item_start.len()) {
+            Ok((typecode, value)) => {
+                if !is_known_typecode(typecode) {
+                    warnings.push(DecodeWarning::UnknownTypecode {
+                        typecode,
+                        value: value.clone(),
+                    });
+                }
+                items.push((typecode, value));
+            }
+            Err(e) => {
+                if mode == DecodeMode::Strict {
+                    return Err(e);
+                }
+                warnings.push(DecodeWarning::TrailingData {
+                    len: item_start.len(),
+                });
+                break;
+            }
         }
-        let (value, next) = rest.split_at(len);
-        out.push((typecode, value.to_vec()));
-        rest = next;
     }
-    Ok(out)
+    if let Err(e) = validate_container(&items) {
+        let warning = DecodeWarning::NonCanonical {
+            reason: e.to_string(),
+        };
+        mode.check(warning, e, &mut warnings)?;
+    }
+    Ok(DecodedContainer { items, warnings })
+}
+
+/// An encoding produced by this crate, for cross-implementation checks.
+#[derive(Clone, Debug)]
+pub struct TestVector {
+    pub hrp: String,
+    pub items: Vec<(u64, Vec<u8>)>,
+    pub encoded: String,
+}
+
+/// Deterministic vectors for each HRP in `hrps`.
+///
+/// The item sets cover single and multi-receiver containers, CompactSize boundaries in
+/// both lengths and typecodes, and an unknown typecode. Values are filled with
+/// `(typecode * 7 + i) mod 256` so other implementations can rebuild them without hex.
+pub fn test_vectors(hrps: &[&str]) -> Result<Vec<TestVector>, Zip316Error> {
+    const ITEM_SETS: &[&[(u64, usize)]] = &[
+        &[(TYPECODE_ORCHARD, 43)],
+        &[(TYPECODE_SAPLING, 43), (TYPECODE_ORCHARD, 43)],
+        &[
+            (TYPECODE_P2PKH, 20),
+            (TYPECODE_SAPLING, 43),
+            (TYPECODE_ORCHARD, 43),
+        ],
+        &[(TYPECODE_ORCHARD, 96)],
+        &[(TYPECODE_ORCHARD, 252)],
+        &[(TYPECODE_ORCHARD, 253)],
+        &[(TYPECODE_ORCHARD, 43), (0x05, 12)],
+        &[(0xfd, 32)],
+    ];
+
+    let mut vectors = Vec::new();
+    for hrp in hrps {
+        for set in ITEM_SETS {
+            let items = set
+                .iter()
+                .map(|&(typecode, len)| {
+                    let value = (0..len)
+                        .map(|i| (typecode as usize * 7 + i) as u8)
+                        .collect::<Vec<_>>();
+                    (typecode, value)
+                })
+                .collect::<Vec<_>>();
+            let borrowed = items
+                .iter()
+                .map(|(typecode, value)| (*typecode, value.as_slice()))
+                .collect::<Vec<_>>();
+            let encoded = encode_unified_container(hrp, &borrowed)?;
+            vectors.push(TestVector {
+                hrp: hrp.to_string(),
+                items,
+                encoded,
+            });
+        }
+    }
+    Ok(vectors)
+}
+
+// `offset` is the position of `rest` within the payload, for error reports.
+fn read_tlv_item(rest: &mut &[u8], offset: usize) -> Result<(u64, Vec<u8>), Zip316Error> {
+    let start_len = rest.len();
+    let typecode = read_compact_size(rest).ok_or(Zip316Error::TlvInvalid {
+        stage: ParseStage::Typecode,
+        offset,
+    })?;
+    let len_offset = offset + (start_len - rest.len());
+    let len = read_compact_size(rest).ok_or(Zip316Error::TlvInvalid {
+        stage: ParseStage::Length,
+        offset: len_offset,
+    })? as usize;
+    if rest.len() < len {
+        return Err(Zip316Error::TlvInvalid {
+            stage: ParseStage::Length,
+            offset: len_offset,
+        });
+    }
+    let (value, next) = rest.split_at(len);
+    *rest = next;
+    Ok((typecode, value.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_container_roundtrip() {
+        let value = [0x42u8; 43];
+        let encoded = encode_single_item_container("jtest", 3, &value).expect("encode");
+        let items = decode_unified_container("jtest", &encoded).expect("decode");
+        assert_eq!(items, vec![(3, value.to_vec())]);
+    }
+
+    #[test]
+    fn decode_rejects_wrong_hrp_and_corruption() {
+        let encoded = encode_single_item_container("j", 3, &[1u8; 43]).expect("encode");
+        assert!(matches!(
+            decode_unified_container("jtest", &encoded),
+            Err(Zip316Error::HrpMismatch)
+        ));
+        assert!(matches!(
+            decode_unified_container("jviewwaytoolongsuffix", &encoded),
+            Err(Zip316Error::HrpTooLong)
+        ));
+
+        let mut corrupted = encoded.into_bytes();
+        let last = corrupted.len() - 1;
+        corrupted[last] = if corrupted[last] == b'q' { b'p' } else { b'q' };
+        let corrupted = String::from_utf8(corrupted).expect("utf8");
+        assert!(matches!(
+            decode_unified_container("j", &corrupted),
+            Err(Zip316Error::Bech32DecodeFailed { offset: None })
+        ));
+
+        let mut bad_char = corrupted.clone().into_bytes();
+        bad_char[5] = b'b';
+        let bad_char = String::from_utf8(bad_char).expect("utf8");
+        let err = decode_unified_container("j", &bad_char).expect_err("bad char");
+        assert_eq!(
+            err.diagnostic(),
+            Some(ParseDiagnostic {
+                stage: ParseStage::Bech32,
+                offset: Some(5),
+            })
+        );
+    }
+
+    #[test]
+    fn multi_item_containers_are_sorted_and_unique() {
+        let orchard = [3u8; 43];
+        let sapling = [2u8; 43];
+        let encoded =
+            encode_unified_container("j", &[(3, &orchard), (2, &sapling)]).expect("encode");
+        let items = decode_unified_container("j", &encoded).expect("decode");
+        assert_eq!(items, vec![(2, sapling.to_vec()), (3, orchard.to_vec())]);
+
+        assert!(matches!(
+            encode_unified_container("j", &[(3, &orchard), (3, &orchard)]),
+            Err(Zip316Error::DuplicateTypecode)
+        ));
+        assert!(matches!(
+            encode_unified_container("j", &[]),
+            Err(Zip316Error::NoItems)
+        ));
+    }
+
+    #[test]
+    fn f4jumble_roundtrip_and_bounds() {
+        let message = (0u8..64).collect::<Vec<_>>();
+        let jumbled = f4jumble(&message).expect("jumble");
+        assert_ne!(jumbled, message);
+        assert_eq!(f4jumble_inv(&jumbled).expect("unjumble"), message);
+        assert!(matches!(
+            f4jumble(&message[..47]),
+            Err(Zip316Error::PayloadTooShort)
+        ));
+    }
+
+    #[test]
+    fn f4jumble_large_payloads_and_spec_maximum() {
+        let mut message = (0..1usize << 16).map(|i| i as u8).collect::<Vec<_>>();
+        f4jumble_mut(&mut message).expect("jumble");
+        f4jumble_inv_mut(&mut message).expect("unjumble");
+        assert!(message.iter().enumerate().all(|(i, b)| *b == i as u8));
+
+        assert!(check_jumble_len(MAX_JUMBLE_LEN).is_ok());
+        let mut message = vec![0u8; MAX_JUMBLE_LEN + 1];
+        assert!(matches!(
+            f4jumble_mut(&mut message),
+            Err(Zip316Error::PayloadTooLong)
+        ));
+    }
+
+    #[test]
+    fn bech32m_roundtrip() {
+        let encoded = bech32m_encode("jtest", &[0xde, 0xad, 0xbe, 0xef]).expect("encode");
+        assert!(encoded.starts_with("jtest1"));
+        let (hrp, data) = bech32m_decode(&encoded.to_uppercase()).expect("decode");
+        assert_eq!(hrp, "jtest");
+        assert_eq!(data, vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn lenient_mode_reports_defects() {
+        // Hand-build a container whose padding carries the wrong HRP and a stray byte.
+        let mut payload = vec![3u8, 43];
+        payload.extend_from_slice(&[9u8; 43]);
+        payload.push(7);
+        let mut padding = [0u8; PADDING_LEN];
+        padding[0] = b'x';
+        padding[15] = 1;
+        payload.extend_from_slice(&padding);
+        let jumbled = f4jumble(&payload).expect("jumble");
+        let encoded = bech32m_encode("j", &jumbled).expect("bech32m");
+
+        assert!(matches!(
+            decode_unified_container("j", &encoded),
+            Err(Zip316Error::PaddingInvalid { offset: 46 })
+        ));
+        let decoded =
+            decode_unified_container_with("j", &encoded, DecodeMode::Lenient).expect("lenient");
+        assert_eq!(decoded.items, vec![(3, vec![9u8; 43])]);
+        assert_eq!(
+            decoded.warnings,
+            vec![
+                DecodeWarning::HrpPaddingMismatch,
+                DecodeWarning::NonZeroPadding,
+                DecodeWarning::TrailingData { len: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_typecodes_pass_through() {
+        let orchard = [3u8; 43];
+        let future = [0xaau8; 12];
+        let encoded =
+            encode_unified_container("j", &[(TYPECODE_ORCHARD, &orchard), (0x05, &future)])
+                .expect("encode");
+
+        let decoded =
+            decode_unified_container_with("j", &encoded, DecodeMode::Strict).expect("decode");
+        assert_eq!(decoded.items.len(), 2);
+        assert_eq!(
+            decoded.warnings,
+            vec![DecodeWarning::UnknownTypecode {
+                typecode: 0x05,
+                value: future.to_vec(),
+            }]
+        );
+        assert_eq!(decoded.reencode("j").expect("reencode"), encoded);
+    }
+
+    #[test]
+    fn test_vectors_decode_back_to_their_items() {
+        let vectors = test_vectors(&["j", "jview"]).expect("vectors");
+        assert_eq!(vectors.len(), 16);
+        for v in &vectors {
+            assert_eq!(
+                decode_unified_container(&v.hrp, &v.encoded).expect("decode"),
+                v.items
+            );
+        }
+    }
+
+    #[test]
+    fn canonicality_rules() {
+        let r = [0u8; 43];
+        assert!(validate_container(&[(0u64, &r), (3, &r)]).is_ok());
+        assert!(matches!(
+            validate_container(&[(3u64, &r), (2, &r)]),
+            Err(Zip316Error::TypecodeOrder)
+        ));
+        assert!(matches!(
+            validate_container(&[(0u64, &r), (1, &r), (3, &r)]),
+            Err(Zip316Error::TransparentConflict)
+        ));
+        assert!(matches!(
+            validate_container(&[(0u64, &r)]),
+            Err(Zip316Error::NoShieldedItem)
+        ));
+
+        // Hand-built out-of-order container: rejected strictly, flagged leniently.
+        let tlvs = [
+            Tlv {
+                typecode: 3,
+                value: &r,
+            },
+            Tlv {
+                typecode: 2,
+                value: &r,
+            },
+        ];
+        let encoded = encode_tlv_container("j", &tlvs).expect("encode");
+        assert!(matches!(
+            decode_unified_container("j", &encoded),
+            Err(Zip316Error::TypecodeOrder)
+        ));
+        let decoded =
+            decode_unified_container_with("j", &encoded, DecodeMode::Lenient).expect("lenient");
+        assert_eq!(
+            decoded.warnings,
+            vec![DecodeWarning::NonCanonical {
+                reason: "typecode_order".to_string(),
+            }]
+        );
+    }
 }
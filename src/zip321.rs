@@ -0,0 +1,389 @@
+//! ZIP-321 payment requests under Juno's `juno:` URI scheme.
+//!
+//! A request names one or more recipients, each with an optional amount, memo, label
+//! and message. A single payment puts its address in the URI path
+//! (`juno:j1...?amount=1.5`); several use `address`, `address.1`, `address.2`, ...
+//! parameters, with each payment's other parameters carrying the same index.
+//!
+//! Amounts are in whole coins with at most eight decimals, memos are base64url without
+//! padding, and labels and messages are percent-encoded UTF-8.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+
+use base64::Engine as _;
+
+use crate::{KeysError, UnifiedAddress};
+
+/// The URI scheme of Juno payment requests.
+pub const SCHEME: &str = "juno";
+
+/// Zatoshis per coin.
+pub const COIN: u64 = 100_000_000;
+
+/// The largest amount a request may ask for, 21 million coins as in Zcash.
+pub const MAX_MONEY: u64 = 21_000_000 * COIN;
+
+/// The longest memo, in bytes.
+pub const MEMO_LEN: usize = 512;
+
+/// One recipient of a payment request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payment {
+    pub address: UnifiedAddress,
+    /// In zatoshis; `None` leaves the amount to the payer.
+    pub amount: Option<u64>,
+    /// Raw memo bytes, at most [`MEMO_LEN`]; only shielded recipients take one.
+    pub memo: Option<Vec<u8>>,
+    /// A name for the recipient, for the payer's display.
+    pub label: Option<String>,
+    /// A description of the payment, for the payer's display.
+    pub message: Option<String>,
+}
+
+impl Payment {
+    /// A payment to `address` with nothing else set.
+    pub fn to(address: UnifiedAddress) -> Self {
+        Payment {
+            address,
+            amount: None,
+            memo: None,
+            label: None,
+            message: None,
+        }
+    }
+}
+
+/// A ZIP-321 payment request for one or more recipients.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionRequest {
+    pub payments: Vec<Payment>,
+}
+
+impl TransactionRequest {
+    /// Checks the payments: at least one, amounts within [`MAX_MONEY`] in total, and
+    /// memos of at most [`MEMO_LEN`] bytes sent only to addresses with a shielded
+    /// receiver.
+    pub fn new(payments: Vec<Payment>) -> Result<Self, KeysError> {
+        if payments.is_empty() {
+            return Err(KeysError::PaymentRequest("no payments"));
+        }
+        let mut total = 0u64;
+        for payment in &payments {
+            if let Some(amount) = payment.amount {
+                total = total
+                    .checked_add(amount)
+                    .filter(|total| *total <= MAX_MONEY)
+                    .ok_or(KeysError::PaymentRequest("amount exceeds the money supply"))?;
+            }
+            if let Some(memo) = &payment.memo {
+                if memo.len() > MEMO_LEN {
+                    return Err(KeysError::PaymentRequest("memo is longer than 512 bytes"));
+                }
+                if !(payment.address.has_orchard() || payment.address.has_sapling()) {
+                    return Err(KeysError::PaymentRequest(
+                        "memo for an address without a shielded receiver",
+                    ));
+                }
+            }
+        }
+        Ok(TransactionRequest { payments })
+    }
+
+    /// The `juno:` URI for this request.
+    pub fn to_uri(&self) -> String {
+        let mut uri = String::from(SCHEME);
+        uri.push(':');
+        let single = self.payments.len() == 1;
+        if single {
+            uri.push_str(self.payments[0].address.as_str());
+        }
+        let mut params = Vec::new();
+        for (i, payment) in self.payments.iter().enumerate() {
+            let index = match i {
+                0 => String::new(),
+                i => alloc::format!(".{i}"),
+            };
+            if !single {
+                params.push(alloc::format!("address{index}={}", payment.address));
+            }
+            if let Some(amount) = payment.amount {
+                params.push(alloc::format!("amount{index}={}", format_amount(amount)));
+            }
+            if let Some(memo) = &payment.memo {
+                let memo = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(memo);
+                params.push(alloc::format!("memo{index}={memo}"));
+            }
+            if let Some(label) = &payment.label {
+                params.push(alloc::format!("label{index}={}", percent_encode(label)));
+            }
+            if let Some(message) = &payment.message {
+                params.push(alloc::format!("message{index}={}", percent_encode(message)));
+            }
+        }
+        if !params.is_empty() {
+            uri.push('?');
+            uri.push_str(&params.join("&"));
+        }
+        uri
+    }
+
+    /// Parses a `juno:` URI, with payments in index order.
+    ///
+    /// Unknown parameters are ignored unless they start with `req-`, which ZIP-321
+    /// reserves for parameters a payer must understand.
+    pub fn from_uri(uri: &str) -> Result<Self, KeysError> {
+        let rest = uri
+            .split_once(':')
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+            .map(|(_, rest)| rest)
+            .ok_or(KeysError::PaymentRequest("not a juno: URI"))?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut fields: BTreeMap<u16, Fields> = BTreeMap::new();
+        if !path.is_empty() {
+            fields.entry(0).or_default().address = Some(path);
+        }
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param
+                .split_once('=')
+                .ok_or(KeysError::PaymentRequest("parameter without a value"))?;
+            let (name, index) = match key.split_once('.') {
+                Some((name, index)) => (name, parse_index(index)?),
+                None => (key, 0),
+            };
+            let entry = fields.entry(index).or_default();
+            let slot = match name {
+                "address" => &mut entry.address,
+                "amount" => &mut entry.amount,
+                "memo" => &mut entry.memo,
+                "label" => &mut entry.label,
+                "message" => &mut entry.message,
+                name if name.starts_with("req-") => {
+                    return Err(KeysError::PaymentRequest("unsupported required parameter"))
+                }
+                _ => continue,
+            };
+            if slot.replace(value).is_some() {
+                return Err(KeysError::PaymentRequest("duplicate parameter"));
+            }
+        }
+
+        let payments = fields
+            .into_values()
+            .map(|fields| {
+                let address = fields
+                    .address
+                    .ok_or(KeysError::PaymentRequest("payment without an address"))?;
+                Ok(Payment {
+                    address: UnifiedAddress::parse(address)?,
+                    amount: fields.amount.map(parse_amount).transpose()?,
+                    memo: fields
+                        .memo
+                        .map(|memo| {
+                            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                                .decode(memo)
+                                .map_err(|_| KeysError::PaymentRequest("memo is not base64url"))
+                        })
+                        .transpose()?,
+                    label: fields.label.map(percent_decode).transpose()?,
+                    message: fields.message.map(percent_decode).transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>, KeysError>>()?;
+        Self::new(payments)
+    }
+}
+
+// One payment's raw parameter values.
+#[derive(Default)]
+struct Fields<'a> {
+    address: Option<&'a str>,
+    amount: Option<&'a str>,
+    memo: Option<&'a str>,
+    label: Option<&'a str>,
+    message: Option<&'a str>,
+}
+
+// A parameter index: 1 to 9999 without leading zeros.
+fn parse_index(s: &str) -> Result<u16, KeysError> {
+    let invalid = KeysError::PaymentRequest("parameter index must be 1 to 9999");
+    if s.is_empty() || s.len() > 4 || s.starts_with('0') || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid);
+    }
+    s.parse().map_err(|_| invalid)
+}
+
+/// Parses a decimal coin amount such as `"1.5"` into zatoshis.
+pub fn parse_amount(s: &str) -> Result<u64, KeysError> {
+    let invalid = KeysError::PaymentRequest("amount must be a decimal with at most 8 places");
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+    let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty()
+        || !digits(whole)
+        || !digits(fraction)
+        || fraction.len() > 8
+        || (s.contains('.') && fraction.is_empty())
+    {
+        return Err(invalid);
+    }
+    let mut zatoshis = 0u64;
+    for b in whole.bytes().chain(fraction.bytes()) {
+        zatoshis = zatoshis
+            .checked_mul(10)
+            .and_then(|z| z.checked_add(u64::from(b - b'0')))
+            .ok_or(KeysError::PaymentRequest("amount exceeds the money supply"))?;
+    }
+    let scale = 10u64.pow(8 - fraction.len() as u32);
+    zatoshis
+        .checked_mul(scale)
+        .filter(|z| *z <= MAX_MONEY)
+        .ok_or(KeysError::PaymentRequest("amount exceeds the money supply"))
+}
+
+/// Formats zatoshis as a decimal coin amount without trailing zeros, e.g. `"1.5"`.
+pub fn format_amount(zatoshis: u64) -> String {
+    let mut out = alloc::format!("{}", zatoshis / COIN);
+    let fraction = zatoshis % COIN;
+    if fraction != 0 {
+        let digits = alloc::format!("{fraction:08}");
+        out.push('.');
+        out.push_str(digits.trim_end_matches('0'));
+    }
+    out
+}
+
+fn percent_decode(s: &str) -> Result<String, KeysError> {
+    let invalid = || KeysError::PaymentRequest("label or message is not percent-encoded UTF-8");
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = tail
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| core::str::from_utf8(hex).ok())
+                .ok_or_else(invalid)?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+// Percent-encodes everything outside ZIP-321's `qchar` set.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~!$'()*+,;:@".contains(&b) {
+            out.push(char::from(b));
+        } else {
+            let _ = write!(out, "%{b:02X}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ufvk_from_seed, Network, Seed, Ufvk};
+
+    fn address(account: u32) -> UnifiedAddress {
+        let seed = Seed::from_bytes(&[7u8; 32]).expect("seed");
+        let ufvk = ufvk_from_seed(&seed, &Network::Mainnet, account).expect("ufvk");
+        Ufvk::parse(&ufvk)
+            .and_then(|ufvk| ufvk.address_at(0u32))
+            .expect("address")
+    }
+
+    #[test]
+    fn amounts_round_trip() {
+        assert_eq!(parse_amount("1.5").ok(), Some(150_000_000));
+        assert_eq!(parse_amount("0.00000001").ok(), Some(1));
+        assert_eq!(parse_amount("21000000").ok(), Some(MAX_MONEY));
+        for bad in [
+            "",
+            ".5",
+            "1.",
+            "1.000000001",
+            "-1",
+            "1e3",
+            "21000000.00000001",
+        ] {
+            assert!(parse_amount(bad).is_err(), "{bad}");
+        }
+        assert_eq!(format_amount(150_000_000), "1.5");
+        assert_eq!(format_amount(1), "0.00000001");
+        assert_eq!(format_amount(2 * COIN), "2");
+    }
+
+    #[test]
+    fn builds_single_and_multi_recipient_uris() {
+        let (a, b) = (address(0), address(1));
+        let mut payment = Payment::to(a.clone());
+        payment.amount = Some(150_000_000);
+        payment.memo = Some(b"invoice #1".to_vec());
+        payment.message = Some("Order 1 & 2".into());
+        let single = TransactionRequest::new(alloc::vec![payment.clone()]).expect("request");
+        assert_eq!(
+            single.to_uri(),
+            alloc::format!("juno:{a}?amount=1.5&memo=aW52b2ljZSAjMQ&message=Order%201%20%26%202")
+        );
+
+        let mut second = Payment::to(b.clone());
+        second.label = Some("Café".into());
+        let multi = TransactionRequest::new(alloc::vec![payment.clone(), second]).expect("request");
+        assert!(multi
+            .to_uri()
+            .starts_with(&alloc::format!("juno:?address={a}&amount=1.5&")));
+        assert!(multi
+            .to_uri()
+            .ends_with(&alloc::format!("&address.1={b}&label.1=Caf%C3%A9")));
+
+        assert_eq!(
+            TransactionRequest::from_uri(&multi.to_uri()).ok(),
+            Some(multi)
+        );
+        assert_eq!(
+            TransactionRequest::from_uri(&single.to_uri()).ok(),
+            Some(single)
+        );
+
+        payment.memo = Some(alloc::vec![0; MEMO_LEN + 1]);
+        assert!(TransactionRequest::new(alloc::vec![payment]).is_err());
+        assert!(TransactionRequest::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn parses_and_rejects_uris() {
+        let a = address(0);
+        let request = TransactionRequest::from_uri(&alloc::format!(
+            "JUNO:?address.2={a}&amount.2=0.1&label.2=a%20b&x-tip=1&address={a}"
+        ))
+        .expect("parse");
+        assert_eq!(request.payments.len(), 2);
+        assert_eq!(request.payments[0].amount, None);
+        assert_eq!(request.payments[1].amount, Some(10_000_000));
+        assert_eq!(request.payments[1].label.as_deref(), Some("a b"));
+
+        for bad in [
+            alloc::format!("zcash:{a}"),
+            alloc::format!("juno:{a}?address={a}"),
+            alloc::format!("juno:{a}?amount=1&amount=2"),
+            alloc::format!("juno:{a}?req-expiry=10"),
+            alloc::format!("juno:?address.0={a}"),
+            String::from("juno:?amount.1=1"),
+            alloc::format!("juno:{a}?memo=!!"),
+            alloc::format!("juno:{a}?message=%ZZ"),
+            String::from("juno:j1notanaddress"),
+        ] {
+            assert!(TransactionRequest::from_uri(&bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! UniFFI bindings for the iOS and Android wallets; the interface is declared in
+//! `src/juno_keys.udl`. Run `make bindings` to generate the Swift and Kotlin sources.
+
+#![deny(warnings)]
+// Triggered by the generated scaffolding.
+#![allow(clippy::empty_line_after_doc_comments)]
+
+use juno_keys::{DerivationRequest, KeysError};
+
+uniffi::include_scaffolding!("juno_keys");
+
+#[derive(Debug, thiserror::Error)]
+pub enum JunoKeysError {
+    #[error("{name}: {message}")]
+    Failed {
+        code: i32,
+        name: String,
+        message: String,
+    },
+}
+
+impl From<KeysError> for JunoKeysError {
+    fn from(err: KeysError) -> Self {
+        let code = err.error_code();
+        JunoKeysError::Failed {
+            code: code.into(),
+            name: code.as_str().to_string(),
+            message: err.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+impl From<Network> for juno_keys::Network {
+    fn from(network: Network) -> Self {
+        match network {
+            Network::Mainnet => juno_keys::Network::Mainnet,
+            Network::Testnet => juno_keys::Network::Testnet,
+            Network::Regtest => juno_keys::Network::Regtest,
+        }
+    }
+}
+
+pub struct Seed(juno_keys::Seed);
+
+impl Seed {
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, JunoKeysError> {
+        let bytes = zeroize::Zeroizing::new(bytes);
+        Ok(Seed(juno_keys::Seed::from_bytes(&bytes)?))
+    }
+
+    pub fn from_base64(base64: String) -> Result<Self, JunoKeysError> {
+        Ok(Seed(juno_keys::Seed::from_base64(&base64)?))
+    }
+
+    pub fn from_hex(hex: String) -> Result<Self, JunoKeysError> {
+        Ok(Seed(juno_keys::Seed::from_hex(&hex)?))
+    }
+
+    pub fn from_mnemonic(phrase: String, passphrase: String) -> Result<Self, JunoKeysError> {
+        Ok(Seed(juno_keys::Seed::from_mnemonic(&phrase, &passphrase)?))
+    }
+
+    pub fn fingerprint(&self) -> Result<String, JunoKeysError> {
+        Ok(juno_keys::seed_fingerprint(&self.0)?)
+    }
+
+    pub fn ufvk(&self, network: Network, account: u32) -> Result<String, JunoKeysError> {
+        Ok(self.derive(network, account, 0)?.ufvk.to_string())
+    }
+
+    pub fn address(
+        &self,
+        network: Network,
+        account: u32,
+        diversifier_index: u32,
+    ) -> Result<String, JunoKeysError> {
+        Ok(self
+            .derive(network, account, diversifier_index)?
+            .address
+            .to_string())
+    }
+
+    fn derive(
+        &self,
+        network: Network,
+        account: u32,
+        diversifier_index: u32,
+    ) -> Result<juno_keys::DerivedKeys, KeysError> {
+        DerivationRequest::new(&self.0)
+            .network(juno_keys::Network::from(network))
+            .account(account)
+            .diversifier_index(diversifier_index)
+            .build()
+    }
+}
+
+pub fn address_from_ufvk(ufvk: String, diversifier_index: u32) -> Result<String, JunoKeysError> {
+    Ok(juno_keys::ua_from_ufvk(&ufvk, diversifier_index)?)
+}
+
+pub fn uivk_from_ufvk(ufvk: String) -> Result<String, JunoKeysError> {
+    Ok(juno_keys::uivk_from_ufvk(&ufvk)?)
+}
+
+pub fn validate_ufvk(ufvk: String) -> Result<(), JunoKeysError> {
+    juno_keys::Ufvk::parse(&ufvk)?;
+    Ok(())
+}
+
+pub fn validate_address(address: String) -> Result<(), JunoKeysError> {
+    juno_keys::UnifiedAddress::parse(&address)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_object_matches_library() {
+        let seed = Seed::from_bytes(vec![7u8; 32]).expect("seed");
+        let ufvk = seed.ufvk(Network::Mainnet, 0).expect("ufvk");
+        assert_eq!(
+            ufvk,
+            juno_keys::ufvk_from_seed(&[7u8; 32], &juno_keys::Network::Mainnet, 0).expect("ufvk")
+        );
+        assert_eq!(
+            seed.address(Network::Mainnet, 0, 2).expect("address"),
+            address_from_ufvk(ufvk.clone(), 2).expect("address")
+        );
+        assert!(validate_ufvk(ufvk).is_ok());
+    }
+
+    #[test]
+    fn errors_carry_stable_codes() {
+        let Err(JunoKeysError::Failed { code, name, .. }) = Seed::from_bytes(vec![0u8; 8]) else {
+            panic!("short seed accepted");
+        };
+        assert_eq!(code, juno_keys::ErrorCode::SeedInvalid as i32);
+        assert_eq!(name, "seed_invalid");
+    }
+}
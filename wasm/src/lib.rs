@@ -0,0 +1,192 @@
+//! wasm-bindgen wrapper for the web wallet: parse and validate unified addresses and
+//! UFVKs, derive addresses from a UFVK, and derive an onboarding seed's UFVK and
+//! addresses, entirely client-side.
+//!
+//! Build the npm package with `make wasm-pkg`. Failures throw a JS `Error` whose
+//! `code` is the library's string error code, e.g. `"encoding_invalid"`.
+
+#![deny(warnings)]
+
+use juno_keys::{DerivationRequest, DerivedKeys, KeysError, Network};
+use wasm_bindgen::prelude::*;
+
+fn js_error(err: KeysError) -> JsValue {
+    let error = js_sys::Error::new(&err.to_string());
+    // Setting a property on a fresh `Error` can't fail.
+    let _ = js_sys::Reflect::set(&error, &"code".into(), &err.code().into());
+    error.into()
+}
+
+fn derive(
+    seed: &[u8],
+    network: &str,
+    account: u32,
+    diversifier_index: u32,
+) -> Result<DerivedKeys, KeysError> {
+    DerivationRequest::new(seed)
+        .network(network.parse::<Network>()?)
+        .account(account)
+        .diversifier_index(diversifier_index)
+        .build()
+}
+
+/// A parsed unified full viewing key.
+#[wasm_bindgen]
+pub struct Ufvk(juno_keys::Ufvk);
+
+#[wasm_bindgen]
+impl Ufvk {
+    /// Throws if `encoded` is not a well-formed UFVK.
+    pub fn parse(encoded: &str) -> Result<Ufvk, JsValue> {
+        juno_keys::Ufvk::parse(encoded).map(Ufvk).map_err(js_error)
+    }
+
+    /// `"mainnet"`, `"testnet"` or `"regtest"`, or `undefined` for non-Juno keys.
+    #[wasm_bindgen(getter)]
+    pub fn network(&self) -> Option<String> {
+        self.0.network().map(|n| n.as_str().to_string())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn fingerprint(&self) -> String {
+        self.0.fingerprint()
+    }
+
+    #[wasm_bindgen(js_name = addressAt)]
+    pub fn address_at(&self, diversifier_index: u32) -> Result<UnifiedAddress, JsValue> {
+        self.0
+            .address_at(diversifier_index)
+            .map(UnifiedAddress)
+            .map_err(js_error)
+    }
+
+    pub fn uivk(&self) -> Result<String, JsValue> {
+        self.0.uivk().map_err(js_error)
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// A parsed unified address.
+#[wasm_bindgen]
+pub struct UnifiedAddress(juno_keys::UnifiedAddress);
+
+#[wasm_bindgen]
+impl UnifiedAddress {
+    /// Throws if `encoded` is not a well-formed unified address.
+    pub fn parse(encoded: &str) -> Result<UnifiedAddress, JsValue> {
+        juno_keys::UnifiedAddress::parse(encoded)
+            .map(UnifiedAddress)
+            .map_err(js_error)
+    }
+
+    /// `"mainnet"`, `"testnet"` or `"regtest"`, or `undefined` for non-Juno addresses.
+    #[wasm_bindgen(getter)]
+    pub fn network(&self) -> Option<String> {
+        self.0.network().map(|n| n.as_str().to_string())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn typecodes(&self) -> Vec<u64> {
+        self.0.typecodes()
+    }
+
+    #[wasm_bindgen(getter, js_name = hasOrchard)]
+    pub fn has_orchard(&self) -> bool {
+        self.0.has_orchard()
+    }
+
+    #[wasm_bindgen(getter, js_name = hasSapling)]
+    pub fn has_sapling(&self) -> bool {
+        self.0.has_sapling()
+    }
+
+    #[wasm_bindgen(getter, js_name = hasTransparent)]
+    pub fn has_transparent(&self) -> bool {
+        self.0.has_transparent()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Derives the UFVK for `account` of `seed` on `network` (`"mainnet"`, `"testnet"` or
+/// `"regtest"`). The seed is a `Uint8Array`; zero it once done.
+#[wasm_bindgen(js_name = ufvkFromSeed)]
+pub fn ufvk_from_seed(seed: &[u8], network: &str, account: u32) -> Result<String, JsValue> {
+    derive(seed, network, account, 0)
+        .map(|keys| keys.ufvk.to_string())
+        .map_err(js_error)
+}
+
+/// Derives the unified address at `diversifierIndex` for `account` of `seed`.
+#[wasm_bindgen(js_name = addressFromSeed)]
+pub fn address_from_seed(
+    seed: &[u8],
+    network: &str,
+    account: u32,
+    diversifier_index: u32,
+) -> Result<String, JsValue> {
+    derive(seed, network, account, diversifier_index)
+        .map(|keys| keys.address.to_string())
+        .map_err(js_error)
+}
+
+/// Derives the unified address at `diversifierIndex` from an encoded UFVK.
+#[wasm_bindgen(js_name = addressFromUfvk)]
+pub fn address_from_ufvk(ufvk: &str, diversifier_index: u32) -> Result<String, JsValue> {
+    juno_keys::ua_from_ufvk(ufvk, diversifier_index).map_err(js_error)
+}
+
+/// `true` if `encoded` is a well-formed UFVK; use `Ufvk.parse` for the reason.
+#[wasm_bindgen(js_name = isValidUfvk)]
+pub fn is_valid_ufvk(encoded: &str) -> bool {
+    juno_keys::Ufvk::parse(encoded).is_ok()
+}
+
+/// `true` if `encoded` is a well-formed unified address; use `UnifiedAddress.parse`
+/// for the reason.
+#[wasm_bindgen(js_name = isValidAddress)]
+pub fn is_valid_address(encoded: &str) -> bool {
+    juno_keys::UnifiedAddress::parse(encoded).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Only success paths: building a JS error needs a JS host.
+    #[test]
+    fn parses_and_derives_without_a_js_host() {
+        let encoded =
+            juno_keys::ufvk_from_seed(&[7u8; 32], &juno_keys::Network::Mainnet, 0).expect("ufvk");
+        assert_eq!(
+            ufvk_from_seed(&[7u8; 32], "mainnet", 0).ok().as_ref(),
+            Some(&encoded)
+        );
+        assert!(is_valid_ufvk(&encoded));
+        let Ok(ufvk) = Ufvk::parse(&encoded) else {
+            panic!("valid UFVK rejected");
+        };
+        assert_eq!(ufvk.network().as_deref(), Some("mainnet"));
+        let Ok(address) = ufvk.address_at(1) else {
+            panic!("address derivation failed");
+        };
+        assert!(address.has_orchard() && !address.has_transparent());
+        assert!(is_valid_address(&address.to_js_string()));
+        assert!(!is_valid_address(&encoded));
+        assert_eq!(
+            address_from_ufvk(&encoded, 1).ok(),
+            Some(address.to_js_string())
+        );
+        assert_eq!(
+            address_from_seed(&[7u8; 32], "mainnet", 0, 1).ok(),
+            Some(address.to_js_string())
+        );
+    }
+}